@@ -1,20 +1,44 @@
+pub mod annotation;
+pub mod assembler;
+pub mod builder;
+pub mod disassembler;
+pub mod javap;
+pub mod mutf8;
+pub mod opcode;
+pub mod signature;
+#[cfg(test)]
+mod test_bytes;
+pub mod writer;
+
 use crate::{JayError, JayResult};
+use annotation::{Annotations, ElementValue, ParameterAnnotations};
+use signature::{ClassSignature, FieldSignature, MethodSignature};
 
 const MIN_SUPPORTED_MAJOR_VERSION: u16 = 45;
 const MAX_SUPPORTED_MAJOR_VERSION: u16 = 69;
 const MAX_SUPPORTED_JAVA_VERSION: u16 = 25;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ClassFile {
     pub minor_version: u16,
     pub major_version: u16,
     pub constant_pool: ConstantPool,
+    pub access_flags: u16,
     pub this_class: String,
     pub super_class: Option<String>,
     pub interfaces: Vec<String>,
     pub methods: Vec<Method>,
     pub fields: Vec<Field>,
     pub bootstrap_methods: Vec<BootstrapMethod>,
+    pub inner_classes: Vec<InnerClass>,
+    pub enclosing_method: Option<EnclosingMethod>,
+    pub nest_host: Option<String>,
+    pub nest_members: Vec<String>,
+    pub permitted_subclasses: Vec<String>,
+    /// Raw generic signature from the class `Signature` attribute.
+    pub signature: Option<String>,
+    pub annotations: Annotations,
+    pub attributes: Vec<Attribute>,
 }
 
 impl ClassFile {
@@ -22,6 +46,70 @@ impl ClassFile {
         Parser::new(bytes).parse_class()
     }
 
+    pub fn is_public(&self) -> bool {
+        self.access_flags & 0x0001 != 0
+    }
+
+    pub fn is_final(&self) -> bool {
+        self.access_flags & 0x0010 != 0
+    }
+
+    pub fn is_super(&self) -> bool {
+        self.access_flags & 0x0020 != 0
+    }
+
+    pub fn is_interface(&self) -> bool {
+        self.access_flags & 0x0200 != 0
+    }
+
+    pub fn is_abstract(&self) -> bool {
+        self.access_flags & 0x0400 != 0
+    }
+
+    pub fn is_synthetic(&self) -> bool {
+        self.access_flags & 0x1000 != 0
+    }
+
+    pub fn is_annotation(&self) -> bool {
+        self.access_flags & 0x2000 != 0
+    }
+
+    pub fn is_enum(&self) -> bool {
+        self.access_flags & 0x4000 != 0
+    }
+
+    pub fn is_module(&self) -> bool {
+        self.access_flags & 0x8000 != 0
+    }
+
+    /// Parses the class generic signature, if the class declares one.
+    pub fn generic_signature(&self) -> JayResult<Option<ClassSignature>> {
+        self.signature
+            .as_deref()
+            .map(ClassSignature::parse)
+            .transpose()
+    }
+
+    /// Reports whether the class declares a `PermittedSubclasses` attribute.
+    pub fn is_sealed(&self) -> bool {
+        !self.permitted_subclasses.is_empty()
+    }
+
+    /// Returns the declared nest host, or this class when it hosts its own nest.
+    pub fn nest_host_name(&self) -> &str {
+        self.nest_host.as_deref().unwrap_or(&self.this_class)
+    }
+
+    /// Returns the `SourceFile` attribute's file name, if the class records one.
+    pub fn source_file(&self) -> Option<&str> {
+        let attribute = self
+            .attributes
+            .iter()
+            .find(|attribute| attribute.name == "SourceFile")?;
+        let index = u16::from_be_bytes(attribute.info.get(..2)?.try_into().ok()?);
+        self.constant_pool.utf8(index).ok()
+    }
+
     pub fn find_method(&self, name: &str, descriptor: &str) -> Option<&Method> {
         self.methods
             .iter()
@@ -29,57 +117,224 @@ impl ClassFile {
     }
 
     pub fn has_field(&self, name: &str, descriptor: &str) -> bool {
+        self.find_field(name, descriptor).is_some()
+    }
+
+    pub fn find_field(&self, name: &str, descriptor: &str) -> Option<&Field> {
         self.fields
             .iter()
-            .any(|field| field.name == name && field.descriptor == descriptor)
+            .find(|field| field.name == name && field.descriptor == descriptor)
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Method {
     pub access_flags: u16,
     pub name: String,
     pub descriptor: String,
     pub code: Option<Code>,
+    /// Raw generic signature from the method `Signature` attribute.
+    pub signature: Option<String>,
+    pub annotations: Annotations,
+    pub parameter_annotations: ParameterAnnotations,
+    /// Default value of an annotation interface element.
+    pub annotation_default: Option<ElementValue>,
+    pub attributes: Vec<Attribute>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Field {
+    pub access_flags: u16,
     pub name: String,
     pub descriptor: String,
+    /// Constant pool index from the field's `ConstantValue` attribute, if present.
+    pub constant_value: Option<u16>,
+    /// Raw generic signature from the field `Signature` attribute.
+    pub signature: Option<String>,
+    pub annotations: Annotations,
+    pub attributes: Vec<Attribute>,
 }
 
-impl Method {
-    pub fn is_static(&self) -> bool {
-        self.access_flags & 0x0008 != 0
+impl Field {
+    /// Parses the field generic signature, if the field declares one.
+    pub fn generic_signature(&self) -> JayResult<Option<FieldSignature>> {
+        self.signature
+            .as_deref()
+            .map(FieldSignature::parse)
+            .transpose()
+    }
+
+    pub fn is_public(&self) -> bool {
+        self.access_flags & 0x0001 != 0
     }
 
     pub fn is_private(&self) -> bool {
         self.access_flags & 0x0002 != 0
     }
 
+    pub fn is_protected(&self) -> bool {
+        self.access_flags & 0x0004 != 0
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.access_flags & 0x0008 != 0
+    }
+
+    pub fn is_final(&self) -> bool {
+        self.access_flags & 0x0010 != 0
+    }
+
+    pub fn is_volatile(&self) -> bool {
+        self.access_flags & 0x0040 != 0
+    }
+
+    pub fn is_transient(&self) -> bool {
+        self.access_flags & 0x0080 != 0
+    }
+
+    pub fn is_synthetic(&self) -> bool {
+        self.access_flags & 0x1000 != 0
+    }
+
+    pub fn is_enum(&self) -> bool {
+        self.access_flags & 0x4000 != 0
+    }
+}
+
+impl Method {
+    /// Parses the method generic signature, if the method declares one.
+    pub fn generic_signature(&self) -> JayResult<Option<MethodSignature>> {
+        self.signature
+            .as_deref()
+            .map(MethodSignature::parse)
+            .transpose()
+    }
+
     pub fn is_public(&self) -> bool {
         self.access_flags & 0x0001 != 0
     }
+
+    pub fn is_private(&self) -> bool {
+        self.access_flags & 0x0002 != 0
+    }
+
+    pub fn is_protected(&self) -> bool {
+        self.access_flags & 0x0004 != 0
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.access_flags & 0x0008 != 0
+    }
+
+    pub fn is_final(&self) -> bool {
+        self.access_flags & 0x0010 != 0
+    }
+
+    pub fn is_synchronized(&self) -> bool {
+        self.access_flags & 0x0020 != 0
+    }
+
+    pub fn is_bridge(&self) -> bool {
+        self.access_flags & 0x0040 != 0
+    }
+
+    pub fn is_varargs(&self) -> bool {
+        self.access_flags & 0x0080 != 0
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.access_flags & 0x0100 != 0
+    }
+
+    pub fn is_abstract(&self) -> bool {
+        self.access_flags & 0x0400 != 0
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.access_flags & 0x0800 != 0
+    }
+
+    pub fn is_synthetic(&self) -> bool {
+        self.access_flags & 0x1000 != 0
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Code {
     pub max_stack: u16,
     pub max_locals: u16,
     pub bytes: Vec<u8>,
+    pub exception_table: Vec<ExceptionHandler>,
+    /// Type annotations on local variables, casts, and other expressions.
+    pub annotations: Annotations,
+    pub attributes: Vec<Attribute>,
+}
+
+impl Code {
+    /// Maps a bytecode offset to its source line through `LineNumberTable`.
+    pub fn line_number(&self, pc: usize) -> Option<u16> {
+        let mut line = None;
+        let mut best_start = None;
+        for attribute in &self.attributes {
+            if attribute.name != "LineNumberTable" {
+                continue;
+            }
+            let entries = attribute.info.get(2..).unwrap_or_default().chunks_exact(4);
+            for entry in entries {
+                let start_pc = usize::from(u16::from_be_bytes([entry[0], entry[1]]));
+                if start_pc <= pc && best_start.is_none_or(|best| start_pc >= best) {
+                    best_start = Some(start_pc);
+                    line = Some(u16::from_be_bytes([entry[2], entry[3]]));
+                }
+            }
+        }
+        line
+    }
 }
 
-#[derive(Debug, Clone)]
+/// An attribute Jay does not model, such as `LineNumberTable` or
+/// `StackMapTable`, kept verbatim so the class file can be written back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attribute {
+    pub name: String,
+    pub info: Vec<u8>,
+}
+
+/// One `exception_table` entry from a `Code` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExceptionHandler {
+    /// First protected bytecode offset, inclusive.
+    pub start_pc: u16,
+    /// Last protected bytecode offset, exclusive.
+    pub end_pc: u16,
+    pub handler_pc: u16,
+    /// Constant pool class index of the caught type, or `0` for `finally` handlers.
+    pub catch_type: u16,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct ConstantPool {
     entries: Vec<CpEntry>,
 }
 
+impl Default for ConstantPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ConstantPool {
+    /// Creates an empty pool; index 0 is never a valid entry.
+    pub fn new() -> Self {
+        Self {
+            entries: vec![CpEntry::Unusable],
+        }
+    }
+
     pub fn utf8(&self, index: u16) -> JayResult<&str> {
         match self.entry(index)? {
             CpEntry::Utf8(value) => Ok(value),
-            other => Err(JayError::new(format!(
+            other => Err(JayError::class_format(format!(
                 "constant pool entry #{index} is not UTF-8: {other:?}"
             ))),
         }
@@ -88,7 +343,7 @@ impl ConstantPool {
     pub fn class_name(&self, index: u16) -> JayResult<&str> {
         match self.entry(index)? {
             CpEntry::Class { name_index } => self.utf8(*name_index),
-            other => Err(JayError::new(format!(
+            other => Err(JayError::class_format(format!(
                 "constant pool entry #{index} is not a class: {other:?}"
             ))),
         }
@@ -97,7 +352,7 @@ impl ConstantPool {
     pub fn string(&self, index: u16) -> JayResult<&str> {
         match self.entry(index)? {
             CpEntry::String { string_index } => self.utf8(*string_index),
-            other => Err(JayError::new(format!(
+            other => Err(JayError::class_format(format!(
                 "constant pool entry #{index} is not a string: {other:?}"
             ))),
         }
@@ -106,16 +361,34 @@ impl ConstantPool {
     pub fn integer(&self, index: u16) -> JayResult<i32> {
         match self.entry(index)? {
             CpEntry::Integer(value) => Ok(*value),
-            other => Err(JayError::new(format!(
+            other => Err(JayError::class_format(format!(
                 "constant pool entry #{index} is not an integer: {other:?}"
             ))),
         }
     }
 
+    pub fn float(&self, index: u16) -> JayResult<f32> {
+        match self.entry(index)? {
+            CpEntry::Float(bits) => Ok(f32::from_bits(*bits)),
+            other => Err(JayError::class_format(format!(
+                "constant pool entry #{index} is not a float: {other:?}"
+            ))),
+        }
+    }
+
+    pub fn double(&self, index: u16) -> JayResult<f64> {
+        match self.entry(index)? {
+            CpEntry::Double(bits) => Ok(f64::from_bits(*bits)),
+            other => Err(JayError::class_format(format!(
+                "constant pool entry #{index} is not a double: {other:?}"
+            ))),
+        }
+    }
+
     pub fn long(&self, index: u16) -> JayResult<i64> {
         match self.entry(index)? {
             CpEntry::Long(value) => Ok(*value),
-            other => Err(JayError::new(format!(
+            other => Err(JayError::class_format(format!(
                 "constant pool entry #{index} is not a long: {other:?}"
             ))),
         }
@@ -127,7 +400,7 @@ impl ConstantPool {
                 class_index,
                 name_and_type_index,
             } => self.member_ref(*class_index, *name_and_type_index),
-            other => Err(JayError::new(format!(
+            other => Err(JayError::class_format(format!(
                 "constant pool entry #{index} is not a field reference: {other:?}"
             ))),
         }
@@ -143,7 +416,7 @@ impl ConstantPool {
                 class_index,
                 name_and_type_index,
             } => self.member_ref(*class_index, *name_and_type_index),
-            other => Err(JayError::new(format!(
+            other => Err(JayError::class_format(format!(
                 "constant pool entry #{index} is not a method reference: {other:?}"
             ))),
         }
@@ -158,7 +431,7 @@ impl ConstantPool {
                 reference_kind: *reference_kind,
                 reference_index: *reference_index,
             }),
-            other => Err(JayError::new(format!(
+            other => Err(JayError::class_format(format!(
                 "constant pool entry #{index} is not a method handle: {other:?}"
             ))),
         }
@@ -178,38 +451,190 @@ impl ConstantPool {
                     name: self.utf8(*name_index)?,
                     descriptor: self.utf8(*descriptor_index)?,
                 }),
-                other => Err(JayError::new(format!(
+                other => Err(JayError::class_format(format!(
                     "constant pool entry #{name_and_type_index} is not name-and-type: {other:?}"
                 ))),
             },
-            other => Err(JayError::new(format!(
+            other => Err(JayError::class_format(format!(
                 "constant pool entry #{index} is not an invokedynamic reference: {other:?}"
             ))),
         }
     }
 
-    fn member_ref(&self, class_index: u16, name_and_type_index: u16) -> JayResult<MemberRef<'_>> {
-        let class_name = self.class_name(class_index)?;
-        match self.entry(name_and_type_index)? {
+    /// Resolves a `CONSTANT_NameAndType` entry to its name and descriptor.
+    pub fn name_and_type(&self, index: u16) -> JayResult<(&str, &str)> {
+        match self.entry(index)? {
             CpEntry::NameAndType {
                 name_index,
                 descriptor_index,
-            } => Ok(MemberRef {
-                class_name,
-                name: self.utf8(*name_index)?,
-                descriptor: self.utf8(*descriptor_index)?,
-            }),
-            other => Err(JayError::new(format!(
-                "constant pool entry #{name_and_type_index} is not name-and-type: {other:?}"
+            } => Ok((self.utf8(*name_index)?, self.utf8(*descriptor_index)?)),
+            other => Err(JayError::class_format(format!(
+                "constant pool entry #{index} is not name-and-type: {other:?}"
             ))),
         }
     }
 
+    fn member_ref(&self, class_index: u16, name_and_type_index: u16) -> JayResult<MemberRef<'_>> {
+        let class_name = self.class_name(class_index)?;
+        let (name, descriptor) = self.name_and_type(name_and_type_index)?;
+        Ok(MemberRef {
+            class_name,
+            name,
+            descriptor,
+        })
+    }
+
+    /// Returns `constant_pool_count`, one more than the highest valid index.
+    pub fn count(&self) -> u16 {
+        self.entries.len() as u16
+    }
+
+    /// Replaces the value of an existing `CONSTANT_Utf8` entry in place.
+    pub fn set_utf8(&mut self, index: u16, value: impl Into<String>) -> JayResult<()> {
+        self.utf8(index)?;
+        self.entries[index as usize] = CpEntry::Utf8(value.into());
+        Ok(())
+    }
+
+    /// Returns the index of a matching `CONSTANT_Utf8` entry, appending one if needed.
+    pub fn intern_utf8(&mut self, value: &str) -> JayResult<u16> {
+        self.intern(CpEntry::Utf8(value.to_string()))
+    }
+
+    pub fn intern_integer(&mut self, value: i32) -> JayResult<u16> {
+        self.intern(CpEntry::Integer(value))
+    }
+
+    pub fn intern_float(&mut self, value: f32) -> JayResult<u16> {
+        self.intern(CpEntry::Float(value.to_bits()))
+    }
+
+    pub fn intern_long(&mut self, value: i64) -> JayResult<u16> {
+        self.intern(CpEntry::Long(value))
+    }
+
+    pub fn intern_double(&mut self, value: f64) -> JayResult<u16> {
+        self.intern(CpEntry::Double(value.to_bits()))
+    }
+
+    /// Interns a `CONSTANT_Class` for an internal name such as `java/lang/Object`.
+    pub fn intern_class(&mut self, name: &str) -> JayResult<u16> {
+        let name_index = self.intern_utf8(name)?;
+        self.intern(CpEntry::Class { name_index })
+    }
+
+    pub fn intern_name_and_type(&mut self, name: &str, descriptor: &str) -> JayResult<u16> {
+        let name_index = self.intern_utf8(name)?;
+        let descriptor_index = self.intern_utf8(descriptor)?;
+        self.intern(CpEntry::NameAndType {
+            name_index,
+            descriptor_index,
+        })
+    }
+
+    pub fn intern_string(&mut self, value: &str) -> JayResult<u16> {
+        let string_index = self.intern_utf8(value)?;
+        self.intern(CpEntry::String { string_index })
+    }
+
+    pub fn intern_field_ref(
+        &mut self,
+        class: &str,
+        name: &str,
+        descriptor: &str,
+    ) -> JayResult<u16> {
+        let class_index = self.intern_class(class)?;
+        let name_and_type_index = self.intern_name_and_type(name, descriptor)?;
+        self.intern(CpEntry::FieldRef {
+            class_index,
+            name_and_type_index,
+        })
+    }
+
+    pub fn intern_method_ref(
+        &mut self,
+        class: &str,
+        name: &str,
+        descriptor: &str,
+    ) -> JayResult<u16> {
+        let class_index = self.intern_class(class)?;
+        let name_and_type_index = self.intern_name_and_type(name, descriptor)?;
+        self.intern(CpEntry::MethodRef {
+            class_index,
+            name_and_type_index,
+        })
+    }
+
+    pub fn intern_interface_method_ref(
+        &mut self,
+        class: &str,
+        name: &str,
+        descriptor: &str,
+    ) -> JayResult<u16> {
+        let class_index = self.intern_class(class)?;
+        let name_and_type_index = self.intern_name_and_type(name, descriptor)?;
+        self.intern(CpEntry::InterfaceMethodRef {
+            class_index,
+            name_and_type_index,
+        })
+    }
+
+    /// Interns a `CONSTANT_MethodHandle` whose `reference_index` is an
+    /// already-interned field or method reference.
+    pub fn intern_method_handle(
+        &mut self,
+        reference_kind: u8,
+        reference_index: u16,
+    ) -> JayResult<u16> {
+        self.intern(CpEntry::MethodHandle {
+            reference_kind,
+            reference_index,
+        })
+    }
+
+    pub fn intern_method_type(&mut self, descriptor: &str) -> JayResult<u16> {
+        let descriptor_index = self.intern_utf8(descriptor)?;
+        self.intern(CpEntry::MethodType { descriptor_index })
+    }
+
+    pub fn intern_invoke_dynamic(
+        &mut self,
+        bootstrap_method_attr_index: u16,
+        name: &str,
+        descriptor: &str,
+    ) -> JayResult<u16> {
+        let name_and_type_index = self.intern_name_and_type(name, descriptor)?;
+        self.intern(CpEntry::InvokeDynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        })
+    }
+
+    fn intern(&mut self, entry: CpEntry) -> JayResult<u16> {
+        if let Some(index) = self.entries.iter().position(|existing| *existing == entry) {
+            return Ok(index as u16);
+        }
+
+        let wide = matches!(entry, CpEntry::Long(_) | CpEntry::Double(_));
+        let slots = if wide { 2 } else { 1 };
+        if self.entries.len() + slots > u16::MAX as usize {
+            return Err(JayError::class_format(
+                "constant pool exceeds 65535 entries",
+            ));
+        }
+        let index = self.entries.len() as u16;
+        self.entries.push(entry);
+        if wide {
+            self.entries.push(CpEntry::Unusable);
+        }
+        Ok(index)
+    }
+
     fn entry(&self, index: u16) -> JayResult<&CpEntry> {
         self.entries
             .get(index as usize)
             .filter(|entry| !matches!(entry, CpEntry::Unusable))
-            .ok_or_else(|| JayError::new(format!("invalid constant pool index #{index}")))
+            .ok_or_else(|| JayError::class_format(format!("invalid constant pool index #{index}")))
     }
 }
 
@@ -242,14 +667,49 @@ pub struct BootstrapMethod {
     pub arguments: Vec<u16>,
 }
 
-#[derive(Debug, Clone)]
+/// One entry from a class-level `InnerClasses` attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InnerClass {
+    pub inner_class: String,
+    /// `None` for local and anonymous classes.
+    pub outer_class: Option<String>,
+    /// `None` for anonymous classes.
+    pub inner_name: Option<String>,
+    pub access_flags: u16,
+}
+
+/// The `EnclosingMethod` attribute of a local or anonymous class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnclosingMethod {
+    pub class_name: String,
+    /// `None` when the class is declared in an initializer rather than a method.
+    pub method_name: Option<String>,
+    pub method_descriptor: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct ClassAttributes {
+    bootstrap_methods: Vec<BootstrapMethod>,
+    inner_classes: Vec<InnerClass>,
+    enclosing_method: Option<EnclosingMethod>,
+    nest_host: Option<String>,
+    nest_members: Vec<String>,
+    permitted_subclasses: Vec<String>,
+    signature: Option<String>,
+    annotations: Annotations,
+    attributes: Vec<Attribute>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum CpEntry {
     Unusable,
     Utf8(String),
     Integer(i32),
-    Float,
+    /// IEEE 754 bits, so interning distinguishes `-0.0` and NaN payloads.
+    Float(u32),
     Long(i64),
-    Double,
+    /// IEEE 754 bits, like [`CpEntry::Float`].
+    Double(u64),
     Class {
         name_index: u16,
     },
@@ -276,14 +736,23 @@ enum CpEntry {
         reference_kind: u8,
         reference_index: u16,
     },
-    MethodType,
-    Dynamic,
+    MethodType {
+        descriptor_index: u16,
+    },
+    Dynamic {
+        bootstrap_method_attr_index: u16,
+        name_and_type_index: u16,
+    },
     InvokeDynamic {
         bootstrap_method_attr_index: u16,
         name_and_type_index: u16,
     },
-    Module,
-    Package,
+    Module {
+        name_index: u16,
+    },
+    Package {
+        name_index: u16,
+    },
 }
 
 struct Parser<'a> {
@@ -299,20 +768,20 @@ impl<'a> Parser<'a> {
     fn parse_class(&mut self) -> JayResult<ClassFile> {
         let magic = self.read_u4()?;
         if magic != 0xCAFEBABE {
-            return Err(JayError::new("invalid class file magic"));
+            return Err(JayError::class_format("invalid class file magic"));
         }
 
         let minor_version = self.read_u2()?;
         let major_version = self.read_u2()?;
         if !(MIN_SUPPORTED_MAJOR_VERSION..=MAX_SUPPORTED_MAJOR_VERSION).contains(&major_version) {
-            return Err(JayError::new(format!(
-                "unsupported class file major version {major_version}; expected Java {MAX_SUPPORTED_JAVA_VERSION} or older"
+            return Err(JayError::unsupported(format!(
+                "class file major version {major_version}; expected Java {MAX_SUPPORTED_JAVA_VERSION} or older"
             )));
         }
 
         let constant_pool = self.parse_constant_pool()?;
 
-        let _access_flags = self.read_u2()?;
+        let access_flags = self.read_u2()?;
         let this_class_index = self.read_u2()?;
         let super_class_index = self.read_u2()?;
         let this_class = constant_pool.class_name(this_class_index)?.to_string();
@@ -325,22 +794,31 @@ impl<'a> Parser<'a> {
         let interfaces = self.parse_interfaces(&constant_pool)?;
         let fields = self.parse_fields(&constant_pool)?;
         let methods = self.parse_methods(&constant_pool)?;
-        let bootstrap_methods = self.parse_class_attributes(&constant_pool)?;
+        let attributes = self.parse_class_attributes(&constant_pool)?;
 
         if self.offset != self.bytes.len() {
-            return Err(JayError::new("trailing bytes after class file"));
+            return Err(JayError::class_format("trailing bytes after class file"));
         }
 
         Ok(ClassFile {
             minor_version,
             major_version,
             constant_pool,
+            access_flags,
             this_class,
             super_class,
             interfaces,
             methods,
             fields,
-            bootstrap_methods,
+            bootstrap_methods: attributes.bootstrap_methods,
+            inner_classes: attributes.inner_classes,
+            enclosing_method: attributes.enclosing_method,
+            nest_host: attributes.nest_host,
+            nest_members: attributes.nest_members,
+            permitted_subclasses: attributes.permitted_subclasses,
+            signature: attributes.signature,
+            annotations: attributes.annotations,
+            attributes: attributes.attributes,
         })
     }
 
@@ -356,15 +834,13 @@ impl<'a> Parser<'a> {
                 1 => {
                     let length = self.read_u2()? as usize;
                     let bytes = self.read_bytes(length)?;
-                    let value = String::from_utf8(bytes.to_vec())
-                        .map_err(|_| JayError::new("invalid UTF-8 in constant pool"))?;
-                    CpEntry::Utf8(value)
+                    let value = mutf8::decode(bytes).map_err(|error| {
+                        error.with_context(format!("constant pool entry #{index}"))
+                    })?;
+                    CpEntry::Utf8(value.into_owned())
                 }
                 3 => CpEntry::Integer(self.read_u4()? as i32),
-                4 => {
-                    self.skip(4)?;
-                    CpEntry::Float
-                }
+                4 => CpEntry::Float(self.read_u4()?),
                 5 => {
                     let high_bytes = self.read_u4()? as u64;
                     let low_bytes = self.read_u4()? as u64;
@@ -375,8 +851,9 @@ impl<'a> Parser<'a> {
                     continue;
                 }
                 6 => {
-                    self.skip(8)?;
-                    entries.push(CpEntry::Double);
+                    let high_bytes = self.read_u4()? as u64;
+                    let low_bytes = self.read_u4()? as u64;
+                    entries.push(CpEntry::Double((high_bytes << 32) | low_bytes));
                     entries.push(CpEntry::Unusable);
                     index += 2;
                     continue;
@@ -407,30 +884,25 @@ impl<'a> Parser<'a> {
                     reference_kind: self.read_u1()?,
                     reference_index: self.read_u2()?,
                 },
-                16 => {
-                    self.skip(2)?;
-                    CpEntry::MethodType
-                }
-                17 => {
-                    self.skip(4)?;
-                    CpEntry::Dynamic
-                }
+                16 => CpEntry::MethodType {
+                    descriptor_index: self.read_u2()?,
+                },
+                17 => CpEntry::Dynamic {
+                    bootstrap_method_attr_index: self.read_u2()?,
+                    name_and_type_index: self.read_u2()?,
+                },
                 18 => CpEntry::InvokeDynamic {
                     bootstrap_method_attr_index: self.read_u2()?,
                     name_and_type_index: self.read_u2()?,
                 },
-                19 => {
-                    self.skip(2)?;
-                    CpEntry::Module
-                }
-                20 => {
-                    self.skip(2)?;
-                    CpEntry::Package
-                }
+                19 => CpEntry::Module {
+                    name_index: self.read_u2()?,
+                },
+                20 => CpEntry::Package {
+                    name_index: self.read_u2()?,
+                },
                 _ => {
-                    return Err(JayError::new(format!(
-                        "unsupported constant pool tag {tag}"
-                    )));
+                    return Err(JayError::unsupported(format!("constant pool tag {tag}")));
                 }
             };
 
@@ -454,11 +926,48 @@ impl<'a> Parser<'a> {
         let count = self.read_u2()? as usize;
         let mut fields = Vec::with_capacity(count);
         for _ in 0..count {
-            self.skip(2)?;
+            let access_flags = self.read_u2()?;
             let name = constant_pool.utf8(self.read_u2()?)?.to_string();
             let descriptor = constant_pool.utf8(self.read_u2()?)?.to_string();
-            self.skip_attributes()?;
-            fields.push(Field { name, descriptor });
+            let attributes_count = self.read_u2()? as usize;
+            let mut constant_value = None;
+            let mut signature = None;
+            let mut annotations = Annotations::default();
+            let mut attributes = Vec::new();
+
+            for _ in 0..attributes_count {
+                let name_index = self.read_u2()?;
+                let attribute_name = constant_pool.utf8(name_index)?;
+                let attribute_length = self.read_u4()? as usize;
+                let attribute_end = self.attribute_end(attribute_name, attribute_length)?;
+                if attribute_name == "ConstantValue" {
+                    if attribute_length != 2 {
+                        return Err(JayError::class_format(
+                            "ConstantValue attribute length mismatch",
+                        ));
+                    }
+                    constant_value = Some(self.read_u2()?);
+                } else if attribute_name == "Signature" {
+                    signature = Some(self.parse_signature(constant_pool, attribute_length)?);
+                } else if !self.parse_annotation_attribute(
+                    constant_pool,
+                    attribute_name,
+                    &mut annotations,
+                )? {
+                    attributes.push(self.read_raw_attribute(attribute_name, attribute_length)?);
+                }
+                self.expect_attribute_end(attribute_name, attribute_end)?;
+            }
+
+            fields.push(Field {
+                access_flags,
+                name,
+                descriptor,
+                constant_value,
+                signature,
+                annotations,
+                attributes,
+            });
         }
         Ok(fields)
     }
@@ -472,22 +981,45 @@ impl<'a> Parser<'a> {
             let descriptor = constant_pool.utf8(self.read_u2()?)?.to_string();
             let attributes_count = self.read_u2()? as usize;
             let mut code = None;
+            let mut signature = None;
+            let mut annotations = Annotations::default();
+            let mut parameter_annotations = ParameterAnnotations::default();
+            let mut annotation_default = None;
+            let mut attributes = Vec::new();
 
             for _ in 0..attributes_count {
                 let name_index = self.read_u2()?;
                 let attribute_name = constant_pool.utf8(name_index)?;
                 let attribute_length = self.read_u4()? as usize;
-                if attribute_name == "Code" {
-                    let code_end = self.offset.checked_add(attribute_length).ok_or_else(|| {
-                        JayError::new("class file offset overflow while reading Code")
-                    })?;
-                    code = Some(self.parse_code(constant_pool)?);
-                    if self.offset != code_end {
-                        return Err(JayError::new("Code attribute length mismatch"));
+                let attribute_end = self.attribute_end(attribute_name, attribute_length)?;
+                match attribute_name {
+                    "Code" => code = Some(self.parse_code(constant_pool)?),
+                    "Signature" => {
+                        signature = Some(self.parse_signature(constant_pool, attribute_length)?);
+                    }
+                    "RuntimeVisibleParameterAnnotations" => {
+                        parameter_annotations.visible =
+                            self.parse_parameter_annotations(constant_pool)?;
+                    }
+                    "RuntimeInvisibleParameterAnnotations" => {
+                        parameter_annotations.invisible =
+                            self.parse_parameter_annotations(constant_pool)?;
+                    }
+                    "AnnotationDefault" => {
+                        annotation_default = Some(self.parse_element_value(constant_pool)?);
+                    }
+                    _ => {
+                        if !self.parse_annotation_attribute(
+                            constant_pool,
+                            attribute_name,
+                            &mut annotations,
+                        )? {
+                            attributes
+                                .push(self.read_raw_attribute(attribute_name, attribute_length)?);
+                        }
                     }
-                } else {
-                    self.skip(attribute_length)?;
                 }
+                self.expect_attribute_end(attribute_name, attribute_end)?;
             }
 
             methods.push(Method {
@@ -495,11 +1027,46 @@ impl<'a> Parser<'a> {
                 name,
                 descriptor,
                 code,
+                signature,
+                annotations,
+                parameter_annotations,
+                annotation_default,
+                attributes,
             });
         }
         Ok(methods)
     }
 
+    fn attribute_end(&self, attribute_name: &str, attribute_length: usize) -> JayResult<usize> {
+        self.offset.checked_add(attribute_length).ok_or_else(|| {
+            JayError::class_format(format!(
+                "class file offset overflow while reading {attribute_name}"
+            ))
+        })
+    }
+
+    fn expect_attribute_end(&self, attribute_name: &str, attribute_end: usize) -> JayResult<()> {
+        if self.offset != attribute_end {
+            return Err(JayError::class_format(format!(
+                "{attribute_name} attribute length mismatch"
+            )));
+        }
+        Ok(())
+    }
+
+    fn parse_signature(
+        &mut self,
+        constant_pool: &ConstantPool,
+        attribute_length: usize,
+    ) -> JayResult<String> {
+        if attribute_length != 2 {
+            return Err(JayError::class_format(
+                "Signature attribute length mismatch",
+            ));
+        }
+        Ok(constant_pool.utf8(self.read_u2()?)?.to_string())
+    }
+
     fn parse_code(&mut self, constant_pool: &ConstantPool) -> JayResult<Code> {
         let max_stack = self.read_u2()?;
         let max_locals = self.read_u2()?;
@@ -507,49 +1074,130 @@ impl<'a> Parser<'a> {
         let bytes = self.read_bytes(code_length)?.to_vec();
 
         let exception_table_length = self.read_u2()? as usize;
-        self.skip(exception_table_length * 8)?;
-        self.skip_attributes_named(constant_pool)?;
+        let mut exception_table = Vec::with_capacity(exception_table_length);
+        for _ in 0..exception_table_length {
+            exception_table.push(ExceptionHandler {
+                start_pc: self.read_u2()?,
+                end_pc: self.read_u2()?,
+                handler_pc: self.read_u2()?,
+                catch_type: self.read_u2()?,
+            });
+        }
+        let (annotations, attributes) = self.parse_code_attributes(constant_pool)?;
 
         Ok(Code {
             max_stack,
             max_locals,
             bytes,
+            exception_table,
+            annotations,
+            attributes,
         })
     }
 
-    fn skip_attributes(&mut self) -> JayResult<()> {
-        let count = self.read_u2()? as usize;
-        for _ in 0..count {
-            self.skip(2)?;
-            let length = self.read_u4()? as usize;
-            self.skip(length)?;
-        }
-        Ok(())
-    }
-
     fn parse_class_attributes(
         &mut self,
         constant_pool: &ConstantPool,
-    ) -> JayResult<Vec<BootstrapMethod>> {
+    ) -> JayResult<ClassAttributes> {
         let count = self.read_u2()? as usize;
-        let mut bootstrap_methods = Vec::new();
+        let mut attributes = ClassAttributes::default();
         for _ in 0..count {
             let name_index = self.read_u2()?;
             let attribute_name = constant_pool.utf8(name_index)?;
             let attribute_length = self.read_u4()? as usize;
-            if attribute_name == "BootstrapMethods" {
-                let attribute_end = self.offset.checked_add(attribute_length).ok_or_else(|| {
-                    JayError::new("class file offset overflow while reading BootstrapMethods")
-                })?;
-                bootstrap_methods = self.parse_bootstrap_methods()?;
-                if self.offset != attribute_end {
-                    return Err(JayError::new("BootstrapMethods attribute length mismatch"));
+            let attribute_end = self.attribute_end(attribute_name, attribute_length)?;
+            match attribute_name {
+                "BootstrapMethods" => {
+                    attributes.bootstrap_methods = self.parse_bootstrap_methods()?;
+                }
+                "InnerClasses" => {
+                    attributes.inner_classes = self.parse_inner_classes(constant_pool)?;
+                }
+                "EnclosingMethod" => {
+                    attributes.enclosing_method = Some(self.parse_enclosing_method(constant_pool)?);
+                }
+                "NestHost" => {
+                    let host_index = self.read_u2()?;
+                    attributes.nest_host = Some(constant_pool.class_name(host_index)?.to_string());
+                }
+                "NestMembers" => {
+                    attributes.nest_members = self.parse_class_list(constant_pool)?;
+                }
+                "PermittedSubclasses" => {
+                    attributes.permitted_subclasses = self.parse_class_list(constant_pool)?;
+                }
+                "Signature" => {
+                    let signature_index = self.read_u2()?;
+                    attributes.signature = Some(constant_pool.utf8(signature_index)?.to_string());
+                }
+                _ => {
+                    if !self.parse_annotation_attribute(
+                        constant_pool,
+                        attribute_name,
+                        &mut attributes.annotations,
+                    )? {
+                        let attribute =
+                            self.read_raw_attribute(attribute_name, attribute_length)?;
+                        attributes.attributes.push(attribute);
+                    }
                 }
-            } else {
-                self.skip(attribute_length)?;
             }
+            self.expect_attribute_end(attribute_name, attribute_end)?;
         }
-        Ok(bootstrap_methods)
+        Ok(attributes)
+    }
+
+    fn parse_inner_classes(&mut self, constant_pool: &ConstantPool) -> JayResult<Vec<InnerClass>> {
+        let count = self.read_u2()? as usize;
+        let mut inner_classes = Vec::with_capacity(count);
+        for _ in 0..count {
+            let inner_class_index = self.read_u2()?;
+            let outer_class_index = self.read_u2()?;
+            let inner_name_index = self.read_u2()?;
+            let access_flags = self.read_u2()?;
+            inner_classes.push(InnerClass {
+                inner_class: constant_pool.class_name(inner_class_index)?.to_string(),
+                outer_class: match outer_class_index {
+                    0 => None,
+                    index => Some(constant_pool.class_name(index)?.to_string()),
+                },
+                inner_name: match inner_name_index {
+                    0 => None,
+                    index => Some(constant_pool.utf8(index)?.to_string()),
+                },
+                access_flags,
+            });
+        }
+        Ok(inner_classes)
+    }
+
+    fn parse_enclosing_method(
+        &mut self,
+        constant_pool: &ConstantPool,
+    ) -> JayResult<EnclosingMethod> {
+        let class_index = self.read_u2()?;
+        let method_index = self.read_u2()?;
+        let (method_name, method_descriptor) = if method_index == 0 {
+            (None, None)
+        } else {
+            let (name, descriptor) = constant_pool.name_and_type(method_index)?;
+            (Some(name.to_string()), Some(descriptor.to_string()))
+        };
+        Ok(EnclosingMethod {
+            class_name: constant_pool.class_name(class_index)?.to_string(),
+            method_name,
+            method_descriptor,
+        })
+    }
+
+    fn parse_class_list(&mut self, constant_pool: &ConstantPool) -> JayResult<Vec<String>> {
+        let count = self.read_u2()? as usize;
+        let mut classes = Vec::with_capacity(count);
+        for _ in 0..count {
+            let class_index = self.read_u2()?;
+            classes.push(constant_pool.class_name(class_index)?.to_string());
+        }
+        Ok(classes)
     }
 
     fn parse_bootstrap_methods(&mut self) -> JayResult<Vec<BootstrapMethod>> {
@@ -570,15 +1218,31 @@ impl<'a> Parser<'a> {
         Ok(bootstrap_methods)
     }
 
-    fn skip_attributes_named(&mut self, constant_pool: &ConstantPool) -> JayResult<()> {
+    fn parse_code_attributes(
+        &mut self,
+        constant_pool: &ConstantPool,
+    ) -> JayResult<(Annotations, Vec<Attribute>)> {
         let count = self.read_u2()? as usize;
+        let mut annotations = Annotations::default();
+        let mut attributes = Vec::new();
         for _ in 0..count {
             let name_index = self.read_u2()?;
-            let _ = constant_pool.utf8(name_index)?;
-            let length = self.read_u4()? as usize;
-            self.skip(length)?;
+            let attribute_name = constant_pool.utf8(name_index)?;
+            let attribute_length = self.read_u4()? as usize;
+            let attribute_end = self.attribute_end(attribute_name, attribute_length)?;
+            if !self.parse_annotation_attribute(constant_pool, attribute_name, &mut annotations)? {
+                attributes.push(self.read_raw_attribute(attribute_name, attribute_length)?);
+            }
+            self.expect_attribute_end(attribute_name, attribute_end)?;
         }
-        Ok(())
+        Ok((annotations, attributes))
+    }
+
+    fn read_raw_attribute(&mut self, name: &str, length: usize) -> JayResult<Attribute> {
+        Ok(Attribute {
+            name: name.to_string(),
+            info: self.read_bytes(length)?.to_vec(),
+        })
     }
 
     fn read_u1(&mut self) -> JayResult<u8> {
@@ -599,22 +1263,19 @@ impl<'a> Parser<'a> {
         let end = self
             .offset
             .checked_add(length)
-            .ok_or_else(|| JayError::new("class file offset overflow"))?;
+            .ok_or_else(|| JayError::class_format("class file offset overflow"))?;
         if end > self.bytes.len() {
-            return Err(JayError::new("unexpected end of class file"));
+            return Err(JayError::class_format("unexpected end of class file"));
         }
         let bytes = &self.bytes[self.offset..end];
         self.offset = end;
         Ok(bytes)
     }
-
-    fn skip(&mut self, length: usize) -> JayResult<()> {
-        self.read_bytes(length).map(|_| ())
-    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::test_bytes::{push_u2, push_u4, push_utf8};
     use super::*;
 
     #[test]
@@ -687,20 +1348,6 @@ mod tests {
 
     #[test]
     fn parses_invokedynamic_and_bootstrap_methods() {
-        fn push_u2(bytes: &mut Vec<u8>, value: u16) {
-            bytes.extend(value.to_be_bytes());
-        }
-
-        fn push_u4(bytes: &mut Vec<u8>, value: u32) {
-            bytes.extend(value.to_be_bytes());
-        }
-
-        fn push_utf8(bytes: &mut Vec<u8>, value: &str) {
-            bytes.push(1);
-            push_u2(bytes, value.len() as u16);
-            bytes.extend(value.as_bytes());
-        }
-
         let mut bytes = Vec::new();
         push_u4(&mut bytes, 0xCAFEBABE);
         push_u2(&mut bytes, 0);
@@ -799,5 +1446,218 @@ mod tests {
         let class_file = ClassFile::parse(&bytes).unwrap();
 
         assert_eq!(class_file.interfaces, vec!["Named", "Taggable"]);
+        assert!(class_file.is_public());
+        assert!(class_file.is_super());
+        assert!(!class_file.is_interface());
+        assert!(!class_file.is_abstract());
+    }
+
+    #[test]
+    fn parses_field_access_flags_and_constant_value() {
+        let bytes = [
+            0xCA, 0xFE, 0xBA, 0xBE, // magic
+            0x00, 0x00, // minor
+            0x00, 0x3D, // major 61
+            0x00, 0x09, // constant_pool_count
+            0x07, 0x00, 0x02, // #1 Class Example
+            0x01, 0x00, 0x07, b'E', b'x', b'a', b'm', b'p', b'l', b'e', // #2 Utf8 Example
+            0x07, 0x00, 0x04, // #3 Class java/lang/Object
+            0x01, 0x00, 0x10, b'j', b'a', b'v', b'a', b'/', b'l', b'a', b'n', b'g', b'/', b'O',
+            b'b', b'j', b'e', b'c', b't', // #4 Utf8 java/lang/Object
+            0x01, 0x00, 0x06, b'A', b'N', b'S', b'W', b'E', b'R', // #5 Utf8 ANSWER
+            0x01, 0x00, 0x01, b'I', // #6 Utf8 I
+            0x01, 0x00, 0x0D, b'C', b'o', b'n', b's', b't', b'a', b'n', b't', b'V', b'a', b'l',
+            b'u', b'e', // #7 Utf8 ConstantValue
+            0x03, 0x00, 0x00, 0x00, 0x2A, // #8 Integer 42
+            0x00, 0x21, // access_flags
+            0x00, 0x01, // this_class
+            0x00, 0x03, // super_class
+            0x00, 0x00, // interfaces_count
+            0x00, 0x01, // fields_count
+            0x00, 0x19, // public static final
+            0x00, 0x05, // name ANSWER
+            0x00, 0x06, // descriptor I
+            0x00, 0x01, // attributes_count
+            0x00, 0x07, // ConstantValue
+            0x00, 0x00, 0x00, 0x02, // attribute_length
+            0x00, 0x08, // constantvalue_index
+            0x00, 0x00, // methods_count
+            0x00, 0x00, // attributes_count
+        ];
+
+        let class_file = ClassFile::parse(&bytes).unwrap();
+        let field = class_file.find_field("ANSWER", "I").unwrap();
+
+        assert!(field.is_public());
+        assert!(field.is_static());
+        assert!(field.is_final());
+        assert!(!field.is_synthetic());
+        assert_eq!(field.constant_value, Some(8));
+        assert_eq!(class_file.constant_pool.integer(8).unwrap(), 42);
+    }
+
+    #[test]
+    fn method_flag_helpers_match_jvm_access_flags() {
+        let method = Method {
+            access_flags: 0x0001 | 0x0020 | 0x0040 | 0x0080 | 0x1000,
+            name: "run".to_string(),
+            descriptor: "([Ljava/lang/String;)V".to_string(),
+            code: None,
+            signature: None,
+            annotations: Annotations::default(),
+            parameter_annotations: ParameterAnnotations::default(),
+            annotation_default: None,
+            attributes: Vec::new(),
+        };
+
+        assert!(method.is_public());
+        assert!(method.is_synchronized());
+        assert!(method.is_bridge());
+        assert!(method.is_varargs());
+        assert!(method.is_synthetic());
+        assert!(!method.is_static());
+        assert!(!method.is_final());
+        assert!(!method.is_native());
+        assert!(!method.is_abstract());
+    }
+
+    #[test]
+    fn parses_nest_inner_class_enclosing_method_and_permitted_subclasses() {
+        let mut bytes = Vec::new();
+        push_u4(&mut bytes, 0xCAFEBABE);
+        push_u2(&mut bytes, 0);
+        push_u2(&mut bytes, 69);
+        push_u2(&mut bytes, 19);
+
+        bytes.extend([7, 0, 2]); // #1 Class Outer$Inner
+        push_utf8(&mut bytes, "Outer$Inner"); // #2
+        bytes.extend([7, 0, 4]); // #3 Class java/lang/Object
+        push_utf8(&mut bytes, "java/lang/Object"); // #4
+        bytes.extend([7, 0, 6]); // #5 Class Outer
+        push_utf8(&mut bytes, "Outer"); // #6
+        push_utf8(&mut bytes, "Inner"); // #7
+        push_utf8(&mut bytes, "InnerClasses"); // #8
+        push_utf8(&mut bytes, "NestHost"); // #9
+        push_utf8(&mut bytes, "NestMembers"); // #10
+        push_utf8(&mut bytes, "EnclosingMethod"); // #11
+        push_utf8(&mut bytes, "PermittedSubclasses"); // #12
+        bytes.extend([12, 0, 14, 0, 15]); // #13 NameAndType #14:#15
+        push_utf8(&mut bytes, "run"); // #14
+        push_utf8(&mut bytes, "()V"); // #15
+        bytes.extend([7, 0, 17]); // #16 Class Outer$Leaf
+        push_utf8(&mut bytes, "Outer$Leaf"); // #17
+        push_utf8(&mut bytes, "Unknown"); // #18
+
+        bytes.extend([0, 0x20]); // access_flags
+        bytes.extend([0, 1]); // this_class
+        bytes.extend([0, 3]); // super_class
+        bytes.extend([0, 0]); // interfaces_count
+        bytes.extend([0, 0]); // fields_count
+        bytes.extend([0, 0]); // methods_count
+        bytes.extend([0, 6]); // attributes_count
+        bytes.extend([0, 8]); // InnerClasses
+        push_u4(&mut bytes, 10);
+        bytes.extend([0, 1, 0, 1, 0, 5, 0, 7, 0, 0x08]);
+        bytes.extend([0, 9]); // NestHost
+        push_u4(&mut bytes, 2);
+        bytes.extend([0, 5]);
+        bytes.extend([0, 10]); // NestMembers
+        push_u4(&mut bytes, 6);
+        bytes.extend([0, 2, 0, 1, 0, 16]);
+        bytes.extend([0, 11]); // EnclosingMethod
+        push_u4(&mut bytes, 4);
+        bytes.extend([0, 5, 0, 13]);
+        bytes.extend([0, 12]); // PermittedSubclasses
+        push_u4(&mut bytes, 6);
+        bytes.extend([0, 2, 0, 1, 0, 16]);
+        bytes.extend([0, 18]); // Unknown
+        push_u4(&mut bytes, 3);
+        bytes.extend([1, 2, 3]);
+
+        let class_file = ClassFile::parse(&bytes).unwrap();
+
+        assert_eq!(
+            class_file.inner_classes,
+            vec![InnerClass {
+                inner_class: "Outer$Inner".to_string(),
+                outer_class: Some("Outer".to_string()),
+                inner_name: Some("Inner".to_string()),
+                access_flags: 0x0008,
+            }]
+        );
+        assert_eq!(class_file.nest_host.as_deref(), Some("Outer"));
+        assert_eq!(class_file.nest_host_name(), "Outer");
+        assert_eq!(class_file.nest_members, vec!["Outer$Inner", "Outer$Leaf"]);
+        assert_eq!(
+            class_file.enclosing_method,
+            Some(EnclosingMethod {
+                class_name: "Outer".to_string(),
+                method_name: Some("run".to_string()),
+                method_descriptor: Some("()V".to_string()),
+            })
+        );
+        assert!(class_file.is_sealed());
+        assert_eq!(
+            class_file.permitted_subclasses,
+            vec!["Outer$Inner", "Outer$Leaf"]
+        );
+    }
+
+    #[test]
+    fn parses_class_field_and_method_signature_attributes() {
+        let mut bytes = Vec::new();
+        push_u4(&mut bytes, 0xCAFEBABE);
+        push_u2(&mut bytes, 0);
+        push_u2(&mut bytes, 69);
+        push_u2(&mut bytes, 14);
+
+        bytes.extend([7, 0, 2]); // #1 Class Box
+        push_utf8(&mut bytes, "Box"); // #2
+        bytes.extend([7, 0, 4]); // #3 Class java/lang/Object
+        push_utf8(&mut bytes, "java/lang/Object"); // #4
+        push_utf8(&mut bytes, "Signature"); // #5
+        push_utf8(&mut bytes, "<T:Ljava/lang/Object;>Ljava/lang/Object;"); // #6
+        push_utf8(&mut bytes, "value"); // #7
+        push_utf8(&mut bytes, "Ljava/lang/Object;"); // #8
+        push_utf8(&mut bytes, "TT;"); // #9
+        push_utf8(&mut bytes, "get"); // #10
+        push_utf8(&mut bytes, "()Ljava/lang/Object;"); // #11
+        push_utf8(&mut bytes, "()TT;"); // #12
+        push_utf8(&mut bytes, "raw"); // #13
+
+        bytes.extend([0, 0x21]); // access_flags
+        bytes.extend([0, 1]); // this_class
+        bytes.extend([0, 3]); // super_class
+        bytes.extend([0, 0]); // interfaces_count
+        bytes.extend([0, 2]); // fields_count
+        bytes.extend([0, 0x02, 0, 7, 0, 8, 0, 1]); // private value
+        bytes.extend([0, 5]);
+        push_u4(&mut bytes, 2);
+        bytes.extend([0, 9]);
+        bytes.extend([0, 0x02, 0, 13, 0, 8, 0, 0]); // private raw, no signature
+        bytes.extend([0, 1]); // methods_count
+        bytes.extend([0, 0x01, 0, 10, 0, 11, 0, 1]); // public get
+        bytes.extend([0, 5]);
+        push_u4(&mut bytes, 2);
+        bytes.extend([0, 12]);
+        bytes.extend([0, 1]); // attributes_count
+        bytes.extend([0, 5]);
+        push_u4(&mut bytes, 2);
+        bytes.extend([0, 6]);
+
+        let class_file = ClassFile::parse(&bytes).unwrap();
+
+        let class_signature = class_file.generic_signature().unwrap().unwrap();
+        assert_eq!(class_signature.type_parameters[0].name, "T");
+        assert_eq!(class_signature.super_class.name, "java/lang/Object");
+        assert_eq!(
+            class_file.fields[0].generic_signature().unwrap(),
+            Some(signature::ReferenceTypeSignature::TypeVariable(
+                "T".to_string()
+            ))
+        );
+        assert_eq!(class_file.fields[1].generic_signature().unwrap(), None);
+        let method_signature = class_file.methods[0].generic_signature().unwrap().unwrap();
+        assert_eq!(method_signature.to_string(), "()TT;");
     }
 }
@@ -0,0 +1,473 @@
+//! Annotation attributes (JVMS 4.7.16 through 4.7.22).
+//!
+//! Element values are resolved against the constant pool while parsing, so the
+//! tree can be inspected without the class file that declared it.
+
+use super::{ConstantPool, Parser};
+use crate::{JayError, JayResult};
+
+/// Declaration and type annotations attached to a class, field, method, or `Code`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Annotations {
+    /// `RuntimeVisibleAnnotations`.
+    pub visible: Vec<Annotation>,
+    /// `RuntimeInvisibleAnnotations`.
+    pub invisible: Vec<Annotation>,
+    /// `RuntimeVisibleTypeAnnotations`.
+    pub visible_type: Vec<TypeAnnotation>,
+    /// `RuntimeInvisibleTypeAnnotations`.
+    pub invisible_type: Vec<TypeAnnotation>,
+}
+
+/// Per-parameter annotations from the `Runtime*ParameterAnnotations` attributes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParameterAnnotations {
+    pub visible: Vec<Vec<Annotation>>,
+    pub invisible: Vec<Vec<Annotation>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    /// Field descriptor of the annotation interface, such as `Ljava/lang/Deprecated;`.
+    pub type_name: String,
+    pub elements: Vec<ElementValuePair>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElementValuePair {
+    pub name: String,
+    pub value: ElementValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElementValue {
+    Byte(i8),
+    Char(u16),
+    Double(f64),
+    Float(f32),
+    Int(i32),
+    Long(i64),
+    Short(i16),
+    Boolean(bool),
+    String(String),
+    Enum {
+        /// Field descriptor of the enum class.
+        type_name: String,
+        const_name: String,
+    },
+    /// Return descriptor of a class literal, such as `Ljava/lang/String;` or `V`.
+    Class(String),
+    Annotation(Annotation),
+    Array(Vec<ElementValue>),
+}
+
+/// A type annotation with the JVMS `target_type` and `target_path` that locate
+/// the annotated type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeAnnotation {
+    pub target_type: u8,
+    pub target_info: TargetInfo,
+    pub target_path: Vec<TypePathEntry>,
+    pub annotation: Annotation,
+}
+
+/// The `target_info` union of a type annotation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TargetInfo {
+    TypeParameter {
+        index: u8,
+    },
+    Supertype {
+        /// `65535` for the superclass, otherwise an `interfaces` index.
+        index: u16,
+    },
+    TypeParameterBound {
+        type_parameter_index: u8,
+        bound_index: u8,
+    },
+    Empty,
+    FormalParameter {
+        index: u8,
+    },
+    Throws {
+        index: u16,
+    },
+    LocalVariable(Vec<LocalVariableTarget>),
+    Catch {
+        exception_table_index: u16,
+    },
+    Offset {
+        offset: u16,
+    },
+    TypeArgument {
+        offset: u16,
+        type_argument_index: u8,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalVariableTarget {
+    pub start_pc: u16,
+    pub length: u16,
+    pub index: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypePathEntry {
+    pub type_path_kind: u8,
+    pub type_argument_index: u8,
+}
+
+impl Annotations {
+    /// Finds a visible or invisible declaration annotation by its descriptor.
+    pub fn find(&self, type_name: &str) -> Option<&Annotation> {
+        self.visible
+            .iter()
+            .chain(&self.invisible)
+            .find(|annotation| annotation.type_name == type_name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.visible.is_empty()
+            && self.invisible.is_empty()
+            && self.visible_type.is_empty()
+            && self.invisible_type.is_empty()
+    }
+}
+
+impl Annotation {
+    pub fn element(&self, name: &str) -> Option<&ElementValue> {
+        self.elements
+            .iter()
+            .find(|pair| pair.name == name)
+            .map(|pair| &pair.value)
+    }
+}
+
+impl Parser<'_> {
+    /// Parses a known annotation attribute into `annotations`, returning
+    /// `false` when `attribute_name` is not one of the annotation attributes.
+    pub(super) fn parse_annotation_attribute(
+        &mut self,
+        constant_pool: &ConstantPool,
+        attribute_name: &str,
+        annotations: &mut Annotations,
+    ) -> JayResult<bool> {
+        match attribute_name {
+            "RuntimeVisibleAnnotations" => {
+                annotations.visible = self.parse_annotation_list(constant_pool)?;
+            }
+            "RuntimeInvisibleAnnotations" => {
+                annotations.invisible = self.parse_annotation_list(constant_pool)?;
+            }
+            "RuntimeVisibleTypeAnnotations" => {
+                annotations.visible_type = self.parse_type_annotations(constant_pool)?;
+            }
+            "RuntimeInvisibleTypeAnnotations" => {
+                annotations.invisible_type = self.parse_type_annotations(constant_pool)?;
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    pub(super) fn parse_parameter_annotations(
+        &mut self,
+        constant_pool: &ConstantPool,
+    ) -> JayResult<Vec<Vec<Annotation>>> {
+        let count = self.read_u1()? as usize;
+        let mut parameters = Vec::with_capacity(count);
+        for _ in 0..count {
+            parameters.push(self.parse_annotation_list(constant_pool)?);
+        }
+        Ok(parameters)
+    }
+
+    pub(super) fn parse_element_value(
+        &mut self,
+        constant_pool: &ConstantPool,
+    ) -> JayResult<ElementValue> {
+        let tag = self.read_u1()?;
+        let value = match tag {
+            b'B' => ElementValue::Byte(constant_pool.integer(self.read_u2()?)? as i8),
+            b'C' => ElementValue::Char(constant_pool.integer(self.read_u2()?)? as u16),
+            b'D' => ElementValue::Double(constant_pool.double(self.read_u2()?)?),
+            b'F' => ElementValue::Float(constant_pool.float(self.read_u2()?)?),
+            b'I' => ElementValue::Int(constant_pool.integer(self.read_u2()?)?),
+            b'J' => ElementValue::Long(constant_pool.long(self.read_u2()?)?),
+            b'S' => ElementValue::Short(constant_pool.integer(self.read_u2()?)? as i16),
+            b'Z' => ElementValue::Boolean(constant_pool.integer(self.read_u2()?)? != 0),
+            b's' => ElementValue::String(constant_pool.utf8(self.read_u2()?)?.to_string()),
+            b'e' => {
+                let type_name = constant_pool.utf8(self.read_u2()?)?.to_string();
+                let const_name = constant_pool.utf8(self.read_u2()?)?.to_string();
+                ElementValue::Enum {
+                    type_name,
+                    const_name,
+                }
+            }
+            b'c' => ElementValue::Class(constant_pool.utf8(self.read_u2()?)?.to_string()),
+            b'@' => ElementValue::Annotation(self.parse_annotation(constant_pool)?),
+            b'[' => {
+                let count = self.read_u2()? as usize;
+                let mut values = Vec::with_capacity(count);
+                for _ in 0..count {
+                    values.push(self.parse_element_value(constant_pool)?);
+                }
+                ElementValue::Array(values)
+            }
+            other => {
+                return Err(JayError::class_format(format!(
+                    "invalid annotation element value tag {:?}",
+                    other as char
+                )));
+            }
+        };
+        Ok(value)
+    }
+
+    fn parse_annotation_list(
+        &mut self,
+        constant_pool: &ConstantPool,
+    ) -> JayResult<Vec<Annotation>> {
+        let count = self.read_u2()? as usize;
+        let mut annotations = Vec::with_capacity(count);
+        for _ in 0..count {
+            annotations.push(self.parse_annotation(constant_pool)?);
+        }
+        Ok(annotations)
+    }
+
+    fn parse_annotation(&mut self, constant_pool: &ConstantPool) -> JayResult<Annotation> {
+        let type_name = constant_pool.utf8(self.read_u2()?)?.to_string();
+        let count = self.read_u2()? as usize;
+        let mut elements = Vec::with_capacity(count);
+        for _ in 0..count {
+            let name = constant_pool.utf8(self.read_u2()?)?.to_string();
+            let value = self.parse_element_value(constant_pool)?;
+            elements.push(ElementValuePair { name, value });
+        }
+        Ok(Annotation {
+            type_name,
+            elements,
+        })
+    }
+
+    fn parse_type_annotations(
+        &mut self,
+        constant_pool: &ConstantPool,
+    ) -> JayResult<Vec<TypeAnnotation>> {
+        let count = self.read_u2()? as usize;
+        let mut annotations = Vec::with_capacity(count);
+        for _ in 0..count {
+            let target_type = self.read_u1()?;
+            let target_info = self.parse_target_info(target_type)?;
+            let path_length = self.read_u1()? as usize;
+            let mut target_path = Vec::with_capacity(path_length);
+            for _ in 0..path_length {
+                target_path.push(TypePathEntry {
+                    type_path_kind: self.read_u1()?,
+                    type_argument_index: self.read_u1()?,
+                });
+            }
+            let annotation = self.parse_annotation(constant_pool)?;
+            annotations.push(TypeAnnotation {
+                target_type,
+                target_info,
+                target_path,
+                annotation,
+            });
+        }
+        Ok(annotations)
+    }
+
+    fn parse_target_info(&mut self, target_type: u8) -> JayResult<TargetInfo> {
+        let target_info = match target_type {
+            0x00 | 0x01 => TargetInfo::TypeParameter {
+                index: self.read_u1()?,
+            },
+            0x10 => TargetInfo::Supertype {
+                index: self.read_u2()?,
+            },
+            0x11 | 0x12 => TargetInfo::TypeParameterBound {
+                type_parameter_index: self.read_u1()?,
+                bound_index: self.read_u1()?,
+            },
+            0x13..=0x15 => TargetInfo::Empty,
+            0x16 => TargetInfo::FormalParameter {
+                index: self.read_u1()?,
+            },
+            0x17 => TargetInfo::Throws {
+                index: self.read_u2()?,
+            },
+            0x40 | 0x41 => {
+                let count = self.read_u2()? as usize;
+                let mut table = Vec::with_capacity(count);
+                for _ in 0..count {
+                    table.push(LocalVariableTarget {
+                        start_pc: self.read_u2()?,
+                        length: self.read_u2()?,
+                        index: self.read_u2()?,
+                    });
+                }
+                TargetInfo::LocalVariable(table)
+            }
+            0x42 => TargetInfo::Catch {
+                exception_table_index: self.read_u2()?,
+            },
+            0x43..=0x46 => TargetInfo::Offset {
+                offset: self.read_u2()?,
+            },
+            0x47..=0x4B => TargetInfo::TypeArgument {
+                offset: self.read_u2()?,
+                type_argument_index: self.read_u1()?,
+            },
+            other => {
+                return Err(JayError::class_format(format!(
+                    "invalid type annotation target type 0x{other:02x}"
+                )));
+            }
+        };
+        Ok(target_info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classfile::ClassFile;
+    use crate::classfile::test_bytes::{push_u2, push_u4, push_utf8};
+
+    fn push_attribute(bytes: &mut Vec<u8>, name_index: u16, body: &[u8]) {
+        push_u2(bytes, name_index);
+        push_u4(bytes, body.len() as u32);
+        bytes.extend(body);
+    }
+
+    /// Builds `Tagged` with one field, one abstract method, and the given
+    /// class-level attribute body for `RuntimeVisibleAnnotations`.
+    fn annotated_class(class_annotations: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        push_u4(&mut bytes, 0xCAFEBABE);
+        push_u2(&mut bytes, 0);
+        push_u2(&mut bytes, 69);
+        push_u2(&mut bytes, 25);
+
+        bytes.extend([7, 0, 2]); // #1 Class Tagged
+        push_utf8(&mut bytes, "Tagged"); // #2
+        bytes.extend([7, 0, 4]); // #3 Class java/lang/Object
+        push_utf8(&mut bytes, "java/lang/Object"); // #4
+        push_utf8(&mut bytes, "RuntimeVisibleAnnotations"); // #5
+        push_utf8(&mut bytes, "RuntimeInvisibleAnnotations"); // #6
+        push_utf8(&mut bytes, "RuntimeVisibleParameterAnnotations"); // #7
+        push_utf8(&mut bytes, "AnnotationDefault"); // #8
+        push_utf8(&mut bytes, "RuntimeVisibleTypeAnnotations"); // #9
+        push_utf8(&mut bytes, "Lorg/junit/Test;"); // #10
+        push_utf8(&mut bytes, "timeout"); // #11
+        bytes.push(5); // #12 Long
+        bytes.extend(250_i64.to_be_bytes());
+        push_utf8(&mut bytes, "Ljava/lang/Deprecated;"); // #14
+        push_utf8(&mut bytes, "since"); // #15
+        push_utf8(&mut bytes, "17"); // #16
+        push_utf8(&mut bytes, "Lcom/example/Level;"); // #17
+        push_utf8(&mut bytes, "HIGH"); // #18
+        push_utf8(&mut bytes, "Ljava/lang/String;"); // #19
+        bytes.push(3); // #20 Integer
+        bytes.extend(1_i32.to_be_bytes());
+        push_utf8(&mut bytes, "value"); // #21
+        push_utf8(&mut bytes, "()I"); // #22
+        push_utf8(&mut bytes, "Lcom/example/NonNull;"); // #23
+        push_utf8(&mut bytes, "flags"); // #24
+
+        bytes.extend([0, 0x21]); // access_flags
+        bytes.extend([0, 1]); // this_class
+        bytes.extend([0, 3]); // super_class
+        bytes.extend([0, 0]); // interfaces_count
+
+        bytes.extend([0, 1]); // fields_count
+        bytes.extend([0, 0x02, 0, 24, 0, 19, 0, 1]); // private String flags
+        // @Deprecated(since = "17")
+        push_attribute(&mut bytes, 6, &[0, 1, 0, 14, 0, 1, 0, 15, b's', 0, 16]);
+
+        bytes.extend([0, 1]); // methods_count
+        bytes.extend([0x04, 0x01, 0, 21, 0, 22, 0, 3]); // public abstract value()I
+        push_attribute(&mut bytes, 8, &[b'I', 0, 20]); // default 1
+        // One parameter with no annotations.
+        push_attribute(&mut bytes, 7, &[1, 0, 0]);
+        // @NonNull on the return type.
+        push_attribute(&mut bytes, 9, &[0, 1, 0x14, 0, 0, 23, 0, 0]);
+
+        bytes.extend([0, 1]); // attributes_count
+        push_attribute(&mut bytes, 5, class_annotations);
+        bytes
+    }
+
+    #[test]
+    fn parses_annotation_element_value_trees() {
+        let mut class_annotations = vec![0, 1, 0, 10, 0, 3];
+        class_annotations.extend([0, 11, b'J', 0, 12]); // timeout = 250L
+        class_annotations.extend([0, 18, b'e', 0, 17, 0, 18]); // HIGH = Level.HIGH
+        class_annotations.extend([0, 21, b'[', 0, 2]); // value = {
+        class_annotations.extend([b'c', 0, 19]); // String.class,
+        class_annotations.extend([b'@', 0, 14, 0, 0]); // @Deprecated }
+
+        let class_file = ClassFile::parse(&annotated_class(&class_annotations)).unwrap();
+
+        let test = class_file.annotations.find("Lorg/junit/Test;").unwrap();
+        assert_eq!(test.element("timeout"), Some(&ElementValue::Long(250)));
+        assert_eq!(
+            test.element("HIGH"),
+            Some(&ElementValue::Enum {
+                type_name: "Lcom/example/Level;".to_string(),
+                const_name: "HIGH".to_string(),
+            })
+        );
+        assert_eq!(
+            test.element("value"),
+            Some(&ElementValue::Array(vec![
+                ElementValue::Class("Ljava/lang/String;".to_string()),
+                ElementValue::Annotation(Annotation {
+                    type_name: "Ljava/lang/Deprecated;".to_string(),
+                    elements: Vec::new(),
+                }),
+            ]))
+        );
+
+        let field = &class_file.fields[0];
+        assert!(field.annotations.visible.is_empty());
+        let deprecated = field.annotations.find("Ljava/lang/Deprecated;").unwrap();
+        assert_eq!(
+            deprecated.element("since"),
+            Some(&ElementValue::String("17".to_string()))
+        );
+
+        let method = &class_file.methods[0];
+        assert_eq!(method.annotation_default, Some(ElementValue::Int(1)));
+        assert_eq!(method.parameter_annotations.visible, vec![Vec::new()]);
+        assert_eq!(
+            method.annotations.visible_type,
+            vec![TypeAnnotation {
+                target_type: 0x14,
+                target_info: TargetInfo::Empty,
+                target_path: Vec::new(),
+                annotation: Annotation {
+                    type_name: "Lcom/example/NonNull;".to_string(),
+                    elements: Vec::new(),
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_element_value_tags() {
+        let class_annotations = [0, 1, 0, 10, 0, 1, 0, 11, b'x', 0, 12];
+
+        let error = ClassFile::parse(&annotated_class(&class_annotations)).unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .contains("invalid annotation element value tag 'x'")
+        );
+    }
+}
@@ -0,0 +1,1021 @@
+//! Assembles class files from a Jasmin-like text format.
+//!
+//! ```text
+//! .version 65 0
+//! .class public super Hello
+//! .super java/lang/Object
+//!
+//! .field private static final GREETING Ljava/lang/String; = "hello"
+//!
+//! .method public static main([Ljava/lang/String;)V
+//!     getstatic java/lang/System/out Ljava/io/PrintStream;
+//!     ldc "hello"
+//!     invokevirtual java/io/PrintStream/println(Ljava/lang/String;)V
+//!     return
+//! .end method
+//! ```
+//!
+//! Class directives are `.version`, `.class`, `.super` (`none` for no
+//! superclass), `.implements`, `.signature`, `.nesthost`, `.nestmember`,
+//! `.permittedsubclass`, and `.attribute <name> <hex bytes>`. A `.field` line
+//! may end with `signature "..."` and `= <constant>`. Method bodies accept
+//! `.limit stack|locals`, `.catch <class|all> from <label> to <label> using
+//! <label>`, `.signature`, `.attribute`, `.bytes <hex>` for raw code, labels
+//! written as `Name:`, and one instruction per line using JVMS mnemonics:
+//!
+//! - fields and methods are `owner/name descriptor` and `owner/name(args)ret`;
+//!   `invokestatic interface ...` references an interface method
+//! - `invokeinterface <method> <count>` and `invokedynamic name(args)ret
+//!   <bootstrap handle> <constant>...` accept a trailing `padding <n>`
+//! - `tableswitch <low>` and `lookupswitch` list one `[key :] label` per
+//!   following line and end with `default : label`
+//! - constants are `123`, `123L`, `1.5F`, `1.5D`, `"text"`, `class <name>`,
+//!   `methodtype <descriptor>`, and `methodhandle <kind> <member>`
+//! - `wide` before a local variable instruction forces the wide form, and
+//!   `ldc` becomes `ldc_w` when its constant lands past pool index 255
+//!
+//! Text after a `;` that starts a token is a comment. Without `.limit`
+//! directives `max_stack` and `max_locals` are computed.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use super::builder::{ClassBuilder, Constant, Handle, Label, MethodBuilder};
+use super::disassembler::{ARRAY_TYPES, CLASS_FLAGS, FIELD_FLAGS, HANDLE_KINDS, METHOD_FLAGS};
+use super::opcode::{self, Operands};
+use super::{Attribute, ClassFile, ExceptionHandler};
+use crate::{JayError, JayResult};
+
+/// Assembles `source` into a class file model.
+pub fn assemble(source: &str) -> JayResult<ClassFile> {
+    let lines = tokenize(source)?;
+    let mut version = None;
+    let mut class: Option<ClassBuilder> = None;
+    let mut extras = Extras::default();
+
+    let mut index = 0;
+    while index < lines.len() {
+        let line = &lines[index];
+        index += 1;
+        let mut cursor = line.cursor();
+        let directive = cursor.word("directive")?;
+        if directive == ".version" {
+            let major = cursor.number("major version")?;
+            let minor = cursor.number("minor version")?;
+            cursor.finish()?;
+            version = Some((major, minor));
+            if let Some(class) = &mut class {
+                class.version(major, minor);
+            }
+            continue;
+        }
+        if directive == ".class" {
+            if class.is_some() {
+                return Err(cursor.error("duplicate .class directive"));
+            }
+            let access_flags = cursor.flags(CLASS_FLAGS)?;
+            let mut builder = ClassBuilder::new(cursor.word("class name")?);
+            cursor.finish()?;
+            builder.access_flags(access_flags);
+            if let Some((major, minor)) = version {
+                builder.version(major, minor);
+            }
+            class = Some(builder);
+            continue;
+        }
+
+        let class = class
+            .as_mut()
+            .ok_or_else(|| cursor.error(format!("{directive} before .class")))?;
+        match directive {
+            ".super" => {
+                let super_class = cursor.word("superclass")?;
+                class.super_class((super_class != "none").then_some(super_class));
+            }
+            ".implements" => {
+                class.interface(cursor.word("interface name")?);
+            }
+            ".signature" => extras.signature = Some(cursor.quoted("signature")?.to_string()),
+            ".nesthost" => extras.nest_host = Some(cursor.word("nest host")?.to_string()),
+            ".nestmember" => extras
+                .nest_members
+                .push(cursor.word("nest member")?.to_string()),
+            ".permittedsubclass" => extras
+                .permitted_subclasses
+                .push(cursor.word("permitted subclass")?.to_string()),
+            ".attribute" => extras.attributes.push(cursor.attribute()?),
+            ".field" => {
+                let access_flags = cursor.flags(FIELD_FLAGS)?;
+                let name = cursor.word("field name")?;
+                let descriptor = cursor.word("field descriptor")?;
+                if cursor.peek_word() == Some("signature") {
+                    cursor.word("signature")?;
+                    let signature = cursor.quoted("field signature")?.to_string();
+                    extras
+                        .field_signatures
+                        .push((extras.field_count, signature));
+                }
+                if cursor.peek_word() == Some("=") {
+                    cursor.word("=")?;
+                    let value = cursor.constant()?;
+                    class.constant_field(access_flags, name, descriptor, value);
+                } else {
+                    class.field(access_flags, name, descriptor);
+                }
+                extras.field_count += 1;
+            }
+            ".method" => {
+                let body_start = index;
+                while index < lines.len() && !lines[index].is_end_method() {
+                    index += 1;
+                }
+                if index == lines.len() {
+                    return Err(cursor.error(".method without .end method"));
+                }
+                assemble_method(class, &mut cursor, &lines[body_start..index], &mut extras)?;
+                index += 1;
+                continue;
+            }
+            ".end" => return Err(cursor.error(".end without .method")),
+            other => return Err(cursor.error(format!("unknown directive {other}"))),
+        }
+        cursor.finish()?;
+    }
+
+    let class = class.ok_or_else(|| JayError::class_format("missing .class directive"))?;
+    let mut class_file = class.build()?;
+    extras.apply(&mut class_file)?;
+    Ok(class_file)
+}
+
+/// Model details `ClassBuilder` does not set, applied once the class is built.
+#[derive(Default)]
+struct Extras {
+    signature: Option<String>,
+    nest_host: Option<String>,
+    nest_members: Vec<String>,
+    permitted_subclasses: Vec<String>,
+    attributes: Vec<Attribute>,
+    field_count: usize,
+    field_signatures: Vec<(usize, String)>,
+    methods: Vec<MethodExtras>,
+}
+
+#[derive(Default)]
+struct MethodExtras {
+    signature: Option<String>,
+    attributes: Vec<Attribute>,
+    /// `.catch` entries given as bytecode offsets rather than labels.
+    handlers: Vec<(u16, u16, u16, Option<String>)>,
+}
+
+impl Extras {
+    fn apply(self, class_file: &mut ClassFile) -> JayResult<()> {
+        class_file.signature = self.signature;
+        class_file.nest_host = self.nest_host;
+        class_file.nest_members = self.nest_members;
+        class_file.permitted_subclasses = self.permitted_subclasses;
+        class_file.attributes = self.attributes;
+        for (index, signature) in self.field_signatures {
+            class_file.fields[index].signature = Some(signature);
+        }
+        for (method, extras) in class_file.methods.iter_mut().zip(self.methods) {
+            method.signature = extras.signature;
+            method.attributes = extras.attributes;
+            for (start_pc, end_pc, handler_pc, catch_type) in extras.handlers {
+                let catch_type = match catch_type {
+                    Some(class_name) => class_file.constant_pool.intern_class(&class_name)?,
+                    None => 0,
+                };
+                if let Some(code) = &mut method.code {
+                    code.exception_table.push(ExceptionHandler {
+                        start_pc,
+                        end_pc,
+                        handler_pc,
+                        catch_type,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn assemble_method(
+    class: &mut ClassBuilder,
+    header: &mut Cursor<'_>,
+    body: &[Line],
+    extras: &mut Extras,
+) -> JayResult<()> {
+    let access_flags = header.flags(METHOD_FLAGS)?;
+    let member = header.word("method name and descriptor")?;
+    let (name, descriptor) = member
+        .find('(')
+        .map(|split| member.split_at(split))
+        .ok_or_else(|| header.error(format!("expected name(descriptor), found {member}")))?;
+    header.finish()?;
+
+    let mut method_extras = MethodExtras::default();
+    let has_code = body.iter().any(|line| {
+        !matches!(
+            line.tokens.first(),
+            Some(Token::Word(word)) if word == ".signature" || word == ".attribute"
+        )
+    });
+    if !has_code {
+        for line in body {
+            let mut cursor = line.cursor();
+            method_attribute(&mut cursor, &mut method_extras)?;
+        }
+        class.abstract_method(access_flags, name, descriptor);
+        extras.methods.push(method_extras);
+        return Ok(());
+    }
+
+    let mut method = class.method(access_flags, name, descriptor);
+    let mut labels = Labels::default();
+    let mut index = 0;
+    while index < body.len() {
+        let mut cursor = body[index].cursor();
+        index += 1;
+        let first = cursor.word("instruction")?;
+        if let Some(name) = first.strip_suffix(':') {
+            let label = labels.define(&mut method, &cursor, name)?;
+            method.bind(label);
+            cursor.finish()?;
+            continue;
+        }
+        match first {
+            ".signature" | ".attribute" => {
+                let mut cursor = body[index - 1].cursor();
+                method_attribute(&mut cursor, &mut method_extras)?;
+            }
+            ".limit" => {
+                match cursor.word("stack or locals")? {
+                    "stack" => method.max_stack(cursor.number("max stack")?),
+                    "locals" => method.max_locals(cursor.number("max locals")?),
+                    other => return Err(cursor.error(format!("unknown limit {other}"))),
+                };
+                cursor.finish()?;
+            }
+            ".catch" => {
+                let catch_type = match cursor.word("catch type")? {
+                    "all" => None,
+                    class_name => Some(class_name),
+                };
+                let mut range = [""; 3];
+                for (slot, keyword) in range.iter_mut().zip(["from", "to", "using"]) {
+                    cursor.keyword(keyword)?;
+                    *slot = cursor.word("label")?;
+                }
+                cursor.finish()?;
+                if range.iter().all(|pc| pc.parse::<u16>().is_ok()) {
+                    let [start, end, handler] = range.map(|pc| pc.parse().expect("u16"));
+                    let catch_type = catch_type.map(str::to_string);
+                    method_extras
+                        .handlers
+                        .push((start, end, handler, catch_type));
+                } else {
+                    let [start, end, handler] =
+                        range.map(|name| labels.get(&mut method, name, cursor.line));
+                    method.try_catch(start, end, handler, catch_type);
+                }
+            }
+            ".bytes" => {
+                let bytes = cursor.hex_bytes()?;
+                method.raw(&bytes);
+            }
+            mnemonic => {
+                instruction(
+                    &mut method,
+                    &mut labels,
+                    mnemonic,
+                    &mut cursor,
+                    body,
+                    &mut index,
+                )?;
+                cursor.finish()?;
+            }
+        }
+    }
+    if let Some((name, line)) = labels.undefined() {
+        return Err(JayError::class_format(format!(
+            "line {line}: undefined label {name}"
+        )));
+    }
+    method.finish();
+    extras.methods.push(method_extras);
+    Ok(())
+}
+
+fn method_attribute(cursor: &mut Cursor<'_>, extras: &mut MethodExtras) -> JayResult<()> {
+    match cursor.word("directive")? {
+        ".signature" => extras.signature = Some(cursor.quoted("signature")?.to_string()),
+        _ => extras.attributes.push(cursor.attribute()?),
+    }
+    cursor.finish()
+}
+
+fn instruction(
+    method: &mut MethodBuilder<'_>,
+    labels: &mut Labels,
+    mnemonic: &str,
+    cursor: &mut Cursor<'_>,
+    body: &[Line],
+    index: &mut usize,
+) -> JayResult<()> {
+    let (wide, mnemonic) = match mnemonic {
+        "wide" => (true, cursor.word("instruction")?),
+        mnemonic => (false, mnemonic),
+    };
+    let info = opcode::by_mnemonic(mnemonic)
+        .filter(|info| info.operands != Operands::Wide)
+        .ok_or_else(|| cursor.error(format!("unknown instruction {mnemonic}")))?;
+    if wide && !matches!(info.operands, Operands::Local | Operands::Iinc) {
+        return Err(cursor.error(format!("wide cannot modify {mnemonic}")));
+    }
+    let op = info.opcode;
+
+    match info.operands {
+        Operands::None => {
+            method.op(op);
+        }
+        Operands::Byte => {
+            let value: i8 = cursor.number("byte value")?;
+            method.raw(&[op, value as u8]);
+        }
+        Operands::Short => {
+            let [high, low] = cursor.number::<i16>("short value")?.to_be_bytes();
+            method.raw(&[op, high, low]);
+        }
+        Operands::ArrayType => {
+            let name = cursor.word("array type")?;
+            let array_type = match ARRAY_TYPES
+                .iter()
+                .position(|array_type| *array_type == name)
+            {
+                Some(position) => position as u8 + 4,
+                None => name
+                    .parse()
+                    .map_err(|_| cursor.error(format!("unknown array type {name}")))?,
+            };
+            method.newarray(array_type);
+        }
+        Operands::Local => {
+            let local: u16 = cursor.number("local variable index")?;
+            match u8::try_from(local) {
+                Ok(local) if !wide => method.raw(&[op, local]),
+                _ => {
+                    let [high, low] = local.to_be_bytes();
+                    method.raw(&[opcode::WIDE, op, high, low])
+                }
+            };
+        }
+        Operands::Iinc => {
+            let local: u16 = cursor.number("local variable index")?;
+            let delta: i16 = cursor.number("increment")?;
+            match (u8::try_from(local), i8::try_from(delta)) {
+                (Ok(local), Ok(delta)) if !wide => method.raw(&[op, local, delta as u8]),
+                _ => {
+                    let [local_high, local_low] = local.to_be_bytes();
+                    let [delta_high, delta_low] = delta.to_be_bytes();
+                    method.raw(&[
+                        opcode::WIDE,
+                        op,
+                        local_high,
+                        local_low,
+                        delta_high,
+                        delta_low,
+                    ])
+                }
+            };
+        }
+        Operands::ConstantU1 => {
+            let constant = cursor.constant()?;
+            let index = cursor.intern(method.constant_pool().intern_constant(&constant))?;
+            match u8::try_from(index) {
+                Ok(index) => method.raw(&[op, index]),
+                Err(_) => {
+                    let [high, low] = index.to_be_bytes();
+                    method.raw(&[opcode::LDC_W, high, low])
+                }
+            };
+        }
+        Operands::Constant => {
+            let index = match op {
+                opcode::LDC_W | opcode::LDC2_W => {
+                    let constant = cursor.constant()?;
+                    method.constant_pool().intern_constant(&constant)
+                }
+                opcode::GETSTATIC..=opcode::PUTFIELD => {
+                    let (owner, name) = cursor.field()?;
+                    let descriptor = cursor.word("field descriptor")?;
+                    method
+                        .constant_pool()
+                        .intern_field_ref(owner, name, descriptor)
+                }
+                opcode::INVOKEVIRTUAL..=opcode::INVOKESTATIC => {
+                    let interface = cursor.peek_word() == Some("interface");
+                    if interface {
+                        cursor.word("interface")?;
+                    }
+                    let (owner, name, descriptor) = cursor.method()?;
+                    let constant_pool = method.constant_pool();
+                    if interface {
+                        constant_pool.intern_interface_method_ref(owner, name, descriptor)
+                    } else {
+                        constant_pool.intern_method_ref(owner, name, descriptor)
+                    }
+                }
+                _ => {
+                    let class_name = cursor.word("class name")?;
+                    method.constant_pool().intern_class(class_name)
+                }
+            };
+            let [high, low] = cursor.intern(index)?.to_be_bytes();
+            method.raw(&[op, high, low]);
+        }
+        Operands::Branch | Operands::WideBranch => {
+            let label = labels.get(method, cursor.word("label")?, cursor.line);
+            method.jump(op, label);
+        }
+        Operands::InvokeInterface => {
+            let (owner, name, descriptor) = cursor.method()?;
+            let index = method
+                .constant_pool()
+                .intern_interface_method_ref(owner, name, descriptor);
+            let [high, low] = cursor.intern(index)?.to_be_bytes();
+            let count = cursor.number("argument count")?;
+            let padding = cursor.padding()?;
+            method.raw(&[op, high, low, count, padding]);
+        }
+        Operands::InvokeDynamic => {
+            let member = cursor.word("call site name and descriptor")?;
+            let (name, descriptor) = member
+                .find('(')
+                .map(|split| member.split_at(split))
+                .ok_or_else(|| {
+                    cursor.error(format!("expected name(descriptor), found {member}"))
+                })?;
+            let bootstrap = cursor.handle()?;
+            let mut arguments = Vec::new();
+            while !cursor.at_end() && cursor.peek_word() != Some("padding") {
+                arguments.push(cursor.constant()?);
+            }
+            let padding: u16 = cursor.padding()?;
+            let [high, low] = method
+                .call_site(&bootstrap, &arguments, name, descriptor)
+                .to_be_bytes();
+            let [padding_high, padding_low] = padding.to_be_bytes();
+            method.raw(&[op, high, low, padding_high, padding_low]);
+        }
+        Operands::MultiANewArray => {
+            let class_name = cursor.word("array class")?;
+            let dimensions = cursor.number("dimensions")?;
+            method.multianewarray(class_name, dimensions);
+        }
+        Operands::TableSwitch => {
+            let low = cursor.number("low key")?;
+            let mut targets = Vec::new();
+            let default = loop {
+                let mut case = switch_case(body, index, cursor)?;
+                if case.peek_word() == Some("default") {
+                    break switch_default(&mut case, labels, method)?;
+                }
+                targets.push(labels.get(method, case.word("label")?, case.line));
+                case.finish()?;
+            };
+            method.tableswitch(low, default, &targets);
+        }
+        Operands::LookupSwitch => {
+            let mut pairs = Vec::new();
+            let default = loop {
+                let mut case = switch_case(body, index, cursor)?;
+                if case.peek_word() == Some("default") {
+                    break switch_default(&mut case, labels, method)?;
+                }
+                let key = case.word("key")?;
+                let key = match key.strip_suffix(':') {
+                    Some(key) => key,
+                    None => {
+                        case.keyword(":")?;
+                        key
+                    }
+                };
+                let key = key
+                    .parse()
+                    .map_err(|_| case.error(format!("invalid key {key}")))?;
+                pairs.push((key, labels.get(method, case.word("label")?, case.line)));
+                case.finish()?;
+            };
+            method.lookupswitch(default, &pairs);
+        }
+        Operands::Wide => unreachable!("wide is filtered out above"),
+    }
+    Ok(())
+}
+
+/// Returns a cursor over the next line of a switch body.
+fn switch_case<'a>(
+    body: &'a [Line],
+    index: &mut usize,
+    switch: &Cursor<'_>,
+) -> JayResult<Cursor<'a>> {
+    let line = body
+        .get(*index)
+        .ok_or_else(|| switch.error("switch without default"))?;
+    *index += 1;
+    Ok(line.cursor())
+}
+
+fn switch_default(
+    case: &mut Cursor<'_>,
+    labels: &mut Labels,
+    method: &mut MethodBuilder<'_>,
+) -> JayResult<Label> {
+    let default = case.word("default")?;
+    if default != "default:" {
+        case.keyword(":")?;
+    }
+    let label = labels.get(method, case.word("label")?, case.line);
+    case.finish()?;
+    Ok(label)
+}
+
+/// Label names of one method body, with the line that first used each.
+#[derive(Default)]
+struct Labels {
+    labels: HashMap<String, (Label, usize, bool)>,
+}
+
+impl Labels {
+    fn get(&mut self, method: &mut MethodBuilder<'_>, name: &str, line: usize) -> Label {
+        if let Some((label, _, _)) = self.labels.get(name) {
+            return *label;
+        }
+        let label = method.new_label();
+        self.labels.insert(name.to_string(), (label, line, false));
+        label
+    }
+
+    fn define(
+        &mut self,
+        method: &mut MethodBuilder<'_>,
+        cursor: &Cursor<'_>,
+        name: &str,
+    ) -> JayResult<Label> {
+        let label = self.get(method, name, cursor.line);
+        let entry = self.labels.get_mut(name).expect("label just created");
+        if entry.2 {
+            return Err(cursor.error(format!("label {name} is defined twice")));
+        }
+        entry.2 = true;
+        Ok(label)
+    }
+
+    fn undefined(&self) -> Option<(&str, usize)> {
+        self.labels
+            .iter()
+            .filter(|(_, (_, _, defined))| !defined)
+            .map(|(name, (_, line, _))| (name.as_str(), *line))
+            .min_by_key(|(_, line)| *line)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+}
+
+struct Line {
+    number: usize,
+    tokens: Vec<Token>,
+}
+
+impl Line {
+    fn cursor(&self) -> Cursor<'_> {
+        Cursor {
+            line: self.number,
+            tokens: &self.tokens,
+            position: 0,
+        }
+    }
+
+    fn is_end_method(&self) -> bool {
+        matches!(
+            self.tokens.as_slice(),
+            [Token::Word(end), Token::Word(method)] if end == ".end" && method == "method"
+        )
+    }
+}
+
+struct Cursor<'a> {
+    line: usize,
+    tokens: &'a [Token],
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn error(&self, message: impl std::fmt::Display) -> JayError {
+        JayError::class_format(format!("line {}: {message}", self.line))
+    }
+
+    fn at_end(&self) -> bool {
+        self.position == self.tokens.len()
+    }
+
+    fn peek_word(&self) -> Option<&'a str> {
+        match self.tokens.get(self.position) {
+            Some(Token::Word(word)) => Some(word),
+            _ => None,
+        }
+    }
+
+    fn word(&mut self, expected: &str) -> JayResult<&'a str> {
+        match self.tokens.get(self.position) {
+            Some(Token::Word(word)) => {
+                self.position += 1;
+                Ok(word)
+            }
+            Some(Token::Quoted(_)) => {
+                Err(self.error(format!("expected {expected}, found a string")))
+            }
+            None => Err(self.error(format!("expected {expected}"))),
+        }
+    }
+
+    fn quoted(&mut self, expected: &str) -> JayResult<&'a str> {
+        match self.tokens.get(self.position) {
+            Some(Token::Quoted(value)) => {
+                self.position += 1;
+                Ok(value)
+            }
+            _ => Err(self.error(format!("expected quoted {expected}"))),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str) -> JayResult<()> {
+        match self.word(keyword)? {
+            word if word == keyword => Ok(()),
+            word => Err(self.error(format!("expected {keyword}, found {word}"))),
+        }
+    }
+
+    fn number<T: FromStr>(&mut self, expected: &str) -> JayResult<T> {
+        let word = self.word(expected)?;
+        word.parse()
+            .map_err(|_| self.error(format!("invalid {expected} {word}")))
+    }
+
+    fn finish(&self) -> JayResult<()> {
+        match self.tokens.get(self.position) {
+            None => Ok(()),
+            Some(Token::Word(word)) => Err(self.error(format!("unexpected {word}"))),
+            Some(Token::Quoted(value)) => Err(self.error(format!("unexpected \"{value}\""))),
+        }
+    }
+
+    /// Consumes access flag keywords and `0x` literals.
+    fn flags(&mut self, names: &[(u16, &str)]) -> JayResult<u16> {
+        let mut access_flags = 0;
+        // The last word on the line is always a name, never a flag.
+        while self.position + 1 < self.tokens.len() {
+            let Some(word) = self.peek_word() else { break };
+            if let Some((flag, _)) = names.iter().find(|(_, name)| *name == word) {
+                access_flags |= flag;
+            } else if let Some(hex) = word.strip_prefix("0x") {
+                access_flags |= u16::from_str_radix(hex, 16)
+                    .map_err(|_| self.error(format!("invalid access flags {word}")))?;
+            } else {
+                break;
+            }
+            self.position += 1;
+        }
+        Ok(access_flags)
+    }
+
+    fn padding<T: FromStr + Default>(&mut self) -> JayResult<T> {
+        if self.peek_word() != Some("padding") {
+            return Ok(T::default());
+        }
+        self.word("padding")?;
+        self.number("padding")
+    }
+
+    fn intern(&self, index: JayResult<u16>) -> JayResult<u16> {
+        index.map_err(|error| self.error(error))
+    }
+
+    /// Reads `owner/name`.
+    fn field(&mut self) -> JayResult<(&'a str, &'a str)> {
+        let member = self.word("field reference")?;
+        member
+            .rsplit_once('/')
+            .ok_or_else(|| self.error(format!("expected owner/name, found {member}")))
+    }
+
+    /// Reads `owner/name(args)ret`.
+    fn method(&mut self) -> JayResult<(&'a str, &'a str, &'a str)> {
+        let member = self.word("method reference")?;
+        member
+            .find('(')
+            .map(|split| member.split_at(split))
+            .and_then(|(path, descriptor)| {
+                let (owner, name) = path.rsplit_once('/')?;
+                Some((owner, name, descriptor))
+            })
+            .ok_or_else(|| self.error(format!("expected owner/name(descriptor), found {member}")))
+    }
+
+    fn handle(&mut self) -> JayResult<Handle> {
+        let kind_name = self.word("method handle kind")?;
+        let kind = HANDLE_KINDS
+            .iter()
+            .position(|kind| *kind == kind_name)
+            .map(|position| position as u8 + 1)
+            .ok_or_else(|| self.error(format!("unknown method handle kind {kind_name}")))?;
+        if kind <= 4 {
+            let (owner, name) = self.field()?;
+            let descriptor = self.word("field descriptor")?;
+            return Ok(Handle {
+                kind,
+                owner: owner.to_string(),
+                name: name.to_string(),
+                descriptor: descriptor.to_string(),
+                interface: false,
+            });
+        }
+        let interface = kind == 9 || self.peek_word() == Some("interface");
+        if kind != 9 && interface {
+            self.word("interface")?;
+        }
+        let (owner, name, descriptor) = self.method()?;
+        Ok(Handle {
+            kind,
+            owner: owner.to_string(),
+            name: name.to_string(),
+            descriptor: descriptor.to_string(),
+            interface,
+        })
+    }
+
+    fn constant(&mut self) -> JayResult<Constant> {
+        if let Ok(value) = self.quoted("string") {
+            return Ok(Constant::String(value.to_string()));
+        }
+        let word = self.word("constant")?;
+        let invalid = || self.error(format!("invalid constant {word}"));
+        Ok(match word {
+            "class" => Constant::Class(self.word("class name")?.to_string()),
+            "methodtype" => Constant::MethodType(self.word("method descriptor")?.to_string()),
+            "methodhandle" => Constant::MethodHandle(self.handle()?),
+            _ if word.ends_with('L') => {
+                Constant::Long(word[..word.len() - 1].parse().map_err(|_| invalid())?)
+            }
+            _ if word.ends_with('F') => {
+                Constant::Float(word[..word.len() - 1].parse().map_err(|_| invalid())?)
+            }
+            _ if word.ends_with('D') => {
+                Constant::Double(word[..word.len() - 1].parse().map_err(|_| invalid())?)
+            }
+            _ if word.contains(['.', 'e', 'E']) => {
+                Constant::Double(word.parse().map_err(|_| invalid())?)
+            }
+            _ => Constant::Int(word.parse().map_err(|_| invalid())?),
+        })
+    }
+
+    /// Reads `<name> <hex bytes>`.
+    fn attribute(&mut self) -> JayResult<Attribute> {
+        let name = self.word("attribute name")?.to_string();
+        let info = self.hex_bytes()?;
+        Ok(Attribute { name, info })
+    }
+
+    /// Reads the remaining words as hexadecimal bytes.
+    fn hex_bytes(&mut self) -> JayResult<Vec<u8>> {
+        let mut bytes = Vec::new();
+        while !self.at_end() {
+            let word = self.word("hex bytes")?;
+            if word.len() % 2 != 0 {
+                return Err(self.error(format!("odd number of hex digits in {word}")));
+            }
+            for pair in word.as_bytes().chunks(2) {
+                let pair = std::str::from_utf8(pair).map_err(|_| self.error("invalid hex"))?;
+                bytes.push(
+                    u8::from_str_radix(pair, 16)
+                        .map_err(|_| self.error(format!("invalid hex byte {pair}")))?,
+                );
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+fn tokenize(source: &str) -> JayResult<Vec<Line>> {
+    let mut lines = Vec::new();
+    for (index, text) in source.lines().enumerate() {
+        let number = index + 1;
+        let mut tokens = Vec::new();
+        let mut chars = text.chars().peekable();
+        loop {
+            while chars.next_if(|ch| ch.is_whitespace()).is_some() {}
+            match chars.peek() {
+                None | Some(';') => break,
+                Some('"') => {
+                    chars.next();
+                    tokens.push(Token::Quoted(unquote(&mut chars, number)?));
+                }
+                Some(_) => {
+                    let mut word = String::new();
+                    while let Some(ch) = chars.next_if(|ch| !ch.is_whitespace()) {
+                        word.push(ch);
+                    }
+                    tokens.push(Token::Word(word));
+                }
+            }
+        }
+        if !tokens.is_empty() {
+            lines.push(Line { number, tokens });
+        }
+    }
+    Ok(lines)
+}
+
+/// Reads a string body up to its closing quote, decoding escapes.
+fn unquote(chars: &mut impl Iterator<Item = char>, line: usize) -> JayResult<String> {
+    let error = |message: &str| JayError::class_format(format!("line {line}: {message}"));
+    let mut value = String::new();
+    let mut units = Vec::new();
+    loop {
+        let ch = chars.next().ok_or_else(|| error("unterminated string"))?;
+        if ch != '\\' {
+            if !units.is_empty() {
+                return Err(error("unpaired surrogate escape"));
+            }
+            if ch == '"' {
+                return Ok(value);
+            }
+            value.push(ch);
+            continue;
+        }
+        let escaped = match chars.next().ok_or_else(|| error("unterminated string"))? {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '"' => '"',
+            '\\' => '\\',
+            'u' => {
+                let hex: String = chars.by_ref().take(4).collect();
+                let unit = u16::from_str_radix(&hex, 16)
+                    .map_err(|_| error(&format!("invalid escape \\u{hex}")))?;
+                units.push(unit);
+                match char::decode_utf16(units.iter().copied()).next() {
+                    Some(Ok(ch)) => {
+                        units.clear();
+                        ch
+                    }
+                    Some(Err(_)) if units.len() == 1 && (0xD800..0xDC00).contains(&unit) => {
+                        continue;
+                    }
+                    _ => return Err(error("unpaired surrogate escape")),
+                }
+            }
+            other => return Err(error(&format!("invalid escape \\{other}"))),
+        };
+        if !units.is_empty() {
+            return Err(error("unpaired surrogate escape"));
+        }
+        value.push(escaped);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classfile::builder::ACC_STATIC;
+    use crate::classfile::disassembler::disassemble;
+
+    const SOURCE: &str = r#"
+.version 65 0
+.class public super Demo
+.super java/lang/Object
+.implements java/lang/Runnable
+.signature "Ljava/lang/Object;Ljava/lang/Runnable;"
+
+.field private static final LIMIT J = 10000000000L
+.field public names Ljava/util/List; signature "Ljava/util/List<Ljava/lang/String;>;"
+
+.method public abstract describe()Ljava/lang/String;
+.end method
+
+.method public run()V
+    return
+.end method
+
+.method public static pick(I)Ljava/lang/String; ; trailing comment
+    iload 0
+    tableswitch 1
+        One
+        Two
+        default : Other
+One:
+    ldc "one é😀"
+    areturn
+Two:
+    iload 0
+    lookupswitch
+        2 : Other
+        -7 : One
+        default : Other
+Other:
+    wide iinc 300 1000
+    wide iload 0
+    invokedynamic makeConcatWithConstants(I)Ljava/lang/String; invokestatic java/lang/invoke/StringConcatFactory/makeConcatWithConstants(Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;Ljava/lang/String;[Ljava/lang/Object;)Ljava/lang/invoke/CallSite; "n=\u0001"
+    areturn
+.end method
+
+.method static guarded()V
+    .limit locals 1
+Start:
+    invokestatic java/lang/Thread/yield()V
+End:
+    return
+Handler:
+    astore 0
+    return
+    .catch java/lang/Exception from Start to End using Handler
+.end method
+"#;
+
+    #[test]
+    fn assembles_and_round_trips_through_the_disassembler() {
+        let class_file = assemble(SOURCE).unwrap();
+        assert_eq!(class_file.this_class, "Demo");
+        assert_eq!(class_file.interfaces, ["java/lang/Runnable"]);
+        assert!(class_file.methods[0].code.is_none());
+        assert_eq!(class_file.methods[3].code.as_ref().unwrap().max_locals, 1);
+        assert_eq!(
+            class_file.fields[1].signature.as_deref(),
+            Some("Ljava/util/List<Ljava/lang/String;>;")
+        );
+
+        let disassemble_bytes = |bytes: Vec<u8>| disassemble(&ClassFile::parse(&bytes).unwrap());
+        let text = disassemble_bytes(class_file.to_bytes().unwrap()).unwrap();
+        let reassembled = assemble(&text).unwrap().to_bytes().unwrap();
+        assert_eq!(disassemble_bytes(reassembled).unwrap(), text);
+        assert!(
+            text.contains("    iinc 300 1000\n    wide iload 0\n"),
+            "{text}"
+        );
+    }
+
+    #[test]
+    fn widens_ldc_whose_constant_is_reinterned_past_index_255() {
+        let mut class = ClassBuilder::new("Constants");
+        let mut method = class.method(ACC_STATIC, "strings", "()V");
+        let first = Constant::String("first".to_string());
+        method.constant_pool().intern_constant(&first).unwrap();
+        for n in 0..300 {
+            method
+                .ldc(Constant::String(format!("s{n}")))
+                .op(opcode::POP);
+        }
+        method.ldc(first).op(opcode::POP).op(opcode::RETURN);
+        method.finish();
+        let bytes = class.build().unwrap().to_bytes().unwrap();
+        let text = disassemble(&ClassFile::parse(&bytes).unwrap()).unwrap();
+        assert!(text.contains("    ldc \"first\"\n"), "{text}");
+
+        let reassembled = assemble(&text).unwrap();
+
+        let code = &reassembled.methods[0].code.as_ref().unwrap().bytes;
+        let tail = &code[code.len() - 5..];
+        assert_eq!(tail[0], opcode::LDC_W);
+        let index = u16::from_be_bytes([tail[1], tail[2]]);
+        assert_eq!(reassembled.constant_pool.string(index).unwrap(), "first");
+    }
+
+    #[test]
+    fn reports_errors_with_line_numbers() {
+        let error = |source: &str| assemble(source).unwrap_err().to_string();
+
+        assert_eq!(
+            error(".super java/lang/Object"),
+            "line 1: .super before .class"
+        );
+        assert_eq!(
+            error(".class A\n.method static f()V\n    frob\n.end method"),
+            "line 3: unknown instruction frob"
+        );
+        assert_eq!(
+            error(".class A\n.method static f()V\n    goto Nowhere\n.end method"),
+            "line 3: undefined label Nowhere"
+        );
+        assert_eq!(
+            error(".class A\n.method static f()V\n    ldc \"open\n"),
+            "line 3: unterminated string"
+        );
+    }
+}
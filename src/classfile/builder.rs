@@ -0,0 +1,1036 @@
+//! Programmatic class file construction.
+//!
+//! `ClassBuilder` assembles a [`ClassFile`] model directly, interning constant
+//! pool entries as instructions reference them. Method bodies use labels for
+//! branch targets; offsets are patched and `max_stack`/`max_locals` computed
+//! when the method is finished. Builder calls never fail individually: the
+//! first error is kept and reported by [`ClassBuilder::build`].
+
+use std::collections::HashMap;
+
+use super::annotation::{Annotations, ParameterAnnotations};
+use super::opcode::{self, Instruction, Operand};
+use super::{BootstrapMethod, ClassFile, Code, ConstantPool, ExceptionHandler, Field, Method};
+use crate::{JayError, JayResult};
+
+pub const ACC_PUBLIC: u16 = 0x0001;
+pub const ACC_PRIVATE: u16 = 0x0002;
+pub const ACC_PROTECTED: u16 = 0x0004;
+pub const ACC_STATIC: u16 = 0x0008;
+pub const ACC_FINAL: u16 = 0x0010;
+pub const ACC_SUPER: u16 = 0x0020;
+pub const ACC_SYNCHRONIZED: u16 = 0x0020;
+pub const ACC_NATIVE: u16 = 0x0100;
+pub const ACC_INTERFACE: u16 = 0x0200;
+pub const ACC_ABSTRACT: u16 = 0x0400;
+pub const ACC_SYNTHETIC: u16 = 0x1000;
+
+/// Class file version written by default (Java 21).
+const DEFAULT_MAJOR_VERSION: u16 = 65;
+
+/// A loadable constant for `ldc`, `ConstantValue`, and bootstrap arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constant {
+    Int(i32),
+    Float(f32),
+    Long(i64),
+    Double(f64),
+    String(String),
+    /// Internal class name such as `java/lang/String`.
+    Class(String),
+    /// Method descriptor such as `(I)V`.
+    MethodType(String),
+    MethodHandle(Handle),
+}
+
+/// A `CONSTANT_MethodHandle` target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Handle {
+    /// JVMS reference kind, `1` (`REF_getField`) through `9` (`REF_invokeInterface`).
+    pub kind: u8,
+    pub owner: String,
+    pub name: String,
+    pub descriptor: String,
+    /// Whether a method handle refers to an interface method.
+    pub interface: bool,
+}
+
+/// A branch target inside one method body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Label(usize);
+
+pub struct ClassBuilder {
+    class_file: ClassFile,
+    error: Option<JayError>,
+}
+
+impl ClassBuilder {
+    /// Starts a public class extending `java/lang/Object`.
+    pub fn new(this_class: &str) -> Self {
+        Self {
+            class_file: ClassFile {
+                minor_version: 0,
+                major_version: DEFAULT_MAJOR_VERSION,
+                constant_pool: ConstantPool::new(),
+                access_flags: ACC_PUBLIC | ACC_SUPER,
+                this_class: this_class.to_string(),
+                super_class: Some("java/lang/Object".to_string()),
+                interfaces: Vec::new(),
+                methods: Vec::new(),
+                fields: Vec::new(),
+                bootstrap_methods: Vec::new(),
+                inner_classes: Vec::new(),
+                enclosing_method: None,
+                nest_host: None,
+                nest_members: Vec::new(),
+                permitted_subclasses: Vec::new(),
+                signature: None,
+                annotations: Annotations::default(),
+                attributes: Vec::new(),
+            },
+            error: None,
+        }
+    }
+
+    pub fn version(&mut self, major: u16, minor: u16) -> &mut Self {
+        self.class_file.major_version = major;
+        self.class_file.minor_version = minor;
+        self
+    }
+
+    pub fn access_flags(&mut self, access_flags: u16) -> &mut Self {
+        self.class_file.access_flags = access_flags;
+        self
+    }
+
+    /// Sets the superclass; `None` is only valid for `java/lang/Object`.
+    pub fn super_class(&mut self, super_class: Option<&str>) -> &mut Self {
+        self.class_file.super_class = super_class.map(str::to_string);
+        self
+    }
+
+    pub fn interface(&mut self, interface: &str) -> &mut Self {
+        self.class_file.interfaces.push(interface.to_string());
+        self
+    }
+
+    pub fn field(&mut self, access_flags: u16, name: &str, descriptor: &str) -> &mut Self {
+        self.push_field(access_flags, name, descriptor, None)
+    }
+
+    /// Adds a field with a `ConstantValue` attribute.
+    pub fn constant_field(
+        &mut self,
+        access_flags: u16,
+        name: &str,
+        descriptor: &str,
+        value: Constant,
+    ) -> &mut Self {
+        let index = self.class_file.constant_pool.intern_constant(&value);
+        let index = self.record(index);
+        self.push_field(access_flags, name, descriptor, index)
+    }
+
+    /// Adds a method without a body, such as an abstract or native method.
+    pub fn abstract_method(
+        &mut self,
+        access_flags: u16,
+        name: &str,
+        descriptor: &str,
+    ) -> &mut Self {
+        self.class_file
+            .methods
+            .push(new_method(access_flags, name, descriptor, None));
+        self
+    }
+
+    /// Starts a method with a `Code` attribute; call [`MethodBuilder::finish`]
+    /// to add it to the class.
+    pub fn method(&mut self, access_flags: u16, name: &str, descriptor: &str) -> MethodBuilder<'_> {
+        MethodBuilder {
+            class: self,
+            access_flags,
+            name: name.to_string(),
+            descriptor: descriptor.to_string(),
+            bytes: Vec::new(),
+            labels: Vec::new(),
+            fixups: Vec::new(),
+            handlers: Vec::new(),
+            max_stack: None,
+            max_locals: None,
+        }
+    }
+
+    /// Returns the class model, or the first error any builder call hit.
+    pub fn build(self) -> JayResult<ClassFile> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.class_file),
+        }
+    }
+
+    fn push_field(
+        &mut self,
+        access_flags: u16,
+        name: &str,
+        descriptor: &str,
+        constant_value: Option<u16>,
+    ) -> &mut Self {
+        self.class_file.fields.push(Field {
+            access_flags,
+            name: name.to_string(),
+            descriptor: descriptor.to_string(),
+            constant_value,
+            signature: None,
+            annotations: Annotations::default(),
+            attributes: Vec::new(),
+        });
+        self
+    }
+
+    /// Returns the index of a matching `BootstrapMethods` entry, appending one if needed.
+    fn bootstrap_method(&mut self, bootstrap: &Handle, arguments: &[Constant]) -> JayResult<u16> {
+        let constant_pool = &mut self.class_file.constant_pool;
+        let bootstrap = BootstrapMethod {
+            method_ref: constant_pool.intern_handle(bootstrap)?,
+            arguments: arguments
+                .iter()
+                .map(|argument| constant_pool.intern_constant(argument))
+                .collect::<JayResult<_>>()?,
+        };
+        let bootstrap_methods = &mut self.class_file.bootstrap_methods;
+        let index = match bootstrap_methods
+            .iter()
+            .position(|existing| *existing == bootstrap)
+        {
+            Some(index) => index,
+            None => {
+                bootstrap_methods.push(bootstrap);
+                bootstrap_methods.len() - 1
+            }
+        };
+        u16::try_from(index).map_err(|_| JayError::class_format("too many bootstrap methods"))
+    }
+
+    fn record<T>(&mut self, result: JayResult<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.error.get_or_insert(error);
+                None
+            }
+        }
+    }
+}
+
+struct Fixup {
+    /// Pc of the instruction the offset is relative to.
+    instruction_pc: usize,
+    /// Position of the offset bytes.
+    position: usize,
+    label: Label,
+    wide: bool,
+}
+
+struct PendingHandler {
+    start: Label,
+    end: Label,
+    handler: Label,
+    catch_type: u16,
+}
+
+/// Emits one method body. Instructions are appended in call order.
+pub struct MethodBuilder<'a> {
+    class: &'a mut ClassBuilder,
+    access_flags: u16,
+    name: String,
+    descriptor: String,
+    bytes: Vec<u8>,
+    labels: Vec<Option<usize>>,
+    fixups: Vec<Fixup>,
+    handlers: Vec<PendingHandler>,
+    max_stack: Option<u16>,
+    max_locals: Option<u16>,
+}
+
+impl MethodBuilder<'_> {
+    pub fn new_label(&mut self) -> Label {
+        self.labels.push(None);
+        Label(self.labels.len() - 1)
+    }
+
+    /// Binds `label` to the next instruction.
+    pub fn bind(&mut self, label: Label) -> &mut Self {
+        if self.labels[label.0].is_some() {
+            self.fail(format!("label {} is bound twice", label.0));
+        }
+        self.labels[label.0] = Some(self.bytes.len());
+        self
+    }
+
+    /// Emits an instruction without operands, such as `IADD` or `RETURN`.
+    pub fn op(&mut self, opcode: u8) -> &mut Self {
+        match opcode::info(opcode) {
+            Some(info) if info.operands == opcode::Operands::None => self.bytes.push(opcode),
+            Some(info) => self.fail(format!("{} takes operands", info.mnemonic)),
+            None => self.fail(format!("invalid opcode 0x{opcode:02x}")),
+        }
+        self
+    }
+
+    /// The class constant pool, for interning operands of [`raw`](Self::raw) bytes.
+    pub fn constant_pool(&mut self) -> &mut ConstantPool {
+        &mut self.class.class_file.constant_pool
+    }
+
+    /// Appends bytes verbatim, for bytecode shapes the typed helpers refuse to emit.
+    pub fn raw(&mut self, bytes: &[u8]) -> &mut Self {
+        self.bytes.extend(bytes);
+        self
+    }
+
+    /// Pushes an `int` with the shortest instruction that encodes it.
+    pub fn int(&mut self, value: i32) -> &mut Self {
+        match value {
+            -1..=5 => self.bytes.push((opcode::ICONST_0 as i32 + value) as u8),
+            -128..=127 => self.bytes.extend([opcode::BIPUSH, value as u8]),
+            -32768..=32767 => {
+                self.bytes.push(opcode::SIPUSH);
+                self.bytes.extend((value as i16).to_be_bytes());
+            }
+            _ => return self.ldc(Constant::Int(value)),
+        }
+        self
+    }
+
+    /// Emits `ldc`, `ldc_w`, or `ldc2_w` as the constant requires.
+    pub fn ldc(&mut self, constant: Constant) -> &mut Self {
+        let index = self.intern(|constant_pool| constant_pool.intern_constant(&constant));
+        if matches!(constant, Constant::Long(_) | Constant::Double(_)) {
+            self.op_u2(opcode::LDC2_W, index)
+        } else if index <= u8::MAX as u16 {
+            self.bytes.extend([opcode::LDC, index as u8]);
+            self
+        } else {
+            self.op_u2(opcode::LDC_W, index)
+        }
+    }
+
+    /// Emits a local variable load, store, or `ret`, choosing the `_n` short
+    /// form or a `wide` prefix from the index.
+    pub fn local(&mut self, opcode: u8, index: u16) -> &mut Self {
+        let short_base = match opcode {
+            opcode::ILOAD..=opcode::ALOAD => Some(opcode::ILOAD_0 + (opcode - opcode::ILOAD) * 4),
+            opcode::ISTORE..=opcode::ASTORE => {
+                Some(opcode::ISTORE_0 + (opcode - opcode::ISTORE) * 4)
+            }
+            opcode::RET => None,
+            _ => {
+                self.fail(format!(
+                    "opcode 0x{opcode:02x} is not a local variable instruction"
+                ));
+                return self;
+            }
+        };
+        match (short_base, index) {
+            (Some(base), 0..=3) => self.bytes.push(base + index as u8),
+            (_, 0..=255) => self.bytes.extend([opcode, index as u8]),
+            _ => {
+                self.bytes.extend([opcode::WIDE, opcode]);
+                self.bytes.extend(index.to_be_bytes());
+            }
+        }
+        self
+    }
+
+    pub fn iinc(&mut self, index: u16, delta: i16) -> &mut Self {
+        if let (Ok(index), Ok(delta)) = (u8::try_from(index), i8::try_from(delta)) {
+            self.bytes.extend([opcode::IINC, index, delta as u8]);
+        } else {
+            self.bytes.extend([opcode::WIDE, opcode::IINC]);
+            self.bytes.extend(index.to_be_bytes());
+            self.bytes.extend(delta.to_be_bytes());
+        }
+        self
+    }
+
+    /// Emits a conditional or unconditional branch to `label`.
+    pub fn jump(&mut self, opcode: u8, label: Label) -> &mut Self {
+        let wide = match opcode::info(opcode).map(|info| info.operands) {
+            Some(opcode::Operands::Branch) => false,
+            Some(opcode::Operands::WideBranch) => true,
+            _ => {
+                self.fail(format!("opcode 0x{opcode:02x} is not a branch"));
+                return self;
+            }
+        };
+        let instruction_pc = self.bytes.len();
+        self.bytes.push(opcode);
+        self.push_offset(instruction_pc, label, wide);
+        self
+    }
+
+    /// Emits `tableswitch` for the keys `low..low + targets.len()`.
+    pub fn tableswitch(&mut self, low: i32, default: Label, targets: &[Label]) -> &mut Self {
+        let Some(high) = i32::try_from(targets.len())
+            .ok()
+            .filter(|count| *count > 0)
+            .and_then(|count| low.checked_add(count - 1))
+        else {
+            self.fail("tableswitch needs between 1 and i32::MAX targets");
+            return self;
+        };
+        let instruction_pc = self.switch_header(opcode::TABLESWITCH, default);
+        self.bytes.extend(low.to_be_bytes());
+        self.bytes.extend(high.to_be_bytes());
+        for target in targets {
+            self.push_offset(instruction_pc, *target, true);
+        }
+        self
+    }
+
+    /// Emits `lookupswitch`; pairs are sorted by key as the JVMS requires.
+    pub fn lookupswitch(&mut self, default: Label, pairs: &[(i32, Label)]) -> &mut Self {
+        let mut pairs = pairs.to_vec();
+        pairs.sort_by_key(|(key, _)| *key);
+        let instruction_pc = self.switch_header(opcode::LOOKUPSWITCH, default);
+        self.bytes.extend((pairs.len() as i32).to_be_bytes());
+        for (key, target) in pairs {
+            self.bytes.extend(key.to_be_bytes());
+            self.push_offset(instruction_pc, target, true);
+        }
+        self
+    }
+
+    /// Emits `getstatic`, `putstatic`, `getfield`, or `putfield`.
+    pub fn field(&mut self, opcode: u8, owner: &str, name: &str, descriptor: &str) -> &mut Self {
+        let index =
+            self.intern(|constant_pool| constant_pool.intern_field_ref(owner, name, descriptor));
+        self.op_u2(opcode, index)
+    }
+
+    /// Emits an invoke instruction. `INVOKEINTERFACE` references an interface
+    /// method; the others reference a class method.
+    pub fn invoke(&mut self, opcode: u8, owner: &str, name: &str, descriptor: &str) -> &mut Self {
+        self.emit_invoke(
+            opcode,
+            owner,
+            name,
+            descriptor,
+            opcode == opcode::INVOKEINTERFACE,
+        )
+    }
+
+    /// Emits `invokestatic` or `invokespecial` against an interface method.
+    pub fn invoke_interface_method(
+        &mut self,
+        opcode: u8,
+        owner: &str,
+        name: &str,
+        descriptor: &str,
+    ) -> &mut Self {
+        self.emit_invoke(opcode, owner, name, descriptor, true)
+    }
+
+    /// Emits `invokedynamic`, adding a `BootstrapMethods` entry when needed.
+    pub fn invokedynamic(
+        &mut self,
+        bootstrap: &Handle,
+        arguments: &[Constant],
+        name: &str,
+        descriptor: &str,
+    ) -> &mut Self {
+        let index = self.call_site(bootstrap, arguments, name, descriptor);
+        self.op_u2(opcode::INVOKEDYNAMIC, index);
+        self.bytes.extend([0, 0]);
+        self
+    }
+
+    /// Interns a `CONSTANT_InvokeDynamic` and its bootstrap method without
+    /// emitting an instruction.
+    pub fn call_site(
+        &mut self,
+        bootstrap: &Handle,
+        arguments: &[Constant],
+        name: &str,
+        descriptor: &str,
+    ) -> u16 {
+        let bootstrap_index = self.class.bootstrap_method(bootstrap, arguments);
+        match self.class.record(bootstrap_index) {
+            Some(bootstrap_index) => self.intern(|constant_pool| {
+                constant_pool.intern_invoke_dynamic(bootstrap_index, name, descriptor)
+            }),
+            None => 0,
+        }
+    }
+
+    /// Emits `new`, `anewarray`, `checkcast`, or `instanceof`.
+    pub fn type_op(&mut self, opcode: u8, class_name: &str) -> &mut Self {
+        let index = self.intern(|constant_pool| constant_pool.intern_class(class_name));
+        self.op_u2(opcode, index)
+    }
+
+    /// Emits `newarray` with a JVMS `atype` code such as `10` for `int`.
+    pub fn newarray(&mut self, array_type: u8) -> &mut Self {
+        self.bytes.extend([opcode::NEWARRAY, array_type]);
+        self
+    }
+
+    pub fn multianewarray(&mut self, array_class: &str, dimensions: u8) -> &mut Self {
+        let index = self.intern(|constant_pool| constant_pool.intern_class(array_class));
+        self.op_u2(opcode::MULTIANEWARRAY, index);
+        self.bytes.push(dimensions);
+        self
+    }
+
+    /// Adds an exception table entry; `None` catches everything (`finally`).
+    pub fn try_catch(
+        &mut self,
+        start: Label,
+        end: Label,
+        handler: Label,
+        catch_type: Option<&str>,
+    ) -> &mut Self {
+        let catch_type = match catch_type {
+            Some(class_name) => self.intern(|constant_pool| constant_pool.intern_class(class_name)),
+            None => 0,
+        };
+        self.handlers.push(PendingHandler {
+            start,
+            end,
+            handler,
+            catch_type,
+        });
+        self
+    }
+
+    /// Overrides the computed `max_stack`.
+    pub fn max_stack(&mut self, max_stack: u16) -> &mut Self {
+        self.max_stack = Some(max_stack);
+        self
+    }
+
+    /// Overrides the computed `max_locals`.
+    pub fn max_locals(&mut self, max_locals: u16) -> &mut Self {
+        self.max_locals = Some(max_locals);
+        self
+    }
+
+    /// Resolves labels, computes frame sizes, and adds the method to the class.
+    pub fn finish(self) {
+        let access_flags = self.access_flags;
+        let name = self.name.clone();
+        let descriptor = self.descriptor.clone();
+        let code = self
+            .assemble()
+            .map_err(|error| error.with_context(format!("method {name}{descriptor}")));
+        if let Some(code) = self.class.record(code) {
+            self.class.class_file.methods.push(new_method(
+                access_flags,
+                &name,
+                &descriptor,
+                Some(code),
+            ));
+        }
+    }
+
+    fn assemble(&self) -> JayResult<Code> {
+        let label_pc = |label: Label| {
+            self.labels[label.0]
+                .ok_or_else(|| JayError::class_format(format!("label {} is never bound", label.0)))
+        };
+
+        let mut bytes = self.bytes.clone();
+        for fixup in &self.fixups {
+            let offset = label_pc(fixup.label)? as i64 - fixup.instruction_pc as i64;
+            if fixup.wide {
+                bytes[fixup.position..fixup.position + 4]
+                    .copy_from_slice(&(offset as i32).to_be_bytes());
+            } else {
+                let offset = i16::try_from(offset).map_err(|_| {
+                    JayError::class_format(format!(
+                        "branch offset {offset} at pc {} does not fit in 16 bits",
+                        fixup.instruction_pc
+                    ))
+                })?;
+                bytes[fixup.position..fixup.position + 2].copy_from_slice(&offset.to_be_bytes());
+            }
+        }
+
+        let exception_table = self
+            .handlers
+            .iter()
+            .map(|handler| {
+                let pc = |label| {
+                    u16::try_from(label_pc(label)?)
+                        .map_err(|_| JayError::class_format("exception handler pc exceeds 65535"))
+                };
+                Ok(ExceptionHandler {
+                    start_pc: pc(handler.start)?,
+                    end_pc: pc(handler.end)?,
+                    handler_pc: pc(handler.handler)?,
+                    catch_type: handler.catch_type,
+                })
+            })
+            .collect::<JayResult<Vec<_>>>()?;
+
+        let max_stack = match self.max_stack {
+            Some(max_stack) => max_stack,
+            None => compute_max_stack(
+                &bytes,
+                &exception_table,
+                &self.class.class_file.constant_pool,
+            )?,
+        };
+        let max_locals = match self.max_locals {
+            Some(max_locals) => max_locals,
+            None => compute_max_locals(
+                &bytes,
+                &self.descriptor,
+                self.access_flags & ACC_STATIC != 0,
+            )?,
+        };
+        Ok(Code {
+            max_stack,
+            max_locals,
+            bytes,
+            exception_table,
+            annotations: Annotations::default(),
+            attributes: Vec::new(),
+        })
+    }
+
+    fn emit_invoke(
+        &mut self,
+        opcode: u8,
+        owner: &str,
+        name: &str,
+        descriptor: &str,
+        interface: bool,
+    ) -> &mut Self {
+        let index = self.intern(|constant_pool| {
+            if interface {
+                constant_pool.intern_interface_method_ref(owner, name, descriptor)
+            } else {
+                constant_pool.intern_method_ref(owner, name, descriptor)
+            }
+        });
+        self.op_u2(opcode, index);
+        if opcode == opcode::INVOKEINTERFACE {
+            let count = match method_slots(descriptor) {
+                Ok((arguments, _)) => arguments + 1,
+                Err(error) => {
+                    self.class.record::<()>(Err(error));
+                    0
+                }
+            };
+            match u8::try_from(count) {
+                Ok(count) => self.bytes.extend([count, 0]),
+                Err(_) => self.fail(format!(
+                    "invokeinterface {name}{descriptor} has too many arguments"
+                )),
+            }
+        }
+        self
+    }
+
+    fn switch_header(&mut self, opcode: u8, default: Label) -> usize {
+        let instruction_pc = self.bytes.len();
+        self.bytes.push(opcode);
+        while !self.bytes.len().is_multiple_of(4) {
+            self.bytes.push(0);
+        }
+        self.push_offset(instruction_pc, default, true);
+        instruction_pc
+    }
+
+    fn push_offset(&mut self, instruction_pc: usize, label: Label, wide: bool) {
+        self.fixups.push(Fixup {
+            instruction_pc,
+            position: self.bytes.len(),
+            label,
+            wide,
+        });
+        let width = if wide { 4 } else { 2 };
+        self.bytes.extend(std::iter::repeat_n(0, width));
+    }
+
+    fn op_u2(&mut self, opcode: u8, operand: u16) -> &mut Self {
+        self.bytes.push(opcode);
+        self.bytes.extend(operand.to_be_bytes());
+        self
+    }
+
+    fn intern(&mut self, intern: impl FnOnce(&mut ConstantPool) -> JayResult<u16>) -> u16 {
+        let index = intern(&mut self.class.class_file.constant_pool);
+        self.class.record(index).unwrap_or(0)
+    }
+
+    fn fail(&mut self, message: impl Into<String>) {
+        let error = JayError::class_format(format!(
+            "method {}{}: {}",
+            self.name,
+            self.descriptor,
+            message.into()
+        ));
+        self.class.record::<()>(Err(error));
+    }
+}
+
+/// Computes `max_stack` by following every reachable path through `code`,
+/// including exception handlers, which start with one stack slot.
+pub fn compute_max_stack(
+    code: &[u8],
+    exception_table: &[ExceptionHandler],
+    constant_pool: &ConstantPool,
+) -> JayResult<u16> {
+    let instructions = opcode::decode_all(code)?;
+    let index_by_pc: HashMap<usize, usize> = instructions
+        .iter()
+        .enumerate()
+        .map(|(index, instruction)| (instruction.pc, index))
+        .collect();
+    let index_of = |target: i64, from: usize| {
+        usize::try_from(target)
+            .ok()
+            .and_then(|target| index_by_pc.get(&target).copied())
+            .ok_or_else(|| {
+                JayError::verify(format!(
+                    "target {target} from pc {from} is not an instruction boundary"
+                ))
+            })
+    };
+
+    let mut depths: Vec<Option<u16>> = vec![None; instructions.len()];
+    let mut pending = Vec::new();
+    if !instructions.is_empty() {
+        pending.push((0, 0u16));
+    }
+    for handler in exception_table {
+        let handler_pc = handler.handler_pc as usize;
+        pending.push((index_of(handler_pc as i64, handler_pc)?, 1));
+    }
+
+    let mut max_stack = 0;
+    while let Some((index, depth)) = pending.pop() {
+        let instruction = &instructions[index];
+        match depths[index] {
+            Some(existing) if existing == depth => continue,
+            Some(existing) => {
+                return Err(JayError::verify(format!(
+                    "inconsistent stack depth at pc {}: {existing} and {depth}",
+                    instruction.pc
+                )));
+            }
+            None => depths[index] = Some(depth),
+        }
+
+        let (pop, push) = stack_effect(instruction, constant_pool)?;
+        let after = depth.checked_sub(pop).ok_or_else(|| {
+            JayError::verify(format!(
+                "stack underflow at pc {}: {} needs {pop} slots but {depth} are available",
+                instruction.pc,
+                instruction.mnemonic()
+            ))
+        })?;
+        let after = after.checked_add(push).ok_or_else(|| {
+            JayError::verify(format!(
+                "stack overflow at pc {}: {} grows the stack past {} slots",
+                instruction.pc,
+                instruction.mnemonic(),
+                u16::MAX
+            ))
+        })?;
+        max_stack = max_stack.max(depth).max(after);
+
+        let is_jsr = matches!(instruction.opcode, opcode::JSR | opcode::JSR_W);
+        for target in instruction.branch_targets() {
+            pending.push((index_of(target, instruction.pc)?, after));
+        }
+        if instruction.falls_through() {
+            if index + 1 == instructions.len() {
+                return Err(JayError::verify(format!(
+                    "execution falls off the end of the code after pc {}",
+                    instruction.pc
+                )));
+            }
+            // A subroutine returns to the next instruction with its return
+            // address already consumed.
+            pending.push((index + 1, if is_jsr { depth } else { after }));
+        }
+    }
+    Ok(max_stack)
+}
+
+/// Computes `max_locals` from the parameters and every local variable slot
+/// the code reads or writes.
+pub fn compute_max_locals(code: &[u8], descriptor: &str, is_static: bool) -> JayResult<u16> {
+    let (arguments, _) = method_slots(descriptor)?;
+    let mut max_locals = arguments as u32 + u32::from(!is_static);
+    for instruction in opcode::decode_all(code)? {
+        let slot_end = match (instruction.opcode, &instruction.operand) {
+            (opcode::IINC, Operand::Iinc { index, .. }) => *index as u32 + 1,
+            (opcode::ILOAD..=opcode::ALOAD, Operand::Local(index)) => {
+                *index as u32 + local_width(instruction.opcode - opcode::ILOAD)
+            }
+            (opcode::ISTORE..=opcode::ASTORE, Operand::Local(index)) => {
+                *index as u32 + local_width(instruction.opcode - opcode::ISTORE)
+            }
+            (opcode::RET, Operand::Local(index)) => *index as u32 + 1,
+            (opcode::ILOAD_0..=opcode::ALOAD_3, _) => {
+                let offset = instruction.opcode - opcode::ILOAD_0;
+                (offset % 4) as u32 + local_width(offset / 4)
+            }
+            (opcode::ISTORE_0..=opcode::ASTORE_3, _) => {
+                let offset = instruction.opcode - opcode::ISTORE_0;
+                (offset % 4) as u32 + local_width(offset / 4)
+            }
+            _ => continue,
+        };
+        max_locals = max_locals.max(slot_end);
+    }
+    u16::try_from(max_locals).map_err(|_| JayError::class_format("max_locals exceeds 65535"))
+}
+
+/// Width of a local of kind `i`, `l`, `f`, `d`, `a` (in that order).
+fn local_width(kind: u8) -> u32 {
+    if kind == 1 || kind == 3 { 2 } else { 1 }
+}
+
+fn stack_effect(instruction: &Instruction, constant_pool: &ConstantPool) -> JayResult<(u16, u16)> {
+    let index = match instruction.operand {
+        Operand::Constant(index)
+        | Operand::InvokeInterface { index, .. }
+        | Operand::InvokeDynamic { index, .. } => index,
+        Operand::MultiANewArray { dimensions, .. } => return Ok((dimensions as u16, 1)),
+        _ => 0,
+    };
+    Ok(match instruction.opcode {
+        opcode::GETSTATIC => (0, field_slots(constant_pool.field_ref(index)?.descriptor)),
+        opcode::PUTSTATIC => (field_slots(constant_pool.field_ref(index)?.descriptor), 0),
+        opcode::GETFIELD => (1, field_slots(constant_pool.field_ref(index)?.descriptor)),
+        opcode::PUTFIELD => (
+            1 + field_slots(constant_pool.field_ref(index)?.descriptor),
+            0,
+        ),
+        opcode::INVOKEVIRTUAL
+        | opcode::INVOKESPECIAL
+        | opcode::INVOKESTATIC
+        | opcode::INVOKEINTERFACE => {
+            let (arguments, result) = method_slots(constant_pool.method_ref(index)?.descriptor)?;
+            let receiver = u16::from(instruction.opcode != opcode::INVOKESTATIC);
+            (arguments + receiver, result)
+        }
+        opcode::INVOKEDYNAMIC => method_slots(constant_pool.invoke_dynamic(index)?.descriptor)?,
+        opcode => {
+            let info = opcode::info(opcode).expect("decoded opcode");
+            (info.pop as u16, info.push as u16)
+        }
+    })
+}
+
+/// Stack slots taken by a value of the given field or return descriptor.
+fn field_slots(descriptor: &str) -> u16 {
+    match descriptor.as_bytes().first() {
+        Some(b'J' | b'D') => 2,
+        Some(b'V') => 0,
+        _ => 1,
+    }
+}
+
+/// Returns the argument and return value slot counts of a method descriptor.
+fn method_slots(descriptor: &str) -> JayResult<(u16, u16)> {
+    let invalid = || JayError::class_format(format!("invalid method descriptor {descriptor}"));
+    let parameters = descriptor.strip_prefix('(').ok_or_else(invalid)?;
+    let (parameters, result) = parameters.split_once(')').ok_or_else(invalid)?;
+    let mut bytes = parameters.bytes();
+    let mut slots = 0;
+    while let Some(byte) = bytes.next() {
+        slots += match byte {
+            b'J' | b'D' => 2,
+            b'B' | b'C' | b'F' | b'I' | b'S' | b'Z' => 1,
+            b'L' => {
+                bytes
+                    .by_ref()
+                    .find(|byte| *byte == b';')
+                    .ok_or_else(invalid)?;
+                1
+            }
+            b'[' => {
+                let mut component = bytes.by_ref().skip_while(|byte| *byte == b'[');
+                if component.next().ok_or_else(invalid)? == b'L' {
+                    component.find(|byte| *byte == b';').ok_or_else(invalid)?;
+                }
+                1
+            }
+            _ => return Err(invalid()),
+        };
+    }
+    if result.is_empty() {
+        return Err(invalid());
+    }
+    Ok((slots, field_slots(result)))
+}
+
+impl ConstantPool {
+    /// Interns a loadable constant and returns its index.
+    pub fn intern_constant(&mut self, constant: &Constant) -> JayResult<u16> {
+        match constant {
+            Constant::Int(value) => self.intern_integer(*value),
+            Constant::Float(value) => self.intern_float(*value),
+            Constant::Long(value) => self.intern_long(*value),
+            Constant::Double(value) => self.intern_double(*value),
+            Constant::String(value) => self.intern_string(value),
+            Constant::Class(name) => self.intern_class(name),
+            Constant::MethodType(descriptor) => self.intern_method_type(descriptor),
+            Constant::MethodHandle(handle) => self.intern_handle(handle),
+        }
+    }
+
+    /// Interns a `CONSTANT_MethodHandle` together with the member it references.
+    pub fn intern_handle(&mut self, handle: &Handle) -> JayResult<u16> {
+        let Handle {
+            kind,
+            owner,
+            name,
+            descriptor,
+            interface,
+        } = handle;
+        let reference_index = match kind {
+            1..=4 => self.intern_field_ref(owner, name, descriptor)?,
+            5..=8 if !interface => self.intern_method_ref(owner, name, descriptor)?,
+            5..=9 => self.intern_interface_method_ref(owner, name, descriptor)?,
+            _ => {
+                return Err(JayError::class_format(format!(
+                    "invalid method handle reference kind {kind}"
+                )));
+            }
+        };
+        self.intern_method_handle(*kind, reference_index)
+    }
+}
+
+fn new_method(access_flags: u16, name: &str, descriptor: &str, code: Option<Code>) -> Method {
+    Method {
+        access_flags,
+        name: name.to_string(),
+        descriptor: descriptor.to_string(),
+        code,
+        signature: None,
+        annotations: Annotations::default(),
+        parameter_annotations: ParameterAnnotations::default(),
+        annotation_default: None,
+        attributes: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classfile::opcode::*;
+
+    #[test]
+    fn patches_branch_offsets_and_computes_frame_sizes() {
+        let mut class = ClassBuilder::new("Sum");
+        let mut method = class.method(ACC_PUBLIC | ACC_STATIC, "sum", "(I)J");
+        let (head, done) = (method.new_label(), method.new_label());
+        method.op(LCONST_0).local(LSTORE, 1).bind(head);
+        method.local(ILOAD, 0).jump(IFLE, done);
+        method
+            .local(LLOAD, 1)
+            .local(ILOAD, 0)
+            .op(I2L)
+            .op(LADD)
+            .local(LSTORE, 1);
+        method.iinc(0, -1).jump(GOTO, head);
+        method.bind(done).local(LLOAD, 1).op(LRETURN);
+        method.finish();
+
+        let class_file = ClassFile::parse(&class.build().unwrap().to_bytes().unwrap()).unwrap();
+        let code = class_file.methods[0].code.as_ref().unwrap();
+
+        assert_eq!(code.max_stack, 4);
+        assert_eq!(code.max_locals, 3);
+        assert_eq!(
+            code.bytes,
+            [
+                LCONST_0, LSTORE_1, ILOAD_0, IFLE, 0x00, 0x0e, LLOAD_1, ILOAD_0, I2L, LADD,
+                LSTORE_1, IINC, 0x00, 0xff, GOTO, 0xff, 0xf4, LLOAD_1, LRETURN,
+            ]
+        );
+    }
+
+    #[test]
+    fn interns_references_and_counts_invokeinterface_arguments() {
+        let mut class = ClassBuilder::new("Caller");
+        let mut method = class.method(ACC_STATIC, "call", "(Ljava/util/List;)V");
+        method
+            .local(ALOAD, 0)
+            .ldc(Constant::String("x".to_string()))
+            .invoke(
+                INVOKEINTERFACE,
+                "java/util/List",
+                "add",
+                "(Ljava/lang/Object;)Z",
+            )
+            .op(POP)
+            .op(RETURN);
+        method.finish();
+
+        let class_file = class.build().unwrap();
+        let code = class_file.methods[0].code.as_ref().unwrap();
+        let index = u16::from_be_bytes([code.bytes[4], code.bytes[5]]);
+        let method_ref = class_file.constant_pool.method_ref(index).unwrap();
+
+        assert_eq!(method_ref.class_name, "java/util/List");
+        assert_eq!(&code.bytes[6..8], [2, 0]);
+        assert_eq!(code.max_stack, 2);
+        assert_eq!(code.max_locals, 1);
+    }
+
+    #[test]
+    fn reports_unbound_labels_and_stack_underflow() {
+        let mut class = ClassBuilder::new("Broken");
+        let mut method = class.method(ACC_STATIC, "jump", "()V");
+        let nowhere = method.new_label();
+        method.jump(GOTO, nowhere);
+        method.finish();
+        let error = class.build().unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("method jump()V: label 0 is never bound")
+        );
+
+        let mut class = ClassBuilder::new("Broken");
+        let mut method = class.method(ACC_STATIC, "add", "()I");
+        method.op(ICONST_1).op(IADD).op(IRETURN);
+        method.finish();
+        let error = class.build().unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("stack underflow at pc 1: iadd needs 2 slots but 1 are available")
+        );
+    }
+
+    #[test]
+    fn reports_stack_overflow_past_u16_slots() {
+        let mut class = ClassBuilder::new("Deep");
+        let mut method = class.method(ACC_STATIC, "push", "()V");
+        for _ in 0..32768 {
+            method.op(LCONST_0);
+        }
+        method.op(RETURN);
+        method.finish();
+        let error = class.build().unwrap_err();
+
+        assert_eq!(error.kind(), &crate::ErrorKind::Verify);
+        assert!(
+            error
+                .to_string()
+                .contains("stack overflow at pc 32767: lconst_0 grows the stack past 65535 slots"),
+            "{error}"
+        );
+    }
+}
@@ -0,0 +1,549 @@
+//! Renders a parsed class file in the text format read by
+//! [`assembler`](super::assembler).
+//!
+//! Attributes the text format cannot express, such as `LineNumberTable` or
+//! annotations, are listed as comments and reported by [`omission_warnings`].
+//! Their constant pool indexes would not survive reassembly into a fresh
+//! constant pool.
+
+use std::collections::BTreeSet;
+
+use super::opcode::{self, Instruction, Operand};
+use super::{ClassFile, Code, ConstantPool, CpEntry, Method};
+use crate::{JayError, JayResult};
+
+pub(super) const CLASS_FLAGS: &[(u16, &str)] = &[
+    (0x0001, "public"),
+    (0x0010, "final"),
+    (0x0020, "super"),
+    (0x0200, "interface"),
+    (0x0400, "abstract"),
+    (0x1000, "synthetic"),
+    (0x2000, "annotation"),
+    (0x4000, "enum"),
+    (0x8000, "module"),
+];
+
+pub(super) const FIELD_FLAGS: &[(u16, &str)] = &[
+    (0x0001, "public"),
+    (0x0002, "private"),
+    (0x0004, "protected"),
+    (0x0008, "static"),
+    (0x0010, "final"),
+    (0x0040, "volatile"),
+    (0x0080, "transient"),
+    (0x1000, "synthetic"),
+    (0x4000, "enum"),
+];
+
+pub(super) const METHOD_FLAGS: &[(u16, &str)] = &[
+    (0x0001, "public"),
+    (0x0002, "private"),
+    (0x0004, "protected"),
+    (0x0008, "static"),
+    (0x0010, "final"),
+    (0x0020, "synchronized"),
+    (0x0040, "bridge"),
+    (0x0080, "varargs"),
+    (0x0100, "native"),
+    (0x0400, "abstract"),
+    (0x0800, "strict"),
+    (0x1000, "synthetic"),
+];
+
+/// `newarray` type codes, indexed from `4`.
+pub(super) const ARRAY_TYPES: &[&str] = &[
+    "boolean", "char", "float", "double", "byte", "short", "int", "long",
+];
+
+pub(super) const HANDLE_KINDS: &[&str] = &[
+    "getfield",
+    "getstatic",
+    "putfield",
+    "putstatic",
+    "invokevirtual",
+    "invokestatic",
+    "invokespecial",
+    "newinvokespecial",
+    "invokeinterface",
+];
+
+/// Renders `class_file` as assembler source.
+pub fn disassemble(class_file: &ClassFile) -> JayResult<String> {
+    let mut lines = vec![
+        format!(
+            ".version {} {}",
+            class_file.major_version, class_file.minor_version
+        ),
+        format!(
+            ".class {}{}",
+            flags(CLASS_FLAGS, class_file.access_flags),
+            class_file.this_class
+        ),
+        format!(
+            ".super {}",
+            class_file.super_class.as_deref().unwrap_or("none")
+        ),
+    ];
+    for interface in &class_file.interfaces {
+        lines.push(format!(".implements {interface}"));
+    }
+    if let Some(signature) = &class_file.signature {
+        lines.push(format!(".signature {}", quote(signature)));
+    }
+    if let Some(nest_host) = &class_file.nest_host {
+        lines.push(format!(".nesthost {nest_host}"));
+    }
+    for member in &class_file.nest_members {
+        lines.push(format!(".nestmember {member}"));
+    }
+    for subclass in &class_file.permitted_subclasses {
+        lines.push(format!(".permittedsubclass {subclass}"));
+    }
+    if !class_file.inner_classes.is_empty() {
+        lines.push("; InnerClasses omitted".to_string());
+    }
+    if class_file.enclosing_method.is_some() {
+        lines.push("; EnclosingMethod omitted".to_string());
+    }
+    if !class_file.annotations.is_empty() {
+        lines.push("; annotations omitted".to_string());
+    }
+    omitted_attributes(&mut lines, "", &class_file.attributes);
+
+    let constant_pool = &class_file.constant_pool;
+    for field in &class_file.fields {
+        let mut line = format!(
+            ".field {}{} {}",
+            flags(FIELD_FLAGS, field.access_flags),
+            field.name,
+            field.descriptor
+        );
+        if let Some(signature) = &field.signature {
+            line.push_str(&format!(" signature {}", quote(signature)));
+        }
+        if let Some(index) = field.constant_value {
+            line.push_str(&format!(" = {}", constant(constant_pool, index)?));
+        }
+        lines.push(String::new());
+        lines.push(line);
+        if !field.annotations.is_empty() {
+            lines.push("; annotations omitted".to_string());
+        }
+        omitted_attributes(&mut lines, "", &field.attributes);
+    }
+
+    for method in &class_file.methods {
+        lines.push(String::new());
+        disassemble_method(&mut lines, class_file, method)?;
+    }
+
+    let mut text = lines.join("\n");
+    text.push('\n');
+    Ok(text)
+}
+
+/// Warnings about what [`disassemble`] leaves out of `class_file`, empty
+/// when reassembly reproduces everything the class carries.
+pub fn omission_warnings(class_file: &ClassFile) -> Vec<String> {
+    let mut omitted = BTreeSet::new();
+    let mut add_attributes = |attributes: &[super::Attribute]| {
+        omitted.extend(attributes.iter().map(|attribute| attribute.name.clone()));
+    };
+    add_attributes(&class_file.attributes);
+    for field in &class_file.fields {
+        add_attributes(&field.attributes);
+    }
+    for method in &class_file.methods {
+        add_attributes(&method.attributes);
+        if let Some(code) = &method.code {
+            add_attributes(&code.attributes);
+        }
+    }
+    if !class_file.inner_classes.is_empty() {
+        omitted.insert("InnerClasses".to_string());
+    }
+    if class_file.enclosing_method.is_some() {
+        omitted.insert("EnclosingMethod".to_string());
+    }
+    let has_annotations = !class_file.annotations.is_empty()
+        || class_file
+            .fields
+            .iter()
+            .any(|field| !field.annotations.is_empty())
+        || class_file.methods.iter().any(|method| {
+            !method.annotations.is_empty()
+                || !method.parameter_annotations.visible.is_empty()
+                || !method.parameter_annotations.invisible.is_empty()
+                || method.annotation_default.is_some()
+                || method
+                    .code
+                    .as_ref()
+                    .is_some_and(|code| !code.annotations.is_empty())
+        });
+    if has_annotations {
+        omitted.insert("annotations".to_string());
+    }
+
+    let mut warnings = Vec::new();
+    if omitted.remove("StackMapTable") {
+        warnings.push(
+            "StackMapTable omitted; the reassembled class fails JVM verification and runs only under jay"
+                .to_string(),
+        );
+    }
+    if !omitted.is_empty() {
+        let names: Vec<_> = omitted.into_iter().collect();
+        warnings.push(format!("omitted {}", names.join(", ")));
+    }
+    warnings
+}
+
+fn disassemble_method(
+    lines: &mut Vec<String>,
+    class_file: &ClassFile,
+    method: &Method,
+) -> JayResult<()> {
+    lines.push(format!(
+        ".method {}{}{}",
+        flags(METHOD_FLAGS, method.access_flags),
+        method.name,
+        method.descriptor
+    ));
+    if let Some(signature) = &method.signature {
+        lines.push(format!("    .signature {}", quote(signature)));
+    }
+    if !method.annotations.is_empty()
+        || !method.parameter_annotations.visible.is_empty()
+        || !method.parameter_annotations.invisible.is_empty()
+        || method.annotation_default.is_some()
+    {
+        lines.push("    ; annotations omitted".to_string());
+    }
+    omitted_attributes(lines, "    ", &method.attributes);
+    if let Some(code) = &method.code {
+        disassemble_code(lines, class_file, code).map_err(|error| {
+            error.with_context(format!("method {}{}", method.name, method.descriptor))
+        })?;
+    }
+    lines.push(".end method".to_string());
+    Ok(())
+}
+
+fn disassemble_code(lines: &mut Vec<String>, class_file: &ClassFile, code: &Code) -> JayResult<()> {
+    lines.push(format!("    .limit stack {}", code.max_stack));
+    lines.push(format!("    .limit locals {}", code.max_locals));
+    omitted_attributes(lines, "    ", &code.attributes);
+    if !code.annotations.is_empty() {
+        lines.push("    ; type annotations omitted".to_string());
+    }
+
+    let Some(instructions) = decodable_instructions(code) else {
+        // Bytecode that does not decode into well-formed, label-addressable
+        // instructions is kept verbatim.
+        for handler in &code.exception_table {
+            lines.push(format!(
+                "    .catch {} from {} to {} using {}",
+                catch_type(&class_file.constant_pool, handler.catch_type)?,
+                handler.start_pc,
+                handler.end_pc,
+                handler.handler_pc
+            ));
+        }
+        for chunk in code.bytes.chunks(16) {
+            let hex: Vec<String> = chunk.iter().map(|byte| format!("{byte:02x}")).collect();
+            lines.push(format!("    .bytes {}", hex.join(" ")));
+        }
+        return Ok(());
+    };
+
+    let mut labels = BTreeSet::new();
+    for instruction in &instructions {
+        labels.extend(
+            instruction
+                .branch_targets()
+                .into_iter()
+                .map(|target| target as usize),
+        );
+    }
+    for handler in &code.exception_table {
+        labels.extend([
+            handler.start_pc as usize,
+            handler.end_pc as usize,
+            handler.handler_pc as usize,
+        ]);
+    }
+
+    for handler in &code.exception_table {
+        lines.push(format!(
+            "    .catch {} from L{} to L{} using L{}",
+            catch_type(&class_file.constant_pool, handler.catch_type)?,
+            handler.start_pc,
+            handler.end_pc,
+            handler.handler_pc
+        ));
+    }
+    for instruction in &instructions {
+        if labels.contains(&instruction.pc) {
+            lines.push(format!("L{}:", instruction.pc));
+        }
+        render_instruction(lines, class_file, instruction)?;
+    }
+    if labels.contains(&code.bytes.len()) {
+        lines.push(format!("L{}:", code.bytes.len()));
+    }
+    Ok(())
+}
+
+/// Decodes `code` when every branch target and handler pc is an
+/// instruction boundary, so each can be written as a label.
+fn decodable_instructions(code: &Code) -> Option<Vec<Instruction>> {
+    let instructions = opcode::decode_all(&code.bytes).ok()?;
+    let mut boundaries: BTreeSet<usize> = instructions.iter().map(|instr| instr.pc).collect();
+    boundaries.insert(code.bytes.len());
+    let targets_valid = instructions.iter().all(|instruction| {
+        instruction
+            .branch_targets()
+            .into_iter()
+            .all(|target| usize::try_from(target).is_ok_and(|pc| boundaries.contains(&pc)))
+    });
+    let handlers_valid = code.exception_table.iter().all(|handler| {
+        [handler.start_pc, handler.end_pc, handler.handler_pc]
+            .iter()
+            .all(|pc| boundaries.contains(&(*pc as usize)))
+    });
+    (targets_valid && handlers_valid).then_some(instructions)
+}
+
+fn render_instruction(
+    lines: &mut Vec<String>,
+    class_file: &ClassFile,
+    instruction: &Instruction,
+) -> JayResult<()> {
+    let constant_pool = &class_file.constant_pool;
+    let mnemonic = instruction.mnemonic();
+    let label = |offset: &i32| format!("L{}", instruction.pc as i64 + *offset as i64);
+    let text = match &instruction.operand {
+        Operand::None => mnemonic.to_string(),
+        Operand::Int(value) if instruction.opcode == opcode::NEWARRAY => {
+            match ARRAY_TYPES.get((*value as usize).wrapping_sub(4)) {
+                Some(name) => format!("newarray {name}"),
+                None => format!("newarray {value}"),
+            }
+        }
+        Operand::Int(value) => format!("{mnemonic} {value}"),
+        Operand::Local(index) => {
+            let prefix = if instruction.wide && *index <= u8::MAX as u16 {
+                "wide "
+            } else {
+                ""
+            };
+            format!("{prefix}{mnemonic} {index}")
+        }
+        Operand::Iinc { index, delta } => {
+            let narrow = *index <= u8::MAX as u16 && i8::try_from(*delta).is_ok();
+            let prefix = if instruction.wide && narrow {
+                "wide "
+            } else {
+                ""
+            };
+            format!("{prefix}iinc {index} {delta}")
+        }
+        Operand::Constant(index) => {
+            let operand = match instruction.opcode {
+                opcode::LDC | opcode::LDC_W | opcode::LDC2_W => constant(constant_pool, *index)?,
+                opcode::GETSTATIC | opcode::PUTSTATIC | opcode::GETFIELD | opcode::PUTFIELD => {
+                    let field = constant_pool.field_ref(*index)?;
+                    format!("{}/{} {}", field.class_name, field.name, field.descriptor)
+                }
+                opcode::INVOKEVIRTUAL | opcode::INVOKESPECIAL | opcode::INVOKESTATIC => {
+                    method_ref(constant_pool, *index, true)?
+                }
+                _ => constant_pool.class_name(*index)?.to_string(),
+            };
+            format!("{mnemonic} {operand}")
+        }
+        Operand::Branch(offset) => format!("{mnemonic} {}", label(offset)),
+        Operand::InvokeInterface {
+            index,
+            count,
+            padding,
+        } => {
+            let mut text = format!(
+                "invokeinterface {} {count}",
+                method_ref(constant_pool, *index, false)?
+            );
+            if *padding != 0 {
+                text.push_str(&format!(" padding {padding}"));
+            }
+            text
+        }
+        Operand::InvokeDynamic { index, padding } => {
+            let call_site = constant_pool.invoke_dynamic(*index)?;
+            let bootstrap = class_file
+                .bootstrap_methods
+                .get(call_site.bootstrap_method_attr_index as usize)
+                .ok_or_else(|| {
+                    JayError::class_format(format!(
+                        "invokedynamic references missing bootstrap method {}",
+                        call_site.bootstrap_method_attr_index
+                    ))
+                })?;
+            let mut text = format!(
+                "invokedynamic {}{} {}",
+                call_site.name,
+                call_site.descriptor,
+                method_handle(constant_pool, bootstrap.method_ref)?
+            );
+            for argument in &bootstrap.arguments {
+                text.push(' ');
+                text.push_str(&constant(constant_pool, *argument)?);
+            }
+            if *padding != 0 {
+                text.push_str(&format!(" padding {padding}"));
+            }
+            text
+        }
+        Operand::MultiANewArray { index, dimensions } => format!(
+            "multianewarray {} {dimensions}",
+            constant_pool.class_name(*index)?
+        ),
+        Operand::TableSwitch {
+            default,
+            low,
+            offsets,
+        } => {
+            lines.push(format!("    tableswitch {low}"));
+            for offset in offsets {
+                lines.push(format!("        {}", label(offset)));
+            }
+            format!("    default : {}", label(default))
+        }
+        Operand::LookupSwitch { default, pairs } => {
+            lines.push("    lookupswitch".to_string());
+            for (key, offset) in pairs {
+                lines.push(format!("        {key} : {}", label(offset)));
+            }
+            format!("    default : {}", label(default))
+        }
+    };
+    lines.push(format!("    {text}"));
+    Ok(())
+}
+
+fn omitted_attributes(lines: &mut Vec<String>, indent: &str, attributes: &[super::Attribute]) {
+    for attribute in attributes {
+        lines.push(format!(
+            "{indent}; {} attribute omitted ({} bytes)",
+            attribute.name,
+            attribute.info.len()
+        ));
+    }
+}
+
+fn flags(names: &[(u16, &str)], access_flags: u16) -> String {
+    let mut text = String::new();
+    let mut remaining = access_flags;
+    for (flag, name) in names {
+        if access_flags & flag != 0 {
+            text.push_str(name);
+            text.push(' ');
+            remaining &= !flag;
+        }
+    }
+    if remaining != 0 {
+        text.push_str(&format!("0x{remaining:04x} "));
+    }
+    text
+}
+
+fn catch_type(constant_pool: &ConstantPool, index: u16) -> JayResult<String> {
+    if index == 0 {
+        return Ok("all".to_string());
+    }
+    Ok(constant_pool.class_name(index)?.to_string())
+}
+
+/// Writes `owner/name(descriptor)`, marking interface methods invoked by
+/// `invokespecial` or `invokestatic` when `mark_interface` is set.
+fn method_ref(constant_pool: &ConstantPool, index: u16, mark_interface: bool) -> JayResult<String> {
+    let method = constant_pool.method_ref(index)?;
+    let interface = mark_interface
+        && matches!(
+            constant_pool.entry(index)?,
+            CpEntry::InterfaceMethodRef { .. }
+        );
+    Ok(format!(
+        "{}{}/{}{}",
+        if interface { "interface " } else { "" },
+        method.class_name,
+        method.name,
+        method.descriptor
+    ))
+}
+
+fn method_handle(constant_pool: &ConstantPool, index: u16) -> JayResult<String> {
+    let handle = constant_pool.method_handle(index)?;
+    let kind = HANDLE_KINDS
+        .get((handle.reference_kind as usize).wrapping_sub(1))
+        .ok_or_else(|| {
+            JayError::class_format(format!(
+                "invalid method handle reference kind {}",
+                handle.reference_kind
+            ))
+        })?;
+    if handle.reference_kind <= 4 {
+        let field = constant_pool.field_ref(handle.reference_index)?;
+        return Ok(format!(
+            "{kind} {}/{} {}",
+            field.class_name, field.name, field.descriptor
+        ));
+    }
+    let mark_interface = handle.reference_kind != 9;
+    Ok(format!(
+        "{kind} {}",
+        method_ref(constant_pool, handle.reference_index, mark_interface)?
+    ))
+}
+
+/// Writes a loadable constant in assembler syntax.
+fn constant(constant_pool: &ConstantPool, index: u16) -> JayResult<String> {
+    Ok(match constant_pool.entry(index)? {
+        CpEntry::Integer(value) => value.to_string(),
+        CpEntry::Float(bits) => format!("{:?}F", f32::from_bits(*bits)),
+        CpEntry::Long(value) => format!("{value}L"),
+        CpEntry::Double(bits) => format!("{:?}D", f64::from_bits(*bits)),
+        CpEntry::String { string_index } => quote(constant_pool.utf8(*string_index)?),
+        CpEntry::Class { name_index } => format!("class {}", constant_pool.utf8(*name_index)?),
+        CpEntry::MethodType { descriptor_index } => {
+            format!("methodtype {}", constant_pool.utf8(*descriptor_index)?)
+        }
+        CpEntry::MethodHandle { .. } => {
+            format!("methodhandle {}", method_handle(constant_pool, index)?)
+        }
+        other => {
+            return Err(JayError::class_format(format!(
+                "constant pool entry #{index} cannot be written as text: {other:?}"
+            )));
+        }
+    })
+}
+
+/// Quotes a string, escaping quotes, backslashes, and control characters.
+pub(super) fn quote(value: &str) -> String {
+    let mut text = String::with_capacity(value.len() + 2);
+    text.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => text.push_str("\\\""),
+            '\\' => text.push_str("\\\\"),
+            '\n' => text.push_str("\\n"),
+            '\r' => text.push_str("\\r"),
+            '\t' => text.push_str("\\t"),
+            ch if ch.is_control() => text.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => text.push(ch),
+        }
+    }
+    text.push('"');
+    text
+}
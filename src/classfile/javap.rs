@@ -0,0 +1,808 @@
+//! Renders a class file in the layout of the JDK `javap` tool.
+//!
+//! The output starts with the class origin, so classes resolved from the boot
+//! image are told apart from classpath classes. `-c` adds disassembled code
+//! and `-v` adds the constant pool, flags, and attribute details.
+
+use super::disassembler::{ARRAY_TYPES, CLASS_FLAGS, FIELD_FLAGS, HANDLE_KINDS, METHOD_FLAGS};
+use super::opcode::{self, Instruction, Operand};
+use super::{Attribute, ClassFile, Code, ConstantPool, CpEntry, Field, Method};
+use crate::{JayError, JayResult};
+
+/// Which sections `javap` prints beyond the member declarations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JavapOptions {
+    /// Disassemble method bodies (`-c`).
+    pub code: bool,
+    /// Print the constant pool, flags, and attributes (`-v`).
+    pub verbose: bool,
+}
+
+/// Renders `class_file`, loaded from `origin`, as `javap` would.
+pub fn javap(class_file: &ClassFile, origin: &str, options: JavapOptions) -> JayResult<String> {
+    let constant_pool = &class_file.constant_pool;
+    let mut lines = vec![format!("Classfile {origin}")];
+    let source_file = class_file.source_file();
+    if let Some(source_file) = source_file {
+        lines.push(format!("  Compiled from \"{source_file}\""));
+    }
+
+    let declaration = class_declaration(class_file);
+    if !options.verbose {
+        lines.push(format!("{declaration} {{"));
+    } else {
+        lines.push(declaration);
+        lines.push(format!("  minor version: {}", class_file.minor_version));
+        lines.push(format!("  major version: {}", class_file.major_version));
+        lines.push(format!(
+            "  flags: {}",
+            flag_names(CLASS_FLAGS, class_file.access_flags)
+        ));
+        lines.push(class_reference(
+            constant_pool,
+            "this_class",
+            &class_file.this_class,
+        ));
+        match &class_file.super_class {
+            Some(super_class) => {
+                lines.push(class_reference(constant_pool, "super_class", super_class))
+            }
+            None => lines.push("  super_class: #0".to_string()),
+        }
+        lines.push(format!(
+            "  interfaces: {}, fields: {}, methods: {}",
+            class_file.interfaces.len(),
+            class_file.fields.len(),
+            class_file.methods.len()
+        ));
+        lines.push("Constant pool:".to_string());
+        constant_pool_lines(&mut lines, constant_pool)?;
+        lines.push("{".to_string());
+    }
+
+    let spaced = options.code || options.verbose;
+    let mut first = true;
+    for field in &class_file.fields {
+        if spaced && !std::mem::take(&mut first) {
+            lines.push(String::new());
+        }
+        field_lines(&mut lines, constant_pool, field, options)?;
+    }
+    for method in &class_file.methods {
+        if spaced && !std::mem::take(&mut first) {
+            lines.push(String::new());
+        }
+        method_lines(&mut lines, class_file, method, options)?;
+    }
+    lines.push("}".to_string());
+
+    if options.verbose {
+        class_attribute_lines(&mut lines, class_file, source_file)?;
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+fn class_declaration(class_file: &ClassFile) -> String {
+    let interface = class_file.is_interface();
+    let mut declaration = modifiers(
+        CLASS_FLAGS,
+        class_file.access_flags,
+        if interface {
+            &["public"]
+        } else {
+            &["public", "final", "abstract"]
+        },
+    );
+    declaration.push_str(if interface { "interface " } else { "class " });
+    declaration.push_str(&class_file.this_class.replace('/', "."));
+    if let Some(super_class) = class_file.super_class.as_deref().filter(|_| !interface) {
+        declaration.push_str(&format!(" extends {}", super_class.replace('/', ".")));
+    }
+    if !class_file.interfaces.is_empty() {
+        let interfaces: Vec<String> = class_file
+            .interfaces
+            .iter()
+            .map(|name| name.replace('/', "."))
+            .collect();
+        let keyword = if interface { "extends" } else { "implements" };
+        declaration.push_str(&format!(" {keyword} {}", interfaces.join(",")));
+    }
+    declaration
+}
+
+fn field_lines(
+    lines: &mut Vec<String>,
+    constant_pool: &ConstantPool,
+    field: &Field,
+    options: JavapOptions,
+) -> JayResult<()> {
+    let java_type = split_type(&field.descriptor)
+        .map(|(java_type, _)| java_type)
+        .unwrap_or_else(|| field.descriptor.clone());
+    let modifiers = modifiers(
+        FIELD_FLAGS,
+        field.access_flags,
+        &[
+            "public",
+            "private",
+            "protected",
+            "static",
+            "final",
+            "volatile",
+            "transient",
+        ],
+    );
+    lines.push(format!("  {modifiers}{java_type} {};", field.name));
+    if !options.verbose {
+        return Ok(());
+    }
+    lines.push(format!("    descriptor: {}", field.descriptor));
+    lines.push(format!(
+        "    flags: {}",
+        flag_names(FIELD_FLAGS, field.access_flags)
+    ));
+    if let Some(index) = field.constant_value {
+        lines.push(format!(
+            "    ConstantValue: {}",
+            describe(constant_pool, index)?
+        ));
+    }
+    if let Some(signature) = &field.signature {
+        lines.push(format!("    Signature: {signature}"));
+    }
+    raw_attribute_lines(lines, "    ", &field.attributes);
+    Ok(())
+}
+
+fn method_lines(
+    lines: &mut Vec<String>,
+    class_file: &ClassFile,
+    method: &Method,
+    options: JavapOptions,
+) -> JayResult<()> {
+    let constant_pool = &class_file.constant_pool;
+    let modifiers = modifiers(
+        METHOD_FLAGS,
+        method.access_flags,
+        &[
+            "public",
+            "private",
+            "protected",
+            "static",
+            "final",
+            "synchronized",
+            "native",
+            "abstract",
+        ],
+    );
+    let declaration = match (method.name.as_str(), method_types(&method.descriptor)) {
+        ("<clinit>", _) => "static {}".to_string(),
+        (name, Some((mut parameters, return_type))) => {
+            if let Some(last) = parameters
+                .last_mut()
+                .filter(|last| method.is_varargs() && last.ends_with("[]"))
+            {
+                last.truncate(last.len() - 2);
+                last.push_str("...");
+            }
+            let parameters = parameters.join(", ");
+            if name == "<init>" {
+                format!("{}({parameters})", class_file.this_class.replace('/', "."))
+            } else {
+                format!("{return_type} {name}({parameters})")
+            }
+        }
+        (name, None) => format!("{name}{}", method.descriptor),
+    };
+    lines.push(format!("  {modifiers}{declaration};"));
+    if options.verbose {
+        lines.push(format!("    descriptor: {}", method.descriptor));
+        lines.push(format!(
+            "    flags: {}",
+            flag_names(METHOD_FLAGS, method.access_flags)
+        ));
+    }
+    if let Some(code) = method
+        .code
+        .as_ref()
+        .filter(|_| options.code || options.verbose)
+    {
+        lines.push("    Code:".to_string());
+        if options.verbose {
+            lines.push(format!(
+                "      stack={}, locals={}, args_size={}",
+                code.max_stack,
+                code.max_locals,
+                args_size(&method.descriptor) + usize::from(!method.is_static())
+            ));
+        }
+        code_lines(lines, constant_pool, code)?;
+        if options.verbose {
+            for attribute in &code.attributes {
+                if attribute.name == "LineNumberTable" {
+                    line_number_lines(lines, &attribute.info);
+                } else {
+                    raw_attribute_lines(lines, "      ", std::slice::from_ref(attribute));
+                }
+            }
+        }
+    }
+    if options.verbose {
+        if let Some(signature) = &method.signature {
+            lines.push(format!("    Signature: {signature}"));
+        }
+        raw_attribute_lines(lines, "    ", &method.attributes);
+    }
+    Ok(())
+}
+
+fn code_lines(lines: &mut Vec<String>, constant_pool: &ConstantPool, code: &Code) -> JayResult<()> {
+    let mut pc = 0;
+    while pc < code.bytes.len() {
+        match Instruction::decode(&code.bytes, pc) {
+            Ok(instruction) => {
+                instruction_lines(lines, constant_pool, &instruction)?;
+                pc += instruction.length;
+            }
+            Err(error) => {
+                lines.push(format!("      {pc:>4}: // {error}"));
+                break;
+            }
+        }
+    }
+
+    if !code.exception_table.is_empty() {
+        lines.push("      Exception table:".to_string());
+        lines.push("         from    to  target type".to_string());
+        for handler in &code.exception_table {
+            let catch_type = match handler.catch_type {
+                0 => "any".to_string(),
+                index => format!("Class {}", constant_pool.class_name(index)?),
+            };
+            lines.push(format!(
+                "         {:>5} {:>5} {:>5}   {catch_type}",
+                handler.start_pc, handler.end_pc, handler.handler_pc
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn instruction_lines(
+    lines: &mut Vec<String>,
+    constant_pool: &ConstantPool,
+    instruction: &Instruction,
+) -> JayResult<()> {
+    let pc = instruction.pc;
+    let mnemonic = if instruction.wide {
+        format!("{}_w", instruction.mnemonic())
+    } else {
+        instruction.mnemonic().to_string()
+    };
+    let target = |offset: i32| pc as i64 + offset as i64;
+    let with_comment = |operands: String, index: u16| -> JayResult<String> {
+        Ok(format!(
+            "{mnemonic:<13} {operands:<19}// {}",
+            describe(constant_pool, index)?
+        ))
+    };
+
+    let text = match &instruction.operand {
+        Operand::None => mnemonic.clone(),
+        Operand::Int(value) if instruction.opcode == opcode::NEWARRAY => {
+            let array_type = ARRAY_TYPES
+                .get((*value as usize).wrapping_sub(4))
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| value.to_string());
+            format!("{mnemonic:<13} {array_type}")
+        }
+        Operand::Int(value) => format!("{mnemonic:<13} {value}"),
+        Operand::Local(index) => format!("{mnemonic:<13} {index}"),
+        Operand::Iinc { index, delta } => format!("{mnemonic:<13} {index}, {delta}"),
+        Operand::Constant(index) => with_comment(format!("#{index}"), *index)?,
+        Operand::Branch(offset) => format!("{mnemonic:<13} {}", target(*offset)),
+        Operand::InvokeInterface { index, count, .. } => {
+            with_comment(format!("#{index},  {count}"), *index)?
+        }
+        Operand::InvokeDynamic { index, .. } => with_comment(format!("#{index},  0"), *index)?,
+        Operand::MultiANewArray { index, dimensions } => {
+            with_comment(format!("#{index},  {dimensions}"), *index)?
+        }
+        Operand::TableSwitch {
+            default,
+            low,
+            offsets,
+        } => {
+            let high = *low as i64 + offsets.len() as i64 - 1;
+            lines.push(format!(
+                "      {pc:>4}: {mnemonic:<13} {{ // {low} to {high}"
+            ));
+            for (key, offset) in (*low as i64..).zip(offsets) {
+                lines.push(format!("      {key:>24}: {}", target(*offset)));
+            }
+            lines.push(format!("      {:>24}: {}", "default", target(*default)));
+            lines.push("            }".to_string());
+            return Ok(());
+        }
+        Operand::LookupSwitch { default, pairs } => {
+            lines.push(format!(
+                "      {pc:>4}: {mnemonic:<13} {{ // {}",
+                pairs.len()
+            ));
+            for (key, offset) in pairs {
+                lines.push(format!("      {key:>24}: {}", target(*offset)));
+            }
+            lines.push(format!("      {:>24}: {}", "default", target(*default)));
+            lines.push("            }".to_string());
+            return Ok(());
+        }
+    };
+    lines.push(format!("      {pc:>4}: {}", text.trim_end()));
+    Ok(())
+}
+
+fn constant_pool_lines(lines: &mut Vec<String>, constant_pool: &ConstantPool) -> JayResult<()> {
+    for index in 1..constant_pool.count() {
+        let (kind, operands) = match &constant_pool.entries[index as usize] {
+            CpEntry::Unusable => continue,
+            CpEntry::Utf8(_) => ("Utf8", String::new()),
+            CpEntry::Integer(_) => ("Integer", String::new()),
+            CpEntry::Float(_) => ("Float", String::new()),
+            CpEntry::Long(_) => ("Long", String::new()),
+            CpEntry::Double(_) => ("Double", String::new()),
+            CpEntry::Class { name_index } => ("Class", format!("#{name_index}")),
+            CpEntry::String { string_index } => ("String", format!("#{string_index}")),
+            CpEntry::FieldRef {
+                class_index,
+                name_and_type_index,
+            } => ("Fieldref", format!("#{class_index}.#{name_and_type_index}")),
+            CpEntry::MethodRef {
+                class_index,
+                name_and_type_index,
+            } => (
+                "Methodref",
+                format!("#{class_index}.#{name_and_type_index}"),
+            ),
+            CpEntry::InterfaceMethodRef {
+                class_index,
+                name_and_type_index,
+            } => (
+                "InterfaceMethodref",
+                format!("#{class_index}.#{name_and_type_index}"),
+            ),
+            CpEntry::NameAndType {
+                name_index,
+                descriptor_index,
+            } => ("NameAndType", format!("#{name_index}:#{descriptor_index}")),
+            CpEntry::MethodHandle {
+                reference_kind,
+                reference_index,
+            } => (
+                "MethodHandle",
+                format!("{reference_kind}:#{reference_index}"),
+            ),
+            CpEntry::MethodType { descriptor_index } => {
+                ("MethodType", format!("#{descriptor_index}"))
+            }
+            CpEntry::Dynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => (
+                "Dynamic",
+                format!("#{bootstrap_method_attr_index}:#{name_and_type_index}"),
+            ),
+            CpEntry::InvokeDynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => (
+                "InvokeDynamic",
+                format!("#{bootstrap_method_attr_index}:#{name_and_type_index}"),
+            ),
+            CpEntry::Module { name_index } => ("Module", format!("#{name_index}")),
+            CpEntry::Package { name_index } => ("Package", format!("#{name_index}")),
+        };
+        let value = resolve(constant_pool, index)?;
+        let number = format!("#{index}");
+        lines.push(if operands.is_empty() {
+            format!("  {number:>5} = {kind:<18} {value}")
+        } else {
+            format!("  {number:>5} = {kind:<18} {operands:<14} // {value}")
+        });
+    }
+    Ok(())
+}
+
+/// Describes a constant the way `javap` comments instruction operands.
+fn describe(constant_pool: &ConstantPool, index: u16) -> JayResult<String> {
+    let kind = match constant_pool.entry(index)? {
+        CpEntry::Integer(_) => "int",
+        CpEntry::Float(_) => "float",
+        CpEntry::Long(_) => "long",
+        CpEntry::Double(_) => "double",
+        CpEntry::Class { .. } => "class",
+        CpEntry::String { .. } => "String",
+        CpEntry::FieldRef { .. } => "Field",
+        CpEntry::MethodRef { .. } => "Method",
+        CpEntry::InterfaceMethodRef { .. } => "InterfaceMethod",
+        CpEntry::MethodHandle { .. } => "MethodHandle",
+        CpEntry::MethodType { .. } => "MethodType",
+        CpEntry::Dynamic { .. } => "Dynamic",
+        CpEntry::InvokeDynamic { .. } => "InvokeDynamic",
+        _ => return resolve(constant_pool, index),
+    };
+    Ok(format!("{kind} {}", resolve(constant_pool, index)?))
+}
+
+/// Resolves a constant to the text `javap` shows after `//`.
+fn resolve(constant_pool: &ConstantPool, index: u16) -> JayResult<String> {
+    let quote_name = |name: &str| {
+        if name.starts_with('<') {
+            format!("\"{name}\"")
+        } else {
+            name.to_string()
+        }
+    };
+    let member = |class_index: u16, name_and_type_index: u16| -> JayResult<String> {
+        let member = constant_pool.member_ref(class_index, name_and_type_index)?;
+        Ok(format!(
+            "{}.{}:{}",
+            member.class_name,
+            quote_name(member.name),
+            member.descriptor
+        ))
+    };
+    let call_site = |bootstrap: u16, name_and_type_index: u16| -> JayResult<String> {
+        let (name, descriptor) = constant_pool.name_and_type(name_and_type_index)?;
+        Ok(format!("#{bootstrap}:{name}:{descriptor}"))
+    };
+
+    Ok(match constant_pool.entry(index)? {
+        CpEntry::Unusable => unreachable!("entry() rejects unusable slots"),
+        CpEntry::Utf8(value) => escape(value),
+        CpEntry::Integer(value) => value.to_string(),
+        CpEntry::Float(bits) => format!("{:?}f", f32::from_bits(*bits)),
+        CpEntry::Long(value) => format!("{value}l"),
+        CpEntry::Double(bits) => format!("{:?}d", f64::from_bits(*bits)),
+        CpEntry::Class { name_index } => {
+            let name = constant_pool.utf8(*name_index)?;
+            if name.starts_with('[') {
+                format!("\"{name}\"")
+            } else {
+                name.to_string()
+            }
+        }
+        CpEntry::String { string_index } => escape(constant_pool.utf8(*string_index)?),
+        CpEntry::FieldRef {
+            class_index,
+            name_and_type_index,
+        }
+        | CpEntry::MethodRef {
+            class_index,
+            name_and_type_index,
+        }
+        | CpEntry::InterfaceMethodRef {
+            class_index,
+            name_and_type_index,
+        } => member(*class_index, *name_and_type_index)?,
+        CpEntry::NameAndType {
+            name_index,
+            descriptor_index,
+        } => format!(
+            "{}:{}",
+            quote_name(constant_pool.utf8(*name_index)?),
+            constant_pool.utf8(*descriptor_index)?
+        ),
+        CpEntry::MethodHandle {
+            reference_kind,
+            reference_index,
+        } => {
+            let kind = HANDLE_KINDS
+                .get((*reference_kind as usize).wrapping_sub(1))
+                .ok_or_else(|| {
+                    JayError::class_format(format!(
+                        "invalid method handle reference kind {reference_kind}"
+                    ))
+                })?;
+            format!(
+                "REF_{} {}",
+                handle_kind_name(kind),
+                resolve(constant_pool, *reference_index)?
+            )
+        }
+        CpEntry::MethodType { descriptor_index } => {
+            constant_pool.utf8(*descriptor_index)?.to_string()
+        }
+        CpEntry::Dynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        }
+        | CpEntry::InvokeDynamic {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        } => call_site(*bootstrap_method_attr_index, *name_and_type_index)?,
+        CpEntry::Module { name_index } | CpEntry::Package { name_index } => {
+            constant_pool.utf8(*name_index)?.to_string()
+        }
+    })
+}
+
+/// Converts a lowercase handle kind such as `invokestatic` to `javap`'s
+/// `invokeStatic` spelling.
+fn handle_kind_name(kind: &str) -> String {
+    match kind {
+        "newinvokespecial" => "newInvokeSpecial".to_string(),
+        _ => {
+            let split = if kind.starts_with("invoke") { 6 } else { 3 };
+            let (prefix, rest) = kind.split_at(split);
+            let mut rest = rest.chars();
+            let first = rest.next().map(|ch| ch.to_ascii_uppercase());
+            format!(
+                "{prefix}{}{}",
+                first.into_iter().collect::<String>(),
+                rest.as_str()
+            )
+        }
+    }
+}
+
+fn class_attribute_lines(
+    lines: &mut Vec<String>,
+    class_file: &ClassFile,
+    source_file: Option<&str>,
+) -> JayResult<()> {
+    let constant_pool = &class_file.constant_pool;
+    if let Some(source_file) = source_file {
+        lines.push(format!("SourceFile: \"{source_file}\""));
+    }
+    if let Some(signature) = &class_file.signature {
+        lines.push(format!("Signature: {signature}"));
+    }
+    if let Some(nest_host) = &class_file.nest_host {
+        lines.push(format!("NestHost: class {nest_host}"));
+    }
+    if !class_file.nest_members.is_empty() {
+        lines.push("NestMembers:".to_string());
+        for member in &class_file.nest_members {
+            lines.push(format!("  {member}"));
+        }
+    }
+    if !class_file.permitted_subclasses.is_empty() {
+        lines.push("PermittedSubclasses:".to_string());
+        for subclass in &class_file.permitted_subclasses {
+            lines.push(format!("  {subclass}"));
+        }
+    }
+    if !class_file.inner_classes.is_empty() {
+        lines.push("InnerClasses:".to_string());
+        for inner in &class_file.inner_classes {
+            let outer = inner.outer_class.as_deref().unwrap_or("-");
+            let name = inner.inner_name.as_deref().unwrap_or("-");
+            lines.push(format!("  {} of {outer} as {name}", inner.inner_class));
+        }
+    }
+    if !class_file.bootstrap_methods.is_empty() {
+        lines.push("BootstrapMethods:".to_string());
+        for (index, bootstrap) in class_file.bootstrap_methods.iter().enumerate() {
+            lines.push(format!(
+                "  {index}: #{} {}",
+                bootstrap.method_ref,
+                resolve(constant_pool, bootstrap.method_ref)?
+            ));
+            lines.push("    Method arguments:".to_string());
+            for argument in &bootstrap.arguments {
+                lines.push(format!(
+                    "      #{argument} {}",
+                    resolve(constant_pool, *argument)?
+                ));
+            }
+        }
+    }
+    let attributes: Vec<Attribute> = class_file
+        .attributes
+        .iter()
+        .filter(|attribute| attribute.name != "SourceFile")
+        .cloned()
+        .collect();
+    raw_attribute_lines(lines, "", &attributes);
+    Ok(())
+}
+
+fn line_number_lines(lines: &mut Vec<String>, info: &[u8]) {
+    lines.push("      LineNumberTable:".to_string());
+    let count = read_u2(info, 0).unwrap_or(0) as usize;
+    for entry in 0..count {
+        let offset = 2 + entry * 4;
+        if let (Some(start_pc), Some(line)) = (read_u2(info, offset), read_u2(info, offset + 2)) {
+            lines.push(format!("        line {line}: {start_pc}"));
+        }
+    }
+}
+
+fn raw_attribute_lines(lines: &mut Vec<String>, indent: &str, attributes: &[Attribute]) {
+    for attribute in attributes {
+        lines.push(format!(
+            "{indent}{}: length = 0x{:X}",
+            attribute.name,
+            attribute.info.len()
+        ));
+    }
+}
+
+fn class_reference(constant_pool: &ConstantPool, label: &str, name: &str) -> String {
+    let index = (1..constant_pool.count())
+        .find(|index| constant_pool.class_name(*index).ok() == Some(name))
+        .unwrap_or(0);
+    let reference = format!("{label}: #{index}");
+    format!("  {reference:<38}// {name}")
+}
+
+fn read_u2(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([
+        *bytes.get(offset)?,
+        *bytes.get(offset + 1)?,
+    ]))
+}
+
+/// Writes `(0x0021) ACC_PUBLIC, ACC_SUPER`.
+fn flag_names(names: &[(u16, &str)], access_flags: u16) -> String {
+    let names: Vec<String> = names
+        .iter()
+        .filter(|(flag, _)| access_flags & flag != 0)
+        .map(|(_, name)| format!("ACC_{}", name.to_uppercase()))
+        .collect();
+    format!("(0x{access_flags:04x}) {}", names.join(", "))
+}
+
+/// Writes the Java source modifiers among `keywords`, each followed by a space.
+fn modifiers(names: &[(u16, &str)], access_flags: u16, keywords: &[&str]) -> String {
+    names
+        .iter()
+        .filter(|(flag, name)| access_flags & flag != 0 && keywords.contains(name))
+        .map(|(_, name)| format!("{name} "))
+        .collect()
+}
+
+/// Splits the first field type off `descriptor`, rendered as Java source.
+fn split_type(descriptor: &str) -> Option<(String, &str)> {
+    let rest = descriptor.trim_start_matches('[');
+    let dimensions = descriptor.len() - rest.len();
+    let (base, rest) = match rest.as_bytes().first()? {
+        b'L' => {
+            let end = rest.find(';')?;
+            (rest[1..end].replace('/', "."), &rest[end + 1..])
+        }
+        byte => {
+            let name = match byte {
+                b'B' => "byte",
+                b'C' => "char",
+                b'D' => "double",
+                b'F' => "float",
+                b'I' => "int",
+                b'J' => "long",
+                b'S' => "short",
+                b'Z' => "boolean",
+                b'V' if dimensions == 0 => "void",
+                _ => return None,
+            };
+            (name.to_string(), &rest[1..])
+        }
+    };
+    Some((base + &"[]".repeat(dimensions), rest))
+}
+
+/// Returns a method descriptor's parameter and return types as Java source.
+fn method_types(descriptor: &str) -> Option<(Vec<String>, String)> {
+    let mut rest = descriptor.strip_prefix('(')?;
+    let mut parameters = Vec::new();
+    loop {
+        if let Some(after) = rest.strip_prefix(')') {
+            let (return_type, tail) = split_type(after)?;
+            return tail.is_empty().then_some((parameters, return_type));
+        }
+        let (parameter, after) = split_type(rest)?;
+        parameters.push(parameter);
+        rest = after;
+    }
+}
+
+/// Counts the local variable slots a method's parameters occupy.
+fn args_size(descriptor: &str) -> usize {
+    let Some(mut rest) = descriptor.strip_prefix('(') else {
+        return 0;
+    };
+    let mut size = 0;
+    while !rest.starts_with(')') {
+        let wide = matches!(rest.as_bytes().first(), Some(b'J' | b'D'));
+        let Some((_, after)) = split_type(rest) else {
+            break;
+        };
+        size += if wide { 2 } else { 1 };
+        rest = after;
+    }
+    size
+}
+
+/// Escapes control characters in constant text.
+fn escape(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\n' => text.push_str("\\n"),
+            '\r' => text.push_str("\\r"),
+            '\t' => text.push_str("\\t"),
+            ch if ch.is_control() => text.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => text.push(ch),
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classfile::builder::{ACC_PUBLIC, ACC_STATIC, ClassBuilder, Constant};
+    use crate::classfile::opcode::*;
+
+    #[test]
+    fn renders_java_types_from_descriptors() {
+        assert_eq!(
+            method_types("([[ILjava/lang/String;J)V"),
+            Some((
+                vec![
+                    "int[][]".to_string(),
+                    "java.lang.String".to_string(),
+                    "long".to_string()
+                ],
+                "void".to_string()
+            ))
+        );
+        assert_eq!(method_types("(I"), None);
+        assert_eq!(args_size("(IJLjava/lang/Object;D)V"), 6);
+        assert_eq!(handle_kind_name("invokestatic"), "invokeStatic");
+        assert_eq!(handle_kind_name("getfield"), "getField");
+    }
+
+    #[test]
+    fn prints_declarations_and_code() {
+        let mut class = ClassBuilder::new("demo/Tool");
+        let mut method = class.method(
+            ACC_PUBLIC | ACC_STATIC | 0x0080,
+            "run",
+            "([Ljava/lang/String;)I",
+        );
+        let done = method.new_label();
+        method
+            .local(ALOAD, 0)
+            .jump(IFNULL, done)
+            .ldc(Constant::Long(7))
+            .op(L2I)
+            .op(IRETURN)
+            .bind(done)
+            .op(ICONST_0)
+            .op(IRETURN);
+        method.finish();
+        let class_file = class.build().unwrap();
+        let options = JavapOptions {
+            code: true,
+            verbose: false,
+        };
+
+        let text = javap(&class_file, "/tmp/demo/Tool.class", options).unwrap();
+
+        assert_eq!(
+            text,
+            "Classfile /tmp/demo/Tool.class\n\
+             public class demo.Tool extends java.lang.Object {\n  \
+             public static int run(java.lang.String...);\n    \
+             Code:\n         \
+             0: aload_0\n         \
+             1: ifnull        9\n         \
+             4: ldc2_w        #1                 // long 7l\n         \
+             7: l2i\n         \
+             8: ireturn\n         \
+             9: iconst_0\n        \
+             10: ireturn\n\
+             }\n"
+        );
+    }
+}
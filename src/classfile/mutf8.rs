@@ -0,0 +1,127 @@
+//! JVM modified UTF-8 (JVMS 4.4.7).
+//!
+//! Differs from standard UTF-8 in two ways: NUL is written as `C0 80`, and
+//! supplementary characters are written as two three-byte surrogates.
+
+use std::borrow::Cow;
+
+use crate::{JayError, JayResult};
+
+/// Decodes modified UTF-8, borrowing when the bytes are plain ASCII.
+pub fn decode(bytes: &[u8]) -> JayResult<Cow<'_, str>> {
+    if bytes.iter().all(|byte| (1..0x80).contains(byte)) {
+        // All-ASCII input without NUL is identical in both encodings.
+        return Ok(Cow::Borrowed(std::str::from_utf8(bytes).expect("ASCII")));
+    }
+
+    let mut value = String::with_capacity(bytes.len());
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let (unit, length) = decode_unit(bytes, offset)?;
+        offset += length;
+        let code_point = if (0xD800..0xDC00).contains(&unit) {
+            let low = match decode_unit(bytes, offset) {
+                Ok((low, length)) if (0xDC00..0xE000).contains(&low) => {
+                    offset += length;
+                    low
+                }
+                _ => return Err(invalid(offset, "unpaired surrogate")),
+            };
+            0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00)
+        } else if (0xDC00..0xE000).contains(&unit) {
+            return Err(invalid(offset, "unpaired surrogate"));
+        } else {
+            unit
+        };
+        value.push(char::from_u32(code_point).expect("valid scalar value"));
+    }
+    Ok(Cow::Owned(value))
+}
+
+/// Encodes `value` as modified UTF-8.
+pub fn encode(value: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut units = [0; 2];
+    for ch in value.chars() {
+        for unit in ch.encode_utf16(&mut units) {
+            let unit = *unit as u32;
+            match unit {
+                0x01..=0x7F => bytes.push(unit as u8),
+                0x00 | 0x80..=0x7FF => {
+                    bytes.push(0xC0 | (unit >> 6) as u8);
+                    bytes.push(0x80 | (unit & 0x3F) as u8);
+                }
+                _ => {
+                    bytes.push(0xE0 | (unit >> 12) as u8);
+                    bytes.push(0x80 | ((unit >> 6) & 0x3F) as u8);
+                    bytes.push(0x80 | (unit & 0x3F) as u8);
+                }
+            }
+        }
+    }
+    bytes
+}
+
+/// Decodes one UTF-16 code unit and returns it with its encoded length.
+fn decode_unit(bytes: &[u8], offset: usize) -> JayResult<(u32, usize)> {
+    let continuation = |index: usize| match bytes.get(offset + index) {
+        Some(byte) if byte & 0xC0 == 0x80 => Ok((byte & 0x3F) as u32),
+        _ => Err(invalid(offset, "truncated or invalid continuation byte")),
+    };
+    match bytes[offset] {
+        0x00 => Err(invalid(offset, "NUL byte")),
+        byte @ 0x01..=0x7F => Ok((byte as u32, 1)),
+        byte @ 0xC0..=0xDF => Ok(((((byte & 0x1F) as u32) << 6) | continuation(1)?, 2)),
+        byte @ 0xE0..=0xEF => Ok((
+            (((byte & 0x0F) as u32) << 12) | (continuation(1)? << 6) | continuation(2)?,
+            3,
+        )),
+        _ => Err(invalid(offset, "invalid lead byte")),
+    }
+}
+
+fn invalid(offset: usize, reason: &str) -> JayError {
+    JayError::class_format(format!("invalid modified UTF-8 at byte {offset}: {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_nul_and_supplementary_characters() {
+        let value = "a\0b é ✓ 😀";
+
+        let bytes = encode(value);
+
+        assert_eq!(&bytes[..4], &[b'a', 0xC0, 0x80, b'b']);
+        assert_eq!(
+            &bytes[bytes.len() - 6..],
+            &[0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80]
+        );
+        assert_eq!(decode(&bytes).unwrap(), value);
+    }
+
+    #[test]
+    fn borrows_plain_ascii() {
+        assert!(matches!(
+            decode(b"java/lang/Object").unwrap(),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn rejects_standard_utf8_forms_that_modified_utf8_forbids() {
+        let error = decode(&[b'a', 0x00]).unwrap_err();
+        assert!(error.to_string().contains("at byte 1: NUL byte"));
+
+        let error = decode("😀".as_bytes()).unwrap_err();
+        assert!(error.to_string().contains("invalid lead byte"));
+
+        let error = decode(&[0xED, 0xA0, 0xBD, b'x']).unwrap_err();
+        assert!(error.to_string().contains("unpaired surrogate"));
+
+        let error = decode(&[0xC3]).unwrap_err();
+        assert!(error.to_string().contains("truncated"));
+    }
+}
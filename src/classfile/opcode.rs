@@ -0,0 +1,593 @@
+//! JVM instruction set metadata and bytecode decoding (JVMS 6.5).
+
+use crate::{JayError, JayResult};
+
+/// Operand layout that follows an opcode byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operands {
+    None,
+    /// Signed byte immediate (`bipush`).
+    Byte,
+    /// Signed short immediate (`sipush`).
+    Short,
+    /// Local variable index; two bytes under `wide`.
+    Local,
+    /// One-byte constant pool index (`ldc`).
+    ConstantU1,
+    /// Two-byte constant pool index.
+    Constant,
+    /// Two-byte signed branch offset.
+    Branch,
+    /// Four-byte signed branch offset.
+    WideBranch,
+    Iinc,
+    InvokeInterface,
+    InvokeDynamic,
+    /// Primitive array type code (`newarray`).
+    ArrayType,
+    MultiANewArray,
+    TableSwitch,
+    LookupSwitch,
+    Wide,
+}
+
+/// Static description of one opcode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpcodeInfo {
+    pub opcode: u8,
+    pub mnemonic: &'static str,
+    pub operands: Operands,
+    /// Operand stack slots consumed, for opcodes with a fixed stack effect.
+    pub pop: u8,
+    /// Operand stack slots produced, for opcodes with a fixed stack effect.
+    pub push: u8,
+}
+
+macro_rules! opcodes {
+    ($($name:ident = $value:literal $mnemonic:literal $operands:ident $pop:literal $push:literal;)*) => {
+        $(pub const $name: u8 = $value;)*
+
+        const TABLE: &[OpcodeInfo] = &[$(OpcodeInfo {
+            opcode: $value,
+            mnemonic: $mnemonic,
+            operands: Operands::$operands,
+            pop: $pop,
+            push: $push,
+        }),*];
+    };
+}
+
+// Field and invoke instructions and `multianewarray` list a zero stack effect
+// here; theirs depends on the referenced descriptor.
+opcodes! {
+    NOP = 0x00 "nop" None 0 0;
+    ACONST_NULL = 0x01 "aconst_null" None 0 1;
+    ICONST_M1 = 0x02 "iconst_m1" None 0 1;
+    ICONST_0 = 0x03 "iconst_0" None 0 1;
+    ICONST_1 = 0x04 "iconst_1" None 0 1;
+    ICONST_2 = 0x05 "iconst_2" None 0 1;
+    ICONST_3 = 0x06 "iconst_3" None 0 1;
+    ICONST_4 = 0x07 "iconst_4" None 0 1;
+    ICONST_5 = 0x08 "iconst_5" None 0 1;
+    LCONST_0 = 0x09 "lconst_0" None 0 2;
+    LCONST_1 = 0x0a "lconst_1" None 0 2;
+    FCONST_0 = 0x0b "fconst_0" None 0 1;
+    FCONST_1 = 0x0c "fconst_1" None 0 1;
+    FCONST_2 = 0x0d "fconst_2" None 0 1;
+    DCONST_0 = 0x0e "dconst_0" None 0 2;
+    DCONST_1 = 0x0f "dconst_1" None 0 2;
+    BIPUSH = 0x10 "bipush" Byte 0 1;
+    SIPUSH = 0x11 "sipush" Short 0 1;
+    LDC = 0x12 "ldc" ConstantU1 0 1;
+    LDC_W = 0x13 "ldc_w" Constant 0 1;
+    LDC2_W = 0x14 "ldc2_w" Constant 0 2;
+    ILOAD = 0x15 "iload" Local 0 1;
+    LLOAD = 0x16 "lload" Local 0 2;
+    FLOAD = 0x17 "fload" Local 0 1;
+    DLOAD = 0x18 "dload" Local 0 2;
+    ALOAD = 0x19 "aload" Local 0 1;
+    ILOAD_0 = 0x1a "iload_0" None 0 1;
+    ILOAD_1 = 0x1b "iload_1" None 0 1;
+    ILOAD_2 = 0x1c "iload_2" None 0 1;
+    ILOAD_3 = 0x1d "iload_3" None 0 1;
+    LLOAD_0 = 0x1e "lload_0" None 0 2;
+    LLOAD_1 = 0x1f "lload_1" None 0 2;
+    LLOAD_2 = 0x20 "lload_2" None 0 2;
+    LLOAD_3 = 0x21 "lload_3" None 0 2;
+    FLOAD_0 = 0x22 "fload_0" None 0 1;
+    FLOAD_1 = 0x23 "fload_1" None 0 1;
+    FLOAD_2 = 0x24 "fload_2" None 0 1;
+    FLOAD_3 = 0x25 "fload_3" None 0 1;
+    DLOAD_0 = 0x26 "dload_0" None 0 2;
+    DLOAD_1 = 0x27 "dload_1" None 0 2;
+    DLOAD_2 = 0x28 "dload_2" None 0 2;
+    DLOAD_3 = 0x29 "dload_3" None 0 2;
+    ALOAD_0 = 0x2a "aload_0" None 0 1;
+    ALOAD_1 = 0x2b "aload_1" None 0 1;
+    ALOAD_2 = 0x2c "aload_2" None 0 1;
+    ALOAD_3 = 0x2d "aload_3" None 0 1;
+    IALOAD = 0x2e "iaload" None 2 1;
+    LALOAD = 0x2f "laload" None 2 2;
+    FALOAD = 0x30 "faload" None 2 1;
+    DALOAD = 0x31 "daload" None 2 2;
+    AALOAD = 0x32 "aaload" None 2 1;
+    BALOAD = 0x33 "baload" None 2 1;
+    CALOAD = 0x34 "caload" None 2 1;
+    SALOAD = 0x35 "saload" None 2 1;
+    ISTORE = 0x36 "istore" Local 1 0;
+    LSTORE = 0x37 "lstore" Local 2 0;
+    FSTORE = 0x38 "fstore" Local 1 0;
+    DSTORE = 0x39 "dstore" Local 2 0;
+    ASTORE = 0x3a "astore" Local 1 0;
+    ISTORE_0 = 0x3b "istore_0" None 1 0;
+    ISTORE_1 = 0x3c "istore_1" None 1 0;
+    ISTORE_2 = 0x3d "istore_2" None 1 0;
+    ISTORE_3 = 0x3e "istore_3" None 1 0;
+    LSTORE_0 = 0x3f "lstore_0" None 2 0;
+    LSTORE_1 = 0x40 "lstore_1" None 2 0;
+    LSTORE_2 = 0x41 "lstore_2" None 2 0;
+    LSTORE_3 = 0x42 "lstore_3" None 2 0;
+    FSTORE_0 = 0x43 "fstore_0" None 1 0;
+    FSTORE_1 = 0x44 "fstore_1" None 1 0;
+    FSTORE_2 = 0x45 "fstore_2" None 1 0;
+    FSTORE_3 = 0x46 "fstore_3" None 1 0;
+    DSTORE_0 = 0x47 "dstore_0" None 2 0;
+    DSTORE_1 = 0x48 "dstore_1" None 2 0;
+    DSTORE_2 = 0x49 "dstore_2" None 2 0;
+    DSTORE_3 = 0x4a "dstore_3" None 2 0;
+    ASTORE_0 = 0x4b "astore_0" None 1 0;
+    ASTORE_1 = 0x4c "astore_1" None 1 0;
+    ASTORE_2 = 0x4d "astore_2" None 1 0;
+    ASTORE_3 = 0x4e "astore_3" None 1 0;
+    IASTORE = 0x4f "iastore" None 3 0;
+    LASTORE = 0x50 "lastore" None 4 0;
+    FASTORE = 0x51 "fastore" None 3 0;
+    DASTORE = 0x52 "dastore" None 4 0;
+    AASTORE = 0x53 "aastore" None 3 0;
+    BASTORE = 0x54 "bastore" None 3 0;
+    CASTORE = 0x55 "castore" None 3 0;
+    SASTORE = 0x56 "sastore" None 3 0;
+    POP = 0x57 "pop" None 1 0;
+    POP2 = 0x58 "pop2" None 2 0;
+    DUP = 0x59 "dup" None 1 2;
+    DUP_X1 = 0x5a "dup_x1" None 2 3;
+    DUP_X2 = 0x5b "dup_x2" None 3 4;
+    DUP2 = 0x5c "dup2" None 2 4;
+    DUP2_X1 = 0x5d "dup2_x1" None 3 5;
+    DUP2_X2 = 0x5e "dup2_x2" None 4 6;
+    SWAP = 0x5f "swap" None 2 2;
+    IADD = 0x60 "iadd" None 2 1;
+    LADD = 0x61 "ladd" None 4 2;
+    FADD = 0x62 "fadd" None 2 1;
+    DADD = 0x63 "dadd" None 4 2;
+    ISUB = 0x64 "isub" None 2 1;
+    LSUB = 0x65 "lsub" None 4 2;
+    FSUB = 0x66 "fsub" None 2 1;
+    DSUB = 0x67 "dsub" None 4 2;
+    IMUL = 0x68 "imul" None 2 1;
+    LMUL = 0x69 "lmul" None 4 2;
+    FMUL = 0x6a "fmul" None 2 1;
+    DMUL = 0x6b "dmul" None 4 2;
+    IDIV = 0x6c "idiv" None 2 1;
+    LDIV = 0x6d "ldiv" None 4 2;
+    FDIV = 0x6e "fdiv" None 2 1;
+    DDIV = 0x6f "ddiv" None 4 2;
+    IREM = 0x70 "irem" None 2 1;
+    LREM = 0x71 "lrem" None 4 2;
+    FREM = 0x72 "frem" None 2 1;
+    DREM = 0x73 "drem" None 4 2;
+    INEG = 0x74 "ineg" None 1 1;
+    LNEG = 0x75 "lneg" None 2 2;
+    FNEG = 0x76 "fneg" None 1 1;
+    DNEG = 0x77 "dneg" None 2 2;
+    ISHL = 0x78 "ishl" None 2 1;
+    LSHL = 0x79 "lshl" None 3 2;
+    ISHR = 0x7a "ishr" None 2 1;
+    LSHR = 0x7b "lshr" None 3 2;
+    IUSHR = 0x7c "iushr" None 2 1;
+    LUSHR = 0x7d "lushr" None 3 2;
+    IAND = 0x7e "iand" None 2 1;
+    LAND = 0x7f "land" None 4 2;
+    IOR = 0x80 "ior" None 2 1;
+    LOR = 0x81 "lor" None 4 2;
+    IXOR = 0x82 "ixor" None 2 1;
+    LXOR = 0x83 "lxor" None 4 2;
+    IINC = 0x84 "iinc" Iinc 0 0;
+    I2L = 0x85 "i2l" None 1 2;
+    I2F = 0x86 "i2f" None 1 1;
+    I2D = 0x87 "i2d" None 1 2;
+    L2I = 0x88 "l2i" None 2 1;
+    L2F = 0x89 "l2f" None 2 1;
+    L2D = 0x8a "l2d" None 2 2;
+    F2I = 0x8b "f2i" None 1 1;
+    F2L = 0x8c "f2l" None 1 2;
+    F2D = 0x8d "f2d" None 1 2;
+    D2I = 0x8e "d2i" None 2 1;
+    D2L = 0x8f "d2l" None 2 2;
+    D2F = 0x90 "d2f" None 2 1;
+    I2B = 0x91 "i2b" None 1 1;
+    I2C = 0x92 "i2c" None 1 1;
+    I2S = 0x93 "i2s" None 1 1;
+    LCMP = 0x94 "lcmp" None 4 1;
+    FCMPL = 0x95 "fcmpl" None 2 1;
+    FCMPG = 0x96 "fcmpg" None 2 1;
+    DCMPL = 0x97 "dcmpl" None 4 1;
+    DCMPG = 0x98 "dcmpg" None 4 1;
+    IFEQ = 0x99 "ifeq" Branch 1 0;
+    IFNE = 0x9a "ifne" Branch 1 0;
+    IFLT = 0x9b "iflt" Branch 1 0;
+    IFGE = 0x9c "ifge" Branch 1 0;
+    IFGT = 0x9d "ifgt" Branch 1 0;
+    IFLE = 0x9e "ifle" Branch 1 0;
+    IF_ICMPEQ = 0x9f "if_icmpeq" Branch 2 0;
+    IF_ICMPNE = 0xa0 "if_icmpne" Branch 2 0;
+    IF_ICMPLT = 0xa1 "if_icmplt" Branch 2 0;
+    IF_ICMPGE = 0xa2 "if_icmpge" Branch 2 0;
+    IF_ICMPGT = 0xa3 "if_icmpgt" Branch 2 0;
+    IF_ICMPLE = 0xa4 "if_icmple" Branch 2 0;
+    IF_ACMPEQ = 0xa5 "if_acmpeq" Branch 2 0;
+    IF_ACMPNE = 0xa6 "if_acmpne" Branch 2 0;
+    GOTO = 0xa7 "goto" Branch 0 0;
+    JSR = 0xa8 "jsr" Branch 0 1;
+    RET = 0xa9 "ret" Local 0 0;
+    TABLESWITCH = 0xaa "tableswitch" TableSwitch 1 0;
+    LOOKUPSWITCH = 0xab "lookupswitch" LookupSwitch 1 0;
+    IRETURN = 0xac "ireturn" None 1 0;
+    LRETURN = 0xad "lreturn" None 2 0;
+    FRETURN = 0xae "freturn" None 1 0;
+    DRETURN = 0xaf "dreturn" None 2 0;
+    ARETURN = 0xb0 "areturn" None 1 0;
+    RETURN = 0xb1 "return" None 0 0;
+    GETSTATIC = 0xb2 "getstatic" Constant 0 0;
+    PUTSTATIC = 0xb3 "putstatic" Constant 0 0;
+    GETFIELD = 0xb4 "getfield" Constant 0 0;
+    PUTFIELD = 0xb5 "putfield" Constant 0 0;
+    INVOKEVIRTUAL = 0xb6 "invokevirtual" Constant 0 0;
+    INVOKESPECIAL = 0xb7 "invokespecial" Constant 0 0;
+    INVOKESTATIC = 0xb8 "invokestatic" Constant 0 0;
+    INVOKEINTERFACE = 0xb9 "invokeinterface" InvokeInterface 0 0;
+    INVOKEDYNAMIC = 0xba "invokedynamic" InvokeDynamic 0 0;
+    NEW = 0xbb "new" Constant 0 1;
+    NEWARRAY = 0xbc "newarray" ArrayType 1 1;
+    ANEWARRAY = 0xbd "anewarray" Constant 1 1;
+    ARRAYLENGTH = 0xbe "arraylength" None 1 1;
+    ATHROW = 0xbf "athrow" None 1 0;
+    CHECKCAST = 0xc0 "checkcast" Constant 1 1;
+    INSTANCEOF = 0xc1 "instanceof" Constant 1 1;
+    MONITORENTER = 0xc2 "monitorenter" None 1 0;
+    MONITOREXIT = 0xc3 "monitorexit" None 1 0;
+    WIDE = 0xc4 "wide" Wide 0 0;
+    MULTIANEWARRAY = 0xc5 "multianewarray" MultiANewArray 0 0;
+    IFNULL = 0xc6 "ifnull" Branch 1 0;
+    IFNONNULL = 0xc7 "ifnonnull" Branch 1 0;
+    GOTO_W = 0xc8 "goto_w" WideBranch 0 0;
+    JSR_W = 0xc9 "jsr_w" WideBranch 0 1;
+}
+
+/// Looks up an opcode; `None` for reserved and unassigned values.
+pub fn info(opcode: u8) -> Option<&'static OpcodeInfo> {
+    TABLE.get(opcode as usize)
+}
+
+/// Looks up an opcode by its lowercase JVMS mnemonic.
+pub fn by_mnemonic(mnemonic: &str) -> Option<&'static OpcodeInfo> {
+    TABLE.iter().find(|info| info.mnemonic == mnemonic)
+}
+
+/// Decoded operand of one instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operand {
+    None,
+    /// `bipush` and `sipush` immediates, and the `newarray` type code.
+    Int(i32),
+    Local(u16),
+    Constant(u16),
+    /// Branch offset relative to the instruction's own pc.
+    Branch(i32),
+    Iinc {
+        index: u16,
+        delta: i16,
+    },
+    InvokeInterface {
+        index: u16,
+        count: u8,
+        /// The trailing byte the JVMS requires to be zero.
+        padding: u8,
+    },
+    InvokeDynamic {
+        index: u16,
+        /// The trailing two bytes the JVMS requires to be zero.
+        padding: u16,
+    },
+    MultiANewArray {
+        index: u16,
+        dimensions: u8,
+    },
+    TableSwitch {
+        default: i32,
+        low: i32,
+        offsets: Vec<i32>,
+    },
+    LookupSwitch {
+        default: i32,
+        pairs: Vec<(i32, i32)>,
+    },
+}
+
+/// One decoded instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub pc: usize,
+    /// The operative opcode; for `wide` forms this is the widened opcode.
+    pub opcode: u8,
+    pub wide: bool,
+    pub operand: Operand,
+    /// Encoded length in bytes, including any `wide` prefix and switch padding.
+    pub length: usize,
+}
+
+impl Instruction {
+    /// Decodes the instruction starting at `pc`.
+    pub fn decode(code: &[u8], pc: usize) -> JayResult<Self> {
+        let mut reader = Reader { code, offset: pc };
+        let mut opcode = reader.u1()?;
+        let wide = opcode == WIDE;
+        if wide {
+            opcode = reader.u1()?;
+        }
+        let info = info(opcode).ok_or_else(|| {
+            JayError::class_format(format!("invalid opcode 0x{opcode:02x} at pc {pc}"))
+        })?;
+        if wide && !matches!(info.operands, Operands::Local | Operands::Iinc) {
+            return Err(JayError::class_format(format!(
+                "wide cannot modify {} at pc {pc}",
+                info.mnemonic
+            )));
+        }
+
+        let operand = match info.operands {
+            Operands::None => Operand::None,
+            Operands::Byte => Operand::Int(reader.u1()? as i8 as i32),
+            Operands::Short => Operand::Int(reader.u2()? as i16 as i32),
+            Operands::ArrayType => Operand::Int(reader.u1()? as i32),
+            Operands::Local if wide => Operand::Local(reader.u2()?),
+            Operands::Local => Operand::Local(reader.u1()? as u16),
+            Operands::ConstantU1 => Operand::Constant(reader.u1()? as u16),
+            Operands::Constant => Operand::Constant(reader.u2()?),
+            Operands::Branch => Operand::Branch(reader.u2()? as i16 as i32),
+            Operands::WideBranch => Operand::Branch(reader.i4()?),
+            Operands::Iinc if wide => Operand::Iinc {
+                index: reader.u2()?,
+                delta: reader.u2()? as i16,
+            },
+            Operands::Iinc => Operand::Iinc {
+                index: reader.u1()? as u16,
+                delta: reader.u1()? as i8 as i16,
+            },
+            Operands::InvokeInterface => Operand::InvokeInterface {
+                index: reader.u2()?,
+                count: reader.u1()?,
+                padding: reader.u1()?,
+            },
+            Operands::InvokeDynamic => Operand::InvokeDynamic {
+                index: reader.u2()?,
+                padding: reader.u2()?,
+            },
+            Operands::MultiANewArray => Operand::MultiANewArray {
+                index: reader.u2()?,
+                dimensions: reader.u1()?,
+            },
+            Operands::TableSwitch => {
+                reader.align()?;
+                let default = reader.i4()?;
+                let low = reader.i4()?;
+                let high = reader.i4()?;
+                if high < low {
+                    return Err(JayError::class_format(format!(
+                        "tableswitch at pc {pc} has high {high} below low {low}"
+                    )));
+                }
+                let offsets = (low..=high)
+                    .map(|_| reader.i4())
+                    .collect::<JayResult<_>>()?;
+                Operand::TableSwitch {
+                    default,
+                    low,
+                    offsets,
+                }
+            }
+            Operands::LookupSwitch => {
+                reader.align()?;
+                let default = reader.i4()?;
+                let count = reader.i4()?;
+                if count < 0 {
+                    return Err(JayError::class_format(format!(
+                        "lookupswitch at pc {pc} has negative pair count {count}"
+                    )));
+                }
+                let pairs = (0..count)
+                    .map(|_| Ok((reader.i4()?, reader.i4()?)))
+                    .collect::<JayResult<_>>()?;
+                Operand::LookupSwitch { default, pairs }
+            }
+            Operands::Wide => unreachable!("wide is consumed as a prefix"),
+        };
+
+        Ok(Self {
+            pc,
+            opcode,
+            wide,
+            operand,
+            length: reader.offset - pc,
+        })
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        TABLE[self.opcode as usize].mnemonic
+    }
+
+    /// Absolute targets of branch and switch instructions, default first.
+    pub fn branch_targets(&self) -> Vec<i64> {
+        let target = |offset: i32| self.pc as i64 + offset as i64;
+        match &self.operand {
+            Operand::Branch(offset) => vec![target(*offset)],
+            Operand::TableSwitch {
+                default, offsets, ..
+            } => std::iter::once(default)
+                .chain(offsets)
+                .map(|offset| target(*offset))
+                .collect(),
+            Operand::LookupSwitch { default, pairs } => std::iter::once(default)
+                .chain(pairs.iter().map(|(_, offset)| offset))
+                .map(|offset| target(*offset))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether execution can continue with the next instruction.
+    pub fn falls_through(&self) -> bool {
+        !matches!(
+            self.opcode,
+            GOTO | GOTO_W
+                | RET
+                | TABLESWITCH
+                | LOOKUPSWITCH
+                | IRETURN
+                | LRETURN
+                | FRETURN
+                | DRETURN
+                | ARETURN
+                | RETURN
+                | ATHROW
+        )
+    }
+}
+
+/// Decodes every instruction of a method body in order.
+pub fn decode_all(code: &[u8]) -> JayResult<Vec<Instruction>> {
+    let mut instructions = Vec::new();
+    let mut pc = 0;
+    while pc < code.len() {
+        let instruction = Instruction::decode(code, pc)?;
+        pc += instruction.length;
+        instructions.push(instruction);
+    }
+    Ok(instructions)
+}
+
+struct Reader<'a> {
+    code: &'a [u8],
+    offset: usize,
+}
+
+impl Reader<'_> {
+    fn u1(&mut self) -> JayResult<u8> {
+        let value = *self
+            .code
+            .get(self.offset)
+            .ok_or_else(|| JayError::class_format("unexpected end of bytecode"))?;
+        self.offset += 1;
+        Ok(value)
+    }
+
+    fn u2(&mut self) -> JayResult<u16> {
+        Ok(u16::from_be_bytes([self.u1()?, self.u1()?]))
+    }
+
+    fn i4(&mut self) -> JayResult<i32> {
+        Ok(i32::from_be_bytes([
+            self.u1()?,
+            self.u1()?,
+            self.u1()?,
+            self.u1()?,
+        ]))
+    }
+
+    /// Skips switch padding up to the next four-byte boundary.
+    fn align(&mut self) -> JayResult<()> {
+        while !self.offset.is_multiple_of(4) {
+            self.u1()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_is_indexed_by_opcode() {
+        for (index, info) in TABLE.iter().enumerate() {
+            assert_eq!(info.opcode as usize, index, "{}", info.mnemonic);
+        }
+        assert_eq!(TABLE.len(), 0xca);
+        assert_eq!(by_mnemonic("invokedynamic").unwrap().opcode, INVOKEDYNAMIC);
+        assert!(info(0xca).is_none());
+    }
+
+    #[test]
+    fn decodes_wide_switch_and_invoke_operands() {
+        let code = [
+            WIDE,
+            ILOAD,
+            0x01,
+            0x00, // wide iload 256
+            TABLESWITCH,
+            0x00,
+            0x00,
+            0x00, // padding
+            0x00,
+            0x00,
+            0x00,
+            0x1c, // default +28
+            0x00,
+            0x00,
+            0x00,
+            0x01,
+            0x00,
+            0x00,
+            0x00,
+            0x02, // low 1, high 2
+            0x00,
+            0x00,
+            0x00,
+            0x1c,
+            0xff,
+            0xff,
+            0xff,
+            0xfc, // +28, -4
+            INVOKEINTERFACE,
+            0x00,
+            0x07,
+            0x02,
+            0x01, // #7, count 2, padding 1
+            RETURN,
+        ];
+
+        let instructions = decode_all(&code).unwrap();
+
+        assert_eq!(instructions[0].operand, Operand::Local(256));
+        assert!(instructions[0].wide);
+        assert_eq!(instructions[1].pc, 4);
+        assert_eq!(instructions[1].length, 24);
+        assert_eq!(instructions[1].branch_targets(), [32, 32, 0]);
+        assert_eq!(
+            instructions[2].operand,
+            Operand::InvokeInterface {
+                index: 7,
+                count: 2,
+                padding: 1
+            }
+        );
+        assert!(!instructions[3].falls_through());
+    }
+
+    #[test]
+    fn rejects_unassigned_opcodes() {
+        let error = decode_all(&[NOP, 0xcb]).unwrap_err();
+
+        assert!(error.to_string().contains("invalid opcode 0xcb at pc 1"));
+    }
+}
@@ -0,0 +1,608 @@
+//! Generic `Signature` attribute parsing (JVMS 4.7.9.1).
+//!
+//! Descriptors erase generics; signatures keep type parameters, bounds,
+//! wildcards, and parameterized types. Every model type displays back to its
+//! JVMS signature form.
+
+use std::fmt;
+
+use crate::{JayError, JayResult};
+
+/// Signature of a generic class or interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassSignature {
+    pub type_parameters: Vec<TypeParameter>,
+    pub super_class: ClassTypeSignature,
+    pub interfaces: Vec<ClassTypeSignature>,
+}
+
+/// Signature of a generic method or constructor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodSignature {
+    pub type_parameters: Vec<TypeParameter>,
+    pub parameters: Vec<JavaTypeSignature>,
+    /// `None` for `void` methods.
+    pub return_type: Option<JavaTypeSignature>,
+    pub throws: Vec<ReferenceTypeSignature>,
+}
+
+/// Signature of a field, record component, or local variable.
+pub type FieldSignature = ReferenceTypeSignature;
+
+/// A formal type parameter such as `T extends Number & Comparable<T>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeParameter {
+    pub name: String,
+    /// `None` when the first bound is an interface, as in `<T::Ljava/lang/Runnable;>`.
+    pub class_bound: Option<ReferenceTypeSignature>,
+    pub interface_bounds: Vec<ReferenceTypeSignature>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JavaTypeSignature {
+    Base(BaseType),
+    Reference(ReferenceTypeSignature),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaseType {
+    Byte,
+    Char,
+    Double,
+    Float,
+    Int,
+    Long,
+    Short,
+    Boolean,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReferenceTypeSignature {
+    Class(ClassTypeSignature),
+    TypeVariable(String),
+    Array(Box<JavaTypeSignature>),
+}
+
+/// A possibly parameterized class type such as `java/util/Map<TK;TV;>.Entry<TK;TV;>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassTypeSignature {
+    /// Internal name of the outermost class, such as `java/util/Map`.
+    pub name: String,
+    pub type_arguments: Vec<TypeArgument>,
+    /// Member class segments that follow `.`, innermost last.
+    pub inner_classes: Vec<SimpleClassTypeSignature>,
+}
+
+/// A member class segment of a [`ClassTypeSignature`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimpleClassTypeSignature {
+    pub name: String,
+    pub type_arguments: Vec<TypeArgument>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeArgument {
+    /// The unbounded wildcard `*`.
+    Any,
+    Exact(ReferenceTypeSignature),
+    /// A `? extends` wildcard, written `+`.
+    Extends(ReferenceTypeSignature),
+    /// A `? super` wildcard, written `-`.
+    Super(ReferenceTypeSignature),
+}
+
+impl ClassSignature {
+    pub fn parse(signature: &str) -> JayResult<Self> {
+        let mut parser = SignatureParser::new(signature);
+        let type_parameters = parser.parse_type_parameters()?;
+        let super_class = parser.parse_class_type()?;
+        let mut interfaces = Vec::new();
+        while !parser.is_at_end() {
+            interfaces.push(parser.parse_class_type()?);
+        }
+        Ok(Self {
+            type_parameters,
+            super_class,
+            interfaces,
+        })
+    }
+}
+
+impl MethodSignature {
+    pub fn parse(signature: &str) -> JayResult<Self> {
+        let mut parser = SignatureParser::new(signature);
+        let type_parameters = parser.parse_type_parameters()?;
+        parser.expect('(')?;
+        let mut parameters = Vec::new();
+        while !parser.consume(')') {
+            parameters.push(parser.parse_java_type()?);
+        }
+        let return_type = if parser.consume('V') {
+            None
+        } else {
+            Some(parser.parse_java_type()?)
+        };
+        let mut throws = Vec::new();
+        while parser.consume('^') {
+            match parser.parse_reference_type()? {
+                ReferenceTypeSignature::Array(_) => {
+                    return Err(parser.error("throws signature must not be an array"));
+                }
+                throw => throws.push(throw),
+            }
+        }
+        parser.expect_end()?;
+        Ok(Self {
+            type_parameters,
+            parameters,
+            return_type,
+            throws,
+        })
+    }
+}
+
+impl ReferenceTypeSignature {
+    /// Parses a field signature.
+    pub fn parse(signature: &str) -> JayResult<Self> {
+        let mut parser = SignatureParser::new(signature);
+        let reference = parser.parse_reference_type()?;
+        parser.expect_end()?;
+        Ok(reference)
+    }
+}
+
+impl ClassTypeSignature {
+    /// Returns the erased internal class name, joining member classes with `$`.
+    pub fn binary_name(&self) -> String {
+        let mut name = self.name.clone();
+        for inner in &self.inner_classes {
+            name.push('$');
+            name.push_str(&inner.name);
+        }
+        name
+    }
+}
+
+impl BaseType {
+    fn from_char(value: char) -> Option<Self> {
+        match value {
+            'B' => Some(Self::Byte),
+            'C' => Some(Self::Char),
+            'D' => Some(Self::Double),
+            'F' => Some(Self::Float),
+            'I' => Some(Self::Int),
+            'J' => Some(Self::Long),
+            'S' => Some(Self::Short),
+            'Z' => Some(Self::Boolean),
+            _ => None,
+        }
+    }
+
+    fn as_char(self) -> char {
+        match self {
+            Self::Byte => 'B',
+            Self::Char => 'C',
+            Self::Double => 'D',
+            Self::Float => 'F',
+            Self::Int => 'I',
+            Self::Long => 'J',
+            Self::Short => 'S',
+            Self::Boolean => 'Z',
+        }
+    }
+}
+
+struct SignatureParser<'a> {
+    signature: &'a str,
+    offset: usize,
+}
+
+impl<'a> SignatureParser<'a> {
+    fn new(signature: &'a str) -> Self {
+        Self {
+            signature,
+            offset: 0,
+        }
+    }
+
+    fn parse_type_parameters(&mut self) -> JayResult<Vec<TypeParameter>> {
+        let mut type_parameters = Vec::new();
+        if !self.consume('<') {
+            return Ok(type_parameters);
+        }
+        loop {
+            let name = self.parse_identifier()?;
+            self.expect(':')?;
+            let class_bound = match self.peek() {
+                Some(':') => None,
+                _ => Some(self.parse_reference_type()?),
+            };
+            let mut interface_bounds = Vec::new();
+            while self.consume(':') {
+                interface_bounds.push(self.parse_reference_type()?);
+            }
+            type_parameters.push(TypeParameter {
+                name,
+                class_bound,
+                interface_bounds,
+            });
+            if self.consume('>') {
+                return Ok(type_parameters);
+            }
+        }
+    }
+
+    fn parse_java_type(&mut self) -> JayResult<JavaTypeSignature> {
+        if let Some(base_type) = self.peek().and_then(BaseType::from_char) {
+            self.offset += 1;
+            return Ok(JavaTypeSignature::Base(base_type));
+        }
+        Ok(JavaTypeSignature::Reference(self.parse_reference_type()?))
+    }
+
+    fn parse_reference_type(&mut self) -> JayResult<ReferenceTypeSignature> {
+        match self.peek() {
+            Some('L') => Ok(ReferenceTypeSignature::Class(self.parse_class_type()?)),
+            Some('T') => {
+                self.offset += 1;
+                let name = self.parse_identifier()?;
+                self.expect(';')?;
+                Ok(ReferenceTypeSignature::TypeVariable(name))
+            }
+            Some('[') => {
+                self.offset += 1;
+                Ok(ReferenceTypeSignature::Array(Box::new(
+                    self.parse_java_type()?,
+                )))
+            }
+            _ => Err(self.error("expected reference type")),
+        }
+    }
+
+    fn parse_class_type(&mut self) -> JayResult<ClassTypeSignature> {
+        self.expect('L')?;
+        let mut name = self.parse_identifier()?;
+        while self.consume('/') {
+            name.push('/');
+            name.push_str(&self.parse_identifier()?);
+        }
+        let type_arguments = self.parse_type_arguments()?;
+        let mut inner_classes = Vec::new();
+        while self.consume('.') {
+            let name = self.parse_identifier()?;
+            let type_arguments = self.parse_type_arguments()?;
+            inner_classes.push(SimpleClassTypeSignature {
+                name,
+                type_arguments,
+            });
+        }
+        self.expect(';')?;
+        Ok(ClassTypeSignature {
+            name,
+            type_arguments,
+            inner_classes,
+        })
+    }
+
+    fn parse_type_arguments(&mut self) -> JayResult<Vec<TypeArgument>> {
+        let mut type_arguments = Vec::new();
+        if !self.consume('<') {
+            return Ok(type_arguments);
+        }
+        loop {
+            let type_argument = if self.consume('*') {
+                TypeArgument::Any
+            } else if self.consume('+') {
+                TypeArgument::Extends(self.parse_reference_type()?)
+            } else if self.consume('-') {
+                TypeArgument::Super(self.parse_reference_type()?)
+            } else {
+                TypeArgument::Exact(self.parse_reference_type()?)
+            };
+            type_arguments.push(type_argument);
+            if self.consume('>') {
+                return Ok(type_arguments);
+            }
+        }
+    }
+
+    fn parse_identifier(&mut self) -> JayResult<String> {
+        let rest = &self.signature[self.offset..];
+        let length = rest
+            .find(['.', ';', '[', '/', '<', '>', ':'])
+            .unwrap_or(rest.len());
+        if length == 0 {
+            return Err(self.error("expected identifier"));
+        }
+        self.offset += length;
+        Ok(rest[..length].to_string())
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.signature[self.offset..].chars().next()
+    }
+
+    fn consume(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.offset += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> JayResult<()> {
+        if self.consume(expected) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{expected}'")))
+        }
+    }
+
+    fn expect_end(&self) -> JayResult<()> {
+        if self.is_at_end() {
+            Ok(())
+        } else {
+            Err(self.error("unexpected trailing characters"))
+        }
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.offset == self.signature.len()
+    }
+
+    fn error(&self, message: &str) -> JayError {
+        JayError::class_format(format!(
+            "malformed signature {}: {message} at offset {}",
+            self.signature, self.offset
+        ))
+    }
+}
+
+fn write_type_parameters(f: &mut fmt::Formatter<'_>, parameters: &[TypeParameter]) -> fmt::Result {
+    if parameters.is_empty() {
+        return Ok(());
+    }
+    write!(f, "<")?;
+    for parameter in parameters {
+        write!(f, "{parameter}")?;
+    }
+    write!(f, ">")
+}
+
+fn write_type_arguments(f: &mut fmt::Formatter<'_>, arguments: &[TypeArgument]) -> fmt::Result {
+    if arguments.is_empty() {
+        return Ok(());
+    }
+    write!(f, "<")?;
+    for argument in arguments {
+        write!(f, "{argument}")?;
+    }
+    write!(f, ">")
+}
+
+impl fmt::Display for ClassSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_type_parameters(f, &self.type_parameters)?;
+        write!(f, "{}", self.super_class)?;
+        for interface in &self.interfaces {
+            write!(f, "{interface}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for MethodSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_type_parameters(f, &self.type_parameters)?;
+        write!(f, "(")?;
+        for parameter in &self.parameters {
+            write!(f, "{parameter}")?;
+        }
+        write!(f, ")")?;
+        match &self.return_type {
+            Some(return_type) => write!(f, "{return_type}")?,
+            None => write!(f, "V")?,
+        }
+        for throw in &self.throws {
+            write!(f, "^{throw}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TypeParameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.name)?;
+        if let Some(class_bound) = &self.class_bound {
+            write!(f, "{class_bound}")?;
+        }
+        for bound in &self.interface_bounds {
+            write!(f, ":{bound}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for JavaTypeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Base(base_type) => write!(f, "{}", base_type.as_char()),
+            Self::Reference(reference) => write!(f, "{reference}"),
+        }
+    }
+}
+
+impl fmt::Display for ReferenceTypeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Class(class_type) => write!(f, "{class_type}"),
+            Self::TypeVariable(name) => write!(f, "T{name};"),
+            Self::Array(component) => write!(f, "[{component}"),
+        }
+    }
+}
+
+impl fmt::Display for ClassTypeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "L{}", self.name)?;
+        write_type_arguments(f, &self.type_arguments)?;
+        for inner in &self.inner_classes {
+            write!(f, ".{}", inner.name)?;
+            write_type_arguments(f, &inner.type_arguments)?;
+        }
+        write!(f, ";")
+    }
+}
+
+impl fmt::Display for TypeArgument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Any => write!(f, "*"),
+            Self::Exact(reference) => write!(f, "{reference}"),
+            Self::Extends(reference) => write!(f, "+{reference}"),
+            Self::Super(reference) => write!(f, "-{reference}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class_type(name: &str, type_arguments: Vec<TypeArgument>) -> ClassTypeSignature {
+        ClassTypeSignature {
+            name: name.to_string(),
+            type_arguments,
+            inner_classes: Vec::new(),
+        }
+    }
+
+    fn type_variable(name: &str) -> ReferenceTypeSignature {
+        ReferenceTypeSignature::TypeVariable(name.to_string())
+    }
+
+    #[test]
+    fn parses_class_signature_with_bounded_type_parameters() {
+        let text = "<T:Ljava/lang/Number;E::Ljava/lang/Comparable<-TE;>;>Ljava/util/AbstractList<TT;>;Ljava/util/RandomAccess;";
+
+        let signature = ClassSignature::parse(text).unwrap();
+
+        assert_eq!(
+            signature.type_parameters,
+            vec![
+                TypeParameter {
+                    name: "T".to_string(),
+                    class_bound: Some(ReferenceTypeSignature::Class(class_type(
+                        "java/lang/Number",
+                        Vec::new()
+                    ))),
+                    interface_bounds: Vec::new(),
+                },
+                TypeParameter {
+                    name: "E".to_string(),
+                    class_bound: None,
+                    interface_bounds: vec![ReferenceTypeSignature::Class(class_type(
+                        "java/lang/Comparable",
+                        vec![TypeArgument::Super(type_variable("E"))]
+                    ))],
+                },
+            ]
+        );
+        assert_eq!(
+            signature.super_class,
+            class_type(
+                "java/util/AbstractList",
+                vec![TypeArgument::Exact(type_variable("T"))]
+            )
+        );
+        assert_eq!(
+            signature.interfaces,
+            vec![class_type("java/util/RandomAccess", Vec::new())]
+        );
+        assert_eq!(signature.to_string(), text);
+    }
+
+    #[test]
+    fn parses_method_signature_with_wildcards_arrays_and_throws() {
+        let text =
+            "<X:Ljava/lang/Throwable;>(Ljava/util/List<+TT;>;[IZ)[TT;^TX;^Ljava/io/IOException;";
+
+        let signature = MethodSignature::parse(text).unwrap();
+
+        assert_eq!(signature.type_parameters.len(), 1);
+        assert_eq!(
+            signature.parameters,
+            vec![
+                JavaTypeSignature::Reference(ReferenceTypeSignature::Class(class_type(
+                    "java/util/List",
+                    vec![TypeArgument::Extends(type_variable("T"))]
+                ))),
+                JavaTypeSignature::Reference(ReferenceTypeSignature::Array(Box::new(
+                    JavaTypeSignature::Base(BaseType::Int)
+                ))),
+                JavaTypeSignature::Base(BaseType::Boolean),
+            ]
+        );
+        assert_eq!(
+            signature.return_type,
+            Some(JavaTypeSignature::Reference(ReferenceTypeSignature::Array(
+                Box::new(JavaTypeSignature::Reference(type_variable("T")))
+            )))
+        );
+        assert_eq!(
+            signature.throws,
+            vec![
+                type_variable("X"),
+                ReferenceTypeSignature::Class(class_type("java/io/IOException", Vec::new())),
+            ]
+        );
+        assert_eq!(signature.to_string(), text);
+    }
+
+    #[test]
+    fn parses_void_method_signature() {
+        let signature = MethodSignature::parse("(Ljava/util/Map<**>;)V").unwrap();
+
+        assert_eq!(signature.return_type, None);
+        assert_eq!(signature.to_string(), "(Ljava/util/Map<**>;)V");
+    }
+
+    #[test]
+    fn parses_field_signature_with_inner_class_type_arguments() {
+        let text = "Ljava/util/Map<TK;TV;>.Entry<TK;*>;";
+
+        let ReferenceTypeSignature::Class(class_type) =
+            ReferenceTypeSignature::parse(text).unwrap()
+        else {
+            panic!("expected class type");
+        };
+
+        assert_eq!(class_type.name, "java/util/Map");
+        assert_eq!(class_type.binary_name(), "java/util/Map$Entry");
+        assert_eq!(
+            class_type.inner_classes,
+            vec![SimpleClassTypeSignature {
+                name: "Entry".to_string(),
+                type_arguments: vec![TypeArgument::Exact(type_variable("K")), TypeArgument::Any],
+            }]
+        );
+        assert_eq!(class_type.to_string(), text);
+    }
+
+    #[test]
+    fn rejects_malformed_signatures() {
+        let error = ReferenceTypeSignature::parse("Ljava/util/List<TT;>").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("malformed signature Ljava/util/List<TT;>: expected ';' at offset 20")
+        );
+
+        let error = MethodSignature::parse("(I)V^[I").unwrap_err();
+        assert!(error.to_string().contains("must not be an array"));
+
+        let error = ClassSignature::parse("<T>Ljava/lang/Object;").unwrap_err();
+        assert!(error.to_string().contains("expected ':' at offset 2"));
+    }
+}
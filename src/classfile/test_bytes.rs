@@ -0,0 +1,16 @@
+//! Big-endian writers for hand-built class file fixtures in unit tests.
+
+pub(crate) fn push_u2(bytes: &mut Vec<u8>, value: u16) {
+    bytes.extend(value.to_be_bytes());
+}
+
+pub(crate) fn push_u4(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend(value.to_be_bytes());
+}
+
+/// Appends a `CONSTANT_Utf8` entry holding ASCII `value`.
+pub(crate) fn push_utf8(bytes: &mut Vec<u8>, value: &str) {
+    bytes.push(1);
+    push_u2(bytes, value.len() as u16);
+    bytes.extend(value.as_bytes());
+}
@@ -0,0 +1,635 @@
+//! Class file serialization (JVMS 4.1).
+//!
+//! Typed attributes are re-encoded against a copy of the constant pool, so
+//! edits that introduce new names or constants intern them on the way out.
+//! Attributes Jay does not model are written back verbatim after the typed
+//! ones; because the pool is only ever appended to, any indexes they hold
+//! stay valid.
+
+use super::annotation::{
+    Annotation, Annotations, ElementValue, ParameterAnnotations, TargetInfo, TypeAnnotation,
+};
+use super::{Attribute, ClassFile, Code, ConstantPool, CpEntry, Field, Method, mutf8};
+use crate::{JayError, JayResult};
+
+impl ClassFile {
+    /// Serializes this class file so that `ClassFile::parse` reads it back
+    /// unchanged.
+    pub fn to_bytes(&self) -> JayResult<Vec<u8>> {
+        let mut writer = ClassWriter {
+            constant_pool: self.constant_pool.clone(),
+        };
+        let body = writer.write_body(self)?;
+
+        let mut bytes = Vec::with_capacity(body.len() + 1024);
+        put_u4(&mut bytes, 0xCAFEBABE);
+        put_u2(&mut bytes, self.minor_version);
+        put_u2(&mut bytes, self.major_version);
+        write_constant_pool(&mut bytes, &writer.constant_pool)?;
+        bytes.extend(body);
+        Ok(bytes)
+    }
+}
+
+struct ClassWriter {
+    constant_pool: ConstantPool,
+}
+
+impl ClassWriter {
+    fn write_body(&mut self, class_file: &ClassFile) -> JayResult<Vec<u8>> {
+        let mut output = Vec::new();
+        put_u2(&mut output, class_file.access_flags);
+        put_u2(
+            &mut output,
+            self.constant_pool.intern_class(&class_file.this_class)?,
+        );
+        let super_class = match &class_file.super_class {
+            Some(super_class) => self.constant_pool.intern_class(super_class)?,
+            None => 0,
+        };
+        put_u2(&mut output, super_class);
+        self.write_class_list(&mut output, &class_file.interfaces, "interfaces")?;
+
+        put_u2(&mut output, count_u2(class_file.fields.len(), "fields")?);
+        for field in &class_file.fields {
+            self.write_field(&mut output, field)?;
+        }
+        put_u2(&mut output, count_u2(class_file.methods.len(), "methods")?);
+        for method in &class_file.methods {
+            self.write_method(&mut output, method)?;
+        }
+
+        let attributes = self.class_attributes(class_file)?;
+        self.write_attributes(&mut output, &attributes)?;
+        Ok(output)
+    }
+
+    fn class_attributes(&mut self, class_file: &ClassFile) -> JayResult<Vec<Attribute>> {
+        let mut attributes = Vec::new();
+        if !class_file.bootstrap_methods.is_empty() {
+            let mut info = Vec::new();
+            put_u2(
+                &mut info,
+                count_u2(class_file.bootstrap_methods.len(), "bootstrap methods")?,
+            );
+            for bootstrap in &class_file.bootstrap_methods {
+                put_u2(&mut info, bootstrap.method_ref);
+                put_u2(
+                    &mut info,
+                    count_u2(bootstrap.arguments.len(), "bootstrap arguments")?,
+                );
+                for argument in &bootstrap.arguments {
+                    put_u2(&mut info, *argument);
+                }
+            }
+            attributes.push(attribute("BootstrapMethods", info));
+        }
+        if !class_file.inner_classes.is_empty() {
+            let mut info = Vec::new();
+            put_u2(
+                &mut info,
+                count_u2(class_file.inner_classes.len(), "inner classes")?,
+            );
+            for inner in &class_file.inner_classes {
+                put_u2(
+                    &mut info,
+                    self.constant_pool.intern_class(&inner.inner_class)?,
+                );
+                let outer_class = match &inner.outer_class {
+                    Some(outer_class) => self.constant_pool.intern_class(outer_class)?,
+                    None => 0,
+                };
+                put_u2(&mut info, outer_class);
+                let inner_name = match &inner.inner_name {
+                    Some(inner_name) => self.constant_pool.intern_utf8(inner_name)?,
+                    None => 0,
+                };
+                put_u2(&mut info, inner_name);
+                put_u2(&mut info, inner.access_flags);
+            }
+            attributes.push(attribute("InnerClasses", info));
+        }
+        if let Some(enclosing) = &class_file.enclosing_method {
+            let mut info = Vec::new();
+            put_u2(
+                &mut info,
+                self.constant_pool.intern_class(&enclosing.class_name)?,
+            );
+            let method = match (&enclosing.method_name, &enclosing.method_descriptor) {
+                (Some(name), Some(descriptor)) => {
+                    self.constant_pool.intern_name_and_type(name, descriptor)?
+                }
+                _ => 0,
+            };
+            put_u2(&mut info, method);
+            attributes.push(attribute("EnclosingMethod", info));
+        }
+        if let Some(nest_host) = &class_file.nest_host {
+            let mut info = Vec::new();
+            put_u2(&mut info, self.constant_pool.intern_class(nest_host)?);
+            attributes.push(attribute("NestHost", info));
+        }
+        if !class_file.nest_members.is_empty() {
+            let mut info = Vec::new();
+            self.write_class_list(&mut info, &class_file.nest_members, "nest members")?;
+            attributes.push(attribute("NestMembers", info));
+        }
+        if !class_file.permitted_subclasses.is_empty() {
+            let mut info = Vec::new();
+            self.write_class_list(
+                &mut info,
+                &class_file.permitted_subclasses,
+                "permitted subclasses",
+            )?;
+            attributes.push(attribute("PermittedSubclasses", info));
+        }
+        self.push_signature(&mut attributes, class_file.signature.as_deref())?;
+        self.push_annotations(&mut attributes, &class_file.annotations)?;
+        attributes.extend(class_file.attributes.iter().cloned());
+        Ok(attributes)
+    }
+
+    fn write_field(&mut self, output: &mut Vec<u8>, field: &Field) -> JayResult<()> {
+        put_u2(output, field.access_flags);
+        put_u2(output, self.constant_pool.intern_utf8(&field.name)?);
+        put_u2(output, self.constant_pool.intern_utf8(&field.descriptor)?);
+
+        let mut attributes = Vec::new();
+        if let Some(constant_value) = field.constant_value {
+            attributes.push(attribute(
+                "ConstantValue",
+                constant_value.to_be_bytes().to_vec(),
+            ));
+        }
+        self.push_signature(&mut attributes, field.signature.as_deref())?;
+        self.push_annotations(&mut attributes, &field.annotations)?;
+        attributes.extend(field.attributes.iter().cloned());
+        self.write_attributes(output, &attributes)
+    }
+
+    fn write_method(&mut self, output: &mut Vec<u8>, method: &Method) -> JayResult<()> {
+        put_u2(output, method.access_flags);
+        put_u2(output, self.constant_pool.intern_utf8(&method.name)?);
+        put_u2(output, self.constant_pool.intern_utf8(&method.descriptor)?);
+
+        let mut attributes = Vec::new();
+        if let Some(code) = &method.code {
+            attributes.push(attribute("Code", self.code_info(code)?));
+        }
+        self.push_signature(&mut attributes, method.signature.as_deref())?;
+        self.push_annotations(&mut attributes, &method.annotations)?;
+        self.push_parameter_annotations(&mut attributes, &method.parameter_annotations)?;
+        if let Some(default) = &method.annotation_default {
+            let mut info = Vec::new();
+            self.write_element_value(&mut info, default)?;
+            attributes.push(attribute("AnnotationDefault", info));
+        }
+        attributes.extend(method.attributes.iter().cloned());
+        self.write_attributes(output, &attributes)
+    }
+
+    fn code_info(&mut self, code: &Code) -> JayResult<Vec<u8>> {
+        let mut info = Vec::with_capacity(code.bytes.len() + 32);
+        put_u2(&mut info, code.max_stack);
+        put_u2(&mut info, code.max_locals);
+        let code_length = u32::try_from(code.bytes.len())
+            .map_err(|_| JayError::class_format("Code is longer than 4294967295 bytes"))?;
+        put_u4(&mut info, code_length);
+        info.extend(&code.bytes);
+        put_u2(
+            &mut info,
+            count_u2(code.exception_table.len(), "exception handlers")?,
+        );
+        for handler in &code.exception_table {
+            put_u2(&mut info, handler.start_pc);
+            put_u2(&mut info, handler.end_pc);
+            put_u2(&mut info, handler.handler_pc);
+            put_u2(&mut info, handler.catch_type);
+        }
+
+        let mut attributes = Vec::new();
+        self.push_annotations(&mut attributes, &code.annotations)?;
+        attributes.extend(code.attributes.iter().cloned());
+        self.write_attributes(&mut info, &attributes)?;
+        Ok(info)
+    }
+
+    fn push_signature(
+        &mut self,
+        attributes: &mut Vec<Attribute>,
+        signature: Option<&str>,
+    ) -> JayResult<()> {
+        if let Some(signature) = signature {
+            let index = self.constant_pool.intern_utf8(signature)?;
+            attributes.push(attribute("Signature", index.to_be_bytes().to_vec()));
+        }
+        Ok(())
+    }
+
+    fn push_annotations(
+        &mut self,
+        attributes: &mut Vec<Attribute>,
+        annotations: &Annotations,
+    ) -> JayResult<()> {
+        for (name, list) in [
+            ("RuntimeVisibleAnnotations", &annotations.visible),
+            ("RuntimeInvisibleAnnotations", &annotations.invisible),
+        ] {
+            if !list.is_empty() {
+                let mut info = Vec::new();
+                self.write_annotation_list(&mut info, list)?;
+                attributes.push(attribute(name, info));
+            }
+        }
+        for (name, list) in [
+            ("RuntimeVisibleTypeAnnotations", &annotations.visible_type),
+            (
+                "RuntimeInvisibleTypeAnnotations",
+                &annotations.invisible_type,
+            ),
+        ] {
+            if !list.is_empty() {
+                let mut info = Vec::new();
+                put_u2(&mut info, count_u2(list.len(), "type annotations")?);
+                for type_annotation in list {
+                    self.write_type_annotation(&mut info, type_annotation)?;
+                }
+                attributes.push(attribute(name, info));
+            }
+        }
+        Ok(())
+    }
+
+    fn push_parameter_annotations(
+        &mut self,
+        attributes: &mut Vec<Attribute>,
+        parameter_annotations: &ParameterAnnotations,
+    ) -> JayResult<()> {
+        for (name, parameters) in [
+            (
+                "RuntimeVisibleParameterAnnotations",
+                &parameter_annotations.visible,
+            ),
+            (
+                "RuntimeInvisibleParameterAnnotations",
+                &parameter_annotations.invisible,
+            ),
+        ] {
+            if !parameters.is_empty() {
+                let count = u8::try_from(parameters.len())
+                    .map_err(|_| JayError::class_format("too many annotated parameters"))?;
+                let mut info = vec![count];
+                for list in parameters {
+                    self.write_annotation_list(&mut info, list)?;
+                }
+                attributes.push(attribute(name, info));
+            }
+        }
+        Ok(())
+    }
+
+    fn write_annotation_list(
+        &mut self,
+        output: &mut Vec<u8>,
+        annotations: &[Annotation],
+    ) -> JayResult<()> {
+        put_u2(output, count_u2(annotations.len(), "annotations")?);
+        for annotation in annotations {
+            self.write_annotation(output, annotation)?;
+        }
+        Ok(())
+    }
+
+    fn write_annotation(&mut self, output: &mut Vec<u8>, annotation: &Annotation) -> JayResult<()> {
+        put_u2(
+            output,
+            self.constant_pool.intern_utf8(&annotation.type_name)?,
+        );
+        put_u2(
+            output,
+            count_u2(annotation.elements.len(), "annotation elements")?,
+        );
+        for pair in &annotation.elements {
+            put_u2(output, self.constant_pool.intern_utf8(&pair.name)?);
+            self.write_element_value(output, &pair.value)?;
+        }
+        Ok(())
+    }
+
+    fn write_element_value(&mut self, output: &mut Vec<u8>, value: &ElementValue) -> JayResult<()> {
+        let constant_pool = &mut self.constant_pool;
+        let (tag, index) = match value {
+            ElementValue::Byte(value) => (b'B', constant_pool.intern_integer(*value as i32)?),
+            ElementValue::Char(value) => (b'C', constant_pool.intern_integer(*value as i32)?),
+            ElementValue::Double(value) => (b'D', constant_pool.intern_double(*value)?),
+            ElementValue::Float(value) => (b'F', constant_pool.intern_float(*value)?),
+            ElementValue::Int(value) => (b'I', constant_pool.intern_integer(*value)?),
+            ElementValue::Long(value) => (b'J', constant_pool.intern_long(*value)?),
+            ElementValue::Short(value) => (b'S', constant_pool.intern_integer(*value as i32)?),
+            ElementValue::Boolean(value) => (b'Z', constant_pool.intern_integer(*value as i32)?),
+            ElementValue::String(value) => (b's', constant_pool.intern_utf8(value)?),
+            ElementValue::Class(descriptor) => (b'c', constant_pool.intern_utf8(descriptor)?),
+            ElementValue::Enum {
+                type_name,
+                const_name,
+            } => {
+                output.push(b'e');
+                put_u2(output, constant_pool.intern_utf8(type_name)?);
+                put_u2(output, constant_pool.intern_utf8(const_name)?);
+                return Ok(());
+            }
+            ElementValue::Annotation(annotation) => {
+                output.push(b'@');
+                return self.write_annotation(output, annotation);
+            }
+            ElementValue::Array(values) => {
+                output.push(b'[');
+                put_u2(output, count_u2(values.len(), "array element values")?);
+                for value in values {
+                    self.write_element_value(output, value)?;
+                }
+                return Ok(());
+            }
+        };
+        output.push(tag);
+        put_u2(output, index);
+        Ok(())
+    }
+
+    fn write_type_annotation(
+        &mut self,
+        output: &mut Vec<u8>,
+        type_annotation: &TypeAnnotation,
+    ) -> JayResult<()> {
+        output.push(type_annotation.target_type);
+        match &type_annotation.target_info {
+            TargetInfo::TypeParameter { index } | TargetInfo::FormalParameter { index } => {
+                output.push(*index);
+            }
+            TargetInfo::Supertype { index } | TargetInfo::Throws { index } => {
+                put_u2(output, *index)
+            }
+            TargetInfo::TypeParameterBound {
+                type_parameter_index,
+                bound_index,
+            } => output.extend([*type_parameter_index, *bound_index]),
+            TargetInfo::Empty => {}
+            TargetInfo::LocalVariable(table) => {
+                put_u2(output, count_u2(table.len(), "local variable targets")?);
+                for target in table {
+                    put_u2(output, target.start_pc);
+                    put_u2(output, target.length);
+                    put_u2(output, target.index);
+                }
+            }
+            TargetInfo::Catch {
+                exception_table_index,
+            } => put_u2(output, *exception_table_index),
+            TargetInfo::Offset { offset } => put_u2(output, *offset),
+            TargetInfo::TypeArgument {
+                offset,
+                type_argument_index,
+            } => {
+                put_u2(output, *offset);
+                output.push(*type_argument_index);
+            }
+        }
+        let path_length = u8::try_from(type_annotation.target_path.len()).map_err(|_| {
+            JayError::class_format("type annotation path is longer than 255 entries")
+        })?;
+        output.push(path_length);
+        for entry in &type_annotation.target_path {
+            output.extend([entry.type_path_kind, entry.type_argument_index]);
+        }
+        self.write_annotation(output, &type_annotation.annotation)
+    }
+
+    fn write_class_list(
+        &mut self,
+        output: &mut Vec<u8>,
+        class_names: &[String],
+        label: &str,
+    ) -> JayResult<()> {
+        put_u2(output, count_u2(class_names.len(), label)?);
+        for class_name in class_names {
+            put_u2(output, self.constant_pool.intern_class(class_name)?);
+        }
+        Ok(())
+    }
+
+    fn write_attributes(
+        &mut self,
+        output: &mut Vec<u8>,
+        attributes: &[Attribute],
+    ) -> JayResult<()> {
+        put_u2(output, count_u2(attributes.len(), "attributes")?);
+        for attribute in attributes {
+            put_u2(output, self.constant_pool.intern_utf8(&attribute.name)?);
+            let length = u32::try_from(attribute.info.len()).map_err(|_| {
+                JayError::class_format(format!(
+                    "{} attribute is longer than 4294967295 bytes",
+                    attribute.name
+                ))
+            })?;
+            put_u4(output, length);
+            output.extend(&attribute.info);
+        }
+        Ok(())
+    }
+}
+
+fn write_constant_pool(output: &mut Vec<u8>, constant_pool: &ConstantPool) -> JayResult<()> {
+    put_u2(output, constant_pool.count());
+    for entry in &constant_pool.entries {
+        match entry {
+            CpEntry::Unusable => {}
+            CpEntry::Utf8(value) => {
+                let bytes = mutf8::encode(value);
+                let length = u16::try_from(bytes.len()).map_err(|_| {
+                    JayError::class_format("UTF-8 constant is longer than 65535 bytes")
+                })?;
+                output.push(1);
+                put_u2(output, length);
+                output.extend(bytes);
+            }
+            CpEntry::Integer(value) => {
+                output.push(3);
+                put_u4(output, *value as u32);
+            }
+            CpEntry::Float(bits) => {
+                output.push(4);
+                put_u4(output, *bits);
+            }
+            CpEntry::Long(value) => {
+                output.push(5);
+                output.extend(value.to_be_bytes());
+            }
+            CpEntry::Double(bits) => {
+                output.push(6);
+                output.extend(bits.to_be_bytes());
+            }
+            CpEntry::Class { name_index } => {
+                output.push(7);
+                put_u2(output, *name_index);
+            }
+            CpEntry::String { string_index } => {
+                output.push(8);
+                put_u2(output, *string_index);
+            }
+            CpEntry::FieldRef {
+                class_index,
+                name_and_type_index,
+            } => write_pair(output, 9, *class_index, *name_and_type_index),
+            CpEntry::MethodRef {
+                class_index,
+                name_and_type_index,
+            } => write_pair(output, 10, *class_index, *name_and_type_index),
+            CpEntry::InterfaceMethodRef {
+                class_index,
+                name_and_type_index,
+            } => write_pair(output, 11, *class_index, *name_and_type_index),
+            CpEntry::NameAndType {
+                name_index,
+                descriptor_index,
+            } => write_pair(output, 12, *name_index, *descriptor_index),
+            CpEntry::MethodHandle {
+                reference_kind,
+                reference_index,
+            } => {
+                output.push(15);
+                output.push(*reference_kind);
+                put_u2(output, *reference_index);
+            }
+            CpEntry::MethodType { descriptor_index } => {
+                output.push(16);
+                put_u2(output, *descriptor_index);
+            }
+            CpEntry::Dynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => write_pair(
+                output,
+                17,
+                *bootstrap_method_attr_index,
+                *name_and_type_index,
+            ),
+            CpEntry::InvokeDynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => write_pair(
+                output,
+                18,
+                *bootstrap_method_attr_index,
+                *name_and_type_index,
+            ),
+            CpEntry::Module { name_index } => {
+                output.push(19);
+                put_u2(output, *name_index);
+            }
+            CpEntry::Package { name_index } => {
+                output.push(20);
+                put_u2(output, *name_index);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_pair(output: &mut Vec<u8>, tag: u8, first: u16, second: u16) {
+    output.push(tag);
+    put_u2(output, first);
+    put_u2(output, second);
+}
+
+fn attribute(name: &str, info: Vec<u8>) -> Attribute {
+    Attribute {
+        name: name.to_string(),
+        info,
+    }
+}
+
+fn count_u2(count: usize, label: &str) -> JayResult<u16> {
+    u16::try_from(count).map_err(|_| JayError::class_format(format!("too many {label}: {count}")))
+}
+
+fn put_u2(output: &mut Vec<u8>, value: u16) {
+    output.extend(value.to_be_bytes());
+}
+
+fn put_u4(output: &mut Vec<u8>, value: u32) {
+    output.extend(value.to_be_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classfile::annotation::ElementValuePair;
+
+    fn empty_class_with_custom_attribute() -> Vec<u8> {
+        vec![
+            0xCA, 0xFE, 0xBA, 0xBE, // magic
+            0x00, 0x00, // minor
+            0x00, 0x41, // major 65
+            0x00, 0x08, // constant_pool_count
+            0x07, 0x00, 0x02, // #1 Class #2
+            0x01, 0x00, 0x05, b'E', b'm', b'p', b't', b'y', // #2 Utf8 Empty
+            0x07, 0x00, 0x04, // #3 Class #4
+            0x01, 0x00, 0x10, b'j', b'a', b'v', b'a', b'/', b'l', b'a', b'n', b'g', b'/', b'O',
+            b'b', b'j', b'e', b'c', b't', // #4 Utf8 java/lang/Object
+            0x05, // #5 Long
+            0x00, 0x00, 0x01, 0x1f, 0x71, 0xfb, 0x04, 0xcb, // 1234567890123
+            0x01, 0x00, 0x06, b'C', b'u', b's', b't', b'o', b'm', // #7 Utf8 Custom
+            0x00, 0x21, // access_flags
+            0x00, 0x01, // this_class
+            0x00, 0x03, // super_class
+            0x00, 0x00, // interfaces_count
+            0x00, 0x00, // fields_count
+            0x00, 0x00, // methods_count
+            0x00, 0x01, // attributes_count
+            0x00, 0x07, 0x00, 0x00, 0x00, 0x03, 0x01, 0x02, 0x03, // Custom
+        ]
+    }
+
+    #[test]
+    fn writes_unmodified_class_files_back_byte_for_byte() {
+        let bytes = empty_class_with_custom_attribute();
+        let class_file = ClassFile::parse(&bytes).unwrap();
+
+        assert_eq!(
+            class_file.attributes,
+            vec![Attribute {
+                name: "Custom".to_string(),
+                info: vec![1, 2, 3],
+            }]
+        );
+        assert_eq!(class_file.to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn interns_constants_introduced_by_edits() {
+        let mut class_file = ClassFile::parse(&empty_class_with_custom_attribute()).unwrap();
+        class_file.interfaces.push("java/lang/Runnable".to_string());
+        class_file.signature = Some("Ljava/lang/Object;Ljava/lang/Runnable;".to_string());
+        class_file.annotations.visible.push(Annotation {
+            type_name: "LMarker;".to_string(),
+            elements: vec![ElementValuePair {
+                name: "value".to_string(),
+                value: ElementValue::Array(vec![
+                    ElementValue::Long(1_234_567_890_123),
+                    ElementValue::Double(0.5),
+                    ElementValue::Boolean(true),
+                ]),
+            }],
+        });
+
+        let reparsed = ClassFile::parse(&class_file.to_bytes().unwrap()).unwrap();
+
+        assert_eq!(reparsed.interfaces, ["java/lang/Runnable"]);
+        assert_eq!(reparsed.signature, class_file.signature);
+        assert_eq!(reparsed.annotations, class_file.annotations);
+        assert_eq!(reparsed.attributes, class_file.attributes);
+        // Ten new slots; the existing Long constant is reused.
+        assert_eq!(reparsed.constant_pool.count(), 18);
+        assert_eq!(reparsed.constant_pool.long(5).unwrap(), 1_234_567_890_123);
+    }
+}
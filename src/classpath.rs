@@ -1,20 +1,127 @@
 use std::path::{Path, PathBuf};
 
-use crate::jdk::default_boot_image_path;
+use crate::jar::JarFile;
+use crate::jdk::{boot_image_java_version, default_boot_image_path, feature_version};
 use crate::jimage::JImage;
 use crate::{JayError, JayResult};
 
+/// An application classpath entry: a class directory or a JAR/zip archive.
+#[derive(Debug, Clone)]
+enum ClasspathEntry {
+    Directory(PathBuf),
+    Archive {
+        archive: JarFile,
+        /// Feature release whose `META-INF/versions/` variants apply, for
+        /// archives whose manifest declares `Multi-Release: true`.
+        release: Option<u32>,
+    },
+}
+
+impl ClasspathEntry {
+    fn open(path: PathBuf, boot_release: Option<u32>) -> JayResult<Self> {
+        if !path.is_file() {
+            return Ok(Self::Directory(path));
+        }
+        let archive = JarFile::open(path)?;
+        let multi_release = archive.manifest()?.is_some_and(|manifest| {
+            manifest
+                .get("Multi-Release")
+                .is_some_and(|value| value.eq_ignore_ascii_case("true"))
+        });
+        Ok(Self::Archive {
+            archive,
+            release: boot_release.filter(|_| multi_release),
+        })
+    }
+
+    fn load_class_bytes(&self, class_name: &str) -> JayResult<Option<Vec<u8>>> {
+        match self {
+            Self::Directory(directory) => {
+                let path = class_file_path(directory, class_name)?;
+                match std::fs::read(&path) {
+                    Ok(bytes) => Ok(Some(bytes)),
+                    Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                    Err(error) => Err(JayError::io(format!(
+                        "could not read class {class_name} at {}: {error}",
+                        path.display()
+                    ))),
+                }
+            }
+            Self::Archive { archive, .. } => archive.read(&self.archive_entry_name(class_name)?),
+        }
+    }
+
+    fn contains_class(&self, class_name: &str) -> JayResult<bool> {
+        match self {
+            Self::Directory(directory) => Ok(class_file_path(directory, class_name)?.is_file()),
+            Self::Archive { archive, .. } => Ok(archive
+                .entry(&self.archive_entry_name(class_name)?)
+                .is_some()),
+        }
+    }
+
+    /// Where `class_name` would be read from, as a path or `jar:file:` URL.
+    fn class_location(&self, class_name: &str) -> JayResult<String> {
+        match self {
+            Self::Directory(directory) => Ok(class_file_path(directory, class_name)?
+                .display()
+                .to_string()),
+            Self::Archive { archive, .. } => {
+                let path = std::fs::canonicalize(archive.path())
+                    .unwrap_or_else(|_| archive.path().to_path_buf());
+                Ok(format!(
+                    "jar:file:{}!/{}",
+                    path.display(),
+                    self.archive_entry_name(class_name)?
+                ))
+            }
+        }
+    }
+
+    /// Picks the newest `META-INF/versions/N/` variant not above the release,
+    /// falling back to the base entry.
+    fn archive_entry_name(&self, class_name: &str) -> JayResult<String> {
+        let name = class_entry_name(class_name)?;
+        if let Self::Archive {
+            archive,
+            release: Some(release),
+        } = self
+        {
+            for version in (9..=*release).rev() {
+                let versioned = format!("META-INF/versions/{version}/{name}");
+                if archive.entry(&versioned).is_some() {
+                    return Ok(versioned);
+                }
+            }
+        }
+        Ok(name)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ClassResolver {
-    classpath: PathBuf,
+    classpath: Vec<ClasspathEntry>,
     boot_image_path: PathBuf,
     boot_image: JImage,
 }
 
 impl ClassResolver {
-    pub fn new(classpath: PathBuf) -> JayResult<Self> {
-        let boot_image_path = default_boot_image_path()?;
+    /// Opens classpath entries in search order; files are read as JAR/zip archives.
+    pub fn new(classpath: Vec<PathBuf>) -> JayResult<Self> {
+        Self::with_boot_image(classpath, default_boot_image_path()?)
+    }
+
+    /// Like [`ClassResolver::new`], with JDK classes from the `lib/modules`
+    /// image at `boot_image_path` instead of the one under `JAVA_HOME`.
+    pub fn with_boot_image(classpath: Vec<PathBuf>, boot_image_path: PathBuf) -> JayResult<Self> {
         let boot_image = JImage::open(&boot_image_path)?;
+        let boot_release = boot_image_java_version(&boot_image_path)
+            .as_deref()
+            .and_then(feature_version);
+        let classpath = classpath
+            .into_iter()
+            .map(|path| ClasspathEntry::open(path, boot_release))
+            .collect::<JayResult<_>>()?;
         Ok(Self {
             classpath,
             boot_image_path,
@@ -22,16 +129,15 @@ impl ClassResolver {
         })
     }
 
+    pub fn boot_image_path(&self) -> &Path {
+        &self.boot_image_path
+    }
+
+    /// Searches the classpath entries in order, then the boot image.
     pub fn load_class_bytes(&self, class_name: &str) -> JayResult<Vec<u8>> {
-        let path = class_file_path(&self.classpath, class_name)?;
-        match std::fs::read(&path) {
-            Ok(bytes) => return Ok(bytes),
-            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
-            Err(error) => {
-                return Err(JayError::new(format!(
-                    "could not read class {class_name} at {}: {error}",
-                    path.display()
-                )));
+        for entry in &self.classpath {
+            if let Some(bytes) = entry.load_class_bytes(class_name)? {
+                return Ok(bytes);
             }
         }
 
@@ -39,15 +145,169 @@ impl ClassResolver {
             return Ok(bytes);
         }
 
-        Err(JayError::new(format!(
-            "could not read class {class_name} at {} or boot image {}",
-            path.display(),
-            self.boot_image_path.display()
+        Err(JayError::class_not_found(format!(
+            "could not read class {class_name}; {}",
+            self.tried_locations(class_name)?
         )))
     }
+
+    /// Describes where `class_name` resolves from: its class file path, a
+    /// `jar:file:` URL, or a `jrt:/<module>/...` location inside the boot image.
+    pub fn class_origin(&self, class_name: &str) -> JayResult<String> {
+        for entry in &self.classpath {
+            if entry.contains_class(class_name)? {
+                return entry.class_location(class_name);
+            }
+        }
+        match self.boot_image.class_module(class_name)? {
+            Some(module) => Ok(format!(
+                "jrt:/{module}/{}.class",
+                class_name.replace('.', "/")
+            )),
+            None => Err(JayError::class_not_found(format!(
+                "could not find class {class_name}; {}",
+                self.tried_locations(class_name)?
+            ))),
+        }
+    }
+
+    /// Returns the boot image module that defines `class_name`, or `None` for
+    /// classpath classes, which belong to the unnamed module.
+    pub fn class_module(&self, class_name: &str) -> JayResult<Option<String>> {
+        if self.is_classpath_class(class_name)? {
+            return Ok(None);
+        }
+        self.boot_image.class_module(class_name)
+    }
+
+    /// Reports whether `class_name` resolves from the application classpath
+    /// rather than the boot image.
+    pub fn is_classpath_class(&self, class_name: &str) -> JayResult<bool> {
+        for entry in &self.classpath {
+            if entry.contains_class(class_name)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn tried_locations(&self, class_name: &str) -> JayResult<String> {
+        let mut locations = self
+            .classpath
+            .iter()
+            .map(|entry| entry.class_location(class_name))
+            .collect::<JayResult<Vec<_>>>()?;
+        locations.push(format!("boot image {}", self.boot_image_path.display()));
+        Ok(format!("tried {}", locations.join(", ")))
+    }
 }
 
-pub fn class_file_path(classpath: &Path, class_name: &str) -> JayResult<PathBuf> {
+/// Reads the manifest of an executable JAR for `java -jar`: returns the
+/// classpath (the JAR, then its `Class-Path` entries resolved relative to the
+/// JAR's directory, transitively) and the `Main-Class`.
+pub fn executable_jar(path: &Path) -> JayResult<(Vec<PathBuf>, String)> {
+    if !path.is_file() {
+        return Err(JayError::io(format!(
+            "unable to access jarfile {}",
+            path.display()
+        )));
+    }
+    let archive = JarFile::open(path)?;
+    let main_class = archive
+        .manifest()?
+        .and_then(|manifest| manifest.get("Main-Class").map(str::trim).map(String::from))
+        .filter(|main_class| !main_class.is_empty())
+        .ok_or_else(|| {
+            JayError::usage(format!("no main manifest attribute, in {}", path.display()))
+        })?;
+
+    let mut classpath = vec![path.to_path_buf()];
+    let mut next = 0;
+    while next < classpath.len() {
+        let jar = classpath[next].clone();
+        next += 1;
+        let manifest = if next == 1 {
+            archive.manifest()?
+        } else if jar.is_file() {
+            JarFile::open(&jar)?.manifest()?
+        } else {
+            None
+        };
+        let Some(class_path) = manifest
+            .as_ref()
+            .and_then(|manifest| manifest.get("Class-Path"))
+        else {
+            continue;
+        };
+        let base = jar.parent().unwrap_or(Path::new("."));
+        for entry in class_path.split_whitespace() {
+            let entry = base.join(entry.strip_prefix("file:").unwrap_or(entry));
+            if entry.exists() && !classpath.contains(&entry) {
+                classpath.push(entry);
+            }
+        }
+    }
+    Ok((classpath, main_class.replace('/', ".")))
+}
+
+/// Splits a classpath list on the platform separator (`:` on Unix) and
+/// expands `dir/*` entries to the JAR files in `dir`, sorted by name, as the
+/// `java` launcher does. An empty entry means the current directory.
+pub fn parse_classpath(value: &str) -> JayResult<Vec<PathBuf>> {
+    let separator = if cfg!(windows) { ';' } else { ':' };
+    let mut entries = Vec::new();
+    for entry in value.split(separator) {
+        let wildcard = if entry == "*" {
+            Some(".")
+        } else {
+            entry.strip_suffix("/*")
+        };
+        match wildcard {
+            Some(directory) => entries.extend(archives_in(Path::new(directory))?),
+            None if entry.is_empty() => entries.push(PathBuf::from(".")),
+            None => entries.push(PathBuf::from(entry)),
+        }
+    }
+    Ok(entries)
+}
+
+fn archives_in(directory: &Path) -> JayResult<Vec<PathBuf>> {
+    let listing_error = |error: std::io::Error| {
+        JayError::io(format!(
+            "could not list classpath wildcard {}/*: {error}",
+            directory.display()
+        ))
+    };
+    // Like the java launcher, a wildcard over a missing directory matches
+    // no archives instead of failing the whole classpath.
+    let listing = match std::fs::read_dir(directory) {
+        Ok(listing) => listing,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(listing_error(error)),
+    };
+    let mut archives = Vec::new();
+    for entry in listing {
+        let path = entry.map_err(listing_error)?.path();
+        if path.is_file()
+            && matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("jar" | "JAR")
+            )
+        {
+            archives.push(path);
+        }
+    }
+    archives.sort();
+    Ok(archives)
+}
+
+/// Maps `com.example.Main` to the archive entry `com/example/Main.class`.
+pub fn class_entry_name(class_name: &str) -> JayResult<String> {
+    validate_class_name(class_name)?;
+    Ok(format!("{}.class", class_name.replace('.', "/")))
+}
+
+fn validate_class_name(class_name: &str) -> JayResult<()> {
     if class_name.is_empty()
         || class_name.starts_with('.')
         || class_name.ends_with('.')
@@ -55,8 +315,15 @@ pub fn class_file_path(classpath: &Path, class_name: &str) -> JayResult<PathBuf>
         || class_name.contains('/')
         || class_name.contains('\\')
     {
-        return Err(JayError::new(format!("invalid class name: {class_name}")));
+        return Err(JayError::class_not_found(format!(
+            "invalid class name: {class_name}"
+        )));
     }
+    Ok(())
+}
+
+pub fn class_file_path(classpath: &Path, class_name: &str) -> JayResult<PathBuf> {
+    validate_class_name(class_name)?;
 
     let mut path = classpath.to_path_buf();
     let mut segments = class_name.split('.').peekable();
@@ -74,7 +341,7 @@ pub fn class_file_path(classpath: &Path, class_name: &str) -> JayResult<PathBuf>
 pub fn load_class_bytes(classpath: &Path, class_name: &str) -> JayResult<Vec<u8>> {
     let path = class_file_path(classpath, class_name)?;
     std::fs::read(&path).map_err(|error| {
-        JayError::new(format!(
+        JayError::io(format!(
             "could not read class {class_name} at {}: {error}",
             path.display()
         ))
@@ -128,13 +395,25 @@ mod tests {
             std::process::id()
         ));
         std::fs::create_dir_all(&root).unwrap();
-        let resolver = ClassResolver::new(root).unwrap();
+        let resolver = ClassResolver::new(vec![root]).unwrap();
 
         let bytes = resolver.load_class_bytes("java.lang.Object").unwrap();
 
         assert_eq!(&bytes[..4], &[0xCA, 0xFE, 0xBA, 0xBE]);
     }
 
+    #[test]
+    fn resolver_distinguishes_classpath_classes_from_boot_classes() {
+        let root =
+            std::env::temp_dir().join(format!("jay-classpath-origin-test-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("com/example")).unwrap();
+        std::fs::write(root.join("com/example/Main.class"), b"class bytes").unwrap();
+        let resolver = ClassResolver::new(vec![root]).unwrap();
+
+        assert!(resolver.is_classpath_class("com.example.Main").unwrap());
+        assert!(!resolver.is_classpath_class("java.lang.Object").unwrap());
+    }
+
     #[test]
     fn resolver_prefers_directory_over_default_jimage() {
         let root = std::env::temp_dir().join(format!(
@@ -143,10 +422,49 @@ mod tests {
         ));
         std::fs::create_dir_all(root.join("java/lang")).unwrap();
         std::fs::write(root.join("java/lang/Object.class"), b"directory bytes").unwrap();
-        let resolver = ClassResolver::new(root).unwrap();
+        let resolver = ClassResolver::new(vec![root]).unwrap();
 
         let bytes = resolver.load_class_bytes("java.lang.Object").unwrap();
 
         assert_eq!(bytes, b"directory bytes");
     }
+
+    #[test]
+    fn resolver_searches_entries_in_order_and_lists_tried_locations() {
+        let root =
+            std::env::temp_dir().join(format!("jay-classpath-entries-test-{}", std::process::id()));
+        let (first, second) = (root.join("first"), root.join("second"));
+        std::fs::create_dir_all(&first).unwrap();
+        std::fs::create_dir_all(&second).unwrap();
+        std::fs::write(first.join("Shared.class"), b"first bytes").unwrap();
+        std::fs::write(second.join("Shared.class"), b"second bytes").unwrap();
+        let resolver = ClassResolver::new(vec![first.clone(), second.clone()]).unwrap();
+
+        assert_eq!(resolver.load_class_bytes("Shared").unwrap(), b"first bytes");
+        let error = resolver
+            .load_class_bytes("Missing")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.starts_with(&format!(
+                "could not read class Missing; tried {}, {}, boot image ",
+                first.join("Missing.class").display(),
+                second.join("Missing.class").display()
+            )),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn wildcard_over_missing_directory_expands_to_no_entries() {
+        let root = std::env::temp_dir().join(format!(
+            "jay-classpath-wildcard-test-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&root).unwrap();
+        let missing = root.join("missing");
+        let classpath = format!("{}/*:{}", missing.display(), root.display());
+
+        assert_eq!(parse_classpath(&classpath).unwrap(), vec![root]);
+    }
 }
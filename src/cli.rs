@@ -1,46 +1,266 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use crate::classfile::javap::JavapOptions;
+use crate::classpath::{executable_jar, parse_classpath};
 use crate::{JayError, JayResult};
 
+const USAGE: &str = "usage: jay -cp <classpath> <fully.qualified.MainClass> [args...]
+       jay -jar <file.jar> [args...]
+       jay check -cp <classpath> <fully.qualified.MainClass>
+       jay asm [-d <directory>] <file.j>
+       jay disasm [-cp <classpath>] <fully.qualified.Class|file.class>
+       jay javap [-cp <classpath>] [-c] [-v] <fully.qualified.Class|file.class>
+
+<classpath> lists directories, JAR files and dir/* wildcards separated by ':'.
+-classpath and --class-path are accepted for -cp; without one, CLASSPATH is used.
+-Dkey=value options before the class or -jar set system properties.
+--error-format=json before the command reports a failure on stderr as one line of JSON.";
+
+const CLASSPATH_FLAGS: &[&str] = &["-cp", "-classpath", "--class-path"];
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Config {
-    pub classpath: PathBuf,
+    /// Classpath entries in search order, with wildcards expanded.
+    pub classpath: Vec<PathBuf>,
     pub main_class: String,
+    /// Arguments after the main class, passed to `main(String[])`.
+    pub args: Vec<String>,
+    /// `-Dkey=value` system properties, in command-line order.
+    pub properties: Vec<(String, String)>,
 }
 
-pub fn parse_args<I, S>(args: I) -> JayResult<Config>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Runs `main` of a class.
+    Run(Config),
+    /// Lists what the interpreter would reject in code reachable from `main`.
+    Check(Config),
+    /// Assembles a text source into `<output_dir>/<class>.class`.
+    Assemble {
+        source: PathBuf,
+        output_dir: PathBuf,
+    },
+    /// Prints the text form of a class file or of a class on the classpath.
+    Disassemble {
+        classpath: Vec<PathBuf>,
+        class: String,
+    },
+    /// Prints a class the way the JDK `javap` tool does.
+    Javap {
+        classpath: Vec<PathBuf>,
+        class: String,
+        options: JavapOptions,
+    },
+}
+
+/// How `jay` reports a failed command on stderr.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// `jay: <message>` followed by `at` lines for Java frames.
+    #[default]
+    Text,
+    /// [`JayError::to_json`], for tools that group failures.
+    Json,
+}
+
+/// Removes leading `--error-format=<text|json>` options, which must come
+/// before the command, and returns the last one with the remaining arguments.
+pub fn parse_error_format<I, S>(args: I) -> JayResult<(ErrorFormat, Vec<String>)>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let mut args: Vec<String> = args.into_iter().map(Into::into).collect();
+    let mut format = ErrorFormat::default();
+    while let Some(value) = args
+        .first()
+        .and_then(|arg| arg.strip_prefix("--error-format="))
+    {
+        format = match value {
+            "text" => ErrorFormat::Text,
+            "json" => ErrorFormat::Json,
+            _ => {
+                return Err(JayError::usage(format!(
+                    "invalid error format {value}; expected text or json"
+                )));
+            }
+        };
+        args.remove(0);
+    }
+    Ok((format, args))
+}
+
+pub fn parse_command<I, S>(args: I) -> JayResult<Command>
 where
     I: IntoIterator<Item = S>,
     S: Into<String>,
 {
     let args: Vec<String> = args.into_iter().map(Into::into).collect();
-    if args.first().map(String::as_str) != Some("-cp") {
-        return Err(JayError::new(
-            "usage: jay -cp <directory> <fully.qualified.MainClass>",
-        ));
+    match args.first().map(String::as_str) {
+        Some("asm") => {
+            let (output_dir, operand) = parse_tool_args(&args[1..], &["-d"], "assembler source")?;
+            Ok(Command::Assemble {
+                source: PathBuf::from(operand),
+                output_dir: PathBuf::from(output_dir),
+            })
+        }
+        Some("disasm") => {
+            let (classpath, class) =
+                parse_tool_args(&args[1..], CLASSPATH_FLAGS, "class to disassemble")?;
+            Ok(Command::Disassemble {
+                classpath: parse_classpath(&classpath)?,
+                class,
+            })
+        }
+        Some("check") => parse_args(&args[1..]).map(Command::Check),
+        Some("javap") => parse_javap_args(&args[1..]),
+        _ => parse_args(args).map(Command::Run),
     }
+}
 
-    if args.len() < 2 {
-        return Err(JayError::new("missing classpath directory after -cp"));
+fn parse_javap_args(args: &[String]) -> JayResult<Command> {
+    let mut classpath = vec![PathBuf::from(".")];
+    let mut options = JavapOptions::default();
+    let mut class = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-c" => options.code = true,
+            "-v" | "-verbose" => options.verbose = true,
+            flag if CLASSPATH_FLAGS.contains(&flag) => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| JayError::usage(format!("missing classpath after {arg}")))?;
+                classpath = parse_classpath(value)?;
+            }
+            option if option.starts_with('-') => {
+                return Err(JayError::usage(format!("unknown javap option {option}")));
+            }
+            name if class.is_none() => class = Some(name.to_string()),
+            _ => return Err(JayError::usage(USAGE)),
+        }
     }
+    let class = class.ok_or_else(|| JayError::usage("missing class for javap"))?;
+    Ok(Command::Javap {
+        classpath,
+        class,
+        options,
+    })
+}
 
-    if args.len() < 3 {
-        return Err(JayError::new("missing main class name"));
+/// Parses `[<flag> <value>] <operand>`, defaulting the value to `.`.
+fn parse_tool_args(args: &[String], flags: &[&str], operand: &str) -> JayResult<(String, String)> {
+    let is_flag = |arg: &String| flags.contains(&arg.as_str());
+    match args {
+        [value] if !is_flag(value) => Ok((".".to_string(), value.clone())),
+        [option, directory, value] if is_flag(option) => Ok((directory.clone(), value.clone())),
+        [] => Err(JayError::usage(format!("missing {operand}"))),
+        [option] | [option, _] if is_flag(option) => Err(JayError::usage(format!(
+            "missing {operand} after {option} <{}>",
+            if option == "-d" {
+                "directory"
+            } else {
+                "classpath"
+            }
+        ))),
+        _ => Err(JayError::usage(USAGE)),
     }
+}
 
-    if args.len() > 3 {
-        return Err(JayError::new("unexpected extra arguments"));
+/// Parses `[-cp <classpath>] <MainClass>`, falling back to the `CLASSPATH`
+/// environment variable when no classpath option is given.
+pub fn parse_args<I, S>(args: I) -> JayResult<Config>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let args: Vec<String> = args.into_iter().map(Into::into).collect();
+    parse_run_args(&args, std::env::var("CLASSPATH").ok())
+}
+
+fn parse_run_args(args: &[String], env_classpath: Option<String>) -> JayResult<Config> {
+    let mut classpath = None;
+    let mut properties = Vec::new();
+    let mut rest = args;
+    loop {
+        rest = match rest {
+            [flag, value, rest @ ..] if CLASSPATH_FLAGS.contains(&flag.as_str()) => {
+                classpath = Some(value.clone());
+                rest
+            }
+            [flag] if CLASSPATH_FLAGS.contains(&flag.as_str()) => {
+                return Err(JayError::usage(format!("missing classpath after {flag}")));
+            }
+            [flag, rest @ ..] if flag.starts_with("--class-path=") => {
+                classpath = Some(flag["--class-path=".len()..].to_string());
+                rest
+            }
+            [flag, rest @ ..] if flag.starts_with("-D") => {
+                properties.push(parse_property(&flag[2..])?);
+                rest
+            }
+            [flag, rest @ ..] if flag == "-jar" => {
+                // Like `java -jar`, the JAR's manifest replaces -cp and CLASSPATH.
+                let [jar, args @ ..] = rest else {
+                    return Err(JayError::usage("missing JAR file after -jar"));
+                };
+                let (classpath, main_class) = executable_jar(Path::new(jar))?;
+                validate_main_class(&main_class)?;
+                return Ok(Config {
+                    classpath,
+                    main_class,
+                    args: args.to_vec(),
+                    properties,
+                });
+            }
+            [first, ..] if first.starts_with('-') => return Err(JayError::usage(USAGE)),
+            _ => break,
+        };
+    }
+
+    let Some(classpath) = classpath.or(env_classpath) else {
+        return Err(JayError::usage(USAGE));
+    };
+    let (main_class, args) = match rest {
+        [] => return Err(JayError::usage("missing main class name")),
+        [main_class, args @ ..] => (main_class.clone(), args.to_vec()),
+    };
+
+    let mut classpath = parse_classpath(&classpath)?;
+    // Like the java launcher, skip entries that do not exist, so a stale
+    // directory in an inherited CLASSPATH does not stop the run.
+    classpath.retain(|entry| entry.exists());
+    for entry in &classpath {
+        if !entry.is_dir() && !is_archive(entry) {
+            return Err(JayError::usage(format!(
+                "classpath is not a directory or JAR file: {}",
+                entry.display()
+            )));
+        }
     }
 
-    let classpath = PathBuf::from(&args[1]);
-    if !classpath.is_dir() {
-        return Err(JayError::new(format!(
-            "classpath is not a directory: {}",
-            classpath.display()
+    validate_main_class(&main_class)?;
+
+    Ok(Config {
+        classpath,
+        main_class,
+        args,
+        properties,
+    })
+}
+
+/// Splits a `-Dkey=value` definition; `-Dkey` alone sets an empty value.
+fn parse_property(definition: &str) -> JayResult<(String, String)> {
+    let (key, value) = definition.split_once('=').unwrap_or((definition, ""));
+    if key.is_empty() {
+        return Err(JayError::usage(format!(
+            "invalid system property definition: -D{definition}"
         )));
     }
+    Ok((key.to_string(), value.to_string()))
+}
 
-    let main_class = args[2].clone();
+fn validate_main_class(main_class: &str) -> JayResult<()> {
     if main_class.is_empty()
         || main_class.starts_with('.')
         || main_class.ends_with('.')
@@ -48,15 +268,22 @@ where
         || main_class.contains('/')
         || main_class.contains('\\')
     {
-        return Err(JayError::new(format!(
+        return Err(JayError::usage(format!(
             "invalid main class name: {main_class}"
         )));
     }
+    Ok(())
+}
 
-    Ok(Config {
-        classpath,
-        main_class,
-    })
+/// Whether `path` is an existing `.jar` or `.zip` file.
+fn is_archive(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| {
+                extension.eq_ignore_ascii_case("jar") || extension.eq_ignore_ascii_case("zip")
+            })
 }
 
 #[cfg(test)]
@@ -87,17 +314,95 @@ mod tests {
         ])
         .unwrap();
 
-        assert_eq!(config.classpath, classpath);
+        assert_eq!(config.classpath, [classpath]);
         assert_eq!(config.main_class, "com.example.Main");
+        assert!(config.args.is_empty());
+    }
+
+    #[test]
+    fn collects_arguments_after_the_main_class() {
+        let classpath = temp_dir("arguments");
+
+        let config = parse_args([
+            "-cp".to_string(),
+            classpath.display().to_string(),
+            "Main".to_string(),
+            "-cp".to_string(),
+            "two words".to_string(),
+        ])
+        .unwrap();
+
+        assert_eq!(config.main_class, "Main");
+        assert_eq!(config.args, ["-cp", "two words"]);
+    }
+
+    #[test]
+    fn parses_system_property_definitions() {
+        let classpath = temp_dir("properties");
+
+        let config = parse_args([
+            "-Dapp.name=demo=1".to_string(),
+            "-cp".to_string(),
+            classpath.display().to_string(),
+            "-Dapp.flag".to_string(),
+            "Main".to_string(),
+            "-Dnot.a.property=x".to_string(),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            config.properties,
+            [
+                ("app.name".to_string(), "demo=1".to_string()),
+                ("app.flag".to_string(), String::new()),
+            ]
+        );
+        assert_eq!(config.args, ["-Dnot.a.property=x"]);
+        assert!(
+            parse_args(["-D=x".to_string()])
+                .unwrap_err()
+                .to_string()
+                .contains("invalid system property definition: -D=x")
+        );
+    }
+
+    #[test]
+    fn strips_leading_error_format_options() {
+        let (format, args) =
+            parse_error_format(["--error-format=json", "-cp", ".", "Main"]).unwrap();
+        assert_eq!(format, ErrorFormat::Json);
+        assert_eq!(args, ["-cp", ".", "Main"]);
+
+        let (format, args) = parse_error_format(["check", "--error-format=json"]).unwrap();
+        assert_eq!(format, ErrorFormat::Text);
+        assert_eq!(args, ["check", "--error-format=json"]);
+
+        let error = parse_error_format(["--error-format=xml"]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid error format xml; expected text or json"
+        );
     }
 
     #[test]
     fn rejects_missing_cp_flag() {
-        let error = parse_args(["Main".to_string()]).unwrap_err();
+        let error = parse_run_args(&["Main".to_string()], None).unwrap_err();
 
         assert!(error.to_string().contains("usage: jay -cp"));
     }
 
+    #[test]
+    fn rejects_missing_or_inaccessible_jar_files() {
+        let error = parse_args(["-jar".to_string()]).unwrap_err();
+        assert_eq!(error.to_string(), "missing JAR file after -jar");
+
+        let error = parse_args(["-jar".to_string(), "/missing/app.jar".to_string()]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unable to access jarfile /missing/app.jar"
+        );
+    }
+
     #[test]
     fn rejects_missing_classpath_value() {
         let error = parse_args(["-cp".to_string()]).unwrap_err();
@@ -115,32 +420,151 @@ mod tests {
     }
 
     #[test]
-    fn rejects_extra_arguments() {
-        let classpath = temp_dir("extra");
+    fn rejects_non_directory_classpath() {
+        let path = std::env::temp_dir().join(format!("jay-cli-test-file-{}", std::process::id()));
+        std::fs::write(&path, b"not a directory").unwrap();
 
         let error = parse_args([
             "-cp".to_string(),
-            classpath.display().to_string(),
+            path.display().to_string(),
             "Main".to_string(),
-            "extra".to_string(),
         ])
         .unwrap_err();
 
-        assert!(error.to_string().contains("unexpected extra"));
+        assert!(error.to_string().contains("classpath is not a directory"));
     }
 
     #[test]
-    fn rejects_non_directory_classpath() {
-        let path = std::env::temp_dir().join(format!("jay-cli-test-file-{}", std::process::id()));
-        std::fs::write(&path, b"not a directory").unwrap();
+    fn accepts_jar_classpath() {
+        let path = temp_dir("jar").join("app.jar");
+        std::fs::write(&path, b"PK").unwrap();
 
-        let error = parse_args([
+        let config = parse_args([
             "-cp".to_string(),
             path.display().to_string(),
             "Main".to_string(),
         ])
-        .unwrap_err();
+        .unwrap();
 
-        assert!(error.to_string().contains("classpath is not a directory"));
+        assert_eq!(config.classpath, [path]);
+    }
+
+    #[test]
+    fn parses_asm_and_disasm_subcommands() {
+        assert_eq!(
+            parse_command(["asm", "Hello.j"]).unwrap(),
+            Command::Assemble {
+                source: PathBuf::from("Hello.j"),
+                output_dir: PathBuf::from("."),
+            }
+        );
+        assert_eq!(
+            parse_command(["asm", "-d", "out", "Hello.j"]).unwrap(),
+            Command::Assemble {
+                source: PathBuf::from("Hello.j"),
+                output_dir: PathBuf::from("out"),
+            }
+        );
+        assert_eq!(
+            parse_command(["disasm", "-cp", "classes", "com.example.Main"]).unwrap(),
+            Command::Disassemble {
+                classpath: vec![PathBuf::from("classes")],
+                class: "com.example.Main".to_string(),
+            }
+        );
+        assert!(
+            parse_command(["asm"])
+                .unwrap_err()
+                .to_string()
+                .contains("missing assembler source")
+        );
+        assert!(
+            parse_command(["disasm", "-cp", "classes"])
+                .unwrap_err()
+                .to_string()
+                .contains("missing class to disassemble")
+        );
+        assert!(
+            parse_command(["disasm", "A", "B"])
+                .unwrap_err()
+                .to_string()
+                .contains("jay disasm")
+        );
+    }
+
+    #[test]
+    fn parses_check_subcommand() {
+        let classpath = temp_dir("check");
+
+        let command = parse_command([
+            "check".to_string(),
+            "-cp".to_string(),
+            classpath.display().to_string(),
+            "com.example.Main".to_string(),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            command,
+            Command::Check(Config {
+                classpath: vec![classpath],
+                main_class: "com.example.Main".to_string(),
+                args: Vec::new(),
+                properties: Vec::new(),
+            })
+        );
+        assert!(
+            parse_command(["check", "Main"])
+                .unwrap_err()
+                .to_string()
+                .contains("usage: jay -cp")
+        );
+    }
+
+    #[test]
+    fn parses_classpath_lists_with_wildcards() {
+        let root = temp_dir("classpath-list");
+        let (classes, libs) = (root.join("classes"), root.join("libs"));
+        std::fs::create_dir_all(&classes).unwrap();
+        std::fs::create_dir_all(&libs).unwrap();
+        for name in ["b.jar", "a.jar", "notes.txt"] {
+            std::fs::write(libs.join(name), b"PK").unwrap();
+        }
+        let classpath = format!("{}:{}/*", classes.display(), libs.display());
+
+        for flag in ["-cp", "-classpath", "--class-path"] {
+            let config = parse_run_args(
+                &[flag.to_string(), classpath.clone(), "Main".to_string()],
+                None,
+            )
+            .unwrap();
+
+            assert_eq!(
+                config.classpath,
+                [classes.clone(), libs.join("a.jar"), libs.join("b.jar")]
+            );
+        }
+        let config = parse_run_args(
+            &[format!("--class-path={classpath}"), "Main".to_string()],
+            None,
+        )
+        .unwrap();
+        assert_eq!(config.classpath.len(), 3);
+    }
+
+    #[test]
+    fn falls_back_to_classpath_environment_variable() {
+        let classes = temp_dir("classpath-env");
+
+        let config =
+            parse_run_args(&["Main".to_string()], Some(classes.display().to_string())).unwrap();
+
+        assert_eq!(config.classpath, std::slice::from_ref(&classes));
+        let config = parse_run_args(
+            &["Main".to_string()],
+            Some(format!("/definitely/not/jay:{}", classes.display())),
+        )
+        .unwrap();
+        assert_eq!(config.classpath, [classes]);
     }
 }
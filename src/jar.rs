@@ -0,0 +1,511 @@
+//! Read-only access to JAR and zip archives on the classpath.
+//!
+//! The central directory is indexed once when the archive is opened; entries
+//! are read through their local headers and checked against the recorded size
+//! and CRC-32. Stored and deflated entries are supported, as are ZIP64
+//! archives and archives with bytes prepended before the first entry.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use flate2::Crc;
+use flate2::read::DeflateDecoder;
+
+use crate::{ErrorKind, JayError, JayResult};
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const END_SIGNATURE: u32 = 0x0605_4b50;
+const ZIP64_END_SIGNATURE: u32 = 0x0606_4b50;
+const ZIP64_LOCATOR_SIGNATURE: u32 = 0x0706_4b50;
+const LOCAL_HEADER_SIZE: usize = 30;
+const CENTRAL_HEADER_SIZE: usize = 46;
+const END_SIZE: usize = 22;
+const ZIP64_END_SIZE: usize = 56;
+const ZIP64_LOCATOR_SIZE: usize = 20;
+const MAX_COMMENT_SIZE: usize = 0xffff;
+const ZIP64_EXTRA_ID: u16 = 0x0001;
+const FLAG_ENCRYPTED: u16 = 0x0001;
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATED: u16 = 8;
+const MANIFEST_NAME: &str = "META-INF/MANIFEST.MF";
+
+/// One file recorded in the central directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JarEntry {
+    pub name: String,
+    /// Compression method: 0 for stored, 8 for deflated.
+    pub method: u16,
+    pub compressed_size: u64,
+    pub uncompressed_size: u64,
+    pub crc32: u32,
+    flags: u16,
+    local_header_offset: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct JarFile {
+    path: PathBuf,
+    bytes: Vec<u8>,
+    entries: HashMap<String, JarEntry>,
+}
+
+impl JarFile {
+    pub fn open(path: impl AsRef<Path>) -> JayResult<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|error| {
+            JayError::io(format!(
+                "could not read archive {}: {error}",
+                path.display()
+            ))
+        })?;
+        Self::parse(path, bytes)
+    }
+
+    fn parse(path: &Path, bytes: Vec<u8>) -> JayResult<Self> {
+        let mut archive = Self {
+            path: path.to_path_buf(),
+            bytes,
+            entries: HashMap::new(),
+        };
+        archive.entries = archive.read_central_directory()?;
+        Ok(archive)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn entry(&self, name: &str) -> Option<&JarEntry> {
+        self.entries.get(name)
+    }
+
+    /// Names of every file and directory entry, in no particular order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// Reads and decompresses an entry, or returns `None` if the archive has no such entry.
+    pub fn read(&self, name: &str) -> JayResult<Option<Vec<u8>>> {
+        let Some(entry) = self.entries.get(name) else {
+            return Ok(None);
+        };
+        if entry.flags & FLAG_ENCRYPTED != 0 {
+            return Err(JayError::of_kind(
+                ErrorKind::UnsupportedFeature {
+                    feature: "encrypted archive entries".to_string(),
+                },
+                format!(
+                    "encrypted entry {name} in archive {} is not supported",
+                    self.path.display()
+                ),
+            ));
+        }
+
+        let offset = usize::try_from(entry.local_header_offset)
+            .map_err(|_| self.corrupted(format!("entry {name} offset is out of range")))?;
+        let header = self.slice(offset, LOCAL_HEADER_SIZE, "local header")?;
+        if u32_at(header, 0) != LOCAL_HEADER_SIGNATURE {
+            return Err(self.corrupted(format!("entry {name} has a bad local header signature")));
+        }
+        let data_offset =
+            offset + LOCAL_HEADER_SIZE + u16_at(header, 26) as usize + u16_at(header, 28) as usize;
+        let compressed_size = usize::try_from(entry.compressed_size)
+            .map_err(|_| self.corrupted(format!("entry {name} size is out of range")))?;
+        let data = self.slice(data_offset, compressed_size, "entry data")?;
+
+        let contents = match entry.method {
+            METHOD_STORED => data.to_vec(),
+            METHOD_DEFLATED => {
+                // Stop one byte past the declared size so a deflate bomb
+                // cannot exhaust memory before the size check below.
+                let mut contents = Vec::new();
+                DeflateDecoder::new(data)
+                    .take(entry.uncompressed_size.saturating_add(1))
+                    .read_to_end(&mut contents)
+                    .map_err(|error| {
+                        self.corrupted(format!("could not inflate entry {name}: {error}"))
+                    })?;
+                if contents.len() as u64 > entry.uncompressed_size {
+                    return Err(self.corrupted(format!(
+                        "entry {name} inflates past the {} bytes the central directory records",
+                        entry.uncompressed_size
+                    )));
+                }
+                contents
+            }
+            method => {
+                return Err(JayError::unsupported(format!(
+                    "compression method {method} for entry {name} in archive {}",
+                    self.path.display()
+                )));
+            }
+        };
+        if contents.len() as u64 != entry.uncompressed_size {
+            return Err(self.corrupted(format!(
+                "entry {name} has {} bytes but the central directory records {}",
+                contents.len(),
+                entry.uncompressed_size
+            )));
+        }
+        let mut crc = Crc::new();
+        crc.update(&contents);
+        if crc.sum() != entry.crc32 {
+            return Err(self.corrupted(format!("entry {name} fails its CRC-32 check")));
+        }
+        Ok(Some(contents))
+    }
+
+    /// Reads `META-INF/MANIFEST.MF`, if the archive has one.
+    pub fn manifest(&self) -> JayResult<Option<Manifest>> {
+        let Some(bytes) = self.read(MANIFEST_NAME)? else {
+            return Ok(None);
+        };
+        Manifest::parse(&bytes).map(Some).map_err(|error| {
+            JayError::io(format!(
+                "invalid manifest in archive {}: {error}",
+                self.path.display()
+            ))
+        })
+    }
+
+    fn read_central_directory(&self) -> JayResult<HashMap<String, JarEntry>> {
+        let end = self.find_end_record()?;
+        let mut entry_count = u16_at(&self.bytes[end..], 10) as u64;
+        let mut directory_size = u32_at(&self.bytes[end..], 12) as u64;
+        let mut directory_offset = u32_at(&self.bytes[end..], 16) as u64;
+        let mut directory_end = end;
+
+        if end >= ZIP64_LOCATOR_SIZE
+            && u32_at(&self.bytes[end - ZIP64_LOCATOR_SIZE..], 0) == ZIP64_LOCATOR_SIGNATURE
+        {
+            let locator = &self.bytes[end - ZIP64_LOCATOR_SIZE..end];
+            let record_offset = self.offset(u64_at(locator, 8), "ZIP64 end record")?;
+            let record = self.slice(record_offset, ZIP64_END_SIZE, "ZIP64 end record")?;
+            if u32_at(record, 0) != ZIP64_END_SIGNATURE {
+                return Err(self.corrupted("bad ZIP64 end record signature"));
+            }
+            entry_count = u64_at(record, 32);
+            directory_size = u64_at(record, 40);
+            directory_offset = u64_at(record, 48);
+            directory_end = record_offset;
+        }
+
+        // Archives with a prepended stub record offsets relative to the zip data.
+        let directory_start = (directory_end as u64)
+            .checked_sub(directory_size)
+            .ok_or_else(|| self.corrupted("central directory overlaps the archive start"))?;
+        let base = directory_start
+            .checked_sub(directory_offset)
+            .ok_or_else(|| self.corrupted("central directory offset is past its location"))?;
+
+        let mut entries = HashMap::new();
+        let mut offset = self.offset(directory_start, "central directory")?;
+        for _ in 0..entry_count {
+            let header = self.slice(offset, CENTRAL_HEADER_SIZE, "central directory")?;
+            if u32_at(header, 0) != CENTRAL_HEADER_SIGNATURE {
+                return Err(self.corrupted("bad central directory entry signature"));
+            }
+            let name_length = u16_at(header, 28) as usize;
+            let extra_length = u16_at(header, 30) as usize;
+            let comment_length = u16_at(header, 32) as usize;
+            let name = self.slice(offset + CENTRAL_HEADER_SIZE, name_length, "entry name")?;
+            let name = String::from_utf8_lossy(name).into_owned();
+            let extra = self.slice(
+                offset + CENTRAL_HEADER_SIZE + name_length,
+                extra_length,
+                "entry extra field",
+            )?;
+
+            let mut entry = JarEntry {
+                name: name.clone(),
+                method: u16_at(header, 10),
+                compressed_size: u32_at(header, 20) as u64,
+                uncompressed_size: u32_at(header, 24) as u64,
+                crc32: u32_at(header, 16),
+                flags: u16_at(header, 8),
+                local_header_offset: u32_at(header, 42) as u64,
+            };
+            apply_zip64_extra(&mut entry, extra);
+            entry.local_header_offset = base
+                .checked_add(entry.local_header_offset)
+                .ok_or_else(|| self.corrupted(format!("entry {name} offset overflows")))?;
+            entries.insert(name, entry);
+            offset += CENTRAL_HEADER_SIZE + name_length + extra_length + comment_length;
+        }
+        Ok(entries)
+    }
+
+    /// Finds the end of central directory record, which may be followed by a comment.
+    fn find_end_record(&self) -> JayResult<usize> {
+        if self.bytes.len() < END_SIZE {
+            return Err(self.corrupted("missing end of central directory record"));
+        }
+        let last = self.bytes.len() - END_SIZE;
+        let first = last.saturating_sub(MAX_COMMENT_SIZE);
+        (first..=last)
+            .rev()
+            .find(|&offset| {
+                u32_at(&self.bytes[offset..], 0) == END_SIGNATURE
+                    && offset + END_SIZE + u16_at(&self.bytes[offset..], 20) as usize
+                        == self.bytes.len()
+            })
+            .ok_or_else(|| self.corrupted("missing end of central directory record"))
+    }
+
+    fn offset(&self, offset: u64, label: &str) -> JayResult<usize> {
+        usize::try_from(offset)
+            .ok()
+            .filter(|&offset| offset <= self.bytes.len())
+            .ok_or_else(|| self.corrupted(format!("{label} is outside the archive")))
+    }
+
+    fn slice(&self, start: usize, length: usize, label: &str) -> JayResult<&[u8]> {
+        start
+            .checked_add(length)
+            .and_then(|end| self.bytes.get(start..end))
+            .ok_or_else(|| self.corrupted(format!("truncated {label}")))
+    }
+
+    fn corrupted(&self, detail: impl std::fmt::Display) -> JayError {
+        JayError::io(format!(
+            "corrupted archive {}: {detail}",
+            self.path.display()
+        ))
+    }
+}
+
+/// Main section attributes of a JAR manifest.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Manifest {
+    attributes: Vec<(String, String)>,
+}
+
+impl Manifest {
+    /// Parses the main section, joining continuation lines that start with a space.
+    pub fn parse(bytes: &[u8]) -> JayResult<Self> {
+        let text = String::from_utf8_lossy(bytes).replace("\r\n", "\n");
+        let mut attributes: Vec<(String, String)> = Vec::new();
+        for (number, line) in text.split(['\n', '\r']).enumerate() {
+            if line.is_empty() {
+                break;
+            }
+            if let Some(continuation) = line.strip_prefix(' ') {
+                let (_, value) = attributes.last_mut().ok_or_else(|| {
+                    JayError::io(format!(
+                        "line {}: continuation without a header",
+                        number + 1
+                    ))
+                })?;
+                value.push_str(continuation);
+                continue;
+            }
+            let (name, value) = line
+                .split_once(':')
+                .filter(|(name, _)| !name.is_empty())
+                .ok_or_else(|| {
+                    JayError::io(format!("line {}: expected `Name: value`", number + 1))
+                })?;
+            attributes.push((name.to_string(), value.trim_start().to_string()));
+        }
+        Ok(Self { attributes })
+    }
+
+    /// Looks up a main attribute; names are case-insensitive.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Replaces saturated 32-bit sizes and offsets with their ZIP64 extra field values.
+fn apply_zip64_extra(entry: &mut JarEntry, mut extra: &[u8]) {
+    while extra.len() >= 4 {
+        let id = u16_at(extra, 0);
+        let size = (u16_at(extra, 2) as usize).min(extra.len() - 4);
+        let mut values = extra[4..4 + size]
+            .chunks_exact(8)
+            .map(|value| u64_at(value, 0));
+        if id == ZIP64_EXTRA_ID {
+            for field in [
+                &mut entry.uncompressed_size,
+                &mut entry.compressed_size,
+                &mut entry.local_header_offset,
+            ] {
+                if *field == u32::MAX as u64 {
+                    match values.next() {
+                        Some(value) => *field = value,
+                        None => break,
+                    }
+                }
+            }
+        }
+        extra = &extra[4 + size..];
+    }
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn u64_at(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::Compression;
+    use flate2::write::DeflateEncoder;
+
+    use super::*;
+
+    /// Writes a minimal zip archive with the given entries and prefix.
+    fn zip(prefix: &[u8], entries: &[(&str, &[u8], bool)]) -> Vec<u8> {
+        let mut bytes = prefix.to_vec();
+        let mut directory = Vec::new();
+        for (name, contents, deflate) in entries {
+            let data = if *deflate {
+                let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(contents).unwrap();
+                encoder.finish().unwrap()
+            } else {
+                contents.to_vec()
+            };
+            let method: u16 = if *deflate { 8 } else { 0 };
+            let mut crc = Crc::new();
+            crc.update(contents);
+            let offset = (bytes.len() - prefix.len()) as u32;
+
+            bytes.extend(LOCAL_HEADER_SIGNATURE.to_le_bytes());
+            bytes.extend([20, 0, 0, 0]);
+            bytes.extend(method.to_le_bytes());
+            bytes.extend([0; 4]);
+            bytes.extend(crc.sum().to_le_bytes());
+            bytes.extend((data.len() as u32).to_le_bytes());
+            bytes.extend((contents.len() as u32).to_le_bytes());
+            bytes.extend((name.len() as u16).to_le_bytes());
+            bytes.extend([0, 0]);
+            bytes.extend(name.as_bytes());
+            bytes.extend(&data);
+
+            directory.extend(CENTRAL_HEADER_SIGNATURE.to_le_bytes());
+            directory.extend([20, 0, 20, 0, 0, 0]);
+            directory.extend(method.to_le_bytes());
+            directory.extend([0; 4]);
+            directory.extend(crc.sum().to_le_bytes());
+            directory.extend((data.len() as u32).to_le_bytes());
+            directory.extend((contents.len() as u32).to_le_bytes());
+            directory.extend((name.len() as u16).to_le_bytes());
+            directory.extend([0; 12]);
+            directory.extend(offset.to_le_bytes());
+            directory.extend(name.as_bytes());
+        }
+        let directory_offset = (bytes.len() - prefix.len()) as u32;
+        let directory_size = directory.len() as u32;
+        bytes.extend(directory);
+        bytes.extend(END_SIGNATURE.to_le_bytes());
+        bytes.extend([0; 4]);
+        bytes.extend((entries.len() as u16).to_le_bytes());
+        bytes.extend((entries.len() as u16).to_le_bytes());
+        bytes.extend(directory_size.to_le_bytes());
+        bytes.extend(directory_offset.to_le_bytes());
+        bytes.extend([0, 0]);
+        bytes
+    }
+
+    fn parse(bytes: Vec<u8>) -> JayResult<JarFile> {
+        JarFile::parse(Path::new("app.jar"), bytes)
+    }
+
+    #[test]
+    fn reads_stored_and_deflated_entries() {
+        let text = b"deflated text deflated text deflated text";
+        let archive = parse(zip(
+            b"#!/bin/sh\nexec java -jar \"$0\"\n",
+            &[
+                ("com/example/Main.class", b"\xca\xfe\xba\xbe", false),
+                ("notes.txt", text, true),
+            ],
+        ))
+        .unwrap();
+
+        assert_eq!(
+            archive.read("com/example/Main.class").unwrap().unwrap(),
+            b"\xca\xfe\xba\xbe"
+        );
+        assert_eq!(archive.read("notes.txt").unwrap().unwrap(), text);
+        assert_eq!(archive.entry("notes.txt").unwrap().method, 8);
+        assert_eq!(archive.read("Missing.class").unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_corrupted_archives() {
+        let error = parse(b"PK not really".to_vec()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "corrupted archive app.jar: missing end of central directory record"
+        );
+
+        let mut bytes = zip(b"", &[("A.class", b"contents", false)]);
+        bytes[LOCAL_HEADER_SIZE + "A.class".len()] ^= 0xff;
+        let error = parse(bytes).unwrap().read("A.class").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "corrupted archive app.jar: entry A.class fails its CRC-32 check"
+        );
+
+        let bytes = zip(b"", &[("A.class", b"contents", false)]);
+        let directory = bytes.len() - END_SIZE - CENTRAL_HEADER_SIZE - "A.class".len();
+        let mut truncated = bytes[..directory].to_vec();
+        truncated.extend(&bytes[bytes.len() - END_SIZE..]);
+        let error = parse(truncated).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("corrupted archive app.jar: central directory"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn stops_inflating_entries_past_their_declared_size() {
+        let mut bytes = zip(b"", &[("bomb.bin", &vec![0; 1 << 20], true)]);
+        let directory = bytes.len() - END_SIZE - CENTRAL_HEADER_SIZE - "bomb.bin".len();
+        bytes[directory + 24..directory + 28].copy_from_slice(&16u32.to_le_bytes());
+
+        let error = parse(bytes).unwrap().read("bomb.bin").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "corrupted archive app.jar: entry bomb.bin inflates past the 16 bytes the central directory records"
+        );
+    }
+
+    #[test]
+    fn parses_manifest_main_attributes_with_continuations() {
+        let manifest = Manifest::parse(
+            b"Manifest-Version: 1.0\r\nMain-Class: com.example.very.long.pack\r\n age.Main\r\n\
+              class-path: lib/a.jar\r\n  lib/b.jar\r\n\r\nName: com/example/\r\nSealed: true\r\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.get("Main-Class"),
+            Some("com.example.very.long.package.Main")
+        );
+        assert_eq!(manifest.get("Class-Path"), Some("lib/a.jar lib/b.jar"));
+        assert_eq!(manifest.get("Sealed"), None);
+        assert_eq!(
+            Manifest::parse(b" orphan\n").unwrap_err().to_string(),
+            "line 1: continuation without a header"
+        );
+    }
+}
@@ -9,13 +9,13 @@ pub fn default_boot_image_path() -> JayResult<PathBuf> {
             return Ok(path);
         }
 
-        return Err(JayError::new(format!(
+        return Err(JayError::usage(format!(
             "JAVA_HOME points to a JDK without lib/modules: {}",
             Path::new(&java_home).display()
         )));
     }
 
-    Err(JayError::new(
+    Err(JayError::usage(
         "JAVA_HOME is not set; set JAVA_HOME to a JDK with lib/modules",
     ))
 }
@@ -24,6 +24,25 @@ pub fn boot_image_path_from_java_home(java_home: &Path) -> PathBuf {
     java_home.join("lib").join("modules")
 }
 
+/// Reads `JAVA_VERSION` from the `release` file of the JDK that contains `boot_image_path`.
+pub fn boot_image_java_version(boot_image_path: &Path) -> Option<String> {
+    let java_home = boot_image_path.parent()?.parent()?;
+    let release = std::fs::read_to_string(java_home.join("release")).ok()?;
+    release.lines().find_map(|line| {
+        let value = line.strip_prefix("JAVA_VERSION=")?;
+        Some(value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Feature release of a Java version string: `17` for `17.0.15`, `8` for `1.8.0_292`.
+pub fn feature_version(version: &str) -> Option<u32> {
+    let mut parts = version.split(|c: char| !c.is_ascii_digit());
+    match parts.next()?.parse().ok()? {
+        1 => parts.next()?.parse().ok(),
+        feature => Some(feature),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -39,4 +58,12 @@ mod tests {
             Path::new("/opt/hostedtoolcache/Java_Temurin-Hotspot_jdk/21/lib/modules")
         );
     }
+
+    #[test]
+    fn reads_feature_versions() {
+        assert_eq!(feature_version("17.0.15"), Some(17));
+        assert_eq!(feature_version("21"), Some(21));
+        assert_eq!(feature_version("1.8.0_292"), Some(8));
+        assert_eq!(feature_version("unknown"), None);
+    }
 }
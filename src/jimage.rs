@@ -1,9 +1,11 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::io::Read;
 use std::path::Path;
 
 use flate2::read::ZlibDecoder;
 
+use crate::classfile::mutf8;
 use crate::{JayError, JayResult};
 
 const IMAGE_MAGIC: u32 = 0xCAFEDADA;
@@ -90,7 +92,7 @@ pub struct ResourceLocation {
 impl JImage {
     pub fn open(path: impl AsRef<Path>) -> JayResult<Self> {
         let bytes = std::fs::read(path.as_ref()).map_err(|error| {
-            JayError::new(format!(
+            JayError::io(format!(
                 "could not read JImage at {}: {error}",
                 path.as_ref().display()
             ))
@@ -100,7 +102,7 @@ impl JImage {
 
     fn parse(bytes: Vec<u8>) -> JayResult<Self> {
         if bytes.len() < HEADER_SIZE {
-            return Err(JayError::new("truncated JImage header"));
+            return Err(JayError::io("truncated JImage header"));
         }
 
         let magic_bytes = &bytes[0..4];
@@ -109,15 +111,15 @@ impl JImage {
         } else if u32::from_be_bytes(magic_bytes.try_into().unwrap()) == IMAGE_MAGIC {
             Endian::Big
         } else {
-            return Err(JayError::new("invalid JImage magic"));
+            return Err(JayError::io("invalid JImage magic"));
         };
 
         let version = endian.read_u32(&bytes[4..8]);
         let major_version = version >> 16;
         let minor_version = version & 0xffff;
         if major_version != MAJOR_VERSION || minor_version != MINOR_VERSION {
-            return Err(JayError::new(format!(
-                "unsupported JImage version {major_version}.{minor_version}"
+            return Err(JayError::unsupported(format!(
+                "JImage version {major_version}.{minor_version}"
             )));
         }
 
@@ -134,14 +136,14 @@ impl JImage {
         let table_bytes = (header.table_length as usize)
             .checked_mul(4)
             .and_then(|size| size.checked_mul(2))
-            .ok_or_else(|| JayError::new("JImage index size overflow"))?;
+            .ok_or_else(|| JayError::io("JImage index size overflow"))?;
         let index_size = HEADER_SIZE
             .checked_add(table_bytes)
             .and_then(|size| size.checked_add(header.locations_size as usize))
             .and_then(|size| size.checked_add(header.strings_size as usize))
-            .ok_or_else(|| JayError::new("JImage index size overflow"))?;
+            .ok_or_else(|| JayError::io("JImage index size overflow"))?;
         if bytes.len() < index_size {
-            return Err(JayError::new("truncated JImage index"));
+            return Err(JayError::io("truncated JImage index"));
         }
 
         let mut image = Self {
@@ -181,7 +183,7 @@ impl JImage {
             let start = self
                 .index_size
                 .checked_add(offset)
-                .ok_or_else(|| JayError::new("JImage resource offset overflow"))?;
+                .ok_or_else(|| JayError::io("JImage resource offset overflow"))?;
             let bytes = self.slice(start, compressed_size, "compressed JImage resource")?;
             return decompress_resource_data(
                 bytes,
@@ -194,7 +196,7 @@ impl JImage {
         let start = self
             .index_size
             .checked_add(offset)
-            .ok_or_else(|| JayError::new("JImage resource offset overflow"))?;
+            .ok_or_else(|| JayError::io("JImage resource offset overflow"))?;
         let bytes = self.slice(start, uncompressed_size, "JImage resource")?;
         Ok(bytes.to_vec())
     }
@@ -208,11 +210,20 @@ impl JImage {
         match matches.len() {
             0 => Ok(None),
             1 => self.get_resource(&matches[0]).map(Some),
-            _ => Err(JayError::new(format!(
+            _ => Err(JayError::io(format!(
                 "ambiguous class {class_name} found in multiple JImage modules"
             ))),
         }
     }
+
+    /// Returns the name of the module that contains `class_name`, if any.
+    pub fn class_module(&self, class_name: &str) -> JayResult<Option<String>> {
+        let Some(location) = self.class_index.get(class_name).and_then(|m| m.first()) else {
+            return Ok(None);
+        };
+        let attributes = self.location_at_offset(location.location_offset)?;
+        Ok(Some(self.entry_name(&attributes)?.module))
+    }
 }
 
 impl JImage {
@@ -260,7 +271,7 @@ impl JImage {
             let Some(next) = remaining.strip_prefix('/') else {
                 return Ok(false);
             };
-            let Some(next) = next.strip_prefix(module) else {
+            let Some(next) = next.strip_prefix(&*module) else {
                 return Ok(false);
             };
             let Some(next) = next.strip_prefix('/') else {
@@ -271,7 +282,7 @@ impl JImage {
 
         let parent = self.string_at(location.attributes[ATTRIBUTE_PARENT] as u32)?;
         if !parent.is_empty() {
-            let Some(next) = remaining.strip_prefix(parent) else {
+            let Some(next) = remaining.strip_prefix(&*parent) else {
                 return Ok(false);
             };
             let Some(next) = next.strip_prefix('/') else {
@@ -281,7 +292,7 @@ impl JImage {
         }
 
         let base = self.string_at(location.attributes[ATTRIBUTE_BASE] as u32)?;
-        let Some(next) = remaining.strip_prefix(base) else {
+        let Some(next) = remaining.strip_prefix(&*base) else {
             return Ok(false);
         };
         remaining = next;
@@ -291,7 +302,7 @@ impl JImage {
             let Some(next) = remaining.strip_prefix('.') else {
                 return Ok(false);
             };
-            let Some(next) = next.strip_prefix(extension) else {
+            let Some(next) = next.strip_prefix(&*extension) else {
                 return Ok(false);
             };
             remaining = next;
@@ -323,7 +334,7 @@ impl JImage {
 
     fn location_at_offset(&self, offset: u32) -> JayResult<LocationAttributes> {
         if offset as usize >= self.header.locations_size as usize {
-            return Err(JayError::new("JImage location offset out of bounds"));
+            return Err(JayError::io("JImage location offset out of bounds"));
         }
         let location_start = HEADER_SIZE + self.header.table_length as usize * 8;
         let mut cursor = location_start + offset as usize;
@@ -332,7 +343,7 @@ impl JImage {
 
         loop {
             if cursor >= location_end {
-                return Err(JayError::new("unterminated JImage location attributes"));
+                return Err(JayError::io("unterminated JImage location attributes"));
             }
             let header = self.bytes[cursor];
             cursor += 1;
@@ -342,11 +353,11 @@ impl JImage {
 
             let kind = (header >> 3) as usize;
             if kind == 0 || kind >= ATTRIBUTE_COUNT {
-                return Err(JayError::new("invalid JImage location attribute kind"));
+                return Err(JayError::io("invalid JImage location attribute kind"));
             }
             let length = (header & 0x07) as usize + 1;
             if cursor + length > location_end {
-                return Err(JayError::new("truncated JImage location attribute"));
+                return Err(JayError::io("truncated JImage location attribute"));
             }
 
             let mut value = 0u64;
@@ -358,16 +369,16 @@ impl JImage {
         }
     }
 
-    fn string_at(&self, offset: u32) -> JayResult<&str> {
+    fn string_at(&self, offset: u32) -> JayResult<Cow<'_, str>> {
         self.image_strings().get(offset)
     }
 
     fn slice(&self, start: usize, length: usize, label: &str) -> JayResult<&[u8]> {
         let end = start
             .checked_add(length)
-            .ok_or_else(|| JayError::new(format!("{label} offset overflow")))?;
+            .ok_or_else(|| JayError::io(format!("{label} offset overflow")))?;
         if end > self.bytes.len() {
-            return Err(JayError::new(format!("{label} out of bounds")));
+            return Err(JayError::io(format!("{label} out of bounds")));
         }
         Ok(&self.bytes[start..end])
     }
@@ -382,13 +393,13 @@ impl JImage {
 
         let mut resource_name = String::new();
         if !parent.is_empty() {
-            resource_name.push_str(parent);
+            resource_name.push_str(&parent);
             resource_name.push('/');
         }
-        resource_name.push_str(base);
+        resource_name.push_str(&base);
         if !extension.is_empty() {
             resource_name.push('.');
-            resource_name.push_str(extension);
+            resource_name.push_str(&extension);
         }
 
         Ok(JImageEntryName {
@@ -452,17 +463,19 @@ impl<'a> ImageStrings<'a> {
         Self { bytes }
     }
 
-    fn get(&self, offset: u32) -> JayResult<&'a str> {
+    /// Returns the NUL-terminated string at `offset`; the table stores
+    /// modified UTF-8, like the class file constant pools it was built from.
+    fn get(&self, offset: u32) -> JayResult<Cow<'a, str>> {
         if offset as usize >= self.bytes.len() {
-            return Err(JayError::new("JImage string offset out of bounds"));
+            return Err(JayError::io("JImage string offset out of bounds"));
         }
         let start = offset as usize;
         let relative_end = self.bytes[start..]
             .iter()
             .position(|byte| *byte == 0)
-            .ok_or_else(|| JayError::new("unterminated JImage string"))?;
-        std::str::from_utf8(&self.bytes[start..start + relative_end])
-            .map_err(|_| JayError::new("invalid UTF-8 in JImage string table"))
+            .ok_or_else(|| JayError::io("unterminated JImage string"))?;
+        mutf8::decode(&self.bytes[start..start + relative_end])
+            .map_err(|error| JayError::io(format!("JImage string table: {error}")))
     }
 }
 
@@ -484,7 +497,7 @@ fn decompress_resource_data(
         let Some(header) = parse_resource_header(&current, endian)? else {
             let expected = final_uncompressed_size as usize;
             if current.len() < expected {
-                return Err(JayError::new("decompressed JImage resource is truncated"));
+                return Err(JayError::io("decompressed JImage resource is truncated"));
             }
             current.truncate(expected);
             return Ok(current);
@@ -493,18 +506,18 @@ fn decompress_resource_data(
         let payload_start = RESOURCE_HEADER_SIZE;
         let payload_end = payload_start
             .checked_add(header.size as usize)
-            .ok_or_else(|| JayError::new("compressed JImage resource size overflow"))?;
+            .ok_or_else(|| JayError::io("compressed JImage resource size overflow"))?;
         if payload_end > current.len() {
-            return Err(JayError::new("truncated compressed JImage resource"));
+            return Err(JayError::io("truncated compressed JImage resource"));
         }
         let payload = &current[payload_start..payload_end];
         let decompressor = strings.get(header.decompressor_name_offset)?;
-        current = match decompressor {
+        current = match decompressor.as_ref() {
             DECOMPRESSOR_ZIP => decompress_zip(payload, header.uncompressed_size)?,
             DECOMPRESSOR_COMPACT_CP => decompress_compact_cp(payload, &header, strings)?,
             other => {
-                return Err(JayError::new(format!(
-                    "unsupported JImage decompressor {other}"
+                return Err(JayError::unsupported(format!(
+                    "JImage decompressor {other}"
                 )));
             }
         };
@@ -531,11 +544,9 @@ fn decompress_zip(data: &[u8], uncompressed_size: u64) -> JayResult<Vec<u8>> {
     let mut output = Vec::new();
     decoder
         .read_to_end(&mut output)
-        .map_err(|error| JayError::new(format!("could not inflate JImage resource: {error}")))?;
+        .map_err(|error| JayError::io(format!("could not inflate JImage resource: {error}")))?;
     if output.len() != uncompressed_size as usize {
-        return Err(JayError::new(
-            "inflated JImage resource has unexpected size",
-        ));
+        return Err(JayError::io("inflated JImage resource has unexpected size"));
     }
     Ok(output)
 }
@@ -558,7 +569,7 @@ fn decompress_compact_cp(
             CP_EXTERNALIZED_STRING => {
                 output.push(CP_UTF8);
                 let string_offset = input.read_compressed_int()?;
-                write_utf8(strings.get(string_offset)?, &mut output)?;
+                write_utf8(&strings.get(string_offset)?, &mut output)?;
             }
             CP_EXTERNALIZED_DESCRIPTOR => {
                 output.push(CP_UTF8);
@@ -566,7 +577,7 @@ fn decompress_compact_cp(
                 let indexes_length = input.read_compressed_int()? as usize;
                 let indexes = input.read_bytes(indexes_length, "compact-cp descriptor indexes")?;
                 let descriptor = strings.get(descriptor_offset)?;
-                let reconstructed = reconstruct_descriptor(descriptor, indexes, strings)?;
+                let reconstructed = reconstruct_descriptor(&descriptor, indexes, strings)?;
                 write_utf8(&reconstructed, &mut output)?;
             }
             CP_UTF8 => {
@@ -583,8 +594,8 @@ fn decompress_compact_cp(
             }
             other => {
                 let Some(size) = CP_ENTRY_SIZES.get(other as usize).copied() else {
-                    return Err(JayError::new(format!(
-                        "unsupported compact-cp constant pool tag {other}"
+                    return Err(JayError::unsupported(format!(
+                        "compact-cp constant pool tag {other}"
                     )));
                 };
                 output.push(other);
@@ -596,7 +607,7 @@ fn decompress_compact_cp(
 
     output.extend(input.remaining());
     if output.len() != header.uncompressed_size as usize {
-        return Err(JayError::new(
+        return Err(JayError::io(
             "compact-cp reconstruction has unexpected size",
         ));
     }
@@ -620,21 +631,23 @@ fn reconstruct_descriptor(
             let package_offset = input.read_compressed_int()?;
             let package = strings.get(package_offset)?;
             if !package.is_empty() {
-                output.push_str(package);
+                output.push_str(&package);
                 output.push('/');
             }
             let class_offset = input.read_compressed_int()?;
-            output.push_str(strings.get(class_offset)?);
+            output.push_str(&strings.get(class_offset)?);
         }
     }
     Ok(output)
 }
 
+/// Writes a `CONSTANT_Utf8` length and body in the class file's modified UTF-8.
 fn write_utf8(value: &str, output: &mut Vec<u8>) -> JayResult<()> {
-    let length = u16::try_from(value.len())
-        .map_err(|_| JayError::new("compact-cp UTF-8 value is too long"))?;
+    let bytes = mutf8::encode(value);
+    let length = u16::try_from(bytes.len())
+        .map_err(|_| JayError::io("compact-cp UTF-8 value is too long"))?;
     output.extend(length.to_be_bytes());
-    output.extend(value.as_bytes());
+    output.extend(bytes);
     Ok(())
 }
 
@@ -658,7 +671,7 @@ impl<'a> Cursor<'a> {
         if first & 0x80 != 0 {
             let length = ((first & 0x60) >> 5) as usize;
             if length == 0 {
-                return Err(JayError::new("invalid compact-cp integer length"));
+                return Err(JayError::io("invalid compact-cp integer length"));
             }
             let mut value = u32::from(first & 0x1f);
             if length > 1 {
@@ -682,9 +695,9 @@ impl<'a> Cursor<'a> {
         let end = self
             .offset
             .checked_add(length)
-            .ok_or_else(|| JayError::new(format!("{label} offset overflow")))?;
+            .ok_or_else(|| JayError::io(format!("{label} offset overflow")))?;
         if end > self.bytes.len() {
-            return Err(JayError::new(format!("truncated {label}")));
+            return Err(JayError::io(format!("truncated {label}")));
         }
         let bytes = &self.bytes[self.offset..end];
         self.offset = end;
@@ -704,7 +717,9 @@ fn class_name_to_resource_name(class_name: &str) -> JayResult<String> {
         || class_name.contains('/')
         || class_name.contains('\\')
     {
-        return Err(JayError::new(format!("invalid class name: {class_name}")));
+        return Err(JayError::class_not_found(format!(
+            "invalid class name: {class_name}"
+        )));
     }
     Ok(format!("{}.class", class_name.replace('.', "/")))
 }
@@ -865,6 +880,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn decompresses_compact_cp_strings_as_modified_utf8() {
+        let value = "nul\0 and 😀";
+        let strings = strings_table(["compact-cp", value]);
+        let mut compact_class = vec![
+            0xCA,
+            0xFE,
+            0xBA,
+            0xBE, // magic
+            0x00,
+            0x00, // minor
+            0x00,
+            0x45, // major
+            0x00,
+            0x02, // constant_pool_count
+            CP_EXTERNALIZED_STRING,
+        ];
+        compact_class.extend(compressed_int(string_offset(&strings, value)));
+        let encoded = mutf8::encode(value);
+        let expected_size = 10 + 1 + 2 + encoded.len();
+        let resource = resource_header(compact_class.len(), expected_size, 1, true, &compact_class);
+
+        let bytes = decompress_resource_data(
+            &resource,
+            expected_size as u64,
+            &ImageStrings::new(&strings),
+            Endian::Little,
+        )
+        .unwrap();
+
+        assert_eq!(&bytes[11..13], &(encoded.len() as u16).to_be_bytes());
+        assert_eq!(&bytes[13..], encoded.as_slice());
+        assert!(encoded.windows(2).any(|window| window == [0xC0, 0x80]));
+    }
+
     #[test]
     fn rejects_bad_jimage_magic() {
         let error = JImage::parse(vec![0; HEADER_SIZE]).unwrap_err();
@@ -947,16 +997,17 @@ mod tests {
     fn strings_table<const N: usize>(values: [&str; N]) -> Vec<u8> {
         let mut bytes = vec![0];
         for value in values {
-            bytes.extend(value.as_bytes());
+            bytes.extend(mutf8::encode(value));
             bytes.push(0);
         }
         bytes
     }
 
     fn string_offset(strings: &[u8], value: &str) -> u32 {
+        let encoded = [mutf8::encode(value), vec![0]].concat();
         strings
-            .windows(value.len() + 1)
-            .position(|window| window == [value.as_bytes(), &[0]].concat())
+            .windows(encoded.len())
+            .position(|window| window == encoded)
             .unwrap() as u32
     }
 
@@ -1,6 +1,7 @@
 pub mod classfile;
 pub mod classpath;
 pub mod cli;
+pub mod jar;
 mod jdk;
 pub mod jimage;
 pub mod vm;
@@ -11,10 +12,58 @@ pub type JayResult<T> = Result<T, JayError>;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct JayError {
+    kind: ErrorKind,
     message: String,
     java_stack_trace: Vec<JavaStackFrame>,
 }
 
+/// What went wrong, for callers that react to failures rather than print them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// A class is missing from the classpath and boot image, or its name is invalid.
+    ClassNotFound,
+    /// A class file, its bytecode, or assembler text is malformed.
+    ClassFormat,
+    /// Valid Java that Jay does not implement yet, such as an opcode or JDK method.
+    UnsupportedFeature { feature: String },
+    /// Bytecode that fails the checks the JVM verifier or linker would make.
+    Verify,
+    /// A Java throwable nobody caught, with its binary class name and message.
+    JavaException {
+        class: String,
+        message: Option<String>,
+    },
+    /// Reading or writing a file, archive, boot image, or standard stream failed.
+    Io,
+    /// The command line or launch request is invalid.
+    Usage,
+    /// A run used up a limit set on its `Vm`, such as its instruction budget.
+    LimitExceeded { limit: String },
+    /// Java code called `System.exit` where no process can end, such as in
+    /// `Vm::invoke_static`.
+    Exit { status: i32 },
+    /// An invariant inside the VM was broken.
+    Internal,
+}
+
+impl ErrorKind {
+    /// Stable snake_case name used in machine-readable reports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ClassNotFound => "class_not_found",
+            Self::ClassFormat => "class_format",
+            Self::UnsupportedFeature { .. } => "unsupported_feature",
+            Self::Verify => "verify",
+            Self::JavaException { .. } => "java_exception",
+            Self::Io => "io",
+            Self::Usage => "usage",
+            Self::LimitExceeded { .. } => "limit_exceeded",
+            Self::Exit { .. } => "exit",
+            Self::Internal => "internal",
+        }
+    }
+}
+
 /// One interpreted Java frame active when a VM runtime error occurred.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct JavaStackFrame {
@@ -26,6 +75,12 @@ pub struct JavaStackFrame {
     pub descriptor: String,
     /// Bytecode program counter for the instruction that failed.
     pub pc: usize,
+    /// Module of a boot image class, such as `java.base`.
+    pub module_name: Option<String>,
+    /// Source file from the class's `SourceFile` attribute.
+    pub file_name: Option<String>,
+    /// Source line for `pc` from the method's `LineNumberTable`.
+    pub line_number: Option<u32>,
 }
 
 impl JavaStackFrame {
@@ -41,18 +96,99 @@ impl JavaStackFrame {
             method_name: method_name.into(),
             descriptor: descriptor.into(),
             pc,
+            module_name: None,
+            file_name: None,
+            line_number: None,
+        }
+    }
+}
+
+/// Formats the frame like `StackTraceElement.toString()`, as in
+/// `java.base/java.lang.Integer.parseInt(Integer.java:652)`.
+impl fmt::Display for JavaStackFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(module_name) = &self.module_name {
+            write!(f, "{module_name}/")?;
+        }
+        write!(f, "{}.{}(", self.class_name, self.method_name)?;
+        match (&self.file_name, self.line_number) {
+            (Some(file_name), Some(line_number)) => write!(f, "{file_name}:{line_number})"),
+            (Some(file_name), None) => write!(f, "{file_name})"),
+            (None, _) => f.write_str("Unknown Source)"),
         }
     }
 }
 
 impl JayError {
+    /// An [`ErrorKind::Internal`] error.
     pub fn new(message: impl Into<String>) -> Self {
+        Self::of_kind(ErrorKind::Internal, message)
+    }
+
+    pub fn of_kind(kind: ErrorKind, message: impl Into<String>) -> Self {
         Self {
+            kind,
             message: message.into(),
             java_stack_trace: Vec::new(),
         }
     }
 
+    pub fn class_not_found(message: impl Into<String>) -> Self {
+        Self::of_kind(ErrorKind::ClassNotFound, message)
+    }
+
+    pub fn class_format(message: impl Into<String>) -> Self {
+        Self::of_kind(ErrorKind::ClassFormat, message)
+    }
+
+    /// An unsupported-feature error whose message is `unsupported <feature>`.
+    pub fn unsupported(feature: impl Into<String>) -> Self {
+        let feature = feature.into();
+        let message = format!("unsupported {feature}");
+        Self::of_kind(ErrorKind::UnsupportedFeature { feature }, message)
+    }
+
+    pub fn verify(message: impl Into<String>) -> Self {
+        Self::of_kind(ErrorKind::Verify, message)
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Self::of_kind(ErrorKind::Io, message)
+    }
+
+    pub fn usage(message: impl Into<String>) -> Self {
+        Self::of_kind(ErrorKind::Usage, message)
+    }
+
+    /// A Java throwable, described as `class: message` like `Throwable.toString()`,
+    /// with the stack trace captured when it was created.
+    pub fn java_exception(
+        class: impl Into<String>,
+        message: Option<String>,
+        stack_trace: Vec<JavaStackFrame>,
+    ) -> Self {
+        let class = class.into();
+        let text = match &message {
+            Some(message) => format!("{class}: {message}"),
+            None => class.clone(),
+        };
+        Self {
+            kind: ErrorKind::JavaException { class, message },
+            message: text,
+            java_stack_trace: stack_trace,
+        }
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    /// Prefixes the message with `context: `, keeping the kind and stack trace.
+    pub fn with_context(mut self, context: impl fmt::Display) -> Self {
+        self.message = format!("{context}: {}", self.message);
+        self
+    }
+
     /// Adds a Java frame to the end of the stacktrace.
     pub fn push_java_stack_frame(&mut self, frame: JavaStackFrame) {
         self.java_stack_trace.push(frame);
@@ -70,6 +206,73 @@ impl JayError {
     }
 }
 
+impl JayError {
+    /// Renders the kind, message, and Java stack trace as one line of JSON, as
+    /// in `{"kind":"verify","message":"...","stack_trace":[{"class":...}]}`.
+    /// Frames carry `class`, `method`, `descriptor`, `pc`, and `line` when known.
+    pub fn to_json(&self) -> String {
+        let mut json = format!("{{\"kind\":{}", json_string(self.kind.name()));
+        match &self.kind {
+            ErrorKind::UnsupportedFeature { feature } => {
+                json.push_str(&format!(",\"feature\":{}", json_string(feature)));
+            }
+            ErrorKind::LimitExceeded { limit } => {
+                json.push_str(&format!(",\"limit\":{}", json_string(limit)));
+            }
+            ErrorKind::Exit { status } => json.push_str(&format!(",\"status\":{status}")),
+            ErrorKind::JavaException { class, message } => {
+                json.push_str(&format!(",\"exception_class\":{}", json_string(class)));
+                let message = message.as_deref().map_or("null".to_string(), json_string);
+                json.push_str(&format!(",\"exception_message\":{message}"));
+            }
+            _ => {}
+        }
+        json.push_str(&format!(
+            ",\"message\":{},\"stack_trace\":[",
+            json_string(&self.message)
+        ));
+        for (index, frame) in self.java_stack_trace.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            json.push_str(&format!(
+                "{{\"class\":{},\"method\":{},\"descriptor\":{},\"pc\":{}",
+                json_string(&frame.class_name),
+                json_string(&frame.method_name),
+                json_string(&frame.descriptor),
+                frame.pc
+            ));
+            if let Some(line_number) = frame.line_number {
+                json.push_str(&format!(",\"line\":{line_number}"));
+            }
+            json.push('}');
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+/// Quotes `value` as a JSON string.
+fn json_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for character in value.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            control if control < ' ' => {
+                quoted.push_str(&format!("\\u{:04x}", u32::from(control)));
+            }
+            other => quoted.push(other),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
 impl fmt::Display for JayError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(&self.message)
@@ -80,7 +283,7 @@ impl std::error::Error for JayError {}
 
 impl From<std::io::Error> for JayError {
     fn from(value: std::io::Error) -> Self {
-        Self::new(value.to_string())
+        Self::io(value.to_string())
     }
 }
 
@@ -96,6 +299,109 @@ mod tests {
         assert_eq!(error.to_string(), "unsupported bytecode");
     }
 
+    #[test]
+    fn stack_frames_display_like_stack_trace_elements() {
+        let mut frame =
+            JavaStackFrame::new("java.lang.Integer", "parseInt", "(Ljava/lang/String;)I", 9);
+        assert_eq!(
+            frame.to_string(),
+            "java.lang.Integer.parseInt(Unknown Source)"
+        );
+
+        frame.module_name = Some("java.base".to_string());
+        frame.file_name = Some("Integer.java".to_string());
+        assert_eq!(
+            frame.to_string(),
+            "java.base/java.lang.Integer.parseInt(Integer.java)"
+        );
+
+        frame.line_number = Some(652);
+        assert_eq!(
+            frame.to_string(),
+            "java.base/java.lang.Integer.parseInt(Integer.java:652)"
+        );
+    }
+
+    #[test]
+    fn context_keeps_kind_and_stack_trace() {
+        let error = JayError::unsupported("bytecode 0xba")
+            .with_java_stack_frame(JavaStackFrame::new("Main", "main", "()V", 0))
+            .with_context("method main()V");
+
+        assert_eq!(
+            error.to_string(),
+            "method main()V: unsupported bytecode 0xba"
+        );
+        assert_eq!(
+            error.kind(),
+            &ErrorKind::UnsupportedFeature {
+                feature: "bytecode 0xba".to_string()
+            }
+        );
+        assert_eq!(error.java_stack_trace().len(), 1);
+    }
+
+    #[test]
+    fn java_exceptions_read_like_throwable_to_string() {
+        let error = JayError::java_exception("java.lang.IllegalStateException", None, Vec::new());
+        assert_eq!(error.to_string(), "java.lang.IllegalStateException");
+
+        let error = JayError::java_exception(
+            "java.lang.ArithmeticException",
+            Some("/ by zero".to_string()),
+            Vec::new(),
+        );
+        assert_eq!(
+            error.to_string(),
+            "java.lang.ArithmeticException: / by zero"
+        );
+        assert_eq!(
+            error.kind(),
+            &ErrorKind::JavaException {
+                class: "java.lang.ArithmeticException".to_string(),
+                message: Some("/ by zero".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn json_reports_kind_message_and_frames() {
+        let mut frame = JavaStackFrame::new("Main", "inner", "()V", 3);
+        frame.line_number = Some(12);
+        let error = JayError::unsupported("bytecode 0xba \"indy\"")
+            .with_java_stack_frame(frame)
+            .with_java_stack_frame(JavaStackFrame::new(
+                "Main",
+                "main",
+                "([Ljava/lang/String;)V",
+                0,
+            ));
+
+        assert_eq!(
+            error.to_json(),
+            r#"{"kind":"unsupported_feature","feature":"bytecode 0xba \"indy\"","message":"unsupported bytecode 0xba \"indy\"","stack_trace":[{"class":"Main","method":"inner","descriptor":"()V","pc":3,"line":12},{"class":"Main","method":"main","descriptor":"([Ljava/lang/String;)V","pc":0}]}"#
+        );
+    }
+
+    #[test]
+    fn json_reports_java_exceptions_and_escapes_control_characters() {
+        let error =
+            JayError::java_exception("java.lang.Error", Some("a\tb\u{1}".to_string()), Vec::new());
+
+        assert_eq!(
+            error.to_json(),
+            r#"{"kind":"java_exception","exception_class":"java.lang.Error","exception_message":"a\tb\u0001","message":"java.lang.Error: a\tb\u0001","stack_trace":[]}"#
+        );
+        assert_eq!(
+            JayError::usage("missing").to_json(),
+            r#"{"kind":"usage","message":"missing","stack_trace":[]}"#
+        );
+        assert_eq!(
+            JayError::of_kind(ErrorKind::Exit { status: 3 }, "exited").to_json(),
+            r#"{"kind":"exit","status":3,"message":"exited","stack_trace":[]}"#
+        );
+    }
+
     #[test]
     fn java_stack_trace_keeps_top_frame_first_order() {
         let mut error = JayError::new("unsupported bytecode");
@@ -1,13 +1,34 @@
 use std::env;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
-use jay::cli;
+use jay::classfile::{ClassFile, assembler, disassembler, javap};
+use jay::classpath::ClassResolver;
+use jay::cli::{self, Command, ErrorFormat};
 use jay::vm::Vm;
+use jay::{ErrorKind, JayError};
 
 fn main() -> ExitCode {
-    match run() {
-        Ok(()) => ExitCode::SUCCESS,
+    let (format, args) = match cli::parse_error_format(env::args().skip(1)) {
+        Ok((format, args)) => (format, Ok(args)),
+        Err(error) => (ErrorFormat::Text, Err(error)),
+    };
+    match args.and_then(|args| run(args, format)) {
+        Ok(status) => status,
         Err(error) => {
+            report(&error, format);
+            ExitCode::from(exit_code(error.kind()))
+        }
+    }
+}
+
+fn report(error: &JayError, format: ErrorFormat) {
+    match format {
+        ErrorFormat::Json => eprintln!("{}", error.to_json()),
+        // The VM already reported uncaught exceptions on System.err, as the
+        // `java` launcher does.
+        ErrorFormat::Text if matches!(error.kind(), ErrorKind::JavaException { .. }) => {}
+        ErrorFormat::Text => {
             eprintln!("jay: {error}");
             for frame in error.java_stack_trace() {
                 eprintln!(
@@ -15,12 +36,124 @@ fn main() -> ExitCode {
                     frame.class_name, frame.method_name, frame.descriptor, frame.pc
                 );
             }
-            ExitCode::FAILURE
         }
     }
 }
 
-fn run() -> jay::JayResult<()> {
-    let config = cli::parse_args(env::args().skip(1))?;
-    Vm::new(config.classpath)?.run_main(&config.main_class)
+/// Exit status for a failed run; uncaught exceptions exit with 1 like `java`.
+fn exit_code(kind: &ErrorKind) -> u8 {
+    match kind {
+        ErrorKind::JavaException { .. } => 1,
+        ErrorKind::Usage => 2,
+        ErrorKind::ClassNotFound => 3,
+        ErrorKind::ClassFormat => 4,
+        ErrorKind::Verify => 5,
+        ErrorKind::UnsupportedFeature { .. } => 6,
+        ErrorKind::Io => 7,
+        ErrorKind::Internal => 8,
+        ErrorKind::LimitExceeded { .. } => 9,
+        ErrorKind::Exit { status } => *status as u8,
+    }
+}
+
+fn run(args: Vec<String>, format: ErrorFormat) -> jay::JayResult<ExitCode> {
+    match cli::parse_command(args)? {
+        Command::Run(config) => {
+            // In JSON mode the error carries the Java stack trace, and stderr
+            // must hold nothing but the JSON line.
+            let mut vm = Vm::builder()
+                .classpath(config.classpath)
+                .print_uncaught_exceptions(format == ErrorFormat::Text)
+                .build()?;
+            for (key, value) in config.properties {
+                vm.set_property(key, value);
+            }
+            // Like the `java` launcher, only the low byte of the status reaches the host.
+            let status = vm.run_main(&config.main_class, &config.args)?;
+            Ok(ExitCode::from(status as u8))
+        }
+        Command::Check(config) => check(config).map(|()| ExitCode::SUCCESS),
+        Command::Assemble { source, output_dir } => {
+            assemble(&source, &output_dir).map(|()| ExitCode::SUCCESS)
+        }
+        Command::Disassemble { classpath, class } => {
+            let (bytes, _) = load_class(classpath, &class)?;
+            let class_file = ClassFile::parse(&bytes)?;
+            print!("{}", disassembler::disassemble(&class_file)?);
+            for warning in disassembler::omission_warnings(&class_file) {
+                eprintln!("jay: warning: {warning}");
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Javap {
+            classpath,
+            class,
+            options,
+        } => {
+            let (bytes, origin) = load_class(classpath, &class)?;
+            let class_file = ClassFile::parse(&bytes)?;
+            print!("{}", javap::javap(&class_file, &origin, options)?);
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
+
+fn check(config: cli::Config) -> jay::JayResult<()> {
+    let report = Vm::new(config.classpath)?.check_main(&config.main_class)?;
+    for issue in &report.issues {
+        println!("{issue}");
+    }
+    if report.is_compatible() {
+        println!(
+            "no incompatibilities found in {} reachable methods",
+            report.methods_checked
+        );
+        Ok(())
+    } else {
+        let first = &report.issues[0];
+        Err(JayError::of_kind(
+            ErrorKind::UnsupportedFeature {
+                feature: format!("{} {}", first.kind, first.detail),
+            },
+            format!(
+                "{} incompatibilities found in {} reachable methods",
+                report.issues.len(),
+                report.methods_checked
+            ),
+        ))
+    }
+}
+
+/// Reads a `.class` file path directly, or resolves a class name through the
+/// classpath and boot image. Returns the bytes and where they came from.
+fn load_class(classpath: Vec<PathBuf>, class: &str) -> jay::JayResult<(Vec<u8>, String)> {
+    if class.ends_with(".class") {
+        let bytes = std::fs::read(class)
+            .map_err(|error| JayError::io(format!("could not read {class}: {error}")))?;
+        let origin = std::fs::canonicalize(class)
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|_| class.to_string());
+        return Ok((bytes, origin));
+    }
+    let class_name = class.replace('/', ".");
+    let resolver = ClassResolver::new(classpath)?;
+    let bytes = resolver.load_class_bytes(&class_name)?;
+    Ok((bytes, resolver.class_origin(&class_name)?))
+}
+
+fn assemble(source: &Path, output_dir: &Path) -> jay::JayResult<()> {
+    let text = std::fs::read_to_string(source)
+        .map_err(|error| JayError::io(format!("could not read {}: {error}", source.display())))?;
+    let class_file =
+        assembler::assemble(&text).map_err(|error| error.with_context(source.display()))?;
+    let bytes = class_file.to_bytes()?;
+
+    let mut path = output_dir.to_path_buf();
+    path.extend(class_file.this_class.split('/'));
+    path.set_extension("class");
+    let write = || {
+        std::fs::create_dir_all(path.parent().unwrap_or(output_dir))?;
+        std::fs::write(&path, &bytes)
+    };
+    write().map_err(|error| JayError::io(format!("could not write {}: {error}", path.display())))
 }
@@ -1,72 +1,114 @@
+mod boxing;
+mod builder;
 mod bytecode;
+mod calls;
+mod compatibility;
 mod descriptors;
+mod exceptions;
 mod fields;
 mod frame;
 mod heap;
+mod input;
 mod interpreter;
 mod invocation;
+mod launcher;
 mod lifecycle;
 mod native;
 mod native_runtime;
+mod properties;
 mod resolution;
 mod runtime;
+mod shutdown;
+mod streams;
+mod throwables;
 mod value;
 
-use std::io::{self, Write};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
 use std::path::PathBuf;
 
-use frame::Frame;
-use interpreter::Interpreter;
+use builder::Limits;
+pub use builder::VmBuilder;
+pub use calls::{JValue, ObjectHandle};
+pub use compatibility::{CompatibilityReport, Incompatibility, IncompatibilityKind};
+use launcher::{HostStreams, StandardStreams};
 
-use crate::classfile::ClassFile;
+use crate::JayResult;
 use crate::classpath::ClassResolver;
-use crate::{JayError, JayResult};
 
 /// Public facade for loading and executing Java class files.
-#[derive(Debug, Clone)]
+///
+/// Each run of `main` starts from fresh class state, so one `Vm` can run
+/// several entry points in turn with the same configuration and streams.
+#[derive(Debug)]
 pub struct Vm {
     classes: ClassResolver,
+    properties: BTreeMap<String, String>,
+    environment: BTreeMap<String, String>,
+    limits: Limits,
+    hide_uncaught_exceptions: bool,
+    streams: RefCell<HostStreams>,
+    session: RefCell<calls::Session>,
 }
 
 impl Vm {
-    pub fn new(classpath: PathBuf) -> JayResult<Self> {
-        Ok(Self {
-            classes: ClassResolver::new(classpath)?,
-        })
+    pub fn new(classpath: Vec<PathBuf>) -> JayResult<Self> {
+        VmBuilder::new().classpath(classpath).build()
     }
 
-    pub fn run_main(&self, main_class: &str) -> JayResult<()> {
-        let stdout = io::stdout();
-        let mut handle = stdout.lock();
-        self.run_main_to_writer(main_class, &mut handle)
+    pub fn builder() -> VmBuilder {
+        VmBuilder::new()
     }
 
-    pub fn run_main_to_writer<W: Write>(&self, main_class: &str, output: &mut W) -> JayResult<()> {
-        let bytes = self.classes.load_class_bytes(main_class)?;
-        let class_file = ClassFile::parse(&bytes)?;
-        let main = class_file
-            .find_method("main", "([Ljava/lang/String;)V")
-            .or_else(|| class_file.find_method("main", "()V"))
-            .ok_or_else(|| JayError::new(format!("main method not found in {main_class}")))?;
+    /// Sets a system property for later runs, like `-Dkey=value`.
+    pub fn set_property(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.properties.insert(key.into(), value.into());
+    }
 
-        if !main.is_public() || !main.is_static() {
-            return Err(JayError::new(format!(
-                "main method in {main_class} must be public static"
-            )));
-        }
+    /// Statically lists what the interpreter would reject in code reachable from `main`.
+    pub fn check_main(&self, main_class: &str) -> JayResult<CompatibilityReport> {
+        compatibility::check_main(&self.classes, main_class)
+    }
 
-        let code = main
-            .code
-            .as_ref()
-            .ok_or_else(|| JayError::new(format!("main method in {main_class} has no Code")))?;
+    /// Runs `main`, passing `args` as its `String[]` like the `java` launcher,
+    /// and returns the exit status: 0, or the one passed to `System.exit`.
+    pub fn run_main(&self, main_class: &str, args: &[String]) -> JayResult<i32> {
+        self.streams
+            .borrow_mut()
+            .run(|mut input, mut output, mut error| {
+                self.run_main_with_io(main_class, args, &mut input, &mut output, &mut error)
+            })
+    }
+
+    /// Runs `main` with `System.out` on `output` and the `Vm`'s other streams.
+    pub fn run_main_to_writer<W: Write>(
+        &self,
+        main_class: &str,
+        args: &[String],
+        output: &mut W,
+    ) -> JayResult<i32> {
+        self.streams
+            .borrow_mut()
+            .run_without_output(|mut input, mut error| {
+                self.run_main_with_io(main_class, args, &mut input, output, &mut error)
+            })
+    }
 
-        let mut interpreter = Interpreter::new(&self.classes, output);
-        let mut frame = Frame::new(code.max_locals);
-        match interpreter.execute(&class_file, main, code, &mut frame)? {
-            None => Ok(()),
-            Some(_) => Err(JayError::new(format!(
-                "main method in {main_class} returned a value"
-            ))),
-        }
+    /// Runs `main` with `System.in`, `System.out`, and `System.err` on the given streams.
+    pub fn run_main_with_io<R: BufRead, W: Write, E: Write>(
+        &self,
+        main_class: &str,
+        args: &[String],
+        input: &mut R,
+        output: &mut W,
+        error: &mut E,
+    ) -> JayResult<i32> {
+        let streams = StandardStreams {
+            input,
+            output,
+            error,
+        };
+        launcher::run_main(self, main_class, args, streams)
     }
 }
@@ -0,0 +1,123 @@
+//! Primitive wrapper objects built and read without running JDK caches.
+
+use std::io::Write;
+
+use super::descriptors::MethodDescriptor;
+use super::frame::Frame;
+use super::heap::{FieldKey, ObjectRef};
+use super::interpreter::Interpreter;
+use super::value::Value;
+use crate::{JayError, JayResult};
+
+/// Wrapper classes Jay boxes itself, with the descriptor of their `value` field.
+const WRAPPERS: &[(&str, &str)] = &[
+    ("java/lang/Integer", "I"),
+    ("java/lang/Long", "J"),
+    ("java/lang/Short", "S"),
+    ("java/lang/Byte", "B"),
+    ("java/lang/Character", "C"),
+    ("java/lang/Boolean", "Z"),
+];
+
+/// Whether `Class.valueOf(primitive)` is answered by [`Interpreter::box_value`].
+///
+/// The JDK versions read caches whose initializers need VM internals.
+pub(super) fn is_boxing_method(class_name: &str, name: &str, descriptor: &str) -> bool {
+    name == "valueOf"
+        && WRAPPERS.iter().any(|(wrapper, value)| {
+            *wrapper == class_name && descriptor == format!("({value})L{wrapper};")
+        })
+}
+
+/// A primitive read back out of a wrapper object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Unboxed {
+    Int(i32),
+    Long(i64),
+    Short(i16),
+    Byte(i8),
+    Char(u16),
+    Boolean(bool),
+}
+
+impl std::fmt::Display for Unboxed {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Unboxed::Int(value) => write!(formatter, "{value}"),
+            Unboxed::Long(value) => write!(formatter, "{value}"),
+            Unboxed::Short(value) => write!(formatter, "{value}"),
+            Unboxed::Byte(value) => write!(formatter, "{value}"),
+            Unboxed::Char(value) => write!(formatter, "{}", java_char(value)),
+            Unboxed::Boolean(value) => write!(formatter, "{value}"),
+        }
+    }
+}
+
+/// Renders a UTF-16 code unit, replacing unpaired surrogates.
+pub(super) fn java_char(value: u16) -> char {
+    char::from_u32(u32::from(value)).unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+impl<'a, W: Write> Interpreter<'a, W> {
+    pub(super) fn invoke_boxing_method(
+        &mut self,
+        caller: &mut Frame,
+        class_name: &str,
+        descriptor: &MethodDescriptor,
+        target_name: &str,
+    ) -> JayResult<()> {
+        let arguments = self.pop_method_arguments(
+            caller,
+            descriptor,
+            &format!("invokestatic target {target_name}"),
+        )?;
+        let [value] = arguments.as_slice() else {
+            return Err(JayError::verify(format!(
+                "{target_name} expected one argument"
+            )));
+        };
+        let reference = self.box_value(class_name, value.clone())?;
+        caller.stack.push(Value::Reference(reference));
+        self.collect_if_needed(caller)?;
+        Ok(())
+    }
+
+    /// Allocates a wrapper object holding `value` in its `value` field.
+    pub(super) fn box_value(&mut self, class_name: &str, value: Value) -> JayResult<ObjectRef> {
+        let &(_, descriptor) = WRAPPERS
+            .iter()
+            .find(|(wrapper, _)| *wrapper == class_name)
+            .ok_or_else(|| JayError::new(format!("{class_name} is not a wrapper class")))?;
+        let reference = self.heap.allocate_instance(class_name);
+        self.heap.put_instance_field(
+            reference,
+            FieldKey::new(class_name, "value", descriptor),
+            value,
+        )?;
+        Ok(reference)
+    }
+
+    /// Reads the primitive inside a wrapper object, or `None` for other objects.
+    pub(super) fn unbox(&self, reference: ObjectRef) -> JayResult<Option<Unboxed>> {
+        let Ok(class_name) = self.heap.instance_class_name(reference) else {
+            return Ok(None);
+        };
+        let Some(&(wrapper, descriptor)) =
+            WRAPPERS.iter().find(|(wrapper, _)| *wrapper == class_name)
+        else {
+            return Ok(None);
+        };
+        let value = self
+            .heap
+            .get_instance_field(reference, &FieldKey::new(wrapper, "value", descriptor))?;
+        Ok(match (descriptor, value) {
+            ("I", Some(Value::Int(value))) => Some(Unboxed::Int(value)),
+            ("J", Some(Value::Long(value))) => Some(Unboxed::Long(value)),
+            ("S", Some(Value::Int(value))) => Some(Unboxed::Short(value as i16)),
+            ("B", Some(Value::Int(value))) => Some(Unboxed::Byte(value as i8)),
+            ("C", Some(Value::Int(value))) => Some(Unboxed::Char(value as u16)),
+            ("Z", Some(Value::Int(value))) => Some(Unboxed::Boolean(value != 0)),
+            _ => None,
+        })
+    }
+}
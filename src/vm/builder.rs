@@ -0,0 +1,170 @@
+//! `VmBuilder`: classpath, boot image, host streams, properties, environment,
+//! and resource limits for an embedded `Vm`.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+use super::launcher::HostStreams;
+use super::{Vm, properties};
+use crate::JayResult;
+use crate::classpath::ClassResolver;
+use crate::jdk::default_boot_image_path;
+
+/// Bounds on the resources one run may use; `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct Limits {
+    /// Objects the heap may hold after a collection before `OutOfMemoryError`.
+    pub(super) max_heap_objects: Option<usize>,
+    /// Interpreted frames that may be active before `StackOverflowError`.
+    pub(super) max_stack_depth: Option<usize>,
+    /// Bytecode instructions a run may execute before it is stopped.
+    pub(super) instruction_budget: Option<u64>,
+}
+
+/// Configures a [`Vm`] for embedding.
+///
+/// Without further settings the result matches [`Vm::new`]: the boot image
+/// under `JAVA_HOME`, the process's standard streams and environment, and no
+/// limits.
+#[derive(Default)]
+pub struct VmBuilder {
+    classpath: Vec<PathBuf>,
+    boot_image: Option<PathBuf>,
+    streams: HostStreams,
+    properties: Vec<(String, String)>,
+    environment: Option<BTreeMap<String, String>>,
+    environment_changes: Vec<(String, Option<String>)>,
+    limits: Limits,
+    hide_uncaught_exceptions: bool,
+}
+
+impl VmBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends entries to the classpath: directories and JAR files, searched in order.
+    pub fn classpath(mut self, entries: impl IntoIterator<Item = impl Into<PathBuf>>) -> Self {
+        self.classpath.extend(entries.into_iter().map(Into::into));
+        self
+    }
+
+    /// Loads JDK classes from this `lib/modules` image instead of the one under `JAVA_HOME`.
+    pub fn boot_image(mut self, path: impl Into<PathBuf>) -> Self {
+        self.boot_image = Some(path.into());
+        self
+    }
+
+    /// Reads `System.in` from `input` instead of the process's standard input.
+    pub fn stdin(mut self, input: impl BufRead + 'static) -> Self {
+        self.streams.input = Some(Box::new(input));
+        self
+    }
+
+    /// Writes `System.out` to `output` instead of the process's standard output.
+    pub fn stdout(mut self, output: impl Write + 'static) -> Self {
+        self.streams.output = Some(Box::new(output));
+        self
+    }
+
+    /// Writes `System.err` to `error` instead of the process's standard error.
+    pub fn stderr(mut self, error: impl Write + 'static) -> Self {
+        self.streams.error = Some(Box::new(error));
+        self
+    }
+
+    /// Sets a system property, like `-Dkey=value`.
+    pub fn property(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.properties.push((key.into(), value.into()));
+        self
+    }
+
+    /// Sets an environment variable seen by `System.getenv`.
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.environment_changes
+            .push((key.into(), Some(value.into())));
+        self
+    }
+
+    /// Hides an environment variable from `System.getenv`.
+    pub fn env_remove(mut self, key: impl Into<String>) -> Self {
+        self.environment_changes.push((key.into(), None));
+        self
+    }
+
+    /// Starts from an empty environment instead of the process's.
+    pub fn env_clear(mut self) -> Self {
+        self.environment = Some(BTreeMap::new());
+        self.environment_changes.clear();
+        self
+    }
+
+    /// Throws `OutOfMemoryError` when more than `objects` objects stay
+    /// reachable. The heap is checked each time it collects garbage, which
+    /// happens every few allocations.
+    pub fn max_heap_objects(mut self, objects: usize) -> Self {
+        self.limits.max_heap_objects = Some(objects);
+        self
+    }
+
+    /// Throws `StackOverflowError` from a call that would make more than
+    /// `frames` interpreted methods active at once.
+    pub fn max_stack_depth(mut self, frames: usize) -> Self {
+        self.limits.max_stack_depth = Some(frames);
+        self
+    }
+
+    /// Stops a run with [`ErrorKind::LimitExceeded`](crate::ErrorKind) once it
+    /// has executed `instructions` bytecode instructions. Java code cannot
+    /// catch this, and shutdown hooks do not run.
+    pub fn instruction_budget(mut self, instructions: u64) -> Self {
+        self.limits.instruction_budget = Some(instructions);
+        self
+    }
+
+    /// Whether an exception escaping `main` or a shutdown hook is printed on
+    /// `System.err` like the `java` launcher does; on by default. The error
+    /// returned from the run describes the exception either way.
+    pub fn print_uncaught_exceptions(mut self, print: bool) -> Self {
+        self.hide_uncaught_exceptions = !print;
+        self
+    }
+
+    /// Opens the classpath and boot image.
+    pub fn build(self) -> JayResult<Vm> {
+        let boot_image = match self.boot_image {
+            Some(path) => path,
+            None => default_boot_image_path()?,
+        };
+        let classes = ClassResolver::with_boot_image(self.classpath, boot_image)?;
+        let mut vm_properties = properties::default_properties(classes.boot_image_path());
+        vm_properties.extend(self.properties);
+        let mut environment = self.environment.unwrap_or_else(|| {
+            std::env::vars_os()
+                .map(|(key, value)| {
+                    (
+                        key.to_string_lossy().into_owned(),
+                        value.to_string_lossy().into_owned(),
+                    )
+                })
+                .collect()
+        });
+        for (key, value) in self.environment_changes {
+            match value {
+                Some(value) => environment.insert(key, value),
+                None => environment.remove(&key),
+            };
+        }
+        Ok(Vm {
+            classes,
+            properties: vm_properties,
+            environment,
+            limits: self.limits,
+            hide_uncaught_exceptions: self.hide_uncaught_exceptions,
+            streams: RefCell::new(self.streams),
+            session: RefCell::default(),
+        })
+    }
+}
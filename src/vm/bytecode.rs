@@ -4,7 +4,7 @@ use crate::{JayError, JayResult};
 
 pub(super) fn read_u1(bytes: &[u8], pc: &mut usize) -> JayResult<u8> {
     if *pc >= bytes.len() {
-        return Err(JayError::new("unexpected end of bytecode"));
+        return Err(JayError::class_format("unexpected end of bytecode"));
     }
     let value = bytes[*pc];
     *pc += 1;
@@ -24,7 +24,7 @@ pub(super) fn read_i2(bytes: &[u8], pc: &mut usize) -> JayResult<i16> {
 pub(super) fn branch_target(code_len: usize, opcode_pc: usize, offset: i16) -> JayResult<usize> {
     let target = opcode_pc as i64 + offset as i64;
     if target < 0 || target >= code_len as i64 {
-        return Err(JayError::new(format!(
+        return Err(JayError::verify(format!(
             "branch target {target} out of bytecode range 0..{code_len}"
         )));
     }
@@ -40,8 +40,8 @@ pub(super) fn int_branch_taken(opcode: u8, value: i32) -> JayResult<bool> {
         0x9c => Ok(value >= 0),
         0x9d => Ok(value > 0),
         0x9e => Ok(value <= 0),
-        _ => Err(JayError::new(format!(
-            "unsupported integer branch opcode 0x{opcode:02x}"
+        _ => Err(JayError::unsupported(format!(
+            "integer branch opcode 0x{opcode:02x}"
         ))),
     }
 }
@@ -54,8 +54,8 @@ pub(super) fn int_compare_branch_taken(opcode: u8, left: i32, right: i32) -> Jay
         0xa2 => Ok(left >= right),
         0xa3 => Ok(left > right),
         0xa4 => Ok(left <= right),
-        _ => Err(JayError::new(format!(
-            "unsupported integer comparison branch opcode 0x{opcode:02x}"
+        _ => Err(JayError::unsupported(format!(
+            "integer comparison branch opcode 0x{opcode:02x}"
         ))),
     }
 }
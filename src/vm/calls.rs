@@ -0,0 +1,374 @@
+//! Calling static Java methods from Rust, with values converted both ways.
+
+use std::io::Write;
+
+use super::Vm;
+use super::descriptors::{MethodDescriptor, ValueType};
+use super::frame::Frame;
+use super::heap::ObjectRef;
+use super::interpreter::{Interpreter, InterpreterState};
+use super::launcher::{StandardStreams, start_interpreter};
+use super::value::Value;
+use crate::{ErrorKind, JayError, JayResult};
+
+/// A Java value passed to or returned from [`Vm::invoke_static`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JValue {
+    /// What a `void` method returns.
+    Void,
+    Null,
+    Boolean(bool),
+    Byte(i8),
+    Char(u16),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    String(String),
+    /// An object array such as `String[]` or `Object[]`, element by element.
+    Array(Vec<JValue>),
+    /// Any other object, left on the Java heap.
+    Object(ObjectHandle),
+}
+
+/// Opaque reference to a Java object returned by [`Vm::invoke_static`].
+///
+/// Only the `Vm` that returned a handle can take it back as an argument. The
+/// object stays alive until [`Vm::release_object`] or until that `Vm` is
+/// dropped, so long-lived embedders should release handles they are done with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObjectHandle(usize);
+
+/// Interpreter state shared by every `invoke_static` call on one `Vm`.
+#[derive(Debug, Default)]
+pub(super) struct Session {
+    state: Option<InterpreterState>,
+    /// Objects behind the handles given out so far, indexed by handle;
+    /// `None` once released.
+    handles: Vec<Option<ObjectRef>>,
+}
+
+impl Vm {
+    /// Calls a static method, as in
+    /// `vm.invoke_static("com.example.Calc", "add", "(II)I", &[JValue::Int(1), JValue::Int(2)])`.
+    ///
+    /// Calls share one heap and class state, unlike [`Vm::run_main`]: classes
+    /// are initialized once and returned handles can be passed back in. An
+    /// exception escaping the method is an
+    /// [`ErrorKind::JavaException`](crate::ErrorKind) error carrying the
+    /// throwable's stack trace; nothing is printed. `System.exit` ends only
+    /// the call, as an [`ErrorKind::Exit`](crate::ErrorKind) error with the
+    /// status, and shutdown hooks do not run.
+    pub fn invoke_static(
+        &self,
+        class_name: &str,
+        method_name: &str,
+        descriptor: &str,
+        arguments: &[JValue],
+    ) -> JayResult<JValue> {
+        let mut session = self.session.borrow_mut();
+        self.streams.borrow_mut().run(|input, mut output, error| {
+            let streams = StandardStreams {
+                input,
+                output: &mut output,
+                error,
+            };
+            let mut interpreter = start_interpreter(self, streams);
+            if let Some(state) = session.state.take() {
+                interpreter.resume(state);
+            }
+            let target = Target {
+                class_name: class_name.replace('.', "/"),
+                method_name,
+                descriptor,
+            };
+            let result = interpreter.call_static(&target, arguments, &mut session.handles);
+            session.state = Some(interpreter.suspend());
+            result
+        })
+    }
+
+    /// Lets the object behind `handle` be garbage collected. Passing the
+    /// handle to a later call is an [`ErrorKind::Usage`](crate::ErrorKind) error.
+    pub fn release_object(&self, handle: ObjectHandle) {
+        if let Some(slot) = self.session.borrow_mut().handles.get_mut(handle.0) {
+            *slot = None;
+        }
+    }
+}
+
+struct Target<'a> {
+    /// Internal form, such as `com/example/Calc`.
+    class_name: String,
+    method_name: &'a str,
+    descriptor: &'a str,
+}
+
+impl Target<'_> {
+    fn name(&self) -> String {
+        format!(
+            "{}.{}{}",
+            self.class_name.replace('/', "."),
+            self.method_name,
+            self.descriptor
+        )
+    }
+}
+
+impl<'a, W: Write> Interpreter<'a, W> {
+    fn call_static(
+        &mut self,
+        target: &Target<'_>,
+        arguments: &[JValue],
+        handles: &mut Vec<Option<ObjectRef>>,
+    ) -> JayResult<JValue> {
+        let target_name = target.name();
+        let (_, parameters, return_type) =
+            MethodDescriptor::parse_with_field_descriptors(target.descriptor)
+                .map_err(|error| error.with_context(format!("calling {target_name}")))?;
+        if parameters.len() != arguments.len() {
+            return Err(JayError::usage(format!(
+                "{target_name} takes {} arguments, but {} were given",
+                parameters.len(),
+                arguments.len()
+            )));
+        }
+        let class_file = self.load_class_file(&target.class_name)?;
+        let method = class_file
+            .find_method(target.method_name, target.descriptor)
+            .filter(|method| method.is_static())
+            .ok_or_else(|| JayError::usage(format!("static method {target_name} not found")))?;
+        if method.is_native() {
+            return Err(JayError::unsupported(format!(
+                "native method {target_name}"
+            )));
+        }
+        let code = method
+            .code
+            .as_ref()
+            .ok_or_else(|| JayError::verify(format!("{target_name} has no Code")))?;
+
+        let mut values = Vec::with_capacity(arguments.len());
+        for (position, (parameter, argument)) in parameters.iter().zip(arguments).enumerate() {
+            let value = self
+                .java_value(argument, parameter, handles)
+                .map_err(|error| {
+                    error.with_context(format!("argument {} of {target_name}", position + 1))
+                })?;
+            values.push(value);
+        }
+        let mut frame = Frame::with_arguments(code.max_locals, values)?;
+        // Objects behind handles stay reachable while Java code runs.
+        self.saved_roots.push(
+            handles
+                .iter()
+                .flatten()
+                .copied()
+                .map(Value::Reference)
+                .collect(),
+        );
+        let result = self
+            .initialize_class_for_use(&target.class_name, &frame)
+            .and_then(|()| self.execute(&class_file, method, code, &mut frame));
+        self.saved_roots.pop();
+
+        let result = match result {
+            Ok(result) => result,
+            Err(error) => {
+                if let Some(status) = self.exit_status.take() {
+                    return Err(JayError::of_kind(
+                        ErrorKind::Exit { status },
+                        format!("{target_name} called System.exit({status})"),
+                    ));
+                }
+                return match self.thrown_exception.take() {
+                    Some(exception) => {
+                        let stack_trace = self.stack_trace(exception)?;
+                        Err(self.exception_error(exception, stack_trace)?)
+                    }
+                    None => Err(error),
+                };
+            }
+        };
+        match (return_type, result) {
+            ("V", None) => Ok(JValue::Void),
+            (return_type, Some(value)) if return_type != "V" => {
+                self.host_value(value, return_type, handles)
+            }
+            _ => Err(JayError::verify(format!(
+                "{target_name} returned a value that does not match its descriptor"
+            ))),
+        }
+    }
+
+    /// Converts `value` for a parameter of type `parameter`, a field descriptor.
+    fn java_value(
+        &mut self,
+        value: &JValue,
+        parameter: &str,
+        handles: &[Option<ObjectRef>],
+    ) -> JayResult<Value> {
+        let converted = match (parameter, value) {
+            ("I", JValue::Int(value)) => Value::Int(*value),
+            ("Z", JValue::Boolean(value)) => Value::Int(i32::from(*value)),
+            ("B", JValue::Byte(value)) => Value::Int(i32::from(*value)),
+            ("C", JValue::Char(value)) => Value::Int(i32::from(*value)),
+            ("S", JValue::Short(value)) => Value::Int(i32::from(*value)),
+            ("J", JValue::Long(value)) => Value::Long(*value),
+            (reference, value) if reference.starts_with(['L', '[']) => {
+                self.java_reference(value, reference, handles)?
+            }
+            (parameter, value) => {
+                return Err(JayError::usage(format!(
+                    "expected a value of type {parameter}, found {value:?}"
+                )));
+            }
+        };
+        Ok(converted)
+    }
+
+    /// Converts `value` for the reference type `descriptor`, checking array
+    /// elements against its component type.
+    fn java_reference(
+        &mut self,
+        value: &JValue,
+        descriptor: &str,
+        handles: &[Option<ObjectRef>],
+    ) -> JayResult<Value> {
+        if let Some(component) = descriptor.strip_prefix('[') {
+            if !component.starts_with(['L', '[']) {
+                return Err(JayError::unsupported(format!(
+                    "primitive array type {descriptor}"
+                )));
+            }
+            return match value {
+                JValue::Null => Ok(Value::Null),
+                JValue::Array(elements) => self.java_array(elements, component, handles),
+                other => Err(JayError::usage(format!(
+                    "expected an array of {component}, found {other:?}"
+                ))),
+            };
+        }
+
+        let class_name = descriptor
+            .strip_prefix('L')
+            .and_then(|class_name| class_name.strip_suffix(';'))
+            .ok_or_else(|| JayError::usage(format!("invalid reference type {descriptor}")))?;
+        let reference = match value {
+            JValue::Null => return Ok(Value::Null),
+            JValue::String(value) => self.heap.allocate_string(value.as_str()),
+            JValue::Object(ObjectHandle(index)) => match handles.get(*index) {
+                Some(Some(reference)) => *reference,
+                Some(None) => {
+                    return Err(JayError::usage(format!(
+                        "object handle #{index} was released"
+                    )));
+                }
+                None => {
+                    return Err(JayError::usage(format!(
+                        "object handle #{index} does not belong to this Vm"
+                    )));
+                }
+            },
+            JValue::Array(elements) => {
+                // Every array is also one of these.
+                if !matches!(
+                    class_name,
+                    "java/lang/Object" | "java/lang/Cloneable" | "java/io/Serializable"
+                ) {
+                    return Err(JayError::usage(format!(
+                        "expected {}, found an array",
+                        class_name.replace('/', ".")
+                    )));
+                }
+                return self.java_array(elements, "Ljava/lang/Object;", handles);
+            }
+            other => {
+                return Err(JayError::usage(format!(
+                    "expected a reference, found {other:?}"
+                )));
+            }
+        };
+        let expected = ValueType::Reference(class_name.to_string());
+        let assignable = match self.heap.value_type(reference)? {
+            Some(actual) => self.is_assignable_type(&actual, &expected)?,
+            None => false,
+        };
+        if !assignable {
+            return Err(JayError::usage(format!(
+                "expected {}, found {}",
+                expected.name(),
+                self.heap.type_name(reference)?
+            )));
+        }
+        Ok(Value::Reference(reference))
+    }
+
+    fn java_array(
+        &mut self,
+        elements: &[JValue],
+        component: &str,
+        handles: &[Option<ObjectRef>],
+    ) -> JayResult<Value> {
+        let array = self.heap.allocate_object_array(elements.len());
+        for (index, element) in elements.iter().enumerate() {
+            let element = self
+                .java_reference(element, component, handles)
+                .map_err(|error| error.with_context(format!("array element {index}")))?;
+            self.heap.store_array_reference(array, index, element)?;
+        }
+        Ok(Value::Reference(array))
+    }
+
+    /// Converts a result of type `return_type`, a field descriptor.
+    fn host_value(
+        &mut self,
+        value: Value,
+        return_type: &str,
+        handles: &mut Vec<Option<ObjectRef>>,
+    ) -> JayResult<JValue> {
+        match (return_type, value) {
+            ("I", Value::Int(value)) => Ok(JValue::Int(value)),
+            ("Z", Value::Int(value)) => Ok(JValue::Boolean(value != 0)),
+            ("B", Value::Int(value)) => Ok(JValue::Byte(value as i8)),
+            ("C", Value::Int(value)) => Ok(JValue::Char(value as u16)),
+            ("S", Value::Int(value)) => Ok(JValue::Short(value as i16)),
+            ("J", Value::Long(value)) => Ok(JValue::Long(value)),
+            (_, value) => self.host_reference(value, handles),
+        }
+    }
+
+    fn host_reference(
+        &mut self,
+        value: Value,
+        handles: &mut Vec<Option<ObjectRef>>,
+    ) -> JayResult<JValue> {
+        let reference = match value {
+            Value::Null => return Ok(JValue::Null),
+            Value::Reference(reference) => reference,
+            other => {
+                return Err(JayError::verify(format!(
+                    "expected a reference result, found {}",
+                    other.type_name(&self.heap)?
+                )));
+            }
+        };
+        match self.heap.value_type(reference)? {
+            Some(value_type) if value_type.is_reference_to("java/lang/String") => {
+                Ok(JValue::String(self.heap.string(reference)?.to_string()))
+            }
+            Some(value_type) if value_type.is_reference_to("[Ljava/lang/Object;") => {
+                let length = self.heap.array_length(reference)?;
+                let mut elements = Vec::with_capacity(length);
+                for index in 0..length {
+                    let element = self.heap.load_array_reference(reference, index)?;
+                    elements.push(self.host_reference(element, handles)?);
+                }
+                Ok(JValue::Array(elements))
+            }
+            _ => {
+                handles.push(Some(reference));
+                Ok(JValue::Object(ObjectHandle(handles.len() - 1)))
+            }
+        }
+    }
+}
@@ -0,0 +1,800 @@
+//! Static compatibility check over the methods reachable from `main`.
+//!
+//! The walk follows method refs, the class initializers the interpreter would
+//! run, and virtual calls dispatched to classes the program instantiates. It
+//! reports what `execute_instruction` and the invoke handlers would reject
+//! without running anything.
+
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::rc::Rc;
+
+use super::boxing::is_boxing_method;
+use super::descriptors::{MethodDescriptor, parse_field_descriptor};
+use super::fields::check_array_component;
+use super::input::{is_input_class, is_input_method, is_reader_constructor, is_system_in};
+use super::interpreter::is_supported_opcode;
+use super::invocation::{Intrinsic, resolve_string_concat_call_site};
+use super::properties::is_property_method;
+use super::shutdown::{is_shutdown_method, is_thread_constructor};
+use super::streams::{is_print_stream_method, is_system_stream};
+use super::throwables::{is_throwable_constructor, is_throwable_method};
+use crate::classfile::opcode::{self, Instruction, Operand};
+use crate::classfile::{ClassFile, Method};
+use crate::classpath::ClassResolver;
+use crate::{JayError, JayResult};
+
+/// What the interpreter would reject.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IncompatibilityKind {
+    Opcode,
+    Bootstrap,
+    Descriptor,
+    NativeMethod,
+    /// A method or field ref that does not resolve the way the interpreter needs.
+    MethodRef,
+}
+
+impl fmt::Display for IncompatibilityKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Opcode => "opcode",
+            Self::Bootstrap => "bootstrap",
+            Self::Descriptor => "descriptor",
+            Self::NativeMethod => "native",
+            Self::MethodRef => "reference",
+        })
+    }
+}
+
+/// One rejected instruction, located by class, method and pc.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Incompatibility {
+    pub class_name: String,
+    pub method_name: String,
+    pub descriptor: String,
+    pub pc: usize,
+    pub kind: IncompatibilityKind,
+    pub detail: String,
+}
+
+impl fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}{} pc {}: {}: {}",
+            self.class_name, self.method_name, self.descriptor, self.pc, self.kind, self.detail
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompatibilityReport {
+    pub methods_checked: usize,
+    /// Sorted by class, method and pc.
+    pub issues: Vec<Incompatibility>,
+}
+
+impl CompatibilityReport {
+    pub fn is_compatible(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Checks every method reachable from `main` in `main_class`.
+pub(super) fn check_main(
+    classes: &ClassResolver,
+    main_class: &str,
+) -> JayResult<CompatibilityReport> {
+    let mut checker = Checker::new(classes);
+    let main_class_name = main_class.replace('.', "/");
+    let class_file = checker.class_file(&main_class_name)?;
+    let main = class_file
+        .find_method("main", "([Ljava/lang/String;)V")
+        .or_else(|| class_file.find_method("main", "()V"))
+        .ok_or_else(|| JayError::usage(format!("main method not found in {main_class}")))?;
+
+    checker.initialize(&main_class_name);
+    checker.enqueue(&main_class_name, main);
+    while let Some((class_name, method_name, descriptor)) = checker.pending.pop_front() {
+        checker.check_method(&class_name, &method_name, &descriptor)?;
+    }
+
+    let mut issues = checker.issues;
+    issues.sort();
+    issues.dedup();
+    Ok(CompatibilityReport {
+        methods_checked: checker.queued.len(),
+        issues,
+    })
+}
+
+type MethodKey = (String, String, String);
+
+/// The method whose code is being checked.
+struct Location<'a> {
+    class_name: &'a str,
+    method_name: &'a str,
+    descriptor: &'a str,
+}
+
+/// An `invokevirtual` or `invokeinterface` site, kept so classes instantiated
+/// later still get their overrides checked.
+#[derive(Clone)]
+struct VirtualCall {
+    caller: MethodKey,
+    pc: usize,
+    class_name: String,
+    method_name: String,
+    descriptor: String,
+}
+
+struct Checker<'a> {
+    classes: &'a ClassResolver,
+    class_files: HashMap<String, JayResult<Rc<ClassFile>>>,
+    queued: HashSet<MethodKey>,
+    pending: VecDeque<MethodKey>,
+    initialized: HashSet<String>,
+    instantiated: BTreeSet<String>,
+    virtual_calls: Vec<VirtualCall>,
+    issues: Vec<Incompatibility>,
+}
+
+impl<'a> Checker<'a> {
+    fn new(classes: &'a ClassResolver) -> Self {
+        Self {
+            classes,
+            class_files: HashMap::new(),
+            queued: HashSet::new(),
+            pending: VecDeque::new(),
+            initialized: HashSet::new(),
+            instantiated: BTreeSet::new(),
+            virtual_calls: Vec::new(),
+            issues: Vec::new(),
+        }
+    }
+
+    fn class_file(&mut self, class_name: &str) -> JayResult<Rc<ClassFile>> {
+        let classes = self.classes;
+        self.class_files
+            .entry(class_name.to_string())
+            .or_insert_with(|| {
+                classes
+                    .load_class_bytes(&class_name.replace('/', "."))
+                    .and_then(|bytes| ClassFile::parse(&bytes))
+                    .map(Rc::new)
+            })
+            .clone()
+    }
+
+    fn enqueue(&mut self, class_name: &str, method: &Method) {
+        let key = (
+            class_name.to_string(),
+            method.name.clone(),
+            method.descriptor.clone(),
+        );
+        if self.queued.insert(key.clone()) {
+            self.pending.push_back(key);
+        }
+    }
+
+    fn report(
+        &mut self,
+        location: &Location<'_>,
+        pc: usize,
+        kind: IncompatibilityKind,
+        detail: impl Into<String>,
+    ) {
+        self.issues.push(Incompatibility {
+            class_name: location.class_name.replace('/', "."),
+            method_name: location.method_name.to_string(),
+            descriptor: location.descriptor.to_string(),
+            pc,
+            kind,
+            detail: detail.into(),
+        });
+    }
+
+    /// Queues `<clinit>` for a class and its superclasses, as `initialize_class` runs them.
+    fn initialize(&mut self, class_name: &str) {
+        let mut next_class_name = Some(class_name.to_string());
+        while let Some(class_name) = next_class_name {
+            if !self.initialized.insert(class_name.clone()) {
+                return;
+            }
+            let Ok(class_file) = self.class_file(&class_name) else {
+                return;
+            };
+            if let Some(method) = class_file.find_method("<clinit>", "()V") {
+                self.enqueue(&class_name, method);
+            }
+            next_class_name = class_file.super_class.clone();
+        }
+    }
+
+    /// Mirrors `initialize_class_for_use`, which leaves boot image classes alone.
+    fn initialize_for_use(&mut self, class_name: &str) {
+        if self
+            .classes
+            .is_classpath_class(&class_name.replace('/', "."))
+            .unwrap_or(false)
+        {
+            self.initialize(class_name);
+        }
+    }
+
+    fn instantiate(&mut self, class_name: &str) {
+        if !self.instantiated.insert(class_name.to_string()) {
+            return;
+        }
+        for call in self.virtual_calls.clone() {
+            self.dispatch(&call, class_name);
+        }
+    }
+
+    fn check_method(
+        &mut self,
+        class_name: &str,
+        method_name: &str,
+        descriptor: &str,
+    ) -> JayResult<()> {
+        let class_file = self.class_file(class_name)?;
+        let Some(code) = class_file
+            .find_method(method_name, descriptor)
+            .and_then(|method| method.code.as_ref())
+        else {
+            return Ok(());
+        };
+        let location = Location {
+            class_name,
+            method_name,
+            descriptor,
+        };
+        let instructions = match opcode::decode_all(&code.bytes) {
+            Ok(instructions) => instructions,
+            Err(error) => {
+                self.report(&location, 0, IncompatibilityKind::Opcode, error.to_string());
+                return Ok(());
+            }
+        };
+        for instruction in &instructions {
+            self.check_instruction(&class_file, &location, instruction);
+        }
+        Ok(())
+    }
+
+    fn check_instruction(
+        &mut self,
+        class_file: &ClassFile,
+        location: &Location<'_>,
+        instruction: &Instruction,
+    ) {
+        let pc = instruction.pc;
+        let opcode = if instruction.wide {
+            opcode::WIDE
+        } else {
+            instruction.opcode
+        };
+        if !is_supported_opcode(opcode) {
+            self.report(
+                location,
+                pc,
+                IncompatibilityKind::Opcode,
+                format!(
+                    "unsupported bytecode 0x{opcode:02x} ({})",
+                    if instruction.wide {
+                        "wide"
+                    } else {
+                        instruction.mnemonic()
+                    }
+                ),
+            );
+            return;
+        }
+
+        let constant_pool = &class_file.constant_pool;
+        match (&instruction.operand, opcode) {
+            (&Operand::Constant(index), opcode::LDC | opcode::LDC_W) => {
+                if constant_pool.string(index).is_ok() {
+                    self.instantiate("java/lang/String");
+                } else if constant_pool.integer(index).is_err()
+                    && constant_pool.class_name(index).is_err()
+                {
+                    self.report(
+                        location,
+                        pc,
+                        IncompatibilityKind::Opcode,
+                        format!("unsupported ldc constant at pool index #{index}"),
+                    );
+                }
+            }
+            (&Operand::Constant(index), opcode::LDC2_W) if constant_pool.long(index).is_err() => {
+                self.report(
+                    location,
+                    pc,
+                    IncompatibilityKind::Opcode,
+                    format!("unsupported ldc2_w constant at pool index #{index}"),
+                );
+            }
+            (
+                &Operand::Constant(index),
+                opcode::GETSTATIC | opcode::PUTSTATIC | opcode::GETFIELD | opcode::PUTFIELD,
+            ) => self.check_field(class_file, location, pc, opcode, index),
+            (&Operand::Constant(index), opcode::NEW) => match constant_pool.class_name(index) {
+                Ok(class_name) => {
+                    self.instantiate(class_name);
+                    self.initialize_for_use(class_name);
+                }
+                Err(error) => self.report(
+                    location,
+                    pc,
+                    IncompatibilityKind::MethodRef,
+                    error.to_string(),
+                ),
+            },
+            (&Operand::Constant(index), opcode::ANEWARRAY) => {
+                if let Err(error) = constant_pool
+                    .class_name(index)
+                    .and_then(check_array_component)
+                {
+                    self.report(location, pc, IncompatibilityKind::Opcode, error.to_string());
+                }
+            }
+            (
+                &Operand::Constant(index),
+                opcode::INVOKEVIRTUAL | opcode::INVOKESPECIAL | opcode::INVOKESTATIC,
+            ) => self.check_invoke(class_file, location, pc, opcode, index),
+            (
+                &Operand::InvokeInterface {
+                    index,
+                    count,
+                    padding,
+                },
+                _,
+            ) => {
+                if count == 0 || padding != 0 {
+                    self.report(
+                        location,
+                        pc,
+                        IncompatibilityKind::Opcode,
+                        format!("invokeinterface has count {count} and padding {padding}"),
+                    );
+                } else {
+                    self.check_invoke(class_file, location, pc, opcode, index);
+                }
+            }
+            (&Operand::InvokeDynamic { index, padding }, _) => {
+                if padding != 0 {
+                    self.report(
+                        location,
+                        pc,
+                        IncompatibilityKind::Opcode,
+                        format!("invokedynamic at pc {pc} has nonzero padding"),
+                    );
+                    return;
+                }
+                match resolve_string_concat_call_site(class_file, index) {
+                    Ok(_) => self.instantiate("java/lang/String"),
+                    Err(error) => self.report(
+                        location,
+                        pc,
+                        IncompatibilityKind::Bootstrap,
+                        error.to_string(),
+                    ),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_field(
+        &mut self,
+        class_file: &ClassFile,
+        location: &Location<'_>,
+        pc: usize,
+        opcode: u8,
+        index: u16,
+    ) {
+        let field = match class_file.constant_pool.field_ref(index) {
+            Ok(field) => field,
+            Err(error) => {
+                return self.report(
+                    location,
+                    pc,
+                    IncompatibilityKind::MethodRef,
+                    error.to_string(),
+                );
+            }
+        };
+        if opcode == opcode::GETSTATIC
+            && (is_system_stream(field.class_name, field.name, field.descriptor)
+                || is_system_in(field.class_name, field.name, field.descriptor))
+        {
+            return;
+        }
+        if let Err(error) = parse_field_descriptor(field.descriptor) {
+            return self.report(
+                location,
+                pc,
+                IncompatibilityKind::Descriptor,
+                error.to_string(),
+            );
+        }
+        if opcode != opcode::GETSTATIC && opcode != opcode::PUTSTATIC {
+            return;
+        }
+        match self.field_class(field.class_name, field.name, field.descriptor) {
+            Some(declaring_class_name) => self.initialize(&declaring_class_name),
+            None => self.report(
+                location,
+                pc,
+                IncompatibilityKind::MethodRef,
+                format!(
+                    "field {}.{}:{} not found",
+                    field.class_name.replace('/', "."),
+                    field.name,
+                    field.descriptor
+                ),
+            ),
+        }
+    }
+
+    fn check_invoke(
+        &mut self,
+        class_file: &ClassFile,
+        location: &Location<'_>,
+        pc: usize,
+        opcode: u8,
+        index: u16,
+    ) {
+        let method = match class_file.constant_pool.method_ref(index) {
+            Ok(method) => method,
+            Err(error) => {
+                return self.report(
+                    location,
+                    pc,
+                    IncompatibilityKind::MethodRef,
+                    error.to_string(),
+                );
+            }
+        };
+        if is_intrinsic(method.class_name, method.name, method.descriptor) {
+            return;
+        }
+        let mnemonic = opcode::info(opcode).map_or("invoke", |info| info.mnemonic);
+        let target_name = format!(
+            "{}.{}{}",
+            method.class_name.replace('/', "."),
+            method.name,
+            method.descriptor
+        );
+        if opcode == opcode::INVOKEVIRTUAL
+            && method.class_name == "java/io/PrintStream"
+            && matches!(method.name, "print" | "println")
+        {
+            return self.report(
+                location,
+                pc,
+                IncompatibilityKind::MethodRef,
+                format!(
+                    "unsupported PrintStream.{} descriptor {}",
+                    method.name, method.descriptor
+                ),
+            );
+        }
+        if let Err(error) = MethodDescriptor::parse(method.descriptor) {
+            return self.report(
+                location,
+                pc,
+                IncompatibilityKind::Descriptor,
+                error.to_string(),
+            );
+        }
+        let resolved = match opcode {
+            opcode::INVOKEVIRTUAL => {
+                self.superclass_method(method.class_name, method.name, method.descriptor)
+            }
+            opcode::INVOKESPECIAL if method.name != "<init>" => {
+                self.superclass_method(method.class_name, method.name, method.descriptor)
+            }
+            opcode::INVOKEINTERFACE => {
+                self.interface_method(method.class_name, method.name, method.descriptor)
+            }
+            _ => self
+                .class_file(method.class_name)
+                .ok()
+                .filter(|target| target.find_method(method.name, method.descriptor).is_some()),
+        };
+        let Some(target_class_file) = resolved else {
+            return self.report(
+                location,
+                pc,
+                IncompatibilityKind::MethodRef,
+                format!("{mnemonic} target {target_name} not found"),
+            );
+        };
+        let target = target_class_file
+            .find_method(method.name, method.descriptor)
+            .expect("resolved class declares the method");
+
+        if opcode == opcode::INVOKESTATIC {
+            self.initialize_for_use(method.class_name);
+        }
+        if opcode == opcode::INVOKESTATIC || opcode == opcode::INVOKESPECIAL || target.is_private()
+        {
+            if target.is_native() {
+                self.report(
+                    location,
+                    pc,
+                    IncompatibilityKind::NativeMethod,
+                    format!("{mnemonic} target {target_name} is native"),
+                );
+            } else {
+                self.enqueue(&target_class_file.this_class, target);
+            }
+            return;
+        }
+
+        let call = VirtualCall {
+            caller: (
+                location.class_name.to_string(),
+                location.method_name.to_string(),
+                location.descriptor.to_string(),
+            ),
+            pc,
+            class_name: method.class_name.to_string(),
+            method_name: method.name.to_string(),
+            descriptor: method.descriptor.to_string(),
+        };
+        self.virtual_calls.push(call.clone());
+        for receiver_class_name in self.instantiated.clone() {
+            self.dispatch(&call, &receiver_class_name);
+        }
+    }
+
+    /// Checks the method a virtual call selects when its receiver is `receiver_class_name`.
+    fn dispatch(&mut self, call: &VirtualCall, receiver_class_name: &str) {
+        if !self.is_subtype(receiver_class_name, &call.class_name)
+            || is_intrinsic(receiver_class_name, &call.method_name, &call.descriptor)
+        {
+            return;
+        }
+        let Some(target_class_file) =
+            self.superclass_method(receiver_class_name, &call.method_name, &call.descriptor)
+        else {
+            return;
+        };
+        if is_intrinsic(
+            &target_class_file.this_class,
+            &call.method_name,
+            &call.descriptor,
+        ) {
+            return;
+        }
+        let target = target_class_file
+            .find_method(&call.method_name, &call.descriptor)
+            .expect("resolved class declares the method");
+        if target.is_abstract() {
+            return;
+        }
+        if target.is_native() {
+            let (class_name, method_name, descriptor) = &call.caller;
+            let location = Location {
+                class_name,
+                method_name,
+                descriptor,
+            };
+            self.report(
+                &location,
+                call.pc,
+                IncompatibilityKind::NativeMethod,
+                format!(
+                    "virtual target {}.{}{} is native",
+                    target_class_file.this_class.replace('/', "."),
+                    call.method_name,
+                    call.descriptor
+                ),
+            );
+        } else {
+            self.enqueue(&target_class_file.this_class, target);
+        }
+    }
+
+    /// Finds the class declaring a method along a superclass chain, like `find_instance_method_class`.
+    fn superclass_method(
+        &mut self,
+        class_name: &str,
+        method_name: &str,
+        descriptor: &str,
+    ) -> Option<Rc<ClassFile>> {
+        let mut next_class_name = Some(class_name.to_string());
+        while let Some(class_name) = next_class_name {
+            let class_file = self.class_file(&class_name).ok()?;
+            if class_file.find_method(method_name, descriptor).is_some() {
+                return Some(class_file);
+            }
+            next_class_name = class_file.super_class.clone();
+        }
+        None
+    }
+
+    /// Finds the interface declaring a method, like `resolve_interface_method`.
+    fn interface_method(
+        &mut self,
+        interface_name: &str,
+        method_name: &str,
+        descriptor: &str,
+    ) -> Option<Rc<ClassFile>> {
+        let mut pending = vec![interface_name.to_string()];
+        let mut visited = HashSet::new();
+        while let Some(interface_name) = pending.pop() {
+            if !visited.insert(interface_name.clone()) {
+                continue;
+            }
+            let class_file = self.class_file(&interface_name).ok()?;
+            if class_file.find_method(method_name, descriptor).is_some() {
+                return Some(class_file);
+            }
+            pending.extend(class_file.interfaces.iter().rev().cloned());
+        }
+        None
+    }
+
+    /// Finds the class declaring a field, like `resolve_field_class`.
+    fn field_class(
+        &mut self,
+        class_name: &str,
+        field_name: &str,
+        descriptor: &str,
+    ) -> Option<String> {
+        let mut pending = vec![class_name.to_string()];
+        let mut visited = HashSet::new();
+        while let Some(class_name) = pending.pop() {
+            if !visited.insert(class_name.clone()) {
+                continue;
+            }
+            let class_file = self.class_file(&class_name).ok()?;
+            if class_file.has_field(field_name, descriptor) {
+                return Some(class_name);
+            }
+            pending.extend(class_file.super_class.clone());
+            pending.extend(class_file.interfaces.iter().rev().cloned());
+        }
+        None
+    }
+
+    fn is_subtype(&mut self, class_name: &str, target_class_name: &str) -> bool {
+        let mut pending = vec![class_name.to_string()];
+        let mut visited = HashSet::new();
+        while let Some(class_name) = pending.pop() {
+            if class_name == target_class_name {
+                return true;
+            }
+            if !visited.insert(class_name.clone()) {
+                continue;
+            }
+            let Ok(class_file) = self.class_file(&class_name) else {
+                continue;
+            };
+            pending.extend(class_file.super_class.clone());
+            pending.extend(class_file.interfaces.iter().cloned());
+        }
+        false
+    }
+}
+
+fn is_intrinsic(class_name: &str, method_name: &str, descriptor: &str) -> bool {
+    Intrinsic::find(class_name, method_name, descriptor).is_some()
+        || is_property_method(class_name, method_name, descriptor)
+        || is_boxing_method(class_name, method_name, descriptor)
+        || (class_name == "java/io/PrintStream" && is_print_stream_method(method_name, descriptor))
+        || (method_name == "<init>" && is_reader_constructor(class_name, descriptor))
+        || (method_name == "<init>" && is_thread_constructor(class_name, descriptor))
+        || (method_name == "<init>" && is_throwable_constructor(class_name, descriptor))
+        || (class_name == "java/lang/Throwable" && is_throwable_method(method_name, descriptor))
+        || is_shutdown_method(class_name, method_name, descriptor)
+        || (is_input_class(class_name) && is_input_method(method_name, descriptor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classfile::builder::{ACC_NATIVE, ACC_PUBLIC, ACC_STATIC, ClassBuilder};
+    use crate::classfile::opcode::*;
+
+    fn write_main(name: &str, build: impl FnOnce(&mut ClassBuilder)) -> ClassResolver {
+        let root = std::env::temp_dir().join(format!(
+            "jay-compatibility-{name}-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&root).unwrap();
+        let mut class = ClassBuilder::new("Main");
+        build(&mut class);
+        let bytes = class.build().unwrap().to_bytes().unwrap();
+        std::fs::write(root.join("Main.class"), bytes).unwrap();
+        ClassResolver::new(vec![root]).unwrap()
+    }
+
+    #[test]
+    fn reports_rejected_opcodes_and_natives_in_reachable_methods() {
+        let classes = write_main("rejected", |class| {
+            let mut main = class.method(ACC_PUBLIC | ACC_STATIC, "main", "([Ljava/lang/String;)V");
+            main.op(LCONST_1)
+                .op(LCONST_1)
+                .op(LDIV)
+                .op(POP2)
+                .invoke(INVOKESTATIC, "Main", "helper", "()V")
+                .invoke(INVOKESTATIC, "Main", "platform", "()V")
+                .op(RETURN);
+            main.finish();
+            let mut helper = class.method(ACC_STATIC, "helper", "()V");
+            helper
+                .op(ICONST_1)
+                .newarray(10)
+                .op(POP)
+                .op(ICONST_1)
+                .type_op(ANEWARRAY, "java/lang/Object")
+                .op(POP)
+                .op(ICONST_1)
+                .type_op(ANEWARRAY, "java/io/ObjectStreamField")
+                .op(POP)
+                .op(RETURN);
+            helper.finish();
+            let mut unused = class.method(ACC_STATIC, "unused", "()V");
+            unused.op(LCONST_1).op(L2I).op(POP).op(RETURN);
+            unused.finish();
+            class.abstract_method(ACC_STATIC | ACC_NATIVE, "platform", "()V");
+        });
+
+        let report = check_main(&classes, "Main").unwrap();
+
+        let lines = report
+            .issues
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                "Main.helper()V pc 1: opcode: unsupported bytecode 0xbc (newarray)",
+                "Main.helper()V pc 10: opcode: unsupported anewarray component java.io.ObjectStreamField",
+                "Main.main([Ljava/lang/String;)V pc 2: opcode: unsupported bytecode 0x6d (ldiv)",
+                "Main.main([Ljava/lang/String;)V pc 7: native: invokestatic target Main.platform()V is native",
+            ]
+        );
+        assert_eq!(report.methods_checked, 2);
+    }
+
+    #[test]
+    fn accepts_supported_programs() {
+        let classes = write_main("supported", |class| {
+            let mut main = class.method(ACC_PUBLIC | ACC_STATIC, "main", "([Ljava/lang/String;)V");
+            main.field(
+                GETSTATIC,
+                "java/lang/System",
+                "out",
+                "Ljava/io/PrintStream;",
+            )
+            .ldc(crate::classfile::builder::Constant::String(
+                "hi".to_string(),
+            ))
+            .invoke(
+                INVOKEVIRTUAL,
+                "java/io/PrintStream",
+                "println",
+                "(Ljava/lang/String;)V",
+            )
+            .op(RETURN);
+            main.finish();
+        });
+
+        let report = check_main(&classes, "Main").unwrap();
+
+        assert!(report.is_compatible(), "{:?}", report.issues);
+        assert_eq!(report.methods_checked, 1);
+    }
+}
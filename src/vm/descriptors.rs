@@ -10,34 +10,50 @@ pub(super) struct MethodDescriptor {
 
 impl MethodDescriptor {
     pub(super) fn parse(descriptor: &str) -> JayResult<Self> {
+        Self::parse_with_field_descriptors(descriptor).map(|(parsed, _, _)| parsed)
+    }
+
+    /// Parses `descriptor` and also returns the field descriptor of each
+    /// parameter and of the return type, which keep the `boolean`, `byte`,
+    /// `char`, and `short` distinctions that [`ValueType::Int`] drops.
+    pub(super) fn parse_with_field_descriptors(
+        descriptor: &str,
+    ) -> JayResult<(Self, Vec<&str>, &str)> {
         let Some(parameters) = descriptor.strip_prefix('(') else {
-            return Err(JayError::new(format!(
+            return Err(JayError::class_format(format!(
                 "invalid method descriptor {descriptor}"
             )));
         };
-        let Some((parameters, return_type)) = parameters.split_once(')') else {
-            return Err(JayError::new(format!(
+        let Some((parameters, return_descriptor)) = parameters.split_once(')') else {
+            return Err(JayError::class_format(format!(
                 "invalid method descriptor {descriptor}"
             )));
         };
 
         let mut parameter_types = Vec::new();
+        let mut parameter_descriptors = Vec::new();
         let mut remaining_parameters = parameters;
         while !remaining_parameters.is_empty() {
             let (parameter_type, remaining) = parse_value_type(remaining_parameters, descriptor)?;
             parameter_types.push(parameter_type);
+            parameter_descriptors
+                .push(&remaining_parameters[..remaining_parameters.len() - remaining.len()]);
             remaining_parameters = remaining;
         }
 
-        let return_type = match return_type {
+        let return_type = match return_descriptor {
             "V" => ReturnType::Void,
-            _ => ReturnType::Type(parse_complete_value_type(return_type, descriptor)?),
+            _ => ReturnType::Type(parse_complete_value_type(return_descriptor, descriptor)?),
         };
 
-        Ok(Self {
-            parameter_types,
-            return_type,
-        })
+        Ok((
+            Self {
+                parameter_types,
+                return_type,
+            },
+            parameter_descriptors,
+            return_descriptor,
+        ))
     }
 }
 
@@ -89,7 +105,7 @@ pub(super) enum FieldType {
 fn parse_complete_value_type(input: &str, descriptor: &str) -> JayResult<ValueType> {
     let (value_type, remaining) = parse_value_type(input, descriptor)?;
     if !remaining.is_empty() {
-        return Err(JayError::new(format!(
+        return Err(JayError::class_format(format!(
             "invalid method descriptor {descriptor}"
         )));
     }
@@ -97,7 +113,7 @@ fn parse_complete_value_type(input: &str, descriptor: &str) -> JayResult<ValueTy
 }
 
 pub(super) fn parse_field_descriptor(descriptor: &str) -> JayResult<FieldType> {
-    if descriptor == "I" || descriptor == "Z" {
+    if matches!(descriptor, "I" | "Z" | "B" | "C" | "S") {
         return Ok(FieldType::Int);
     }
 
@@ -114,13 +130,13 @@ pub(super) fn parse_field_descriptor(descriptor: &str) -> JayResult<FieldType> {
     }
 
     if descriptor.starts_with('[') {
-        return Err(JayError::new(format!(
-            "unsupported array field descriptor {descriptor}"
+        return Err(JayError::unsupported(format!(
+            "array field descriptor {descriptor}"
         )));
     }
 
-    Err(JayError::new(format!(
-        "unsupported field descriptor {descriptor}"
+    Err(JayError::unsupported(format!(
+        "field descriptor {descriptor}"
     )))
 }
 
@@ -129,7 +145,7 @@ fn parse_value_type<'a>(input: &'a str, descriptor: &str) -> JayResult<(ValueTyp
         return Ok((ValueType::Int, remaining));
     }
 
-    if let Some(remaining) = input.strip_prefix('Z') {
+    if let Some(remaining) = input.strip_prefix(['Z', 'C', 'B', 'S']) {
         return Ok((ValueType::Int, remaining));
     }
 
@@ -140,12 +156,12 @@ fn parse_value_type<'a>(input: &'a str, descriptor: &str) -> JayResult<(ValueTyp
     if let Some(array_type) = input.strip_prefix('[') {
         if let Some(reference_type) = array_type.strip_prefix('L') {
             let Some(end_index) = reference_type.find(';') else {
-                return Err(JayError::new(format!(
+                return Err(JayError::class_format(format!(
                     "invalid method descriptor {descriptor}"
                 )));
             };
             if end_index == 0 {
-                return Err(JayError::new(format!(
+                return Err(JayError::class_format(format!(
                     "invalid method descriptor {descriptor}"
                 )));
             }
@@ -155,19 +171,19 @@ fn parse_value_type<'a>(input: &'a str, descriptor: &str) -> JayResult<(ValueTyp
             return Ok((ValueType::Reference(array_descriptor), remaining));
         }
 
-        return Err(JayError::new(format!(
-            "unsupported array type in method descriptor {descriptor}"
+        return Err(JayError::unsupported(format!(
+            "array type in method descriptor {descriptor}"
         )));
     }
 
     if let Some(reference_type) = input.strip_prefix('L') {
         let Some(end_index) = reference_type.find(';') else {
-            return Err(JayError::new(format!(
+            return Err(JayError::class_format(format!(
                 "invalid method descriptor {descriptor}"
             )));
         };
         if end_index == 0 {
-            return Err(JayError::new(format!(
+            return Err(JayError::class_format(format!(
                 "invalid method descriptor {descriptor}"
             )));
         }
@@ -177,8 +193,14 @@ fn parse_value_type<'a>(input: &'a str, descriptor: &str) -> JayResult<(ValueTyp
         return Ok((ValueType::Reference(class_name), remaining));
     }
 
-    Err(JayError::new(format!(
-        "unsupported method descriptor type in {descriptor}"
+    if input.starts_with(['F', 'D']) {
+        return Err(JayError::unsupported(format!(
+            "method descriptor type in {descriptor}"
+        )));
+    }
+
+    Err(JayError::class_format(format!(
+        "invalid method descriptor {descriptor}"
     )))
 }
 
@@ -192,11 +214,15 @@ fn is_supported_object_array_descriptor(descriptor: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ErrorKind;
 
     #[test]
     fn parses_supported_field_descriptors() {
         assert_eq!(parse_field_descriptor("I").unwrap(), FieldType::Int);
         assert_eq!(parse_field_descriptor("Z").unwrap(), FieldType::Int);
+        assert_eq!(parse_field_descriptor("B").unwrap(), FieldType::Int);
+        assert_eq!(parse_field_descriptor("C").unwrap(), FieldType::Int);
+        assert_eq!(parse_field_descriptor("S").unwrap(), FieldType::Int);
         assert_eq!(parse_field_descriptor("J").unwrap(), FieldType::Long);
         assert_eq!(
             parse_field_descriptor("Ljava/lang/String;").unwrap(),
@@ -248,6 +274,29 @@ mod tests {
         assert_eq!(descriptor.return_type, ReturnType::Type(ValueType::Int));
     }
 
+    #[test]
+    fn keeps_field_descriptors_of_parameters_and_return_type() {
+        let (descriptor, parameters, return_type) = MethodDescriptor::parse_with_field_descriptors(
+            "(ZJ[Ljava/lang/String;Ljava/util/List;)C",
+        )
+        .unwrap();
+
+        assert_eq!(descriptor.parameter_types.len(), 4);
+        assert_eq!(
+            parameters,
+            ["Z", "J", "[Ljava/lang/String;", "Ljava/util/List;"]
+        );
+        assert_eq!(return_type, "C");
+        assert_eq!(
+            MethodDescriptor::parse("(X)V").unwrap_err().kind(),
+            &ErrorKind::ClassFormat
+        );
+        assert_eq!(
+            MethodDescriptor::parse("(D)V").unwrap_err().to_string(),
+            "unsupported method descriptor type in (D)V"
+        );
+    }
+
     #[test]
     fn parses_long_method_descriptors() {
         let descriptor = MethodDescriptor::parse("(J)J").unwrap();
@@ -0,0 +1,115 @@
+//! Java exception objects, `athrow`, and exception-table handler lookup.
+
+use std::io::Write;
+
+use super::frame::Frame;
+use super::heap::{FieldKey, ObjectRef};
+use super::interpreter::Interpreter;
+use super::value::Value;
+use crate::classfile::{ClassFile, Code};
+use crate::{JavaStackFrame, JayError, JayResult};
+
+impl<'a, W: Write> Interpreter<'a, W> {
+    /// Allocates a VM-created throwable and starts unwinding with it.
+    ///
+    /// The object is built without running its constructor, because the JDK
+    /// `Throwable` initializer depends on stack-trace machinery Jay does not
+    /// interpret; only the message, cause, and stack trace are set.
+    pub(super) fn throw_new(
+        &mut self,
+        class_name: &str,
+        message: Option<&str>,
+        cause: Option<ObjectRef>,
+    ) -> JayError {
+        let reference = self.heap.allocate_instance(class_name);
+        let message = match message {
+            Some(message) => Value::Reference(self.heap.allocate_string(message)),
+            None => Value::Null,
+        };
+        let cause = cause.map_or(Value::Null, Value::Reference);
+        for (field, value) in [(detail_message_field(), message), (cause_field(), cause)] {
+            if let Err(error) = self.heap.put_instance_field(reference, field, value) {
+                return error;
+            }
+        }
+        if let Err(error) = self.fill_in_stack_trace(reference) {
+            return error;
+        }
+        self.throw(reference)
+    }
+
+    /// Starts unwinding with an existing throwable object.
+    pub(super) fn throw(&mut self, exception: ObjectRef) -> JayError {
+        self.thrown_exception = Some(exception);
+        // The interpreter adds frames to the error as it unwinds.
+        self.exception_error(exception, Vec::new())
+            .unwrap_or_else(|error| error)
+    }
+
+    pub(super) fn athrow(&mut self, frame: &mut Frame) -> JayResult<()> {
+        match frame.pop_reference()? {
+            Value::Reference(exception) => Err(self.throw(exception)),
+            _ => Err(self.throw_new("java/lang/NullPointerException", None, None)),
+        }
+    }
+
+    /// Finds the handler for the in-flight throwable at `pc`, clearing the
+    /// throwable and resetting the operand stack when one matches.
+    pub(super) fn catch_thrown_exception(
+        &mut self,
+        class_file: &ClassFile,
+        code: &Code,
+        frame: &mut Frame,
+        pc: usize,
+    ) -> JayResult<Option<usize>> {
+        let Some(exception) = self.thrown_exception else {
+            return Ok(None);
+        };
+        let exception_class = self.heap.instance_class_name(exception)?.to_string();
+        for handler in &code.exception_table {
+            if pc < handler.start_pc as usize || pc >= handler.end_pc as usize {
+                continue;
+            }
+            if handler.catch_type != 0 {
+                let catch_type = class_file.constant_pool.class_name(handler.catch_type)?;
+                if !self.is_assignable_reference(&exception_class, catch_type)? {
+                    continue;
+                }
+            }
+
+            self.thrown_exception = None;
+            frame.stack.clear();
+            frame.stack.push(Value::Reference(exception));
+            return Ok(Some(handler.handler_pc as usize));
+        }
+        Ok(None)
+    }
+
+    /// An [`ErrorKind::JavaException`](crate::ErrorKind) error for a throwable.
+    pub(super) fn exception_error(
+        &self,
+        exception: ObjectRef,
+        stack_trace: Vec<JavaStackFrame>,
+    ) -> JayResult<JayError> {
+        let message = match self
+            .heap
+            .get_instance_field(exception, &detail_message_field())?
+        {
+            Some(Value::Reference(message)) => Some(self.heap.string(message)?.to_string()),
+            _ => None,
+        };
+        Ok(JayError::java_exception(
+            self.heap.type_name(exception)?,
+            message,
+            stack_trace,
+        ))
+    }
+}
+
+pub(super) fn detail_message_field() -> FieldKey {
+    FieldKey::new("java/lang/Throwable", "detailMessage", "Ljava/lang/String;")
+}
+
+pub(super) fn cause_field() -> FieldKey {
+    FieldKey::new("java/lang/Throwable", "cause", "Ljava/lang/Throwable;")
+}
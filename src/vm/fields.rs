@@ -5,11 +5,25 @@ use std::io::Write;
 use super::descriptors::{FieldType, parse_field_descriptor};
 use super::frame::Frame;
 use super::heap::FieldKey;
+use super::input::is_system_in;
 use super::interpreter::Interpreter;
+use super::streams::is_system_stream;
 use super::value::Value;
 use crate::classfile::ClassFile;
 use crate::{JayError, JayResult};
 
+/// Rejects `anewarray` component classes the heap cannot model; only
+/// `Object[]` is allocated today.
+pub(super) fn check_array_component(class_name: &str) -> JayResult<()> {
+    if class_name != "java/lang/Object" {
+        return Err(JayError::unsupported(format!(
+            "anewarray component {}",
+            class_name.replace('/', ".")
+        )));
+    }
+    Ok(())
+}
+
 impl<'a, W: Write> Interpreter<'a, W> {
     pub(super) fn new_object(
         &mut self,
@@ -18,9 +32,15 @@ impl<'a, W: Write> Interpreter<'a, W> {
         index: u16,
     ) -> JayResult<()> {
         let class_name = class_file.constant_pool.class_name(index)?;
+        self.link_class(class_name)?;
+        if !self.linked_classes[class_name].is_instantiable() {
+            let message = class_name.replace('/', ".");
+            return Err(self.throw_new("java/lang/InstantiationError", Some(&message), None));
+        }
+        self.initialize_class_for_use(class_name, frame)?;
         let reference = self.heap.allocate_instance(class_name);
         frame.stack.push(Value::Reference(reference));
-        self.collect_if_needed(frame);
+        self.collect_if_needed(frame)?;
         Ok(())
     }
 
@@ -30,13 +50,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
         frame: &mut Frame,
         index: u16,
     ) -> JayResult<()> {
-        let class_name = class_file.constant_pool.class_name(index)?;
-        if class_name != "java/lang/Object" {
-            return Err(JayError::new(format!(
-                "unsupported anewarray component {}",
-                class_name.replace('/', ".")
-            )));
-        }
+        check_array_component(class_file.constant_pool.class_name(index)?)?;
 
         let length = frame.pop_int()?;
         if length < 0 {
@@ -45,7 +59,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
 
         let reference = self.heap.allocate_object_array(length as usize);
         frame.stack.push(Value::Reference(reference));
-        self.collect_if_needed(frame);
+        self.collect_if_needed(frame)?;
         Ok(())
     }
 
@@ -59,7 +73,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
         if let Ok(value) = constant_pool.string(index) {
             let reference = self.heap.allocate_string(value);
             frame.stack.push(Value::Reference(reference));
-            self.collect_if_needed(frame);
+            self.collect_if_needed(frame)?;
             return Ok(());
         }
 
@@ -71,12 +85,12 @@ impl<'a, W: Write> Interpreter<'a, W> {
         if let Ok(class_name) = constant_pool.class_name(index) {
             let reference = self.class_mirror(class_name);
             frame.stack.push(Value::Reference(reference));
-            self.collect_if_needed(frame);
+            self.collect_if_needed(frame)?;
             return Ok(());
         }
 
-        Err(JayError::new(format!(
-            "unsupported ldc constant at pool index #{index}"
+        Err(JayError::unsupported(format!(
+            "ldc constant at pool index #{index}"
         )))
     }
 
@@ -91,8 +105,8 @@ impl<'a, W: Write> Interpreter<'a, W> {
             return Ok(());
         }
 
-        Err(JayError::new(format!(
-            "unsupported ldc2_w constant at pool index #{index}"
+        Err(JayError::unsupported(format!(
+            "ldc2_w constant at pool index #{index}"
         )))
     }
 
@@ -103,11 +117,13 @@ impl<'a, W: Write> Interpreter<'a, W> {
         index: u16,
     ) -> JayResult<()> {
         let field = class_file.constant_pool.field_ref(index)?;
-        if field.class_name == "java/lang/System"
-            && field.name == "out"
-            && field.descriptor == "Ljava/io/PrintStream;"
-        {
-            frame.stack.push(Value::PrintStream);
+        if is_system_stream(field.class_name, field.name, field.descriptor) {
+            let stream = self.system_stream(field.name)?;
+            frame.stack.push(stream);
+            Ok(())
+        } else if is_system_in(field.class_name, field.name, field.descriptor) {
+            let stream = self.system_in()?;
+            frame.stack.push(stream);
             Ok(())
         } else {
             let field_type = parse_field_descriptor(field.descriptor)?;
@@ -141,7 +157,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
                     frame.stack.push(Value::Null);
                     Ok(())
                 }
-                (_, Some(other)) => Err(JayError::new(format!(
+                (_, Some(other)) => Err(JayError::verify(format!(
                     "getstatic {}.{}:{} found {}",
                     field.class_name.replace('/', "."),
                     field.name,
@@ -207,7 +223,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
                 frame.stack.push(Value::Null);
                 Ok(())
             }
-            (_, Some(other)) => Err(JayError::new(format!(
+            (_, Some(other)) => Err(JayError::verify(format!(
                 "getfield {}.{}:{} found {}",
                 field.class_name.replace('/', "."),
                 field.name,
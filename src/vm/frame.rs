@@ -1,7 +1,7 @@
 //! Stack frame storage for local variables and operand stack operations.
 
 use super::descriptors::{FieldType, ValueType};
-use super::heap::{Heap, ObjectRef};
+use super::heap::ObjectRef;
 use super::value::Value;
 use crate::{JayError, JayResult};
 
@@ -22,7 +22,7 @@ impl Frame {
         let mut frame = Self::new(max_locals);
         let required_locals = arguments.iter().map(value_local_width).sum::<usize>();
         if required_locals > frame.locals.len() {
-            return Err(JayError::new(format!(
+            return Err(JayError::verify(format!(
                 "method expects {} argument locals but max locals is {}",
                 required_locals,
                 frame.locals.len()
@@ -95,7 +95,7 @@ impl Frame {
             .stack
             .last()
             .cloned()
-            .ok_or_else(|| JayError::new("operand stack underflow on dup"))?;
+            .ok_or_else(|| JayError::verify("operand stack underflow on dup"))?;
         self.stack.push(value);
         Ok(())
     }
@@ -103,7 +103,7 @@ impl Frame {
     pub(super) fn duplicate_top_insert_two_down(&mut self) -> JayResult<()> {
         let len = self.stack.len();
         if len < 2 {
-            return Err(JayError::new("operand stack underflow on dup_x1"));
+            return Err(JayError::verify("operand stack underflow on dup_x1"));
         }
 
         let value = self.stack[len - 1].clone();
@@ -116,7 +116,7 @@ impl Frame {
             (Value::Reference(left), Value::Reference(right)) => Ok(left == right),
             (Value::Null, Value::Null) => Ok(true),
             (Value::Reference(_), Value::Null) | (Value::Null, Value::Reference(_)) => Ok(false),
-            _ => Err(JayError::new(format!(
+            _ => Err(JayError::verify(format!(
                 "expected references for comparison, found {left:?} and {right:?}"
             ))),
         }
@@ -125,10 +125,10 @@ impl Frame {
     fn local_int(&self, index: usize) -> JayResult<i32> {
         match self.local_slot(index)? {
             Value::Int(value) => Ok(*value),
-            Value::Uninitialized => Err(JayError::new(format!(
+            Value::Uninitialized => Err(JayError::verify(format!(
                 "local variable #{index} is uninitialized"
             ))),
-            other => Err(JayError::new(format!(
+            other => Err(JayError::verify(format!(
                 "expected int in local variable #{index}, found {other:?}"
             ))),
         }
@@ -138,10 +138,10 @@ impl Frame {
         self.ensure_category_two_local(index)?;
         match self.local_slot(index)? {
             Value::Long(value) => Ok(*value),
-            Value::Uninitialized => Err(JayError::new(format!(
+            Value::Uninitialized => Err(JayError::verify(format!(
                 "local variable #{index} is uninitialized"
             ))),
-            other => Err(JayError::new(format!(
+            other => Err(JayError::verify(format!(
                 "expected long in local variable #{index}, found {other:?}"
             ))),
         }
@@ -150,10 +150,10 @@ impl Frame {
     fn local_reference(&self, index: usize) -> JayResult<&Value> {
         match self.local_slot(index)? {
             value @ (Value::Reference(_) | Value::Null) => Ok(value),
-            Value::Uninitialized => Err(JayError::new(format!(
+            Value::Uninitialized => Err(JayError::verify(format!(
                 "local variable #{index} is uninitialized"
             ))),
-            other => Err(JayError::new(format!(
+            other => Err(JayError::verify(format!(
                 "expected reference in local variable #{index}, found {other:?}"
             ))),
         }
@@ -161,7 +161,7 @@ impl Frame {
 
     fn local_slot(&self, index: usize) -> JayResult<&Value> {
         self.locals.get(index).ok_or_else(|| {
-            JayError::new(format!(
+            JayError::verify(format!(
                 "invalid local variable index #{index}; max locals {}",
                 self.locals.len()
             ))
@@ -171,7 +171,7 @@ impl Frame {
     fn local_slot_mut(&mut self, index: usize) -> JayResult<&mut Value> {
         let max_locals = self.locals.len();
         self.locals.get_mut(index).ok_or_else(|| {
-            JayError::new(format!(
+            JayError::verify(format!(
                 "invalid local variable index #{index}; max locals {max_locals}"
             ))
         })
@@ -182,37 +182,16 @@ impl Frame {
             return Ok(());
         }
 
-        Err(JayError::new(format!(
+        Err(JayError::verify(format!(
             "invalid category-2 local variable index #{index}; max locals {}",
             self.locals.len()
         )))
     }
 
-    pub(super) fn pop_print_stream(&mut self) -> JayResult<()> {
-        match self.pop()? {
-            Value::PrintStream => Ok(()),
-            other => Err(JayError::new(format!(
-                "expected PrintStream receiver on stack, found {other:?}"
-            ))),
-        }
-    }
-
-    pub(super) fn pop_string_reference(&mut self, heap: &Heap) -> JayResult<ObjectRef> {
-        match self.pop()? {
-            Value::Reference(reference) => {
-                let _ = heap.string(reference)?;
-                Ok(reference)
-            }
-            other => Err(JayError::new(format!(
-                "expected string on stack, found {other:?}"
-            ))),
-        }
-    }
-
     pub(super) fn pop_int(&mut self) -> JayResult<i32> {
         match self.pop()? {
             Value::Int(value) => Ok(value),
-            other => Err(JayError::new(format!(
+            other => Err(JayError::verify(format!(
                 "expected int on stack, found {other:?}"
             ))),
         }
@@ -221,7 +200,7 @@ impl Frame {
     pub(super) fn pop_long(&mut self) -> JayResult<i64> {
         match self.pop()? {
             Value::Long(value) => Ok(value),
-            other => Err(JayError::new(format!(
+            other => Err(JayError::verify(format!(
                 "expected long on stack, found {other:?}"
             ))),
         }
@@ -231,7 +210,7 @@ impl Frame {
         match self.pop_reference()? {
             Value::Reference(reference) => Ok(reference),
             Value::Null => Err(JayError::new("null reference on stack")),
-            other => Err(JayError::new(format!(
+            other => Err(JayError::verify(format!(
                 "expected reference on stack, found {other:?}"
             ))),
         }
@@ -240,7 +219,7 @@ impl Frame {
     pub(super) fn pop_reference(&mut self) -> JayResult<Value> {
         match self.pop()? {
             value @ (Value::Reference(_) | Value::Null) => Ok(value),
-            other => Err(JayError::new(format!(
+            other => Err(JayError::verify(format!(
                 "expected reference on stack, found {other:?}"
             ))),
         }
@@ -265,7 +244,7 @@ impl Frame {
     pub(super) fn pop(&mut self) -> JayResult<Value> {
         self.stack
             .pop()
-            .ok_or_else(|| JayError::new("operand stack underflow"))
+            .ok_or_else(|| JayError::verify("operand stack underflow"))
     }
 
     pub(super) fn roots(&self) -> impl Iterator<Item = &Value> {
@@ -279,17 +258,14 @@ impl Frame {
 fn value_local_width(value: &Value) -> usize {
     match value {
         Value::Long(_) => 2,
-        Value::Uninitialized
-        | Value::Null
-        | Value::Int(_)
-        | Value::Reference(_)
-        | Value::PrintStream => 1,
+        Value::Uninitialized | Value::Null | Value::Int(_) | Value::Reference(_) => 1,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::vm::heap::Heap;
 
     #[test]
     fn garbage_collection_keeps_frame_local_and_stack_references() {
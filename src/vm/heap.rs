@@ -11,6 +11,13 @@ const DEFAULT_GC_THRESHOLD: usize = 8;
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(super) struct ObjectRef(usize);
 
+impl ObjectRef {
+    /// Stand-in for `System.identityHashCode`, stable while the object is live.
+    pub(super) fn identity_hash(self) -> i32 {
+        (self.0 as u32).wrapping_add(1).wrapping_mul(0x9e37_79b9) as i32 & i32::MAX
+    }
+}
+
 #[derive(Debug)]
 pub(super) struct Heap {
     objects: Vec<Option<HeapObject>>,
@@ -105,11 +112,11 @@ impl Heap {
     pub(super) fn string(&self, reference: ObjectRef) -> JayResult<&str> {
         match self.object(reference)?.kind {
             ObjectKind::String(ref value) => Ok(value),
-            ObjectKind::Instance { ref class_name, .. } => Err(JayError::new(format!(
+            ObjectKind::Instance { ref class_name, .. } => Err(JayError::verify(format!(
                 "expected String reference, found {}",
                 class_name.replace('/', ".")
             ))),
-            ObjectKind::ObjectArray { .. } => Err(JayError::new(
+            ObjectKind::ObjectArray { .. } => Err(JayError::verify(
                 "expected String reference, found java.lang.Object[]",
             )),
         }
@@ -138,10 +145,10 @@ impl Heap {
     pub(super) fn instance_class_name(&self, reference: ObjectRef) -> JayResult<&str> {
         match self.object(reference)?.kind {
             ObjectKind::Instance { ref class_name, .. } => Ok(class_name),
-            ObjectKind::String(_) => {
-                Err(JayError::new("expected instance reference, found String"))
-            }
-            ObjectKind::ObjectArray { .. } => Err(JayError::new(
+            ObjectKind::String(_) => Err(JayError::verify(
+                "expected instance reference, found String",
+            )),
+            ObjectKind::ObjectArray { .. } => Err(JayError::verify(
                 "expected instance reference, found java.lang.Object[]",
             )),
         }
@@ -158,10 +165,10 @@ impl Heap {
                 fields.insert(field, value);
                 Ok(())
             }
-            ObjectKind::String(_) => Err(JayError::new(
+            ObjectKind::String(_) => Err(JayError::verify(
                 "expected instance reference for putfield, found String",
             )),
-            ObjectKind::ObjectArray { .. } => Err(JayError::new(
+            ObjectKind::ObjectArray { .. } => Err(JayError::verify(
                 "expected instance reference for putfield, found java.lang.Object[]",
             )),
         }
@@ -174,10 +181,10 @@ impl Heap {
     ) -> JayResult<Option<Value>> {
         match self.object(reference)?.kind {
             ObjectKind::Instance { ref fields, .. } => Ok(fields.get(field).cloned()),
-            ObjectKind::String(_) => Err(JayError::new(
+            ObjectKind::String(_) => Err(JayError::verify(
                 "expected instance reference for getfield, found String",
             )),
-            ObjectKind::ObjectArray { .. } => Err(JayError::new(
+            ObjectKind::ObjectArray { .. } => Err(JayError::verify(
                 "expected instance reference for getfield, found java.lang.Object[]",
             )),
         }
@@ -186,7 +193,7 @@ impl Heap {
     pub(super) fn array_length(&self, reference: ObjectRef) -> JayResult<usize> {
         match self.object(reference)?.kind {
             ObjectKind::ObjectArray { ref elements } => Ok(elements.len()),
-            _ => Err(JayError::new(format!(
+            _ => Err(JayError::verify(format!(
                 "expected object array reference, found {}",
                 self.type_name(reference)?
             ))),
@@ -208,7 +215,7 @@ impl Heap {
                 };
                 Ok(value.clone())
             }
-            _ => Err(JayError::new(format!(
+            _ => Err(JayError::verify(format!(
                 "expected object array reference, found {}",
                 self.type_name(reference)?
             ))),
@@ -222,7 +229,7 @@ impl Heap {
         value: Value,
     ) -> JayResult<()> {
         if !matches!(value, Value::Reference(_) | Value::Null) {
-            return Err(JayError::new(format!(
+            return Err(JayError::verify(format!(
                 "expected reference for object array store, found {}",
                 value.type_name(self)?
             )));
@@ -239,13 +246,18 @@ impl Heap {
                 *slot = value;
                 Ok(())
             }
-            _ => Err(JayError::new(format!(
+            _ => Err(JayError::verify(format!(
                 "expected object array reference, found {}",
                 self.type_name(reference)?
             ))),
         }
     }
 
+    /// Objects allocated and not yet swept, live or not.
+    pub(super) fn object_count(&self) -> usize {
+        self.objects.len() - self.free_slots.len()
+    }
+
     pub(super) fn should_collect(&self) -> bool {
         self.allocations_since_gc >= self.gc_threshold
     }
@@ -528,4 +540,18 @@ mod tests {
         heap.collect(std::iter::empty::<&Value>());
         assert!(!heap.should_collect());
     }
+
+    #[test]
+    fn object_count_drops_when_unreachable_objects_are_swept() {
+        let mut heap = Heap::new();
+        let live = heap.allocate_string("live");
+        heap.allocate_string("dead");
+        assert_eq!(heap.object_count(), 2);
+
+        heap.collect([Value::Reference(live)].iter());
+        assert_eq!(heap.object_count(), 1);
+
+        heap.allocate_string("reused");
+        assert_eq!(heap.object_count(), 2);
+    }
 }
@@ -0,0 +1,373 @@
+//! `System.in` and the `InputStreamReader`, `BufferedReader`, and `Scanner`
+//! stacks built on it, answered from a host reader.
+
+use std::io::{self, BufRead, Write};
+
+use super::descriptors::MethodDescriptor;
+use super::frame::Frame;
+use super::heap::{FieldKey, ObjectRef};
+use super::interpreter::Interpreter;
+use super::value::Value;
+use crate::{ErrorKind, JayError, JayResult};
+
+/// Reader classes whose constructors Jay answers when they wrap `System.in`.
+const READER_CONSTRUCTORS: &[(&str, &str)] = &[
+    ("java/io/InputStreamReader", "(Ljava/io/InputStream;)V"),
+    ("java/io/BufferedReader", "(Ljava/io/Reader;)V"),
+    ("java/util/Scanner", "(Ljava/io/InputStream;)V"),
+];
+
+/// Methods answered for objects reading from the host input, as `(name, descriptor)`.
+const INPUT_METHODS: &[(&str, &str)] = &[
+    ("read", "()I"),
+    ("available", "()I"),
+    ("ready", "()Z"),
+    ("close", "()V"),
+    ("readLine", "()Ljava/lang/String;"),
+    ("hasNext", "()Z"),
+    ("next", "()Ljava/lang/String;"),
+    ("hasNextInt", "()Z"),
+    ("nextInt", "()I"),
+    ("hasNextLong", "()Z"),
+    ("nextLong", "()J"),
+    ("hasNextLine", "()Z"),
+    ("nextLine", "()Ljava/lang/String;"),
+];
+
+pub(super) fn is_reader_constructor(class_name: &str, descriptor: &str) -> bool {
+    READER_CONSTRUCTORS.contains(&(class_name, descriptor))
+}
+
+pub(super) fn is_input_method(name: &str, descriptor: &str) -> bool {
+    INPUT_METHODS.contains(&(name, descriptor))
+}
+
+/// Classes whose [`INPUT_METHODS`] the static checker trusts to reach the host input.
+pub(super) fn is_input_class(class_name: &str) -> bool {
+    matches!(
+        class_name,
+        "java/io/InputStream"
+            | "java/io/Reader"
+            | "java/io/InputStreamReader"
+            | "java/io/BufferedReader"
+            | "java/util/Scanner"
+    )
+}
+
+pub(super) fn is_system_in(class_name: &str, name: &str, descriptor: &str) -> bool {
+    class_name == "java/lang/System" && name == "in" && descriptor == "Ljava/io/InputStream;"
+}
+
+/// Marks objects whose reads go to the host input.
+fn host_input_field() -> FieldKey {
+    FieldKey::new("java/lang/Object", "__jay_input", "Z")
+}
+
+/// `System.in`, hidden from `System.<clinit>` like the output streams.
+fn system_in_field() -> FieldKey {
+    FieldKey::new("java/lang/System", "__jay_in", "Ljava/io/InputStream;")
+}
+
+/// Buffered host input shared by `System.in` and every reader wrapping it.
+pub(super) struct HostInput<'a> {
+    reader: &'a mut dyn BufRead,
+    /// Bytes read from the host but not yet consumed.
+    buffer: Vec<u8>,
+    /// Low surrogate owed by `Reader.read()` after a supplementary character.
+    low_surrogate: Option<u16>,
+}
+
+impl<'a> HostInput<'a> {
+    pub(super) fn new(reader: &'a mut dyn BufRead) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+            low_surrogate: None,
+        }
+    }
+
+    /// Reads one more line from the host; `false` at end of input.
+    fn fill(&mut self) -> io::Result<bool> {
+        Ok(self.reader.read_until(b'\n', &mut self.buffer)? > 0)
+    }
+
+    fn available(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn has_line(&mut self) -> io::Result<bool> {
+        Ok(!self.buffer.is_empty() || self.fill()?)
+    }
+
+    /// `InputStream.read()`: the next byte.
+    fn read_byte(&mut self) -> io::Result<Option<u8>> {
+        if !self.has_line()? {
+            return Ok(None);
+        }
+        Ok(Some(self.buffer.remove(0)))
+    }
+
+    /// `Reader.read()`: the next UTF-16 code unit.
+    fn read_char(&mut self) -> io::Result<Option<u16>> {
+        if let Some(low) = self.low_surrogate.take() {
+            return Ok(Some(low));
+        }
+        if !self.has_line()? {
+            return Ok(None);
+        }
+        let length = match self.buffer[0] {
+            0xf0..=0xf7 => 4,
+            0xe0..=0xef => 3,
+            0xc0..=0xdf => 2,
+            _ => 1,
+        }
+        .min(self.buffer.len());
+        let bytes = self.buffer.drain(..length).collect::<Vec<_>>();
+        let character = std::str::from_utf8(&bytes)
+            .ok()
+            .and_then(|text| text.chars().next())
+            .unwrap_or(char::REPLACEMENT_CHARACTER);
+        let mut units = [0; 2];
+        let units = character.encode_utf16(&mut units);
+        if let [high, low] = units {
+            self.low_surrogate = Some(*low);
+            return Ok(Some(*high));
+        }
+        Ok(Some(units[0]))
+    }
+
+    /// `BufferedReader.readLine()`: the next line without its terminator.
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        if !self.has_line()? {
+            return Ok(None);
+        }
+        while !self.buffer.contains(&b'\n') && self.fill()? {}
+        let end = self
+            .buffer
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .map_or(self.buffer.len(), |index| index + 1);
+        let mut line = self.buffer.drain(..end).collect::<Vec<_>>();
+        if line.last() == Some(&b'\n') {
+            line.pop();
+        }
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        Ok(Some(String::from_utf8_lossy(&line).into_owned()))
+    }
+
+    /// The next whitespace-delimited token and where it ends, without consuming it.
+    fn peek_token(&mut self) -> io::Result<Option<(String, usize)>> {
+        loop {
+            if let Some(start) = self
+                .buffer
+                .iter()
+                .position(|byte| !byte.is_ascii_whitespace())
+            {
+                let end = self.buffer[start..]
+                    .iter()
+                    .position(u8::is_ascii_whitespace)
+                    .map(|length| start + length);
+                match end {
+                    Some(end) => {
+                        let token = String::from_utf8_lossy(&self.buffer[start..end]);
+                        return Ok(Some((token.into_owned(), end)));
+                    }
+                    None if !self.fill()? => {
+                        let token = String::from_utf8_lossy(&self.buffer[start..]);
+                        return Ok(Some((token.into_owned(), self.buffer.len())));
+                    }
+                    None => {}
+                }
+            } else if !self.fill()? {
+                return Ok(None);
+            }
+        }
+    }
+
+    fn consume(&mut self, length: usize) {
+        self.buffer.drain(..length);
+    }
+}
+
+impl<'a, W: Write> Interpreter<'a, W> {
+    /// Reads `System.in`, creating the host-backed stream on first use.
+    pub(super) fn system_in(&mut self) -> JayResult<Value> {
+        if let Some(stream) = self.static_fields.get(&system_in_field()) {
+            return Ok(stream.clone());
+        }
+        let stream = self.heap.allocate_instance("java/io/BufferedInputStream");
+        self.heap
+            .put_instance_field(stream, host_input_field(), Value::Int(1))?;
+        self.static_fields
+            .insert(system_in_field(), Value::Reference(stream));
+        Ok(Value::Reference(stream))
+    }
+
+    /// Handles `System.setIn`.
+    pub(super) fn set_system_in(&mut self, caller: &mut Frame) -> JayResult<()> {
+        let stream = caller.pop_reference()?;
+        self.static_fields.insert(system_in_field(), stream);
+        Ok(())
+    }
+
+    /// Whether reads on `reference` go to the host input.
+    pub(super) fn is_host_input(&self, reference: ObjectRef) -> JayResult<bool> {
+        if self.heap.instance_class_name(reference).is_err() {
+            return Ok(false);
+        }
+        Ok(matches!(
+            self.heap
+                .get_instance_field(reference, &host_input_field())?,
+            Some(Value::Int(1))
+        ))
+    }
+
+    /// Builds an `InputStreamReader`, `BufferedReader`, or `Scanner` over `System.in`.
+    pub(super) fn invoke_reader_constructor(
+        &mut self,
+        caller: &mut Frame,
+        class_name: &str,
+        descriptor: &MethodDescriptor,
+        target_name: &str,
+    ) -> JayResult<()> {
+        let arguments = self.pop_constructor_arguments(
+            caller,
+            descriptor,
+            &format!("invokespecial constructor target {target_name}"),
+        )?;
+        let receiver = caller.pop_object_ref()?;
+        let source = match arguments.as_slice() {
+            [Value::Reference(source)] => *source,
+            _ => return Err(self.throw_new("java/lang/NullPointerException", None, None)),
+        };
+        if !self.is_host_input(source)? {
+            let feature = format!(
+                "{} over {}",
+                class_name.replace('/', "."),
+                self.heap.type_name(source)?
+            );
+            let message = format!("{feature} is not supported; only System.in can be read");
+            return Err(JayError::of_kind(
+                ErrorKind::UnsupportedFeature { feature },
+                message,
+            ));
+        }
+        self.heap
+            .put_instance_field(receiver, host_input_field(), Value::Int(1))
+    }
+
+    pub(super) fn invoke_input_method(
+        &mut self,
+        caller: &mut Frame,
+        receiver: ObjectRef,
+        method_name: &str,
+    ) -> JayResult<()> {
+        let receiver_class_name = self.heap.instance_class_name(receiver)?;
+        let is_reader = matches!(
+            receiver_class_name,
+            "java/io/InputStreamReader" | "java/io/BufferedReader"
+        );
+        let result = match method_name {
+            "close" => return Ok(()),
+            "read" if is_reader => Value::Int(self.input.read_char()?.map_or(-1, i32::from)),
+            "read" => Value::Int(self.input.read_byte()?.map_or(-1, i32::from)),
+            "available" => Value::Int(self.input.available().min(i32::MAX as usize) as i32),
+            "ready" => Value::Int(i32::from(self.input.available() > 0)),
+            "readLine" => match self.input.read_line()? {
+                Some(line) => Value::Reference(self.heap.allocate_string(line)),
+                None => Value::Null,
+            },
+            "hasNextLine" => Value::Int(i32::from(self.input.has_line()?)),
+            "nextLine" => match self.input.read_line()? {
+                Some(line) => Value::Reference(self.heap.allocate_string(line)),
+                None => return Err(self.no_such_element(Some("No line found"))),
+            },
+            "hasNext" => Value::Int(i32::from(self.input.peek_token()?.is_some())),
+            "hasNextInt" | "hasNextLong" => {
+                let token = self.input.peek_token()?;
+                let valid = token.is_some_and(|(token, _)| {
+                    if method_name == "hasNextInt" {
+                        token.parse::<i32>().is_ok()
+                    } else {
+                        token.parse::<i64>().is_ok()
+                    }
+                });
+                Value::Int(i32::from(valid))
+            }
+            "next" | "nextInt" | "nextLong" => {
+                let Some((token, end)) = self.input.peek_token()? else {
+                    return Err(self.no_such_element(None));
+                };
+                let value = match method_name {
+                    "nextInt" => token.parse().map(Value::Int).ok(),
+                    "nextLong" => token.parse().map(Value::Long).ok(),
+                    _ => Some(Value::Reference(self.heap.allocate_string(token.as_str()))),
+                };
+                let Some(value) = value else {
+                    // Like `Scanner`, a mismatched token stays unread.
+                    let message = format!("For input string: \"{token}\"");
+                    return Err(self.throw_new(
+                        "java/util/InputMismatchException",
+                        Some(&message),
+                        None,
+                    ));
+                };
+                self.input.consume(end);
+                value
+            }
+            _ => {
+                return Err(JayError::unsupported(format!("input method {method_name}")));
+            }
+        };
+        caller.stack.push(result);
+        self.collect_if_needed(caller)?;
+        Ok(())
+    }
+
+    fn no_such_element(&mut self, message: Option<&str>) -> JayError {
+        self.throw_new("java/util/NoSuchElementException", message, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_lines_bytes_and_characters() {
+        let mut source: &[u8] = b"first\r\nse\xc3\xa9\n\xf0\x9f\x98\x80last";
+        let mut input = HostInput::new(&mut source);
+
+        assert_eq!(input.read_line().unwrap().as_deref(), Some("first"));
+        assert_eq!(input.read_byte().unwrap(), Some(b's'));
+        assert_eq!(input.read_char().unwrap(), Some(u16::from(b'e')));
+        assert_eq!(input.read_char().unwrap(), Some(0xe9));
+        assert_eq!(input.read_char().unwrap(), Some(u16::from(b'\n')));
+        assert_eq!(input.read_char().unwrap(), Some(0xd83d));
+        assert_eq!(input.read_char().unwrap(), Some(0xde00));
+        assert_eq!(input.read_line().unwrap().as_deref(), Some("last"));
+        assert_eq!(input.read_line().unwrap(), None);
+        assert_eq!(input.read_byte().unwrap(), None);
+    }
+
+    #[test]
+    fn scans_tokens_across_lines_without_consuming_on_peek() {
+        let mut source: &[u8] = b"  12 abc\n\n  7\ntail line\n";
+        let mut input = HostInput::new(&mut source);
+
+        let (token, end) = input.peek_token().unwrap().unwrap();
+        assert_eq!(token, "12");
+        assert_eq!(input.peek_token().unwrap().unwrap().0, "12");
+        input.consume(end);
+        assert_eq!(input.read_line().unwrap().as_deref(), Some(" abc"));
+        let (token, end) = input.peek_token().unwrap().unwrap();
+        assert_eq!(token, "7");
+        input.consume(end);
+        assert_eq!(input.read_line().unwrap().as_deref(), Some(""));
+        assert_eq!(input.read_line().unwrap().as_deref(), Some("tail line"));
+        assert!(input.peek_token().unwrap().is_none());
+        assert!(!input.has_line().unwrap());
+    }
+}
@@ -1,53 +1,98 @@
 //! Core bytecode dispatch loop for the VM interpreter.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap};
 use std::io::Write;
 
+use super::builder::Limits;
 use super::bytecode::{
     branch_target, int_branch_taken, int_compare_branch_taken, read_i2, read_u1, read_u2,
 };
 use super::frame::Frame;
 use super::heap::{FieldKey, Heap, ObjectRef};
+use super::input::HostInput;
+use super::lifecycle::ClassInitState;
+use super::resolution::LinkedClass;
 use super::runtime::checked_array_index;
 use super::value::Value;
 use crate::classfile::{ClassFile, Code, Method};
 use crate::classpath::ClassResolver;
-use crate::{JavaStackFrame, JayError, JayResult};
+use crate::{ErrorKind, JavaStackFrame, JayError, JayResult};
 
 pub(super) struct Interpreter<'a, W: Write> {
     pub(super) classes: &'a ClassResolver,
     pub(super) output: &'a mut W,
+    /// Host writer behind `System.err`.
+    pub(super) error: &'a mut dyn Write,
+    /// Host input behind `System.in`.
+    pub(super) input: HostInput<'a>,
     pub(super) heap: Heap,
     pub(super) saved_roots: Vec<Vec<Value>>,
     pub(super) static_fields: HashMap<FieldKey, Value>,
     /// Heap-allocated `java.lang.Class` mirrors loaded by class literals.
     pub(super) class_mirrors: HashMap<String, ObjectRef>,
-    pub(super) initialized_classes: HashSet<String>,
-    pub(super) initializing_classes: HashSet<String>,
+    /// JVMS 5.5 initialization state for every class whose initialization has started.
+    pub(super) class_states: HashMap<String, ClassInitState>,
+    /// Classes linked so far, by internal name.
+    pub(super) linked_classes: HashMap<String, LinkedClass>,
+    /// Java throwable currently unwinding through interpreted frames.
+    pub(super) thrown_exception: Option<ObjectRef>,
+    /// System properties read and written by `System.getProperty` and friends.
+    pub(super) properties: BTreeMap<String, String>,
+    /// Status passed to `System.exit` while the interpreter unwinds to stop.
+    pub(super) exit_status: Option<i32>,
+    /// Threads registered with `Runtime.addShutdownHook`, in registration order.
+    pub(super) shutdown_hooks: Vec<ObjectRef>,
+    /// Set once shutdown hooks start running.
+    pub(super) shutting_down: bool,
+    /// Interpreted methods currently executing, outermost first, each at its
+    /// current instruction; `Throwable.fillInStackTrace` reads it.
+    pub(super) call_stack: Vec<JavaStackFrame>,
+    /// Environment variables read by `System.getenv`.
+    pub(super) environment: BTreeMap<String, String>,
+    pub(super) limits: Limits,
+    /// Whether uncaught exceptions are printed on `System.err`.
+    pub(super) print_uncaught_exceptions: bool,
+    /// Instructions executed so far, counted against `limits.instruction_budget`.
+    pub(super) instructions_executed: u64,
+}
+
+/// Heap and class state an interpreter leaves behind, so a later interpreter
+/// can resume with the same objects and initialized classes.
+#[derive(Debug)]
+pub(super) struct InterpreterState {
+    heap: Heap,
+    static_fields: HashMap<FieldKey, Value>,
+    class_mirrors: HashMap<String, ObjectRef>,
+    class_states: HashMap<String, ClassInitState>,
+    linked_classes: HashMap<String, LinkedClass>,
+    properties: BTreeMap<String, String>,
 }
 
 struct MethodContext<'a> {
-    class_name: &'a str,
-    method_name: &'a str,
-    descriptor: &'a str,
+    class_file: &'a ClassFile,
+    method: &'a Method,
+    code: &'a Code,
 }
 
 impl<'a> MethodContext<'a> {
-    pub(super) fn new(class_file: &'a ClassFile, method: &'a Method) -> Self {
+    pub(super) fn new(class_file: &'a ClassFile, method: &'a Method, code: &'a Code) -> Self {
         Self {
-            class_name: &class_file.this_class,
-            method_name: &method.name,
-            descriptor: &method.descriptor,
+            class_file,
+            method,
+            code,
         }
     }
 
     fn stack_frame(&self, pc: usize) -> JavaStackFrame {
-        JavaStackFrame::new(
-            self.class_name.replace('/', "."),
-            self.method_name,
-            self.descriptor,
+        let mut frame = JavaStackFrame::new(
+            self.class_file.this_class.replace('/', "."),
+            &self.method.name,
+            &self.method.descriptor,
             pc,
-        )
+        );
+        frame.file_name = self.class_file.source_file().map(str::to_string);
+        frame.line_number = self.code.line_number(pc).map(u32::from);
+        frame
     }
 }
 
@@ -56,17 +101,86 @@ enum InstructionResult {
     Return(Option<Value>),
 }
 
+/// Whether `execute_instruction` dispatches `opcode`; a unit test checks the
+/// two agree.
+pub(super) fn is_supported_opcode(opcode: u8) -> bool {
+    matches!(
+        opcode,
+        0x00..=0x0a
+            | 0x10..=0x16
+            | 0x19..=0x21
+            | 0x2a..=0x2d
+            | 0x32
+            | 0x36
+            | 0x37
+            | 0x3a..=0x42
+            | 0x4b..=0x4e
+            | 0x53
+            | 0x57..=0x5a
+            | 0x60
+            | 0x64
+            | 0x68
+            | 0x6c
+            | 0x84
+            | 0x99..=0xa7
+            | 0xac
+            | 0xad
+            | 0xb0..=0xbb
+            | 0xbd..=0xc0
+            | 0xc6
+            | 0xc7
+    )
+}
+
 impl<'a, W: Write> Interpreter<'a, W> {
-    pub(super) fn new(classes: &'a ClassResolver, output: &'a mut W) -> Self {
+    pub(super) fn new(
+        classes: &'a ClassResolver,
+        output: &'a mut W,
+        error: &'a mut dyn Write,
+        input: HostInput<'a>,
+        properties: BTreeMap<String, String>,
+    ) -> Self {
         Self {
             classes,
             output,
+            error,
+            input,
             heap: Heap::new(),
             saved_roots: Vec::new(),
             static_fields: HashMap::new(),
             class_mirrors: HashMap::new(),
-            initialized_classes: HashSet::new(),
-            initializing_classes: HashSet::new(),
+            class_states: HashMap::new(),
+            linked_classes: HashMap::new(),
+            thrown_exception: None,
+            properties,
+            exit_status: None,
+            shutdown_hooks: Vec::new(),
+            shutting_down: false,
+            call_stack: Vec::new(),
+            environment: BTreeMap::new(),
+            limits: Limits::default(),
+            print_uncaught_exceptions: true,
+            instructions_executed: 0,
+        }
+    }
+
+    pub(super) fn resume(&mut self, state: InterpreterState) {
+        self.heap = state.heap;
+        self.static_fields = state.static_fields;
+        self.class_mirrors = state.class_mirrors;
+        self.class_states = state.class_states;
+        self.linked_classes = state.linked_classes;
+        self.properties = state.properties;
+    }
+
+    pub(super) fn suspend(self) -> InterpreterState {
+        InterpreterState {
+            heap: self.heap,
+            static_fields: self.static_fields,
+            class_mirrors: self.class_mirrors,
+            class_states: self.class_states,
+            linked_classes: self.linked_classes,
+            properties: self.properties,
         }
     }
 
@@ -77,22 +191,71 @@ impl<'a, W: Write> Interpreter<'a, W> {
         code: &Code,
         frame: &mut Frame,
     ) -> JayResult<Option<Value>> {
-        let context = MethodContext::new(class_file, method);
+        if self
+            .limits
+            .max_stack_depth
+            .is_some_and(|max| self.call_stack.len() >= max)
+        {
+            return Err(self.throw_new("java/lang/StackOverflowError", None, None));
+        }
+        self.call_stack.push(JavaStackFrame::new(
+            class_file.this_class.replace('/', "."),
+            &method.name,
+            &method.descriptor,
+            0,
+        ));
+        let result = self.execute_method(class_file, method, code, frame);
+        self.call_stack.pop();
+        result
+    }
+
+    fn execute_method(
+        &mut self,
+        class_file: &ClassFile,
+        method: &Method,
+        code: &Code,
+        frame: &mut Frame,
+    ) -> JayResult<Option<Value>> {
+        let context = MethodContext::new(class_file, method, code);
         let mut pc = 0usize;
         while pc < code.bytes.len() {
             let opcode_pc = pc;
+            if let Some(active) = self.call_stack.last_mut() {
+                active.pc = opcode_pc;
+            }
+            if let Some(budget) = self.limits.instruction_budget
+                && self.instructions_executed >= budget
+            {
+                let limit = "instruction budget".to_string();
+                return Err(JayError::of_kind(
+                    ErrorKind::LimitExceeded { limit },
+                    format!("instruction budget of {budget} exhausted"),
+                )
+                .with_java_stack_frame(context.stack_frame(opcode_pc)));
+            }
+            self.instructions_executed += 1;
             let opcode = read_u1(&code.bytes, &mut pc)
                 .map_err(|error| error.with_java_stack_frame(context.stack_frame(opcode_pc)))?;
-            let result = self
-                .execute_instruction(class_file, code, frame, &mut pc, opcode_pc, opcode)
-                .map_err(|error| error.with_java_stack_frame(context.stack_frame(opcode_pc)))?;
-            match result {
-                InstructionResult::Continue => {}
-                InstructionResult::Return(value) => return Ok(value),
+            match self.execute_instruction(class_file, code, frame, &mut pc, opcode_pc, opcode) {
+                Ok(InstructionResult::Continue) => {}
+                Ok(InstructionResult::Return(value)) => return Ok(value),
+                Err(error) => {
+                    let handler = self
+                        .catch_thrown_exception(class_file, code, frame, opcode_pc)
+                        .map_err(|error| {
+                            error.with_java_stack_frame(context.stack_frame(opcode_pc))
+                        })?;
+                    match handler {
+                        Some(handler_pc) => pc = handler_pc,
+                        None => {
+                            return Err(error.with_java_stack_frame(context.stack_frame(opcode_pc)));
+                        }
+                    }
+                }
             }
         }
 
-        Err(JayError::new("main method completed without return")
+        Err(JayError::verify("main method completed without return")
             .with_java_stack_frame(context.stack_frame(pc)))
     }
 
@@ -192,7 +355,11 @@ impl<'a, W: Write> Interpreter<'a, W> {
                 let right = frame.pop_int()?;
                 let left = frame.pop_int()?;
                 if right == 0 {
-                    return Err(JayError::new("integer division by zero"));
+                    return Err(self.throw_new(
+                        "java/lang/ArithmeticException",
+                        Some("/ by zero"),
+                        None,
+                    ));
                 }
                 frame.stack.push(Value::Int(left.wrapping_div(right)));
             }
@@ -274,7 +441,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
                 let count = read_u1(&code.bytes, pc)?;
                 let zero = read_u1(&code.bytes, pc)?;
                 if zero != 0 {
-                    return Err(JayError::new(format!(
+                    return Err(JayError::class_format(format!(
                         "invokeinterface at pc {opcode_pc} has nonzero padding"
                     )));
                 }
@@ -284,7 +451,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
                 let index = read_u2(&code.bytes, pc)?;
                 let zero = read_u2(&code.bytes, pc)?;
                 if zero != 0 {
-                    return Err(JayError::new(format!(
+                    return Err(JayError::class_format(format!(
                         "invokedynamic at pc {opcode_pc} has nonzero padding"
                     )));
                 }
@@ -305,6 +472,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
                     .map_err(|_| JayError::new("array length exceeds int range"))?;
                 frame.stack.push(Value::Int(length));
             }
+            0xbf => self.athrow(frame)?,
             0xc0 => {
                 let index = read_u2(&code.bytes, pc)?;
                 self.check_cast(class_file, frame, index)?;
@@ -333,11 +501,59 @@ impl<'a, W: Write> Interpreter<'a, W> {
                     .store_array_reference(reference, checked_array_index(index)?, value)?;
             }
             _ => {
-                return Err(JayError::new(format!(
-                    "unsupported bytecode 0x{opcode:02x} at pc {opcode_pc}"
+                return Err(JayError::unsupported(format!(
+                    "bytecode 0x{opcode:02x} at pc {opcode_pc}"
                 )));
             }
         }
         Ok(InstructionResult::Continue)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classfile::builder::ClassBuilder;
+
+    #[test]
+    fn supported_opcodes_match_the_dispatch() {
+        let classes = ClassResolver::new(Vec::new()).unwrap();
+        let class_file = ClassBuilder::new("Probe").build().unwrap();
+        let (mut output, mut error, mut input) = (Vec::new(), Vec::new(), &b""[..]);
+        let mut interpreter = Interpreter::new(
+            &classes,
+            &mut output,
+            &mut error,
+            HostInput::new(&mut input),
+            BTreeMap::new(),
+        );
+
+        for opcode in 0..=u8::MAX {
+            let mut bytes = vec![opcode];
+            bytes.extend([0; 16]);
+            let code = Code {
+                max_stack: 4,
+                max_locals: 4,
+                bytes,
+                exception_table: Vec::new(),
+                annotations: Default::default(),
+                attributes: Vec::new(),
+            };
+            let mut frame = Frame::new(4);
+            let mut pc = 1;
+            let result =
+                interpreter.execute_instruction(&class_file, &code, &mut frame, &mut pc, 0, opcode);
+            let dispatched = !matches!(
+                result.as_ref().map_err(JayError::kind),
+                Err(ErrorKind::UnsupportedFeature { feature }) if feature.starts_with("bytecode 0x")
+            );
+            interpreter.thrown_exception = None;
+
+            assert_eq!(
+                dispatched,
+                is_supported_opcode(opcode),
+                "opcode 0x{opcode:02x}"
+            );
+        }
+    }
+}
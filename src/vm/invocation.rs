@@ -2,15 +2,126 @@
 
 use std::io::Write;
 
+use super::boxing::is_boxing_method;
 use super::descriptors::{MethodDescriptor, ReturnType};
 use super::frame::Frame;
+use super::heap::ObjectRef;
+use super::input::{is_input_method, is_reader_constructor};
 use super::interpreter::Interpreter;
 use super::native_runtime::current_time_millis;
+use super::properties::is_property_method;
 use super::runtime::apply_string_concat_recipe;
+use super::shutdown::{is_shutdown_method, is_thread_constructor};
+use super::streams::is_print_stream_method;
+use super::throwables::{is_throwable_constructor, is_throwable_method};
 use super::value::Value;
 use crate::classfile::ClassFile;
 use crate::{JayError, JayResult};
 
+/// JDK methods the invoke handlers answer with Rust shims instead of bytecode.
+///
+/// Handlers dispatch through [`Intrinsic::find`], so they and `jay check` read
+/// the same table; `PrintStream`, input, shutdown, `Throwable`, property, and
+/// boxing shims keep their own tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Intrinsic {
+    DesiredAssertionStatus,
+    ObjectInit,
+    CurrentTimeMillis,
+    RegisterNatives,
+    SetErr,
+    SetIn,
+    SetOut,
+    SimpleDateFormatInit,
+    SimpleDateFormatFormat,
+    SimpleDateFormatSetTimeZone,
+    LocalDateTimeNow,
+    DateToString,
+    TimeZoneGetTimeZone,
+}
+
+/// Every [`Intrinsic`] by `(class, name, descriptor)`.
+pub(super) const INTRINSIC_METHODS: &[((&str, &str, &str), Intrinsic)] = &[
+    (
+        ("java/lang/Class", "desiredAssertionStatus", "()Z"),
+        Intrinsic::DesiredAssertionStatus,
+    ),
+    (("java/lang/Object", "<init>", "()V"), Intrinsic::ObjectInit),
+    (
+        ("java/lang/System", "currentTimeMillis", "()J"),
+        Intrinsic::CurrentTimeMillis,
+    ),
+    (
+        ("java/lang/System", "registerNatives", "()V"),
+        Intrinsic::RegisterNatives,
+    ),
+    (
+        ("java/lang/System", "setErr", "(Ljava/io/PrintStream;)V"),
+        Intrinsic::SetErr,
+    ),
+    (
+        ("java/lang/System", "setIn", "(Ljava/io/InputStream;)V"),
+        Intrinsic::SetIn,
+    ),
+    (
+        ("java/lang/System", "setOut", "(Ljava/io/PrintStream;)V"),
+        Intrinsic::SetOut,
+    ),
+    (
+        (
+            "java/text/SimpleDateFormat",
+            "<init>",
+            "(Ljava/lang/String;)V",
+        ),
+        Intrinsic::SimpleDateFormatInit,
+    ),
+    (
+        (
+            "java/text/SimpleDateFormat",
+            "format",
+            "(Ljava/util/Date;)Ljava/lang/String;",
+        ),
+        Intrinsic::SimpleDateFormatFormat,
+    ),
+    (
+        (
+            "java/text/SimpleDateFormat",
+            "setTimeZone",
+            "(Ljava/util/TimeZone;)V",
+        ),
+        Intrinsic::SimpleDateFormatSetTimeZone,
+    ),
+    (
+        (
+            "java/time/LocalDateTime",
+            "now",
+            "()Ljava/time/LocalDateTime;",
+        ),
+        Intrinsic::LocalDateTimeNow,
+    ),
+    (
+        ("java/util/Date", "toString", "()Ljava/lang/String;"),
+        Intrinsic::DateToString,
+    ),
+    (
+        (
+            "java/util/TimeZone",
+            "getTimeZone",
+            "(Ljava/lang/String;)Ljava/util/TimeZone;",
+        ),
+        Intrinsic::TimeZoneGetTimeZone,
+    ),
+];
+
+impl Intrinsic {
+    pub(super) fn find(class_name: &str, method_name: &str, descriptor: &str) -> Option<Self> {
+        INTRINSIC_METHODS
+            .iter()
+            .find(|(signature, _)| *signature == (class_name, method_name, descriptor))
+            .map(|(_, intrinsic)| *intrinsic)
+    }
+}
+
 impl<'a, W: Write> Interpreter<'a, W> {
     pub(super) fn invoke_virtual(
         &mut self,
@@ -19,51 +130,8 @@ impl<'a, W: Write> Interpreter<'a, W> {
         index: u16,
     ) -> JayResult<()> {
         let method = class_file.constant_pool.method_ref(index)?;
-        if method.class_name == "java/io/PrintStream" && method.name == "println" {
-            return match method.descriptor {
-                "(Ljava/lang/String;)V" => {
-                    let reference = frame.pop_string_reference(&self.heap)?;
-                    frame.pop_print_stream()?;
-                    let value = self.heap.string(reference)?;
-                    writeln!(self.output, "{value}")?;
-                    Ok(())
-                }
-                "(Ljava/lang/Object;)V" => {
-                    let value = frame.pop_reference()?;
-                    frame.pop_print_stream()?;
-                    let text = self.println_object_text(value)?;
-                    writeln!(self.output, "{text}")?;
-                    Ok(())
-                }
-                "(I)V" => {
-                    let value = frame.pop_int()?;
-                    frame.pop_print_stream()?;
-                    writeln!(self.output, "{value}")?;
-                    Ok(())
-                }
-                "(J)V" => {
-                    let value = frame.pop_long()?;
-                    frame.pop_print_stream()?;
-                    writeln!(self.output, "{value}")?;
-                    Ok(())
-                }
-                "(Z)V" => {
-                    let value = frame.pop_int()?;
-                    frame.pop_print_stream()?;
-                    let text = if value == 0 { "false" } else { "true" };
-                    writeln!(self.output, "{text}")?;
-                    Ok(())
-                }
-                _ => Err(JayError::new(format!(
-                    "unsupported PrintStream.println descriptor {}",
-                    method.descriptor
-                ))),
-            };
-        }
-
-        if method.class_name == "java/lang/Class"
-            && method.name == "desiredAssertionStatus"
-            && method.descriptor == "()Z"
+        if Intrinsic::find(method.class_name, method.name, method.descriptor)
+            == Some(Intrinsic::DesiredAssertionStatus)
         {
             let receiver = frame.pop_object_ref()?;
             let receiver_class_name = self.heap.instance_class_name(receiver)?;
@@ -81,6 +149,16 @@ impl<'a, W: Write> Interpreter<'a, W> {
         let target_method_name = method.name.to_string();
         let target_descriptor = method.descriptor.to_string();
         let descriptor = MethodDescriptor::parse(&target_descriptor)?;
+        if is_shutdown_method(method.class_name, &target_method_name, &target_descriptor) {
+            let target_name = format!("java.lang.Runtime.{target_method_name}{target_descriptor}");
+            return self.invoke_shutdown_method(
+                frame,
+                "java/lang/Runtime",
+                &target_method_name,
+                &descriptor,
+                &target_name,
+            );
+        }
         let mut arguments = self.pop_method_arguments(
             frame,
             &descriptor,
@@ -93,29 +171,54 @@ impl<'a, W: Write> Interpreter<'a, W> {
         )?;
         let receiver = frame.pop_object_ref()?;
         let receiver_class_name = self.heap.instance_class_name(receiver)?.to_string();
-        if target_method_name == "toString"
-            && target_descriptor == "()Ljava/lang/String;"
-            && receiver_class_name == "java/util/Date"
+        if is_print_stream_method(&target_method_name, &target_descriptor)
+            && let Some(stream) = self.host_stream(receiver)?
         {
-            return self.invoke_date_to_string(frame, receiver);
+            return self.invoke_print_stream(
+                frame,
+                receiver,
+                stream,
+                &target_method_name,
+                &target_descriptor,
+                &arguments,
+            );
         }
-        if target_method_name == "format"
-            && target_descriptor == "(Ljava/util/Date;)Ljava/lang/String;"
-            && receiver_class_name == "java/text/SimpleDateFormat"
+        if is_input_method(&target_method_name, &target_descriptor)
+            && self.is_host_input(receiver)?
         {
-            return self.invoke_simple_date_format(frame, receiver, &arguments);
+            return self.invoke_input_method(frame, receiver, &target_method_name);
+        }
+        match Intrinsic::find(
+            &receiver_class_name,
+            &target_method_name,
+            &target_descriptor,
+        ) {
+            Some(Intrinsic::DateToString) => return self.invoke_date_to_string(frame, receiver),
+            Some(Intrinsic::SimpleDateFormatFormat) => {
+                return self.invoke_simple_date_format(frame, receiver, &arguments);
+            }
+            Some(Intrinsic::SimpleDateFormatSetTimeZone) => {
+                return self.invoke_simple_date_format_set_time_zone(receiver, &arguments);
+            }
+            _ => {}
         }
-        if target_method_name == "setTimeZone"
-            && target_descriptor == "(Ljava/util/TimeZone;)V"
-            && receiver_class_name == "java/text/SimpleDateFormat"
+        if target_method_name == "toString"
+            && target_descriptor == "()Ljava/lang/String;"
+            && receiver_class_name == "java/lang/StackTraceElement"
         {
-            return self.invoke_simple_date_format_set_time_zone(receiver, &arguments);
+            let text = self.stack_trace_element_text(receiver)?;
+            frame
+                .stack
+                .push(Value::Reference(self.heap.allocate_string(text)));
+            self.collect_if_needed(frame)?;
+            return Ok(());
         }
         let (declaring_class_file, declaring_method) = self.resolve_instance_method(
             method.class_name,
             &target_method_name,
             &target_descriptor,
         )?;
+        self.check_private_method_access(class_file, &declaring_class_file, &declaring_method)?;
         let (target_class_file, target_method) = if declaring_method.is_private() {
             (declaring_class_file, declaring_method)
         } else {
@@ -133,11 +236,16 @@ impl<'a, W: Write> Interpreter<'a, W> {
                         target_method_name,
                         target_descriptor
                     );
-                    JayError::new(format!("invokevirtual target {target_name} not found"))
+                    JayError::verify(format!("invokevirtual target {target_name} not found"))
                 })?
                 .clone();
             (class_file, method)
         };
+        if target_class_file.this_class == "java/lang/Throwable"
+            && is_throwable_method(&target_method_name, &target_descriptor)
+        {
+            return self.invoke_throwable_method(frame, receiver, &target_method_name, &arguments);
+        }
         let target_name = format!(
             "{}.{}{}",
             target_class_file.this_class.replace('/', "."),
@@ -146,14 +254,24 @@ impl<'a, W: Write> Interpreter<'a, W> {
         );
 
         if target_method.is_static() {
-            return Err(JayError::new(format!(
+            return Err(JayError::verify(format!(
                 "invokevirtual target {target_name} must not be static"
             )));
         }
 
-        if target_method.access_flags & 0x0100 != 0 || target_method.access_flags & 0x0400 != 0 {
-            return Err(JayError::new(format!(
-                "invokevirtual target {target_name} must not be native or abstract"
+        if target_method.is_abstract() {
+            let message = format!(
+                "{}.{}{}",
+                receiver_class_name.replace('/', "."),
+                target_method_name,
+                target_descriptor
+            );
+            return Err(self.throw_new("java/lang/AbstractMethodError", Some(&message), None));
+        }
+
+        if target_method.is_native() {
+            return Err(JayError::verify(format!(
+                "invokevirtual target {target_name} must not be native"
             )));
         }
 
@@ -161,7 +279,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
             .code
             .as_ref()
             .ok_or_else(|| {
-                JayError::new(format!("invokevirtual target {target_name} has no Code"))
+                JayError::verify(format!("invokevirtual target {target_name} has no Code"))
             })?
             .clone();
 
@@ -197,57 +315,92 @@ impl<'a, W: Write> Interpreter<'a, W> {
         );
 
         if target_method_name != "<init>" {
-            return Err(JayError::new(format!(
-                "unsupported invokespecial target {target_name}"
-            )));
+            return self.invoke_special_method(
+                caller_class_file,
+                caller,
+                &target_class_name,
+                &target_method_name,
+                &target_descriptor,
+                &target_name,
+            );
         }
 
         let descriptor = MethodDescriptor::parse(&target_descriptor)?;
         if descriptor.return_type != ReturnType::Void {
-            return Err(JayError::new(format!(
+            return Err(JayError::verify(format!(
                 "invokespecial constructor target {target_name} must return void"
             )));
         }
 
-        if target_class_name == "java/lang/Object" && target_descriptor == "()V" {
-            self.pop_constructor_arguments(
+        match Intrinsic::find(&target_class_name, &target_method_name, &target_descriptor) {
+            Some(Intrinsic::ObjectInit) => {
+                self.pop_constructor_arguments(
+                    caller,
+                    &descriptor,
+                    &format!("invokespecial constructor target {target_name}"),
+                )?;
+                let _receiver = caller.pop_reference()?;
+                return Ok(());
+            }
+            Some(Intrinsic::SimpleDateFormatInit) => {
+                return self.invoke_simple_date_format_constructor(
+                    caller,
+                    &descriptor,
+                    &target_name,
+                );
+            }
+            _ => {}
+        }
+
+        if is_throwable_constructor(&target_class_name, &target_descriptor) {
+            return self.invoke_throwable_constructor(
                 caller,
+                &target_descriptor,
                 &descriptor,
-                &format!("invokespecial constructor target {target_name}"),
-            )?;
-            let _receiver = caller.pop_reference()?;
-            return Ok(());
+                &target_name,
+            );
         }
 
-        if target_class_name == "java/text/SimpleDateFormat"
-            && target_descriptor == "(Ljava/lang/String;)V"
-        {
-            return self.invoke_simple_date_format_constructor(caller, &descriptor, &target_name);
+        if is_thread_constructor(&target_class_name, &target_descriptor) {
+            return self.invoke_thread_constructor(
+                caller,
+                &target_descriptor,
+                &descriptor,
+                &target_name,
+            );
+        }
+
+        if is_reader_constructor(&target_class_name, &target_descriptor) {
+            return self.invoke_reader_constructor(
+                caller,
+                &target_class_name,
+                &descriptor,
+                &target_name,
+            );
         }
 
         let loaded_class_file;
         let target_class_file = if target_class_name == caller_class_file.this_class {
             caller_class_file
         } else {
-            let binary_name = target_class_name.replace('/', ".");
-            let bytes = self.classes.load_class_bytes(&binary_name)?;
-            loaded_class_file = ClassFile::parse(&bytes)?;
+            loaded_class_file = self.load_class_file(&target_class_name)?;
             &loaded_class_file
         };
         let method = target_class_file
             .find_method(&target_method_name, &target_descriptor)
             .ok_or_else(|| {
-                JayError::new(format!("invokespecial target {target_name} not found"))
+                JayError::verify(format!("invokespecial target {target_name} not found"))
             })?;
 
         if method.is_static() {
-            return Err(JayError::new(format!(
+            return Err(JayError::verify(format!(
                 "invokespecial constructor target {target_name} must not be static"
             )));
         }
+        self.check_private_method_access(caller_class_file, target_class_file, method)?;
 
-        if method.access_flags & 0x0100 != 0 || method.access_flags & 0x0400 != 0 {
-            return Err(JayError::new(format!(
+        if method.is_native() || method.is_abstract() {
+            return Err(JayError::verify(format!(
                 "invokespecial constructor target {target_name} must not be native or abstract"
             )));
         }
@@ -256,7 +409,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
             .code
             .as_ref()
             .ok_or_else(|| {
-                JayError::new(format!("invokespecial target {target_name} has no Code"))
+                JayError::verify(format!("invokespecial target {target_name} has no Code"))
             })?
             .clone();
 
@@ -275,89 +428,81 @@ impl<'a, W: Write> Interpreter<'a, W> {
         self.saved_roots.pop();
         match result? {
             None => Ok(()),
-            Some(_) => Err(JayError::new(format!(
+            Some(_) => Err(JayError::verify(format!(
                 "invokespecial constructor target {target_name} returned a value"
             ))),
         }
     }
 
-    pub(super) fn invoke_dynamic(
+    /// `invokespecial` of a superclass or private method: selected from the
+    /// named class upward, without dispatching on the receiver.
+    fn invoke_special_method(
         &mut self,
-        class_file: &ClassFile,
-        frame: &mut Frame,
-        index: u16,
+        caller_class_file: &ClassFile,
+        caller: &mut Frame,
+        target_class_name: &str,
+        target_method_name: &str,
+        target_descriptor: &str,
+        target_name: &str,
     ) -> JayResult<()> {
-        let dynamic = class_file.constant_pool.invoke_dynamic(index)?;
-        let bootstrap = class_file
-            .bootstrap_methods
-            .get(dynamic.bootstrap_method_attr_index as usize)
-            .ok_or_else(|| {
-                JayError::new(format!(
-                    "invokedynamic bootstrap method #{} not found",
-                    dynamic.bootstrap_method_attr_index
-                ))
-            })?;
-        let method_handle = class_file
-            .constant_pool
-            .method_handle(bootstrap.method_ref)?;
-        if method_handle.reference_kind != 6 {
-            return Err(JayError::new(format!(
-                "unsupported invokedynamic bootstrap method handle kind {}",
-                method_handle.reference_kind
+        let label = format!("invokespecial target {target_name}");
+        let descriptor = MethodDescriptor::parse(target_descriptor)?;
+        let class_file = self
+            .find_instance_method_class(target_class_name, target_method_name, target_descriptor)?
+            .ok_or_else(|| JayError::verify(format!("{label} not found")))?;
+        let method = class_file
+            .find_method(target_method_name, target_descriptor)
+            .ok_or_else(|| JayError::verify(format!("{label} not found")))?
+            .clone();
+        self.check_private_method_access(caller_class_file, &class_file, &method)?;
+        if method.is_static() || method.is_abstract() || method.is_native() {
+            return Err(JayError::verify(format!(
+                "{label} must not be static, abstract, or native"
             )));
         }
 
-        let bootstrap_method = class_file
-            .constant_pool
-            .method_ref(method_handle.reference_index)?;
-        if bootstrap_method.class_name != "java/lang/invoke/StringConcatFactory"
-            || bootstrap_method.name != "makeConcatWithConstants"
+        let mut arguments = self.pop_method_arguments(caller, &descriptor, &label)?;
+        let receiver = caller.pop_object_ref()?;
+        if class_file.this_class == "java/lang/Throwable"
+            && is_throwable_method(target_method_name, target_descriptor)
         {
-            return Err(JayError::new(format!(
-                "unsupported invokedynamic bootstrap {}.{}{}",
-                bootstrap_method.class_name, bootstrap_method.name, bootstrap_method.descriptor
-            )));
-        }
-
-        if dynamic.name != "makeConcatWithConstants" {
-            return Err(JayError::new(format!(
-                "unsupported invokedynamic call site {}{}",
-                dynamic.name, dynamic.descriptor
-            )));
-        }
-
-        let descriptor = MethodDescriptor::parse(dynamic.descriptor)?;
-        if !descriptor.return_type.is_reference_to("java/lang/String") {
-            return Err(JayError::new(format!(
-                "unsupported invokedynamic return type in {}{}",
-                dynamic.name, dynamic.descriptor
-            )));
+            return self.invoke_throwable_method(caller, receiver, target_method_name, &arguments);
         }
+        let code = method
+            .code
+            .as_ref()
+            .ok_or_else(|| JayError::verify(format!("{label} has no Code")))?
+            .clone();
+        arguments.insert(0, Value::Reference(receiver));
+        let mut callee = Frame::with_arguments(code.max_locals, arguments)?;
+        self.saved_roots
+            .push(caller.roots().cloned().collect::<Vec<_>>());
+        let result = self.execute(&class_file, &method, &code, &mut callee);
+        self.saved_roots.pop();
+        self.complete_call(caller, descriptor.return_type, result?, &label)
+    }
 
-        let [recipe_index] = bootstrap.arguments.as_slice() else {
-            return Err(JayError::new(format!(
-                "unsupported StringConcatFactory bootstrap argument count {}",
-                bootstrap.arguments.len()
-            )));
-        };
-        let recipe = class_file.constant_pool.string(*recipe_index)?.to_string();
+    pub(super) fn invoke_dynamic(
+        &mut self,
+        class_file: &ClassFile,
+        frame: &mut Frame,
+        index: u16,
+    ) -> JayResult<()> {
+        let call_site = resolve_string_concat_call_site(class_file, index)?;
         let arguments = self.pop_method_arguments(
             frame,
-            &descriptor,
-            &format!(
-                "invokedynamic call site {}{}",
-                dynamic.name, dynamic.descriptor
-            ),
+            &call_site.descriptor,
+            &format!("invokedynamic call site {}", call_site.name),
         )?;
         let mut text_arguments = Vec::with_capacity(arguments.len());
         for argument in arguments {
             text_arguments.push(self.string_concat_argument(argument)?);
         }
 
-        let value = apply_string_concat_recipe(&recipe, &text_arguments)?;
+        let value = apply_string_concat_recipe(&call_site.recipe, &text_arguments)?;
         let reference = self.heap.allocate_string(value);
         frame.stack.push(Value::Reference(reference));
-        self.collect_if_needed(frame);
+        self.collect_if_needed(frame)?;
         Ok(())
     }
 
@@ -369,7 +514,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
         count: u8,
     ) -> JayResult<()> {
         if count == 0 {
-            return Err(JayError::new(
+            return Err(JayError::verify(
                 "invokeinterface argument count must be nonzero",
             ));
         }
@@ -395,6 +540,11 @@ impl<'a, W: Write> Interpreter<'a, W> {
             &target_method_name,
             &target_descriptor,
         )?;
+        self.check_private_method_access(
+            caller_class_file,
+            &declaring_class_file,
+            &declaring_method,
+        )?;
         let (target_class_file, target_method) = if declaring_method.is_private() {
             (declaring_class_file, declaring_method)
         } else if let Some(class_file) = self.find_instance_method_class(
@@ -411,7 +561,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
                         target_method_name,
                         target_descriptor
                     );
-                    JayError::new(format!("invokeinterface target {target_name} not found"))
+                    JayError::verify(format!("invokeinterface target {target_name} not found"))
                 })?
                 .clone();
             (class_file, method)
@@ -426,14 +576,24 @@ impl<'a, W: Write> Interpreter<'a, W> {
         );
 
         if target_method.is_static() {
-            return Err(JayError::new(format!(
+            return Err(JayError::verify(format!(
                 "invokeinterface target {target_name} must not be static"
             )));
         }
 
-        if target_method.access_flags & 0x0100 != 0 || target_method.access_flags & 0x0400 != 0 {
-            return Err(JayError::new(format!(
-                "invokeinterface target {target_name} must not be native or abstract"
+        if target_method.is_abstract() {
+            let message = format!(
+                "{}.{}{}",
+                receiver_class_name.replace('/', "."),
+                target_method_name,
+                target_descriptor
+            );
+            return Err(self.throw_new("java/lang/AbstractMethodError", Some(&message), None));
+        }
+
+        if target_method.is_native() {
+            return Err(JayError::verify(format!(
+                "invokeinterface target {target_name} must not be native"
             )));
         }
 
@@ -441,7 +601,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
             .code
             .as_ref()
             .ok_or_else(|| {
-                JayError::new(format!("invokeinterface target {target_name} has no Code"))
+                JayError::verify(format!("invokeinterface target {target_name} has no Code"))
             })?
             .clone();
 
@@ -476,25 +636,51 @@ impl<'a, W: Write> Interpreter<'a, W> {
             target_descriptor
         );
 
-        if target_class_name == "java/lang/System"
-            && target_method_name == "currentTimeMillis"
-            && target_descriptor == "()J"
-        {
-            caller.stack.push(Value::Long(current_time_millis()?));
-            return Ok(());
+        let intrinsic =
+            Intrinsic::find(&target_class_name, &target_method_name, &target_descriptor);
+        match intrinsic {
+            Some(Intrinsic::CurrentTimeMillis) => {
+                caller.stack.push(Value::Long(current_time_millis()?));
+                return Ok(());
+            }
+            Some(Intrinsic::TimeZoneGetTimeZone) => {
+                let descriptor = MethodDescriptor::parse(&target_descriptor)?;
+                return self.invoke_time_zone_get_time_zone(caller, &descriptor, &target_name);
+            }
+            Some(Intrinsic::LocalDateTimeNow) => return self.invoke_local_date_time_now(caller),
+            Some(Intrinsic::SetOut) => return self.set_system_stream(caller, "out"),
+            Some(Intrinsic::SetErr) => return self.set_system_stream(caller, "err"),
+            Some(Intrinsic::SetIn) => return self.set_system_in(caller),
+            _ => {}
         }
-        if target_class_name == "java/util/TimeZone"
-            && target_method_name == "getTimeZone"
-            && target_descriptor == "(Ljava/lang/String;)Ljava/util/TimeZone;"
-        {
+        if is_shutdown_method(&target_class_name, &target_method_name, &target_descriptor) {
             let descriptor = MethodDescriptor::parse(&target_descriptor)?;
-            return self.invoke_time_zone_get_time_zone(caller, &descriptor, &target_name);
+            return self.invoke_shutdown_method(
+                caller,
+                &target_class_name,
+                &target_method_name,
+                &descriptor,
+                &target_name,
+            );
         }
-        if target_class_name == "java/time/LocalDateTime"
-            && target_method_name == "now"
-            && target_descriptor == "()Ljava/time/LocalDateTime;"
-        {
-            return self.invoke_local_date_time_now(caller);
+        if is_boxing_method(&target_class_name, &target_method_name, &target_descriptor) {
+            let descriptor = MethodDescriptor::parse(&target_descriptor)?;
+            return self.invoke_boxing_method(
+                caller,
+                &target_class_name,
+                &descriptor,
+                &target_name,
+            );
+        }
+        if is_property_method(&target_class_name, &target_method_name, &target_descriptor) {
+            let descriptor = MethodDescriptor::parse(&target_descriptor)?;
+            return self.invoke_property_method(
+                caller,
+                &target_class_name,
+                &target_method_name,
+                &descriptor,
+                &target_name,
+            );
         }
 
         let descriptor = MethodDescriptor::parse(&target_descriptor)?;
@@ -502,32 +688,30 @@ impl<'a, W: Write> Interpreter<'a, W> {
         let target_class_file = if target_class_name == caller_class_file.this_class {
             caller_class_file
         } else {
-            let binary_name = target_class_name.replace('/', ".");
-            let bytes = self.classes.load_class_bytes(&binary_name)?;
-            loaded_class_file = ClassFile::parse(&bytes)?;
+            loaded_class_file = self.load_class_file(&target_class_name)?;
             &loaded_class_file
         };
         let method = target_class_file
             .find_method(&target_method_name, &target_descriptor)
-            .ok_or_else(|| JayError::new(format!("invokestatic target {target_name} not found")))?;
+            .ok_or_else(|| {
+                JayError::verify(format!("invokestatic target {target_name} not found"))
+            })?;
 
         if !method.is_static() {
-            return Err(JayError::new(format!(
+            return Err(JayError::verify(format!(
                 "invokestatic target {target_name} must be static"
             )));
         }
+        self.check_private_method_access(caller_class_file, target_class_file, method)?;
 
-        if target_class_name == "java/lang/System"
-            && target_method_name == "registerNatives"
-            && target_descriptor == "()V"
-        {
+        if intrinsic == Some(Intrinsic::RegisterNatives) {
             // HotSpot uses this to register VM natives; Jay dispatches supported
             // native behavior through explicit Rust shims, so there is no table to populate.
             return Ok(());
         }
 
-        if method.access_flags & 0x0100 != 0 || method.access_flags & 0x0400 != 0 {
-            return Err(JayError::new(format!(
+        if method.is_native() || method.is_abstract() {
+            return Err(JayError::verify(format!(
                 "invokestatic target {target_name} must not be native or abstract"
             )));
         }
@@ -535,8 +719,11 @@ impl<'a, W: Write> Interpreter<'a, W> {
         let code = method
             .code
             .as_ref()
-            .ok_or_else(|| JayError::new(format!("invokestatic target {target_name} has no Code")))?
+            .ok_or_else(|| {
+                JayError::verify(format!("invokestatic target {target_name} has no Code"))
+            })?
             .clone();
+        self.initialize_class_for_use(&target_class_name, caller)?;
 
         let arguments = self.pop_method_arguments(
             caller,
@@ -555,4 +742,111 @@ impl<'a, W: Write> Interpreter<'a, W> {
             &format!("invokestatic target {target_name}"),
         )
     }
+
+    /// Runs a no-argument instance method on `receiver` from Rust, dispatching
+    /// on its class like `invokevirtual`; `roots` stay reachable meanwhile.
+    pub(super) fn call_instance_method(
+        &mut self,
+        roots: &[Value],
+        receiver: ObjectRef,
+        method_name: &str,
+        descriptor: &str,
+    ) -> JayResult<Option<Value>> {
+        let receiver_class_name = self.heap.instance_class_name(receiver)?.to_string();
+        let target_name = format!(
+            "{}.{method_name}{descriptor}",
+            receiver_class_name.replace('/', ".")
+        );
+        let class_file = self
+            .find_instance_method_class(&receiver_class_name, method_name, descriptor)?
+            .ok_or_else(|| JayError::verify(format!("{target_name} not found")))?;
+        let method = class_file
+            .find_method(method_name, descriptor)
+            .ok_or_else(|| JayError::verify(format!("{target_name} not found")))?
+            .clone();
+        let code = method
+            .code
+            .as_ref()
+            .ok_or_else(|| JayError::verify(format!("{target_name} has no Code")))?
+            .clone();
+        let mut callee = Frame::with_arguments(code.max_locals, vec![Value::Reference(receiver)])?;
+        self.saved_roots.push(roots.to_vec());
+        let result = self.execute(&class_file, &method, &code, &mut callee);
+        self.saved_roots.pop();
+        result
+    }
+}
+
+/// A linked `StringConcatFactory.makeConcatWithConstants` call site, the only
+/// `invokedynamic` bootstrap Jay supports.
+pub(super) struct StringConcatCallSite {
+    /// Call-site name and descriptor, as shown in diagnostics.
+    pub(super) name: String,
+    pub(super) descriptor: MethodDescriptor,
+    pub(super) recipe: String,
+}
+
+/// Validates the bootstrap behind an `invokedynamic` constant and reads its recipe.
+pub(super) fn resolve_string_concat_call_site(
+    class_file: &ClassFile,
+    index: u16,
+) -> JayResult<StringConcatCallSite> {
+    let dynamic = class_file.constant_pool.invoke_dynamic(index)?;
+    let bootstrap = class_file
+        .bootstrap_methods
+        .get(dynamic.bootstrap_method_attr_index as usize)
+        .ok_or_else(|| {
+            JayError::class_format(format!(
+                "invokedynamic bootstrap method #{} not found",
+                dynamic.bootstrap_method_attr_index
+            ))
+        })?;
+    let method_handle = class_file
+        .constant_pool
+        .method_handle(bootstrap.method_ref)?;
+    if method_handle.reference_kind != 6 {
+        return Err(JayError::unsupported(format!(
+            "invokedynamic bootstrap method handle kind {}",
+            method_handle.reference_kind
+        )));
+    }
+
+    let bootstrap_method = class_file
+        .constant_pool
+        .method_ref(method_handle.reference_index)?;
+    if bootstrap_method.class_name != "java/lang/invoke/StringConcatFactory"
+        || bootstrap_method.name != "makeConcatWithConstants"
+    {
+        return Err(JayError::unsupported(format!(
+            "invokedynamic bootstrap {}.{}{}",
+            bootstrap_method.class_name, bootstrap_method.name, bootstrap_method.descriptor
+        )));
+    }
+
+    if dynamic.name != "makeConcatWithConstants" {
+        return Err(JayError::unsupported(format!(
+            "invokedynamic call site {}{}",
+            dynamic.name, dynamic.descriptor
+        )));
+    }
+
+    let descriptor = MethodDescriptor::parse(dynamic.descriptor)?;
+    if !descriptor.return_type.is_reference_to("java/lang/String") {
+        return Err(JayError::unsupported(format!(
+            "invokedynamic return type in {}{}",
+            dynamic.name, dynamic.descriptor
+        )));
+    }
+
+    let [recipe_index] = bootstrap.arguments.as_slice() else {
+        return Err(JayError::unsupported(format!(
+            "StringConcatFactory bootstrap argument count {}",
+            bootstrap.arguments.len()
+        )));
+    };
+    Ok(StringConcatCallSite {
+        name: format!("{}{}", dynamic.name, dynamic.descriptor),
+        descriptor,
+        recipe: class_file.constant_pool.string(*recipe_index)?.to_string(),
+    })
 }
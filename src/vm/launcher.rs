@@ -0,0 +1,160 @@
+//! Finding and running a class's `main` method with host standard streams.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use super::Vm;
+use super::frame::Frame;
+use super::input::HostInput;
+use super::interpreter::Interpreter;
+use super::value::Value;
+use crate::classfile::ClassFile;
+use crate::{JayError, JayResult};
+
+/// Host streams behind `System.in`, `System.out`, and `System.err`.
+pub(super) struct StandardStreams<'a, W: Write> {
+    pub(super) input: &'a mut dyn BufRead,
+    pub(super) output: &'a mut W,
+    pub(super) error: &'a mut dyn Write,
+}
+
+/// Handles a `Vm` was built with; `None` stands for the process's own stream.
+#[derive(Default)]
+pub(super) struct HostStreams {
+    pub(super) input: Option<Box<dyn BufRead>>,
+    pub(super) output: Option<Box<dyn Write>>,
+    pub(super) error: Option<Box<dyn Write>>,
+}
+
+impl HostStreams {
+    /// Calls `run` with the configured handles, filling gaps with the process's
+    /// streams. Process stdin and stdout stay locked until `run` returns; custom
+    /// handles leave them free for the rest of the host.
+    pub(super) fn run<T>(
+        &mut self,
+        run: impl FnOnce(&mut dyn BufRead, &mut dyn Write, &mut dyn Write) -> T,
+    ) -> T {
+        let mut process_output;
+        let output: &mut dyn Write = match &mut self.output {
+            Some(output) => output,
+            None => {
+                process_output = io::stdout().lock();
+                &mut process_output
+            }
+        };
+        run_with_input_and_error(&mut self.input, &mut self.error, |input, error| {
+            run(input, output, error)
+        })
+    }
+
+    /// Like [`HostStreams::run`] for callers that bring their own stdout.
+    pub(super) fn run_without_output<T>(
+        &mut self,
+        run: impl FnOnce(&mut dyn BufRead, &mut dyn Write) -> T,
+    ) -> T {
+        run_with_input_and_error(&mut self.input, &mut self.error, run)
+    }
+}
+
+fn run_with_input_and_error<T>(
+    input: &mut Option<Box<dyn BufRead>>,
+    error: &mut Option<Box<dyn Write>>,
+    run: impl FnOnce(&mut dyn BufRead, &mut dyn Write) -> T,
+) -> T {
+    let mut process_input;
+    let input: &mut dyn BufRead = match input {
+        Some(input) => input,
+        None => {
+            process_input = io::stdin().lock();
+            &mut process_input
+        }
+    };
+    let mut process_error;
+    let error: &mut dyn Write = match error {
+        Some(error) => error,
+        None => {
+            process_error = io::stderr();
+            &mut process_error
+        }
+    };
+    run(input, error)
+}
+
+impl fmt::Debug for HostStreams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let describe = |custom: bool| if custom { "custom" } else { "process" };
+        f.debug_struct("HostStreams")
+            .field("input", &describe(self.input.is_some()))
+            .field("output", &describe(self.output.is_some()))
+            .field("error", &describe(self.error.is_some()))
+            .finish()
+    }
+}
+
+/// A fresh interpreter with the `Vm`'s properties, environment, limits, and
+/// uncaught exception reporting.
+pub(super) fn start_interpreter<'a, W: Write>(
+    vm: &'a Vm,
+    streams: StandardStreams<'a, W>,
+) -> Interpreter<'a, W> {
+    let mut interpreter = Interpreter::new(
+        &vm.classes,
+        streams.output,
+        streams.error,
+        HostInput::new(streams.input),
+        vm.properties.clone(),
+    );
+    interpreter.environment = vm.environment.clone();
+    interpreter.limits = vm.limits;
+    interpreter.print_uncaught_exceptions = !vm.hide_uncaught_exceptions;
+    interpreter
+}
+
+pub(super) fn run_main<'a, W: Write>(
+    vm: &'a Vm,
+    main_class: &str,
+    args: &[String],
+    streams: StandardStreams<'a, W>,
+) -> JayResult<i32> {
+    let bytes = vm.classes.load_class_bytes(main_class)?;
+    let class_file = ClassFile::parse(&bytes)?;
+    let main = class_file
+        .find_method("main", "([Ljava/lang/String;)V")
+        .or_else(|| class_file.find_method("main", "()V"))
+        .ok_or_else(|| JayError::usage(format!("main method not found in {main_class}")))?;
+
+    if !main.is_public() || !main.is_static() {
+        return Err(JayError::usage(format!(
+            "main method in {main_class} must be public static"
+        )));
+    }
+
+    let code = main
+        .code
+        .as_ref()
+        .ok_or_else(|| JayError::verify(format!("main method in {main_class} has no Code")))?;
+
+    let mut interpreter = start_interpreter(vm, streams);
+    let mut frame = if main.descriptor == "([Ljava/lang/String;)V" {
+        let array = interpreter.heap.allocate_object_array(args.len());
+        for (index, arg) in args.iter().enumerate() {
+            let arg = interpreter.heap.allocate_string(arg.as_str());
+            interpreter
+                .heap
+                .store_array_reference(array, index, Value::Reference(arg))?;
+        }
+        Frame::with_arguments(code.max_locals, vec![Value::Reference(array)])?
+    } else {
+        Frame::new(code.max_locals)
+    };
+    let outcome = interpreter
+        .initialize_class(&class_file.this_class, &frame)
+        .and_then(|()| interpreter.execute(&class_file, main, code, &mut frame))
+        .and_then(|result| match result {
+            None => Ok(()),
+            Some(_) => Err(JayError::verify(format!(
+                "main method in {main_class} returned a value"
+            ))),
+        });
+    interpreter.shut_down(outcome)
+}
@@ -3,16 +3,28 @@
 use std::io::Write;
 
 use super::frame::Frame;
-use super::heap::ObjectRef;
+use super::heap::{FieldKey, ObjectRef};
 use super::interpreter::Interpreter;
 use super::value::Value;
 use crate::classfile::ClassFile;
 use crate::{JayError, JayResult};
 
+/// JVMS 5.5 initialization state of a class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ClassInitState {
+    /// `<clinit>` is running; recursive requests from the same thread succeed immediately.
+    InProgress,
+    Initialized,
+    /// Initialization failed; later initialization requests throw `NoClassDefFoundError`.
+    Erroneous,
+}
+
 impl<'a, W: Write> Interpreter<'a, W> {
-    pub(super) fn collect_if_needed(&mut self, current_frame: &Frame) {
+    /// Collects garbage every few allocations, then throws `OutOfMemoryError`
+    /// if the live objects still exceed the run's heap limit.
+    pub(super) fn collect_if_needed(&mut self, current_frame: &Frame) -> JayResult<()> {
         if !self.heap.should_collect() {
-            return;
+            return Ok(());
         }
 
         let roots = self
@@ -22,9 +34,17 @@ impl<'a, W: Write> Interpreter<'a, W> {
             .cloned()
             .chain(self.static_fields.values().cloned())
             .chain(self.class_mirrors.values().copied().map(Value::Reference))
+            .chain(self.thrown_exception.map(Value::Reference))
+            .chain(self.shutdown_hooks.iter().copied().map(Value::Reference))
             .chain(current_frame.roots().cloned())
             .collect::<Vec<_>>();
         self.heap.collect(roots.iter());
+        match self.limits.max_heap_objects {
+            Some(max) if self.heap.object_count() > max => {
+                Err(self.throw_new("java/lang/OutOfMemoryError", Some("Java heap space"), None))
+            }
+            _ => Ok(()),
+        }
     }
 
     pub(super) fn class_mirror(&mut self, class_name: &str) -> ObjectRef {
@@ -38,31 +58,137 @@ impl<'a, W: Write> Interpreter<'a, W> {
         reference
     }
 
+    /// Initializes a class following JVMS 5.5, including its superclass chain.
+    ///
+    /// Recursive requests while the class is being initialized return
+    /// immediately. A failed initializer leaves the class erroneous: the first
+    /// failure surfaces as `ExceptionInInitializerError` (or the thrown `Error`
+    /// itself), and every later access throws `NoClassDefFoundError`.
     pub(super) fn initialize_class(
         &mut self,
         class_name: &str,
         current_frame: &Frame,
     ) -> JayResult<()> {
-        if self.initialized_classes.contains(class_name)
-            || self.initializing_classes.contains(class_name)
-        {
-            return Ok(());
+        match self.class_states.get(class_name) {
+            Some(ClassInitState::InProgress | ClassInitState::Initialized) => return Ok(()),
+            Some(ClassInitState::Erroneous) => {
+                let message = format!(
+                    "Could not initialize class {}",
+                    class_name.replace('/', ".")
+                );
+                return Err(self.throw_new("java/lang/NoClassDefFoundError", Some(&message), None));
+            }
+            None => {}
         }
 
+        self.link_class(class_name)?;
         let class_file = self.load_class_file(class_name)?;
-        self.initializing_classes.insert(class_name.to_string());
-        let result = if let Some(super_class) = class_file.super_class.as_deref() {
-            self.initialize_class(super_class, current_frame)
-                .and_then(|_| self.execute_class_initializer(&class_file, current_frame))
-        } else {
+        self.class_states
+            .insert(class_name.to_string(), ClassInitState::InProgress);
+        let result = self.prepare_constant_fields(&class_file).and_then(|()| {
+            if let Some(super_class) = class_file.super_class.as_deref() {
+                self.initialize_class_for_use(super_class, current_frame)?;
+            }
             self.execute_class_initializer(&class_file, current_frame)
-        };
-        self.initializing_classes.remove(class_name);
-        result?;
-        self.initialized_classes.insert(class_name.to_string());
+        });
+
+        match result {
+            Ok(()) => {
+                self.class_states
+                    .insert(class_name.to_string(), ClassInitState::Initialized);
+                Ok(())
+            }
+            Err(error) => {
+                self.class_states
+                    .insert(class_name.to_string(), ClassInitState::Erroneous);
+                Err(self.initialization_failure(error)?)
+            }
+        }
+    }
+
+    /// Assigns `ConstantValue` attributes to static fields before `<clinit>` runs.
+    ///
+    /// javac folds `static final` constants into this attribute instead of
+    /// emitting initializer code, so without it `getstatic` would read the
+    /// default value. `float` and `double` constants are left unset because
+    /// reading those fields is unsupported.
+    fn prepare_constant_fields(&mut self, class_file: &ClassFile) -> JayResult<()> {
+        let constant_pool = &class_file.constant_pool;
+        for field in &class_file.fields {
+            let Some(index) = field.constant_value else {
+                continue;
+            };
+            if !field.is_static() {
+                continue;
+            }
+
+            let value = match field.descriptor.as_str() {
+                "I" | "Z" | "B" | "C" | "S" => Value::Int(constant_pool.integer(index)?),
+                "J" => Value::Long(constant_pool.long(index)?),
+                "Ljava/lang/String;" => {
+                    Value::Reference(self.heap.allocate_string(constant_pool.string(index)?))
+                }
+                // javac inlines these constants at every use site, so
+                // classes that declare them must still initialize; a
+                // `getstatic` of one fails as an unsupported descriptor.
+                "F" | "D" => continue,
+                descriptor => {
+                    return Err(JayError::class_format(format!(
+                        "ConstantValue attribute on field {}.{} of type {descriptor}",
+                        class_file.this_class, field.name
+                    )));
+                }
+            };
+            let field_key = FieldKey::new(&class_file.this_class, &field.name, &field.descriptor);
+            self.static_fields.insert(field_key, value);
+        }
         Ok(())
     }
 
+    /// Initializes the class named by a `new` or `invokestatic` instruction,
+    /// or the superclass of a class being initialized.
+    ///
+    /// Boot image classes keep initializing on first static field access only:
+    /// most JDK initializers still need bytecodes and natives Jay does not
+    /// implement, and the JDK types Jay supports are backed by Rust shims.
+    /// Where a class came from is recorded when it is linked, so the
+    /// classpath is searched once per class rather than on every use.
+    pub(super) fn initialize_class_for_use(
+        &mut self,
+        class_name: &str,
+        current_frame: &Frame,
+    ) -> JayResult<()> {
+        match self.class_states.get(class_name) {
+            Some(ClassInitState::Initialized) => return Ok(()),
+            Some(_) => {}
+            None => {
+                self.link_class(class_name)?;
+                if !self.linked_classes[class_name].is_from_classpath() {
+                    return Ok(());
+                }
+            }
+        }
+        self.initialize_class(class_name, current_frame)
+    }
+
+    /// Wraps a Java exception escaping `<clinit>` unless it is already an `Error`.
+    fn initialization_failure(&mut self, error: JayError) -> JayResult<JayError> {
+        let Some(exception) = self.thrown_exception else {
+            return Ok(error);
+        };
+        let exception_class = self.heap.instance_class_name(exception)?.to_string();
+        if self.is_assignable_reference(&exception_class, "java/lang/Error")? {
+            return Ok(error);
+        }
+
+        self.thrown_exception = None;
+        Ok(self.throw_new(
+            "java/lang/ExceptionInInitializerError",
+            None,
+            Some(exception),
+        ))
+    }
+
     pub(super) fn execute_class_initializer(
         &mut self,
         class_file: &ClassFile,
@@ -73,14 +199,14 @@ impl<'a, W: Write> Interpreter<'a, W> {
         };
 
         if !method.is_static() {
-            return Err(JayError::new(format!(
+            return Err(JayError::verify(format!(
                 "class initializer for {} must be static",
                 class_file.this_class.replace('/', ".")
             )));
         }
 
         let code = method.code.as_ref().ok_or_else(|| {
-            JayError::new(format!(
+            JayError::verify(format!(
                 "class initializer for {} has no Code",
                 class_file.this_class.replace('/', ".")
             ))
@@ -93,10 +219,43 @@ impl<'a, W: Write> Interpreter<'a, W> {
         self.saved_roots.pop();
         match result? {
             None => Ok(()),
-            Some(_) => Err(JayError::new(format!(
+            Some(_) => Err(JayError::verify(format!(
                 "class initializer for {} returned a value",
                 class_file.this_class.replace('/', ".")
             ))),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::classpath::ClassResolver;
+    use crate::vm::input::HostInput;
+
+    #[test]
+    fn boot_classes_are_linked_once_and_left_uninitialized() {
+        let classes = ClassResolver::new(Vec::new()).unwrap();
+        let (mut output, mut error, mut input) = (Vec::new(), Vec::new(), &b""[..]);
+        let mut interpreter = Interpreter::new(
+            &classes,
+            &mut output,
+            &mut error,
+            HostInput::new(&mut input),
+            BTreeMap::new(),
+        );
+        let frame = Frame::new(0);
+
+        interpreter
+            .initialize_class_for_use("java/lang/Math", &frame)
+            .unwrap();
+        interpreter
+            .initialize_class_for_use("java/lang/Math", &frame)
+            .unwrap();
+
+        assert!(!interpreter.linked_classes["java/lang/Math"].is_from_classpath());
+        assert!(!interpreter.class_states.contains_key("java/lang/Math"));
+    }
+}
@@ -119,8 +119,8 @@ pub(super) fn format_simple_date(
             two_digits(date_time.second),
             time_zone.id()
         )),
-        _ => Err(JayError::new(format!(
-            "unsupported SimpleDateFormat pattern {pattern}"
+        _ => Err(JayError::unsupported(format!(
+            "SimpleDateFormat pattern {pattern}"
         ))),
     }
 }
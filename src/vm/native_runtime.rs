@@ -48,7 +48,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
             Value::Long(time_zone.offset_millis()),
         )?;
         caller.stack.push(Value::Reference(reference));
-        self.collect_if_needed(caller);
+        self.collect_if_needed(caller)?;
         Ok(())
     }
 
@@ -61,7 +61,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
             Value::Long(epoch_millis),
         )?;
         caller.stack.push(Value::Reference(reference));
-        self.collect_if_needed(caller);
+        self.collect_if_needed(caller)?;
         Ok(())
     }
 
@@ -73,7 +73,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
         let fast_time = self.date_fast_time(receiver)?;
         let reference = self.heap.allocate_string(native::date_to_string(fast_time));
         caller.stack.push(Value::Reference(reference));
-        self.collect_if_needed(caller);
+        self.collect_if_needed(caller)?;
         Ok(())
     }
 
@@ -98,7 +98,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
         let output = native::format_simple_date(&pattern, fast_time, time_zone)?;
         let reference = self.heap.allocate_string(output);
         caller.stack.push(Value::Reference(reference));
-        self.collect_if_needed(caller);
+        self.collect_if_needed(caller)?;
         Ok(())
     }
 
@@ -0,0 +1,239 @@
+//! System properties: the VM table, `-D` overrides, and the JDK methods that
+//! read it, plus `System.getenv` over the VM's environment.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+
+use super::descriptors::MethodDescriptor;
+use super::frame::Frame;
+use super::interpreter::Interpreter;
+use super::value::Value;
+use crate::jdk::boot_image_java_version;
+use crate::{JayError, JayResult};
+
+/// Property accessors answered from the VM table, as `(class, name, descriptor)`.
+pub(super) const PROPERTY_METHODS: &[(&str, &str, &str)] = &[
+    (
+        "java/lang/System",
+        "getProperty",
+        "(Ljava/lang/String;)Ljava/lang/String;",
+    ),
+    (
+        "java/lang/System",
+        "getProperty",
+        "(Ljava/lang/String;Ljava/lang/String;)Ljava/lang/String;",
+    ),
+    (
+        "java/lang/System",
+        "setProperty",
+        "(Ljava/lang/String;Ljava/lang/String;)Ljava/lang/String;",
+    ),
+    (
+        "java/lang/System",
+        "getenv",
+        "(Ljava/lang/String;)Ljava/lang/String;",
+    ),
+    ("java/lang/Boolean", "getBoolean", "(Ljava/lang/String;)Z"),
+    (
+        "java/lang/Integer",
+        "getInteger",
+        "(Ljava/lang/String;)Ljava/lang/Integer;",
+    ),
+    (
+        "java/lang/Integer",
+        "getInteger",
+        "(Ljava/lang/String;I)Ljava/lang/Integer;",
+    ),
+    (
+        "java/lang/Integer",
+        "getInteger",
+        "(Ljava/lang/String;Ljava/lang/Integer;)Ljava/lang/Integer;",
+    ),
+];
+
+pub(super) fn is_property_method(class_name: &str, name: &str, descriptor: &str) -> bool {
+    PROPERTY_METHODS.contains(&(class_name, name, descriptor))
+}
+
+/// What `println` ends lines with, as in `System.lineSeparator()`.
+pub(super) fn line_separator() -> &'static str {
+    if cfg!(windows) { "\r\n" } else { "\n" }
+}
+
+/// The standard properties every run starts with, before `-D` overrides.
+pub(super) fn default_properties(boot_image_path: &Path) -> BTreeMap<String, String> {
+    let mut properties = BTreeMap::new();
+    let mut set = |key: &str, value: String| {
+        properties.insert(key.to_string(), value);
+    };
+    if let Some(version) = boot_image_java_version(boot_image_path) {
+        set("java.version", version);
+    }
+    if let Some(java_home) = boot_image_path.parent().and_then(Path::parent) {
+        set("java.home", java_home.display().to_string());
+    }
+    let os_name = match std::env::consts::OS {
+        "linux" => "Linux",
+        "macos" => "Mac OS X",
+        "windows" => "Windows",
+        "freebsd" => "FreeBSD",
+        other => other,
+    };
+    set("os.name", os_name.to_string());
+    set("line.separator", line_separator().to_string());
+    set("file.separator", std::path::MAIN_SEPARATOR.to_string());
+    if let Ok(user_dir) = std::env::current_dir() {
+        set("user.dir", user_dir.display().to_string());
+    }
+    properties
+}
+
+impl<'a, W: Write> Interpreter<'a, W> {
+    pub(super) fn invoke_property_method(
+        &mut self,
+        caller: &mut Frame,
+        class_name: &str,
+        method_name: &str,
+        descriptor: &MethodDescriptor,
+        target_name: &str,
+    ) -> JayResult<()> {
+        let arguments = self.pop_method_arguments(
+            caller,
+            descriptor,
+            &format!("invokestatic target {target_name}"),
+        )?;
+        let key = self.optional_string(&arguments[0])?;
+        let result = match (class_name, method_name) {
+            ("java/lang/System", "getProperty") => {
+                let key = self.checked_property_key(key)?;
+                match (self.properties.get(&key), arguments.get(1)) {
+                    (Some(value), _) => self.string_value(value.clone()),
+                    (None, Some(default)) => default.clone(),
+                    (None, None) => Value::Null,
+                }
+            }
+            ("java/lang/System", "setProperty") => {
+                let key = self.checked_property_key(key)?;
+                let Some(value) = self.optional_string(&arguments[1])? else {
+                    return Err(self.throw_new("java/lang/NullPointerException", None, None));
+                };
+                match self.properties.insert(key, value) {
+                    Some(previous) => self.string_value(previous),
+                    None => Value::Null,
+                }
+            }
+            ("java/lang/System", "getenv") => {
+                let Some(name) = key else {
+                    return Err(self.throw_new("java/lang/NullPointerException", None, None));
+                };
+                match self.environment.get(&name) {
+                    Some(value) => self.string_value(value.clone()),
+                    None => Value::Null,
+                }
+            }
+            ("java/lang/Boolean", "getBoolean") => {
+                let value = key.and_then(|key| self.properties.get(&key));
+                Value::Int(value.is_some_and(|value| value.eq_ignore_ascii_case("true")) as i32)
+            }
+            ("java/lang/Integer", "getInteger") => {
+                let value = key
+                    .and_then(|key| self.properties.get(&key))
+                    .and_then(|value| decode_int(value));
+                match (value, arguments.get(1)) {
+                    (Some(value), _) | (None, Some(&Value::Int(value))) => {
+                        Value::Reference(self.box_value("java/lang/Integer", Value::Int(value))?)
+                    }
+                    (None, Some(default)) => default.clone(),
+                    (None, None) => Value::Null,
+                }
+            }
+            _ => {
+                return Err(JayError::unsupported(format!(
+                    "property method {target_name}"
+                )));
+            }
+        };
+        caller.stack.push(result);
+        self.collect_if_needed(caller)?;
+        Ok(())
+    }
+
+    fn optional_string(&self, value: &Value) -> JayResult<Option<String>> {
+        match value {
+            Value::Null => Ok(None),
+            Value::Reference(reference) => Ok(Some(self.heap.string(*reference)?.to_string())),
+            other => Err(JayError::verify(format!(
+                "expected String argument, found {}",
+                other.type_name(&self.heap)?
+            ))),
+        }
+    }
+
+    fn string_value(&mut self, value: String) -> Value {
+        Value::Reference(self.heap.allocate_string(value))
+    }
+
+    /// Rejects keys the way `System.checkKey` does.
+    fn checked_property_key(&mut self, key: Option<String>) -> JayResult<String> {
+        match key {
+            None => Err(self.throw_new(
+                "java/lang/NullPointerException",
+                Some("key can't be null"),
+                None,
+            )),
+            Some(key) if key.is_empty() => Err(self.throw_new(
+                "java/lang/IllegalArgumentException",
+                Some("key can't be empty"),
+                None,
+            )),
+            Some(key) => Ok(key),
+        }
+    }
+}
+
+/// Parses an `int` with `Integer.decode` syntax: an optional sign, then a
+/// decimal, `0x`/`0X`/`#` hexadecimal, or leading-zero octal number.
+fn decode_int(text: &str) -> Option<i32> {
+    let (negative, digits) = match text.as_bytes().first()? {
+        b'-' => (true, &text[1..]),
+        b'+' => (false, &text[1..]),
+        _ => (false, text),
+    };
+    let (radix, digits) = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+        .or_else(|| digits.strip_prefix('#'))
+    {
+        (16, hex)
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        (8, &digits[1..])
+    } else {
+        (10, digits)
+    };
+    if digits.is_empty() || digits.starts_with(['-', '+']) {
+        return None;
+    }
+    let magnitude = i64::from_str_radix(digits, radix).ok()?;
+    i32::try_from(if negative { -magnitude } else { magnitude }).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_integers_like_integer_decode() {
+        assert_eq!(decode_int("42"), Some(42));
+        assert_eq!(decode_int("-0x10"), Some(-16));
+        assert_eq!(decode_int("#ff"), Some(255));
+        assert_eq!(decode_int("010"), Some(8));
+        assert_eq!(decode_int("0"), Some(0));
+        assert_eq!(decode_int("-2147483648"), Some(i32::MIN));
+        assert_eq!(decode_int("2147483648"), None);
+        assert_eq!(decode_int("--1"), None);
+        assert_eq!(decode_int("0x"), None);
+        assert_eq!(decode_int("twelve"), None);
+        assert_eq!(decode_int(""), None);
+    }
+}
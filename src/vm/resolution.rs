@@ -9,13 +9,132 @@ use super::value::Value;
 use crate::classfile::{ClassFile, Method};
 use crate::{JayError, JayResult};
 
+/// What linking recorded about a class.
+#[derive(Debug, Clone)]
+pub(super) struct LinkedClass {
+    is_interface: bool,
+    is_abstract: bool,
+    /// Whether the class resolved from the classpath rather than the boot image.
+    from_classpath: bool,
+    /// Direct subclasses a sealed class permits; empty when it is not sealed.
+    permitted_subclasses: Vec<String>,
+}
+
+impl LinkedClass {
+    /// Whether `new` may create instances of the class.
+    pub(super) fn is_instantiable(&self) -> bool {
+        !self.is_interface && !self.is_abstract
+    }
+
+    /// Whether the class came from the application classpath.
+    pub(super) fn is_from_classpath(&self) -> bool {
+        self.from_classpath
+    }
+
+    fn is_sealed(&self) -> bool {
+        !self.permitted_subclasses.is_empty()
+    }
+}
+
 impl<'a, W: Write> Interpreter<'a, W> {
     pub(super) fn load_class_file(&self, internal_class_name: &str) -> JayResult<ClassFile> {
-        let binary_name = internal_class_name.replace('/', ".");
-        let bytes = self.classes.load_class_bytes(&binary_name)?;
+        let bytes = self
+            .classes
+            .load_class_bytes(&internal_class_name.replace('/', "."))?;
         ClassFile::parse(&bytes)
     }
 
+    /// Links a class the first time it is used, recording what later uses
+    /// need so the class file is not re-read for them.
+    ///
+    /// A classpath class whose direct supertype is sealed without listing it
+    /// in `PermittedSubclasses` throws `IncompatibleClassChangeError`. Boot
+    /// image classes are trusted as consistent. A class that fails to link
+    /// is not recorded, so every later use fails the same way.
+    pub(super) fn link_class(&mut self, class_name: &str) -> JayResult<()> {
+        if self.linked_classes.contains_key(class_name) {
+            return Ok(());
+        }
+        let class_file = self.load_class_file(class_name)?;
+        let from_classpath = self
+            .classes
+            .is_classpath_class(&class_name.replace('/', "."))?;
+        if from_classpath {
+            for supertype in class_file.super_class.iter().chain(&class_file.interfaces) {
+                self.link_class(supertype)?;
+                let linked = &self.linked_classes[supertype.as_str()];
+                if linked.is_sealed()
+                    && !linked.permitted_subclasses.contains(&class_file.this_class)
+                {
+                    let (relation, kind) = if linked.is_interface {
+                        ("implement", "interface")
+                    } else {
+                        ("inherit from", "class")
+                    };
+                    let message = format!(
+                        "class {} cannot {relation} sealed {kind} {}",
+                        class_file.this_class.replace('/', "."),
+                        supertype.replace('/', ".")
+                    );
+                    return Err(self.throw_new(
+                        "java/lang/IncompatibleClassChangeError",
+                        Some(&message),
+                        None,
+                    ));
+                }
+            }
+        }
+        self.linked_classes.insert(
+            class_name.to_string(),
+            LinkedClass {
+                is_interface: class_file.is_interface(),
+                is_abstract: class_file.is_abstract(),
+                from_classpath,
+                permitted_subclasses: class_file.permitted_subclasses,
+            },
+        );
+        Ok(())
+    }
+
+    /// Enforces private method access: outside its own class, a private
+    /// method is only accessible from nestmates sharing a validated nest host.
+    pub(super) fn check_private_method_access(
+        &mut self,
+        caller: &ClassFile,
+        target: &ClassFile,
+        method: &Method,
+    ) -> JayResult<()> {
+        if !method.is_private()
+            || caller.this_class == target.this_class
+            || self.nest_host(caller)? == self.nest_host(target)?
+        {
+            return Ok(());
+        }
+
+        let message = format!(
+            "class {} tried to access private method '{}.{}{}'",
+            caller.this_class.replace('/', "."),
+            target.this_class.replace('/', "."),
+            method.name,
+            method.descriptor
+        );
+        Err(self.throw_new("java/lang/IllegalAccessError", Some(&message), None))
+    }
+
+    /// Returns the nest host of `class_file`, falling back to the class itself
+    /// when the declared host does not list it in `NestMembers`.
+    fn nest_host(&self, class_file: &ClassFile) -> JayResult<String> {
+        let Some(host) = &class_file.nest_host else {
+            return Ok(class_file.this_class.clone());
+        };
+        let host_file = self.load_class_file(host)?;
+        if host_file.nest_members.contains(&class_file.this_class) {
+            Ok(host.clone())
+        } else {
+            Ok(class_file.this_class.clone())
+        }
+    }
+
     pub(super) fn resolve_instance_method_class(
         &self,
         receiver_class_name: &str,
@@ -24,7 +143,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
     ) -> JayResult<ClassFile> {
         self.find_instance_method_class(receiver_class_name, method_name, descriptor)?
             .ok_or_else(|| {
-                JayError::new(format!(
+                JayError::verify(format!(
                     "invokevirtual target {}.{}{} not found",
                     receiver_class_name.replace('/', "."),
                     method_name,
@@ -62,7 +181,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
         let method = class_file
             .find_method(method_name, descriptor)
             .ok_or_else(|| {
-                JayError::new(format!(
+                JayError::verify(format!(
                     "invokevirtual target {}.{}{} not found",
                     owner_class_name.replace('/', "."),
                     method_name,
@@ -98,7 +217,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
             }
         }
 
-        Err(JayError::new(format!(
+        Err(JayError::verify(format!(
             "invokeinterface target {}.{}{} not found",
             owner_interface_name.replace('/', "."),
             method_name,
@@ -132,7 +251,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
             }
         }
 
-        Err(JayError::new(format!(
+        Err(JayError::verify(format!(
             "field {}.{}:{} not found",
             class_name.replace('/', "."),
             field_name,
@@ -159,7 +278,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
             return Ok(());
         }
 
-        Err(JayError::new(format!(
+        Err(JayError::verify(format!(
             "{target_description} {action} {}, expected {}",
             value.type_name(&self.heap)?,
             expected_type.name()
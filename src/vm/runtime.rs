@@ -5,10 +5,9 @@ use std::io::Write;
 use super::descriptors::{self, MethodDescriptor, ReturnType};
 use super::frame::Frame;
 use super::interpreter::Interpreter;
-use super::native;
 use super::value::Value;
 use crate::classfile::ClassFile;
-use crate::{JayError, JayResult};
+use crate::{ErrorKind, JayError, JayResult};
 
 impl<'a, W: Write> Interpreter<'a, W> {
     pub(super) fn check_cast(
@@ -56,7 +55,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
         if !matches!(first, Value::Long(_)) {
             let second = frame.pop()?;
             if matches!(second, Value::Long(_)) {
-                return Err(JayError::new(
+                return Err(JayError::verify(
                     "invalid pop2 operand shape: category-1 value over category-2 value",
                 ));
             }
@@ -73,7 +72,7 @@ impl<'a, W: Write> Interpreter<'a, W> {
     ) -> JayResult<()> {
         match (return_type, result) {
             (ReturnType::Void, None) => Ok(()),
-            (ReturnType::Void, Some(_)) => Err(JayError::new(format!(
+            (ReturnType::Void, Some(_)) => Err(JayError::verify(format!(
                 "{target_description} returned a value from void method"
             ))),
             (ReturnType::Type(descriptors::ValueType::Reference(_)), Some(Value::Null)) => {
@@ -87,14 +86,14 @@ impl<'a, W: Write> Interpreter<'a, W> {
                     caller.stack.push(value);
                     Ok(())
                 } else {
-                    Err(JayError::new(format!(
+                    Err(JayError::verify(format!(
                         "{target_description} returned {}, expected {}",
                         value.type_name(&self.heap)?,
                         return_type.name()
                     )))
                 }
             }
-            (ReturnType::Type(return_type), None) => Err(JayError::new(format!(
+            (ReturnType::Type(return_type), None) => Err(JayError::verify(format!(
                 "{target_description} returned void from {} method",
                 return_type.name()
             ))),
@@ -106,36 +105,8 @@ impl<'a, W: Write> Interpreter<'a, W> {
             Value::Null => Ok("null".to_string()),
             Value::Int(value) => Ok(value.to_string()),
             Value::Reference(reference) => Ok(self.heap.string(reference)?.to_string()),
-            other => Err(JayError::new(format!(
-                "unsupported string concat argument {}",
-                other.type_name(&self.heap)?
-            ))),
-        }
-    }
-
-    /// Formats the focused subset of values supported by PrintStream.println(Object).
-    pub(super) fn println_object_text(&self, value: Value) -> JayResult<String> {
-        match value {
-            Value::Null => Ok("null".to_string()),
-            Value::Reference(reference) => match self.heap.value_type(reference)? {
-                Some(descriptors::ValueType::Reference(class_name)) => match class_name.as_str() {
-                    "java/lang/String" => Ok(self.heap.string(reference)?.to_string()),
-                    "java/util/Date" => Ok(native::date_to_string(self.date_fast_time(reference)?)),
-                    "java/time/LocalDateTime" => Ok(native::local_date_time_to_string(
-                        self.local_date_time_epoch_millis(reference)?,
-                    )),
-                    _ => Err(JayError::new(format!(
-                        "unsupported PrintStream.println(Object) value {}",
-                        self.heap.type_name(reference)?
-                    ))),
-                },
-                _ => Err(JayError::new(format!(
-                    "unsupported PrintStream.println(Object) value {}",
-                    self.heap.type_name(reference)?
-                ))),
-            },
-            other => Err(JayError::new(format!(
-                "unsupported PrintStream.println(Object) value {}",
+            other => Err(JayError::unsupported(format!(
+                "string concat argument {}",
                 other.type_name(&self.heap)?
             ))),
         }
@@ -169,7 +140,7 @@ pub(super) fn apply_string_concat_recipe(recipe: &str, arguments: &[String]) ->
         match character {
             '\u{0001}' => {
                 let Some(argument) = arguments.get(argument_index) else {
-                    return Err(JayError::new(
+                    return Err(JayError::verify(
                         "StringConcatFactory recipe has more placeholders than arguments",
                     ));
                 };
@@ -177,7 +148,10 @@ pub(super) fn apply_string_concat_recipe(recipe: &str, arguments: &[String]) ->
                 argument_index += 1;
             }
             '\u{0002}' => {
-                return Err(JayError::new(
+                return Err(JayError::of_kind(
+                    ErrorKind::UnsupportedFeature {
+                        feature: "StringConcatFactory constant placeholders".to_string(),
+                    },
                     "StringConcatFactory constant placeholders are unsupported",
                 ));
             }
@@ -186,7 +160,7 @@ pub(super) fn apply_string_concat_recipe(recipe: &str, arguments: &[String]) ->
     }
 
     if argument_index != arguments.len() {
-        return Err(JayError::new(
+        return Err(JayError::verify(
             "StringConcatFactory recipe has fewer placeholders than arguments",
         ));
     }
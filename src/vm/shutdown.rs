@@ -0,0 +1,199 @@
+//! `System.exit`, `Runtime` shutdown hooks, and the exit status of a run.
+
+use std::io::Write;
+
+use super::descriptors::MethodDescriptor;
+use super::frame::Frame;
+use super::heap::{FieldKey, ObjectRef};
+use super::interpreter::Interpreter;
+use super::value::Value;
+use crate::{JayError, JayResult};
+
+/// Methods answered by the shutdown shims, as `(class, name, descriptor)`.
+const SHUTDOWN_METHODS: &[(&str, &str, &str)] = &[
+    ("java/lang/System", "exit", "(I)V"),
+    ("java/lang/Runtime", "exit", "(I)V"),
+    ("java/lang/Runtime", "halt", "(I)V"),
+    (
+        "java/lang/Runtime",
+        "addShutdownHook",
+        "(Ljava/lang/Thread;)V",
+    ),
+    (
+        "java/lang/Runtime",
+        "removeShutdownHook",
+        "(Ljava/lang/Thread;)Z",
+    ),
+];
+
+/// `Thread` constructors Jay answers; the JDK ones need a running thread.
+const THREAD_CONSTRUCTORS: &[&str] = &[
+    "()V",
+    "(Ljava/lang/Runnable;)V",
+    "(Ljava/lang/String;)V",
+    "(Ljava/lang/Runnable;Ljava/lang/String;)V",
+];
+
+pub(super) fn is_shutdown_method(class_name: &str, name: &str, descriptor: &str) -> bool {
+    SHUTDOWN_METHODS.contains(&(class_name, name, descriptor))
+}
+
+pub(super) fn is_thread_constructor(class_name: &str, descriptor: &str) -> bool {
+    class_name == "java/lang/Thread" && THREAD_CONSTRUCTORS.contains(&descriptor)
+}
+
+fn thread_target_field() -> FieldKey {
+    FieldKey::new("java/lang/Thread", "target", "Ljava/lang/Runnable;")
+}
+
+fn thread_name_field() -> FieldKey {
+    FieldKey::new("java/lang/Thread", "name", "Ljava/lang/String;")
+}
+
+/// Counter behind default `Thread-N` names, like `Thread.threadInitNumber`.
+fn thread_number_field() -> FieldKey {
+    FieldKey::new("java/lang/Thread", "__jay_threadNumber", "I")
+}
+
+impl<'a, W: Write> Interpreter<'a, W> {
+    /// Stops the interpreter with `status`; the error unwinds past every handler.
+    fn exit(&mut self, status: i32) -> JayError {
+        self.exit_status = Some(status);
+        JayError::new(format!("exited with status {status}"))
+    }
+
+    pub(super) fn invoke_shutdown_method(
+        &mut self,
+        caller: &mut Frame,
+        class_name: &str,
+        method_name: &str,
+        descriptor: &MethodDescriptor,
+        target_name: &str,
+    ) -> JayResult<()> {
+        let arguments =
+            self.pop_method_arguments(caller, descriptor, &format!("invoke target {target_name}"))?;
+        if class_name == "java/lang/Runtime" {
+            caller.pop_object_ref()?;
+        }
+        let hook = match arguments.as_slice() {
+            [Value::Reference(hook)] => Some(*hook),
+            _ => None,
+        };
+        match (method_name, arguments.as_slice()) {
+            ("exit", [Value::Int(status)]) => Err(self.exit(*status)),
+            ("halt", [Value::Int(status)]) => {
+                self.shutdown_hooks.clear();
+                Err(self.exit(*status))
+            }
+            ("addShutdownHook" | "removeShutdownHook", _) => {
+                let Some(hook) = hook else {
+                    return Err(self.throw_new("java/lang/NullPointerException", None, None));
+                };
+                if self.shutting_down {
+                    return Err(self.throw_new(
+                        "java/lang/IllegalStateException",
+                        Some("Shutdown in progress"),
+                        None,
+                    ));
+                }
+                let registered = self.shutdown_hooks.contains(&hook);
+                if method_name == "removeShutdownHook" {
+                    self.shutdown_hooks.retain(|registered| *registered != hook);
+                    caller.stack.push(Value::Int(i32::from(registered)));
+                } else if registered {
+                    return Err(self.throw_new(
+                        "java/lang/IllegalArgumentException",
+                        Some("Hook previously registered"),
+                        None,
+                    ));
+                } else {
+                    self.shutdown_hooks.push(hook);
+                }
+                Ok(())
+            }
+            _ => Err(JayError::unsupported(format!(
+                "shutdown method {target_name}"
+            ))),
+        }
+    }
+
+    /// Builds a `Thread` by setting only the fields `run` and `getName` read.
+    pub(super) fn invoke_thread_constructor(
+        &mut self,
+        caller: &mut Frame,
+        target_descriptor: &str,
+        descriptor: &MethodDescriptor,
+        target_name: &str,
+    ) -> JayResult<()> {
+        let arguments = self.pop_constructor_arguments(
+            caller,
+            descriptor,
+            &format!("invokespecial constructor target {target_name}"),
+        )?;
+        let thread = caller.pop_object_ref()?;
+        let (target, name) = match (target_descriptor, arguments.as_slice()) {
+            (_, [target, name]) => (target.clone(), name.clone()),
+            ("(Ljava/lang/String;)V", [name]) => (Value::Null, name.clone()),
+            (_, [target]) => (target.clone(), Value::Null),
+            _ => (Value::Null, Value::Null),
+        };
+        let name = match name {
+            Value::Null => {
+                let number = match self.static_fields.get(&thread_number_field()) {
+                    Some(Value::Int(number)) => *number,
+                    _ => 0,
+                };
+                self.static_fields
+                    .insert(thread_number_field(), Value::Int(number + 1));
+                Value::Reference(self.heap.allocate_string(format!("Thread-{number}")))
+            }
+            name => name,
+        };
+        self.heap
+            .put_instance_field(thread, thread_target_field(), target)?;
+        self.heap
+            .put_instance_field(thread, thread_name_field(), name)
+    }
+
+    /// Finishes a run the way the `java` launcher does: an exception escaping
+    /// `main` is reported on `System.err`, shutdown hooks run after `main`
+    /// returns, throws, or calls `System.exit`, and the result is the exit
+    /// status. Uncaught exceptions stay errors, which exit with 1.
+    pub(super) fn shut_down(&mut self, outcome: JayResult<()>) -> JayResult<i32> {
+        let outcome = match (outcome, self.exit_status.take()) {
+            (_, Some(status)) => Ok(status),
+            (Ok(()), None) => Ok(0),
+            (Err(error), None) => match self.thrown_exception.take() {
+                Some(exception) => Err(self.report_uncaught(exception, "main")?),
+                // A VM failure is not a Java exit; hooks would only fail the same way.
+                None => return Err(error),
+            },
+        };
+        self.thrown_exception = None;
+        self.shutting_down = true;
+        let mut index = 0;
+        while let Some(&hook) = self.shutdown_hooks.get(index) {
+            index += 1;
+            let roots = [Value::Reference(hook)];
+            if let Err(error) = self.call_instance_method(&roots, hook, "run", "()V") {
+                if let Some(status) = self.exit_status {
+                    // Exiting from a hook halts at once, skipping the remaining hooks.
+                    return Ok(status);
+                }
+                let Some(exception) = self.thrown_exception.take() else {
+                    return Err(error);
+                };
+                let name = self.thread_name(hook)?;
+                self.report_uncaught(exception, &name)?;
+            }
+        }
+        outcome
+    }
+
+    fn thread_name(&self, thread: ObjectRef) -> JayResult<String> {
+        match self.heap.get_instance_field(thread, &thread_name_field())? {
+            Some(Value::Reference(name)) => Ok(self.heap.string(name)?.to_string()),
+            _ => Ok("Thread".to_string()),
+        }
+    }
+}
@@ -0,0 +1,713 @@
+//! `System.out` and `System.err` as heap `PrintStream` objects backed by host writers.
+
+use std::io::Write;
+
+use super::boxing::{Unboxed, java_char};
+use super::frame::Frame;
+use super::heap::{FieldKey, ObjectRef};
+use super::interpreter::Interpreter;
+use super::native;
+use super::properties::line_separator;
+use super::value::Value;
+use crate::{ErrorKind, JayError, JayResult};
+
+/// Host stream ids stored in a `PrintStream`'s hidden stream field.
+const STDOUT: i32 = 1;
+const STDERR: i32 = 2;
+
+/// `PrintStream` methods answered by the host writer behind a stream, as `(name, descriptor)`.
+const PRINT_STREAM_METHODS: &[(&str, &str)] = &[
+    ("print", "(Z)V"),
+    ("print", "(C)V"),
+    ("print", "(I)V"),
+    ("print", "(J)V"),
+    ("print", "(Ljava/lang/String;)V"),
+    ("print", "(Ljava/lang/Object;)V"),
+    ("println", "()V"),
+    ("println", "(Z)V"),
+    ("println", "(C)V"),
+    ("println", "(I)V"),
+    ("println", "(J)V"),
+    ("println", "(Ljava/lang/String;)V"),
+    ("println", "(Ljava/lang/Object;)V"),
+    (
+        "printf",
+        "(Ljava/lang/String;[Ljava/lang/Object;)Ljava/io/PrintStream;",
+    ),
+    (
+        "printf",
+        "(Ljava/util/Locale;Ljava/lang/String;[Ljava/lang/Object;)Ljava/io/PrintStream;",
+    ),
+    (
+        "format",
+        "(Ljava/lang/String;[Ljava/lang/Object;)Ljava/io/PrintStream;",
+    ),
+    (
+        "format",
+        "(Ljava/util/Locale;Ljava/lang/String;[Ljava/lang/Object;)Ljava/io/PrintStream;",
+    ),
+    ("write", "(I)V"),
+    ("flush", "()V"),
+];
+
+pub(super) fn is_print_stream_method(name: &str, descriptor: &str) -> bool {
+    PRINT_STREAM_METHODS.contains(&(name, descriptor))
+}
+
+/// Whether `System.<name>` is one of the standard output streams.
+pub(super) fn is_system_stream(class_name: &str, name: &str, descriptor: &str) -> bool {
+    class_name == "java/lang/System"
+        && matches!(name, "out" | "err")
+        && descriptor == "Ljava/io/PrintStream;"
+}
+
+fn stream_id_field() -> FieldKey {
+    FieldKey::new("java/io/PrintStream", "__jay_stream", "I")
+}
+
+/// Where `System.<name>` lives; hidden so `System.<clinit>` cannot overwrite it.
+fn system_stream_field(name: &str) -> FieldKey {
+    FieldKey::new(
+        "java/lang/System",
+        format!("__jay_{name}"),
+        "Ljava/io/PrintStream;",
+    )
+}
+
+impl<'a, W: Write> Interpreter<'a, W> {
+    /// Reads `System.out` or `System.err`, creating the host-backed stream on first use.
+    ///
+    /// `System` itself is not initialized here: its `<clinit>` wires streams
+    /// through VM internals Jay does not interpret.
+    pub(super) fn system_stream(&mut self, name: &str) -> JayResult<Value> {
+        let field = system_stream_field(name);
+        if let Some(stream) = self.static_fields.get(&field) {
+            return Ok(stream.clone());
+        }
+        let id = if name == "out" { STDOUT } else { STDERR };
+        let stream = self.heap.allocate_instance("java/io/PrintStream");
+        self.heap
+            .put_instance_field(stream, stream_id_field(), Value::Int(id))?;
+        self.static_fields.insert(field, Value::Reference(stream));
+        Ok(Value::Reference(stream))
+    }
+
+    /// Handles `System.setOut` and `System.setErr`.
+    pub(super) fn set_system_stream(&mut self, caller: &mut Frame, name: &str) -> JayResult<()> {
+        let stream = caller.pop_reference()?;
+        self.static_fields.insert(system_stream_field(name), stream);
+        Ok(())
+    }
+
+    /// The host stream behind a `PrintStream`, if Jay created it.
+    pub(super) fn host_stream(&self, reference: ObjectRef) -> JayResult<Option<i32>> {
+        if self.heap.instance_class_name(reference)? != "java/io/PrintStream" {
+            return Ok(None);
+        }
+        match self
+            .heap
+            .get_instance_field(reference, &stream_id_field())?
+        {
+            Some(Value::Int(id)) => Ok(Some(id)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Writes `text` to a `PrintStream` object, as `print(String)` would.
+    pub(super) fn write_to_print_stream(&mut self, stream: Value, text: &str) -> JayResult<()> {
+        let Value::Reference(stream) = stream else {
+            return Err(self.throw_new("java/lang/NullPointerException", None, None));
+        };
+        let Some(stream) = self.host_stream(stream)? else {
+            let feature = format!("printing to {}", self.heap.type_name(stream)?);
+            let message = format!(
+                "{feature} is not supported; only System.out and System.err can be written"
+            );
+            return Err(JayError::of_kind(
+                ErrorKind::UnsupportedFeature { feature },
+                message,
+            ));
+        };
+        self.stream_writer(stream).write_all(text.as_bytes())?;
+        Ok(())
+    }
+
+    fn stream_writer(&mut self, stream: i32) -> &mut dyn Write {
+        if stream == STDERR {
+            &mut *self.error
+        } else {
+            &mut *self.output
+        }
+    }
+
+    pub(super) fn invoke_print_stream(
+        &mut self,
+        caller: &mut Frame,
+        receiver: ObjectRef,
+        stream: i32,
+        method_name: &str,
+        descriptor: &str,
+        arguments: &[Value],
+    ) -> JayResult<()> {
+        let mut roots = caller.roots().cloned().collect::<Vec<_>>();
+        roots.push(Value::Reference(receiver));
+        roots.extend(arguments.iter().cloned());
+        let text = match (method_name, arguments) {
+            ("flush", _) => {
+                self.stream_writer(stream).flush()?;
+                return Ok(());
+            }
+            ("write", [Value::Int(byte)]) => {
+                self.stream_writer(stream).write_all(&[*byte as u8])?;
+                return Ok(());
+            }
+            ("printf" | "format", [.., format, arguments]) => {
+                let Value::Reference(format) = format else {
+                    return Err(self.throw_new("java/lang/NullPointerException", None, None));
+                };
+                let format = self.heap.string(*format)?.to_string();
+                let arguments = match arguments {
+                    Value::Reference(array) => (0..self.heap.array_length(*array)?)
+                        .map(|index| self.heap.load_array_reference(*array, index))
+                        .collect::<JayResult<Vec<_>>>()?,
+                    _ => Vec::new(),
+                };
+                let text = self.format_text(&roots, &format, &arguments)?;
+                self.stream_writer(stream).write_all(text.as_bytes())?;
+                caller.stack.push(Value::Reference(receiver));
+                return Ok(());
+            }
+            (_, []) => String::new(),
+            (_, [value]) => self.print_text(&roots, descriptor, value.clone())?,
+            _ => {
+                return Err(JayError::unsupported(format!(
+                    "PrintStream.{method_name} descriptor {descriptor}"
+                )));
+            }
+        };
+        let writer = self.stream_writer(stream);
+        writer.write_all(text.as_bytes())?;
+        if method_name == "println" {
+            writer.write_all(line_separator().as_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn print_text(&mut self, roots: &[Value], descriptor: &str, value: Value) -> JayResult<String> {
+        Ok(match (descriptor, value) {
+            ("(Z)V", Value::Int(value)) => (value != 0).to_string(),
+            ("(C)V", Value::Int(value)) => java_char(value as u16).to_string(),
+            (_, Value::Int(value)) => value.to_string(),
+            (_, Value::Long(value)) => value.to_string(),
+            (_, value) => self.object_text(roots, value)?,
+        })
+    }
+
+    /// `String.valueOf(Object)`: runs `toString()` for objects without a Rust shim.
+    ///
+    /// `roots` keeps the caller's live references reachable while Java code runs.
+    pub(super) fn object_text(&mut self, roots: &[Value], value: Value) -> JayResult<String> {
+        let reference = match value {
+            Value::Null => return Ok("null".to_string()),
+            Value::Reference(reference) => reference,
+            other => {
+                return Err(JayError::verify(format!(
+                    "expected object to print, found {}",
+                    other.type_name(&self.heap)?
+                )));
+            }
+        };
+        if let Some(unboxed) = self.unbox(reference)? {
+            return Ok(unboxed.to_string());
+        }
+        let class_name = match self.heap.instance_class_name(reference) {
+            Ok(class_name) => class_name.to_string(),
+            // Strings and arrays are not instances with fields.
+            Err(_) => {
+                return match self.heap.string(reference) {
+                    Ok(text) => Ok(text.to_string()),
+                    Err(_) => Ok(self.default_object_text(reference)?),
+                };
+            }
+        };
+        match class_name.as_str() {
+            "java/util/Date" => return Ok(native::date_to_string(self.date_fast_time(reference)?)),
+            "java/time/LocalDateTime" => {
+                return Ok(native::local_date_time_to_string(
+                    self.local_date_time_epoch_millis(reference)?,
+                ));
+            }
+            _ => {}
+        }
+
+        if class_name == "java/lang/StackTraceElement" {
+            return self.stack_trace_element_text(reference);
+        }
+
+        const TO_STRING: &str = "()Ljava/lang/String;";
+        let class_file = self
+            .find_instance_method_class(&class_name, "toString", TO_STRING)?
+            .ok_or_else(|| JayError::verify(format!("{class_name}.toString() not found")))?;
+        match class_file.this_class.as_str() {
+            "java/lang/Object" => return self.default_object_text(reference),
+            "java/lang/Throwable" => return self.throwable_text(roots, reference),
+            _ => {}
+        }
+        match self.call_instance_method(roots, reference, "toString", TO_STRING)? {
+            Some(Value::Reference(text)) => Ok(self.heap.string(text)?.to_string()),
+            Some(Value::Null) => Ok("null".to_string()),
+            _ => Err(JayError::verify(format!(
+                "{}.toString{TO_STRING} did not return a String",
+                class_file.this_class.replace('/', ".")
+            ))),
+        }
+    }
+
+    /// `Object.toString()`: the class name and identity hash in hex.
+    fn default_object_text(&self, reference: ObjectRef) -> JayResult<String> {
+        Ok(format!(
+            "{}@{:x}",
+            self.heap.type_name(reference)?,
+            reference.identity_hash()
+        ))
+    }
+
+    /// `String.format` for the conversions in [`parse_format`].
+    fn format_text(
+        &mut self,
+        roots: &[Value],
+        format: &str,
+        arguments: &[Value],
+    ) -> JayResult<String> {
+        let pieces = match parse_format(format) {
+            Ok(pieces) => pieces,
+            Err(error) => return Err(self.throw_format_error(error)),
+        };
+        let mut output = String::new();
+        let mut next_argument = 0;
+        for piece in pieces {
+            let spec = match piece {
+                FormatPiece::Text(text) => {
+                    output.push_str(&text);
+                    continue;
+                }
+                FormatPiece::Spec(spec) => spec,
+            };
+            match spec.conversion {
+                'n' => {
+                    output.push_str(line_separator());
+                    continue;
+                }
+                '%' => {
+                    output.push_str(&pad(&spec, "%".to_string()));
+                    continue;
+                }
+                _ => {}
+            }
+            let index = spec.index.unwrap_or_else(|| {
+                next_argument += 1;
+                next_argument
+            });
+            let Some(value) = arguments.get(index - 1).cloned() else {
+                let error = FormatError::missing_argument(&spec);
+                return Err(self.throw_format_error(error));
+            };
+            let argument = match (spec.conversion.to_ascii_lowercase(), value) {
+                (_, Value::Null) => FormatArgument::Null,
+                ('s', value) => FormatArgument::Text(self.object_text(roots, value)?),
+                (_, Value::Reference(reference)) => match self.unbox(reference)? {
+                    Some(unboxed) => FormatArgument::Unboxed(unboxed),
+                    None => FormatArgument::Other(self.heap.type_name(reference)?),
+                },
+                (_, other) => FormatArgument::Other(other.type_name(&self.heap)?),
+            };
+            match format_argument(&spec, &argument) {
+                Ok(text) => output.push_str(&text),
+                Err(error) => return Err(self.throw_format_error(error)),
+            }
+        }
+        Ok(output)
+    }
+
+    fn throw_format_error(&mut self, error: FormatError) -> JayError {
+        self.throw_new(error.class_name, Some(&error.message), None)
+    }
+}
+
+enum FormatPiece {
+    Text(String),
+    Spec(FormatSpec),
+}
+
+/// One `%[index$][flags][width][.precision]conversion` specifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FormatSpec {
+    source: String,
+    index: Option<usize>,
+    left_justify: bool,
+    zero_pad: bool,
+    plus: bool,
+    grouping: bool,
+    width: Option<usize>,
+    precision: Option<usize>,
+    conversion: char,
+}
+
+/// A format argument reduced to what the supported conversions read.
+enum FormatArgument {
+    Null,
+    Unboxed(Unboxed),
+    Text(String),
+    /// Any other object, by type name.
+    Other(String),
+}
+
+/// A `java.util` formatter exception to throw.
+#[derive(Debug, PartialEq, Eq)]
+struct FormatError {
+    class_name: &'static str,
+    message: String,
+}
+
+impl FormatError {
+    fn unknown_conversion(conversion: &str) -> Self {
+        Self {
+            class_name: "java/util/UnknownFormatConversionException",
+            message: format!("Conversion = '{conversion}'"),
+        }
+    }
+
+    fn missing_argument(spec: &FormatSpec) -> Self {
+        Self {
+            class_name: "java/util/MissingFormatArgumentException",
+            message: format!("Format specifier '{}'", spec.source),
+        }
+    }
+
+    fn conversion_mismatch(spec: &FormatSpec, type_name: &str) -> Self {
+        Self {
+            class_name: "java/util/IllegalFormatConversionException",
+            message: format!("{} != {type_name}", spec.conversion),
+        }
+    }
+}
+
+/// Splits a format string into literal text and specifiers.
+///
+/// Supports the `b c d o x s n %` conversions (and their upper-case forms),
+/// explicit `n$` indexes, the `- 0 + ,` flags, width, and precision. The
+/// floating-point conversions parse but only accept `null`, since Jay has no
+/// `float` or `double` values.
+fn parse_format(format: &str) -> Result<Vec<FormatPiece>, FormatError> {
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut chars = format.char_indices().peekable();
+    while let Some((start, character)) = chars.next() {
+        if character != '%' {
+            text.push(character);
+            continue;
+        }
+        if !text.is_empty() {
+            pieces.push(FormatPiece::Text(std::mem::take(&mut text)));
+        }
+
+        let number = |chars: &mut std::iter::Peekable<std::str::CharIndices<'_>>| {
+            let mut digits = String::new();
+            while let Some(&(_, digit)) = chars.peek().filter(|(_, c)| c.is_ascii_digit()) {
+                digits.push(digit);
+                chars.next();
+            }
+            digits
+        };
+        let mut spec = FormatSpec {
+            source: String::new(),
+            index: None,
+            left_justify: false,
+            zero_pad: false,
+            plus: false,
+            grouping: false,
+            width: None,
+            precision: None,
+            conversion: '%',
+        };
+        let mut lookahead = chars.clone();
+        let digits = number(&mut lookahead);
+        if !digits.is_empty() && lookahead.peek().is_some_and(|&(_, c)| c == '$') {
+            lookahead.next();
+            spec.index = digits.parse().ok().filter(|&index| index > 0);
+            chars = lookahead;
+        }
+        while let Some(&(_, flag)) = chars.peek() {
+            match flag {
+                '-' => spec.left_justify = true,
+                '0' => spec.zero_pad = true,
+                '+' => spec.plus = true,
+                ',' => spec.grouping = true,
+                _ => break,
+            }
+            chars.next();
+        }
+        let width = number(&mut chars);
+        spec.width = width.parse().ok();
+        if chars.peek().is_some_and(|&(_, c)| c == '.') {
+            chars.next();
+            let precision = number(&mut chars);
+            if precision.is_empty() {
+                return Err(FormatError::unknown_conversion("."));
+            }
+            spec.precision = precision.parse().ok();
+        }
+        let Some((end, conversion)) = chars.next() else {
+            return Err(FormatError::unknown_conversion("%"));
+        };
+        if !"bBcCdoxXsSneEfgGaA%".contains(conversion) {
+            return Err(FormatError::unknown_conversion(&conversion.to_string()));
+        }
+        spec.conversion = conversion;
+        spec.source = format[start..end + conversion.len_utf8()].to_string();
+        pieces.push(FormatPiece::Spec(spec));
+    }
+    if !text.is_empty() {
+        pieces.push(FormatPiece::Text(text));
+    }
+    Ok(pieces)
+}
+
+fn format_argument(spec: &FormatSpec, argument: &FormatArgument) -> Result<String, FormatError> {
+    let mismatch = || {
+        let type_name = match argument {
+            FormatArgument::Unboxed(unboxed) => unboxed_type_name(unboxed),
+            FormatArgument::Text(_) => "java.lang.String",
+            FormatArgument::Other(type_name) => type_name,
+            FormatArgument::Null => "null",
+        };
+        Err(FormatError::conversion_mismatch(spec, type_name))
+    };
+    let text = match (spec.conversion.to_ascii_lowercase(), argument) {
+        ('b', FormatArgument::Null) => "false".to_string(),
+        ('b', FormatArgument::Unboxed(Unboxed::Boolean(value))) => value.to_string(),
+        ('b', _) => "true".to_string(),
+        (_, FormatArgument::Null) => "null".to_string(),
+        ('s', FormatArgument::Text(text)) => text.clone(),
+        ('c', FormatArgument::Unboxed(Unboxed::Char(value))) => java_char(*value).to_string(),
+        ('c', FormatArgument::Unboxed(unboxed)) => match integral(unboxed) {
+            Some((value, 8 | 16 | 32)) => char::from_u32(value as u32)
+                .ok_or_else(|| FormatError {
+                    class_name: "java/util/IllegalFormatCodePointException",
+                    message: format!("Code point = {value:#x}"),
+                })?
+                .to_string(),
+            _ => return mismatch(),
+        },
+        ('d', FormatArgument::Unboxed(unboxed)) => match integral(unboxed) {
+            Some((value, _)) => {
+                let digits = value.unsigned_abs().to_string();
+                let digits = if spec.grouping {
+                    group_thousands(&digits)
+                } else {
+                    digits
+                };
+                let sign = if value < 0 {
+                    "-"
+                } else if spec.plus {
+                    "+"
+                } else {
+                    ""
+                };
+                return Ok(pad_number(spec, sign, digits));
+            }
+            None => return mismatch(),
+        },
+        ('o' | 'x', FormatArgument::Unboxed(unboxed)) => match integral(unboxed) {
+            Some((value, bits)) => {
+                let value = value as u64 & (u64::MAX >> (64 - bits));
+                let digits = match spec.conversion {
+                    'o' => format!("{value:o}"),
+                    'x' => format!("{value:x}"),
+                    _ => format!("{value:X}"),
+                };
+                return Ok(pad_number(spec, "", digits));
+            }
+            None => return mismatch(),
+        },
+        _ => return mismatch(),
+    };
+    let text = match spec.precision {
+        Some(precision) => text.chars().take(precision).collect(),
+        None => text,
+    };
+    let text = if spec.conversion.is_ascii_uppercase() {
+        text.to_uppercase()
+    } else {
+        text
+    };
+    Ok(pad(spec, text))
+}
+
+/// An integral wrapper's value and width in bits.
+fn integral(unboxed: &Unboxed) -> Option<(i64, u32)> {
+    match *unboxed {
+        Unboxed::Int(value) => Some((value.into(), 32)),
+        Unboxed::Long(value) => Some((value, 64)),
+        Unboxed::Short(value) => Some((value.into(), 16)),
+        Unboxed::Byte(value) => Some((value.into(), 8)),
+        Unboxed::Char(_) | Unboxed::Boolean(_) => None,
+    }
+}
+
+fn unboxed_type_name(unboxed: &Unboxed) -> &'static str {
+    match unboxed {
+        Unboxed::Int(_) => "java.lang.Integer",
+        Unboxed::Long(_) => "java.lang.Long",
+        Unboxed::Short(_) => "java.lang.Short",
+        Unboxed::Byte(_) => "java.lang.Byte",
+        Unboxed::Char(_) => "java.lang.Character",
+        Unboxed::Boolean(_) => "java.lang.Boolean",
+    }
+}
+
+fn group_thousands(digits: &str) -> String {
+    let mut grouped = String::new();
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Pads to the width with spaces, honoring `-`.
+fn pad(spec: &FormatSpec, text: String) -> String {
+    let width = spec.width.unwrap_or(0);
+    let length = text.chars().count();
+    if length >= width {
+        text
+    } else if spec.left_justify {
+        format!("{text}{}", " ".repeat(width - length))
+    } else {
+        format!("{}{text}", " ".repeat(width - length))
+    }
+}
+
+/// Pads a number, putting `0` padding between the sign and the digits.
+fn pad_number(spec: &FormatSpec, sign: &str, digits: String) -> String {
+    let digits = if spec.conversion == 'X' {
+        digits.to_uppercase()
+    } else {
+        digits
+    };
+    let width = spec.width.unwrap_or(0);
+    let length = sign.len() + digits.len();
+    if spec.zero_pad && !spec.left_justify && length < width {
+        return format!("{sign}{}{digits}", "0".repeat(width - length));
+    }
+    pad(spec, format!("{sign}{digits}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(format: &str, arguments: &[FormatArgument]) -> Result<String, FormatError> {
+        let mut output = String::new();
+        let mut next = 0;
+        for piece in parse_format(format)? {
+            match piece {
+                FormatPiece::Text(text) => output.push_str(&text),
+                FormatPiece::Spec(spec) if spec.conversion == 'n' => output.push('\n'),
+                FormatPiece::Spec(spec) if spec.conversion == '%' => {
+                    output.push_str(&pad(&spec, "%".to_string()));
+                }
+                FormatPiece::Spec(spec) => {
+                    let index = spec.index.unwrap_or_else(|| {
+                        next += 1;
+                        next
+                    });
+                    let argument = arguments
+                        .get(index - 1)
+                        .ok_or_else(|| FormatError::missing_argument(&spec))?;
+                    output.push_str(&format_argument(&spec, argument)?);
+                }
+            }
+        }
+        Ok(output)
+    }
+
+    #[test]
+    fn formats_common_conversions() {
+        let int = |value| FormatArgument::Unboxed(Unboxed::Int(value));
+        assert_eq!(
+            format(
+                "%d|%5d|%-5d|%05d|%+d|%,d%n",
+                &[int(7), int(-42), int(3), int(-42), int(9), int(1234567)]
+            )
+            .unwrap(),
+            "7|  -42|3    |-0042|+9|1,234,567\n"
+        );
+        assert_eq!(
+            format(
+                "%x %X %08x %o",
+                &[
+                    int(255),
+                    int(-1),
+                    FormatArgument::Unboxed(Unboxed::Byte(-1)),
+                    int(8)
+                ]
+            )
+            .unwrap(),
+            "ff FFFFFFFF 000000ff 10"
+        );
+        assert_eq!(
+            format(
+                "%s|%S|%.2s|%-4s|%b|%c|%2$s %%",
+                &[
+                    FormatArgument::Text("jay".to_string()),
+                    FormatArgument::Text("vm".to_string()),
+                    FormatArgument::Text("abc".to_string()),
+                    FormatArgument::Null,
+                    FormatArgument::Null,
+                    FormatArgument::Unboxed(Unboxed::Char(u16::from(b'z'))),
+                ]
+            )
+            .unwrap(),
+            "jay|VM|ab|null|false|z|vm %"
+        );
+    }
+
+    #[test]
+    fn reports_formatter_exceptions() {
+        let error = |format_string, arguments: &[FormatArgument]| {
+            let error = format(format_string, arguments).unwrap_err();
+            (error.class_name, error.message)
+        };
+        assert_eq!(
+            error("%q", &[]),
+            (
+                "java/util/UnknownFormatConversionException",
+                "Conversion = 'q'".to_string()
+            )
+        );
+        assert_eq!(
+            error("%s %5d", &[FormatArgument::Text(String::new())]),
+            (
+                "java/util/MissingFormatArgumentException",
+                "Format specifier '%5d'".to_string()
+            )
+        );
+        assert_eq!(
+            error("%d", &[FormatArgument::Text("x".to_string())]),
+            (
+                "java/util/IllegalFormatConversionException",
+                "d != java.lang.String".to_string()
+            )
+        );
+        assert_eq!(
+            error("%f", &[FormatArgument::Unboxed(Unboxed::Int(1))]),
+            (
+                "java/util/IllegalFormatConversionException",
+                "f != java.lang.Integer".to_string()
+            )
+        );
+    }
+}
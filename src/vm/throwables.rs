@@ -0,0 +1,380 @@
+//! `Throwable` construction, stack traces, and `printStackTrace` output.
+//!
+//! The JDK fills stack traces through VM internals, so Jay answers the
+//! `Throwable` constructors and stack-trace methods itself, recording frames
+//! from the interpreter's call stack as `StackTraceElement` objects.
+
+use std::io::Write;
+
+use super::descriptors::MethodDescriptor;
+use super::exceptions::{cause_field, detail_message_field};
+use super::frame::Frame;
+use super::heap::{FieldKey, ObjectRef};
+use super::interpreter::Interpreter;
+use super::properties::line_separator;
+use super::value::Value;
+use crate::{JavaStackFrame, JayError, JayResult};
+
+/// `Throwable` constructors Jay answers, by descriptor.
+const THROWABLE_CONSTRUCTORS: &[&str] = &[
+    "()V",
+    "(Ljava/lang/String;)V",
+    "(Ljava/lang/String;Ljava/lang/Throwable;)V",
+    "(Ljava/lang/Throwable;)V",
+    "(Ljava/lang/String;Ljava/lang/Throwable;ZZ)V",
+];
+
+/// `Throwable` methods Jay answers unless a subclass overrides them.
+const THROWABLE_METHODS: &[(&str, &str)] = &[
+    ("fillInStackTrace", "()Ljava/lang/Throwable;"),
+    ("getStackTrace", "()[Ljava/lang/StackTraceElement;"),
+    ("printStackTrace", "()V"),
+    ("printStackTrace", "(Ljava/io/PrintStream;)V"),
+    ("toString", "()Ljava/lang/String;"),
+];
+
+pub(super) fn is_throwable_constructor(class_name: &str, descriptor: &str) -> bool {
+    class_name == "java/lang/Throwable" && THROWABLE_CONSTRUCTORS.contains(&descriptor)
+}
+
+pub(super) fn is_throwable_method(name: &str, descriptor: &str) -> bool {
+    THROWABLE_METHODS.contains(&(name, descriptor))
+}
+
+fn stack_trace_field() -> FieldKey {
+    FieldKey::new(
+        "java/lang/Throwable",
+        "stackTrace",
+        "[Ljava/lang/StackTraceElement;",
+    )
+}
+
+fn element_field(name: &str, descriptor: &str) -> FieldKey {
+    FieldKey::new("java/lang/StackTraceElement", name, descriptor)
+}
+
+/// Compares frames the way `StackTraceElement.equals` does, on the printed
+/// parts only, so frames that differ just in pc or descriptor are shared.
+fn same_element(frame: &JavaStackFrame, other: &JavaStackFrame) -> bool {
+    frame.class_name == other.class_name
+        && frame.method_name == other.method_name
+        && frame.module_name == other.module_name
+        && frame.file_name == other.file_name
+        && frame.line_number == other.line_number
+}
+
+impl<'a, W: Write> Interpreter<'a, W> {
+    pub(super) fn invoke_throwable_constructor(
+        &mut self,
+        caller: &mut Frame,
+        target_descriptor: &str,
+        descriptor: &MethodDescriptor,
+        target_name: &str,
+    ) -> JayResult<()> {
+        let arguments = self.pop_constructor_arguments(
+            caller,
+            descriptor,
+            &format!("invokespecial constructor target {target_name}"),
+        )?;
+        let exception = caller.pop_object_ref()?;
+        // Like the JDK, a cause of `this` means "not yet set", so `initCause` still works.
+        let (message, cause) = match (target_descriptor, arguments.as_slice()) {
+            ("(Ljava/lang/Throwable;)V", [Value::Reference(cause)]) => {
+                let mut roots = caller.roots().cloned().collect::<Vec<_>>();
+                roots.extend([Value::Reference(exception), Value::Reference(*cause)]);
+                let text = self.object_text(&roots, Value::Reference(*cause))?;
+                (
+                    Value::Reference(self.heap.allocate_string(text)),
+                    Value::Reference(*cause),
+                )
+            }
+            ("(Ljava/lang/Throwable;)V", [cause]) => (Value::Null, cause.clone()),
+            (_, [message, cause, ..]) => (message.clone(), cause.clone()),
+            (_, [message]) => (message.clone(), Value::Reference(exception)),
+            _ => (Value::Null, Value::Reference(exception)),
+        };
+        self.heap
+            .put_instance_field(exception, detail_message_field(), message)?;
+        self.heap
+            .put_instance_field(exception, cause_field(), cause)?;
+        if let [.., Value::Int(0)] = arguments.as_slice() {
+            // `writableStackTrace == false`: the trace stays empty for good.
+            return self
+                .heap
+                .put_instance_field(exception, stack_trace_field(), Value::Null);
+        }
+        self.fill_in_stack_trace(exception)
+    }
+
+    pub(super) fn invoke_throwable_method(
+        &mut self,
+        caller: &mut Frame,
+        exception: ObjectRef,
+        method_name: &str,
+        arguments: &[Value],
+    ) -> JayResult<()> {
+        let mut roots = caller.roots().cloned().collect::<Vec<_>>();
+        roots.push(Value::Reference(exception));
+        roots.extend(arguments.iter().cloned());
+        let result = match (method_name, arguments) {
+            ("fillInStackTrace", _) => {
+                if self
+                    .heap
+                    .get_instance_field(exception, &stack_trace_field())?
+                    != Some(Value::Null)
+                {
+                    self.fill_in_stack_trace(exception)?;
+                }
+                Some(Value::Reference(exception))
+            }
+            ("getStackTrace", _) => {
+                let elements = self.stack_trace_elements(exception)?;
+                let copy = self.heap.allocate_object_array(elements.len());
+                for (index, element) in elements.into_iter().enumerate() {
+                    self.heap.store_array_reference(copy, index, element)?;
+                }
+                Some(Value::Reference(copy))
+            }
+            ("printStackTrace", []) => {
+                let text = self.stack_trace_text(&roots, exception)?;
+                let stream = self.system_stream("err")?;
+                self.write_to_print_stream(stream, &text)?;
+                None
+            }
+            ("printStackTrace", [stream]) => {
+                let text = self.stack_trace_text(&roots, exception)?;
+                self.write_to_print_stream(stream.clone(), &text)?;
+                None
+            }
+            ("toString", _) => {
+                let text = self.throwable_text(&roots, exception)?;
+                Some(Value::Reference(self.heap.allocate_string(text)))
+            }
+            _ => {
+                return Err(JayError::unsupported(format!(
+                    "Throwable method {method_name}"
+                )));
+            }
+        };
+        caller.stack.extend(result);
+        self.collect_if_needed(caller)?;
+        Ok(())
+    }
+
+    /// Records the active frames as `exception`'s stack trace, leaving out the
+    /// frames still constructing it, as `Throwable.fillInStackTrace` does.
+    pub(super) fn fill_in_stack_trace(&mut self, exception: ObjectRef) -> JayResult<()> {
+        let class_name = self.heap.instance_class_name(exception)?.to_string();
+        let mut frames = Vec::new();
+        for active in self.call_stack.clone().into_iter().rev() {
+            let constructing = frames.is_empty()
+                && (active.method_name == "fillInStackTrace"
+                    || active.method_name == "<init>"
+                        && self.is_assignable_reference(
+                            &class_name,
+                            &active.class_name.replace('.', "/"),
+                        )?);
+            if !constructing {
+                frames.push(self.locate_frame(active));
+            }
+        }
+        let trace = self.heap.allocate_object_array(frames.len());
+        for (index, frame) in frames.iter().enumerate() {
+            let element = self.stack_trace_element(frame)?;
+            self.heap
+                .store_array_reference(trace, index, Value::Reference(element))?;
+        }
+        self.heap
+            .put_instance_field(exception, stack_trace_field(), Value::Reference(trace))
+    }
+
+    /// Adds the source file, line, and module that `StackTraceElement` reports.
+    fn locate_frame(&self, mut frame: JavaStackFrame) -> JavaStackFrame {
+        if let Ok(class_file) = self.load_class_file(&frame.class_name.replace('.', "/")) {
+            frame.file_name = class_file.source_file().map(str::to_string);
+            frame.line_number = class_file
+                .find_method(&frame.method_name, &frame.descriptor)
+                .and_then(|method| method.code.as_ref())
+                .and_then(|code| code.line_number(frame.pc))
+                .map(u32::from);
+        }
+        frame.module_name = self.classes.class_module(&frame.class_name).ok().flatten();
+        frame
+    }
+
+    fn stack_trace_element(&mut self, frame: &JavaStackFrame) -> JayResult<ObjectRef> {
+        let element = self.heap.allocate_instance("java/lang/StackTraceElement");
+        let strings = [
+            ("declaringClass", Some(frame.class_name.as_str())),
+            ("methodName", Some(frame.method_name.as_str())),
+            ("fileName", frame.file_name.as_deref()),
+            ("moduleName", frame.module_name.as_deref()),
+            ("__jay_descriptor", Some(frame.descriptor.as_str())),
+        ];
+        for (name, value) in strings {
+            let value = match value {
+                Some(value) => Value::Reference(self.heap.allocate_string(value)),
+                None => Value::Null,
+            };
+            self.heap.put_instance_field(
+                element,
+                element_field(name, "Ljava/lang/String;"),
+                value,
+            )?;
+        }
+        let line_number = frame.line_number.map_or(-1, |line| line as i32);
+        let pc = i32::try_from(frame.pc).unwrap_or(i32::MAX);
+        for (name, value) in [("lineNumber", line_number), ("__jay_pc", pc)] {
+            self.heap
+                .put_instance_field(element, element_field(name, "I"), Value::Int(value))?;
+        }
+        Ok(element)
+    }
+
+    /// The `StackTraceElement` objects recorded for `exception`.
+    fn stack_trace_elements(&self, exception: ObjectRef) -> JayResult<Vec<Value>> {
+        match self
+            .heap
+            .get_instance_field(exception, &stack_trace_field())?
+        {
+            Some(Value::Reference(trace)) => (0..self.heap.array_length(trace)?)
+                .map(|index| self.heap.load_array_reference(trace, index))
+                .collect(),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Reads `exception`'s stack trace back as host frames.
+    pub(super) fn stack_trace(&self, exception: ObjectRef) -> JayResult<Vec<JavaStackFrame>> {
+        self.stack_trace_elements(exception)?
+            .into_iter()
+            .filter_map(|element| match element {
+                Value::Reference(element) => Some(self.stack_frame_of(element)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn stack_frame_of(&self, element: ObjectRef) -> JayResult<JavaStackFrame> {
+        let string = |name: &str| -> JayResult<Option<String>> {
+            match self
+                .heap
+                .get_instance_field(element, &element_field(name, "Ljava/lang/String;"))?
+            {
+                Some(Value::Reference(text)) => Ok(Some(self.heap.string(text)?.to_string())),
+                _ => Ok(None),
+            }
+        };
+        let int = |name: &str| -> JayResult<i32> {
+            match self
+                .heap
+                .get_instance_field(element, &element_field(name, "I"))?
+            {
+                Some(Value::Int(value)) => Ok(value),
+                _ => Ok(-1),
+            }
+        };
+        let mut frame = JavaStackFrame::new(
+            string("declaringClass")?.unwrap_or_default(),
+            string("methodName")?.unwrap_or_default(),
+            string("__jay_descriptor")?.unwrap_or_default(),
+            usize::try_from(int("__jay_pc")?).unwrap_or_default(),
+        );
+        frame.module_name = string("moduleName")?;
+        frame.file_name = string("fileName")?;
+        frame.line_number = u32::try_from(int("lineNumber")?).ok();
+        Ok(frame)
+    }
+
+    /// `StackTraceElement.toString()`.
+    pub(super) fn stack_trace_element_text(&self, element: ObjectRef) -> JayResult<String> {
+        Ok(self.stack_frame_of(element)?.to_string())
+    }
+
+    /// `Throwable.toString()`: the class name and `getLocalizedMessage()`.
+    pub(super) fn throwable_text(
+        &mut self,
+        roots: &[Value],
+        exception: ObjectRef,
+    ) -> JayResult<String> {
+        let class_name = self.heap.type_name(exception)?;
+        let message = self.call_instance_method(
+            roots,
+            exception,
+            "getLocalizedMessage",
+            "()Ljava/lang/String;",
+        )?;
+        match message {
+            Some(Value::Reference(message)) => {
+                Ok(format!("{class_name}: {}", self.heap.string(message)?))
+            }
+            _ => Ok(class_name),
+        }
+    }
+
+    /// What `printStackTrace` prints: `toString()`, the frames, and the
+    /// `Caused by:` chain with frames shared with the enclosing trace elided.
+    pub(super) fn stack_trace_text(
+        &mut self,
+        roots: &[Value],
+        exception: ObjectRef,
+    ) -> JayResult<String> {
+        let newline = line_separator();
+        let mut text = String::new();
+        let mut seen = Vec::new();
+        let mut enclosing: Vec<JavaStackFrame> = Vec::new();
+        let mut caption = "";
+        let mut current = Some(exception);
+        while let Some(exception) = current {
+            let description = self.throwable_text(roots, exception)?;
+            if seen.contains(&exception) {
+                text.push_str(&format!(
+                    "{caption}[CIRCULAR REFERENCE: {description}]{newline}"
+                ));
+                break;
+            }
+            seen.push(exception);
+            text.push_str(&format!("{caption}{description}{newline}"));
+            let trace = self.stack_trace(exception)?;
+            let in_common = trace
+                .iter()
+                .rev()
+                .zip(enclosing.iter().rev())
+                .take_while(|(frame, enclosing)| same_element(frame, enclosing))
+                .count();
+            for frame in &trace[..trace.len() - in_common] {
+                text.push_str(&format!("\tat {frame}{newline}"));
+            }
+            if in_common != 0 {
+                text.push_str(&format!("\t... {in_common} more{newline}"));
+            }
+            enclosing = trace;
+            caption = "Caused by: ";
+            current = match self.heap.get_instance_field(exception, &cause_field())? {
+                Some(Value::Reference(cause)) if cause != exception => Some(cause),
+                _ => None,
+            };
+        }
+        Ok(text)
+    }
+
+    /// Prints an exception that escaped a thread the way the JDK's default
+    /// uncaught exception handler does, and returns it as an error.
+    pub(super) fn report_uncaught(
+        &mut self,
+        exception: ObjectRef,
+        thread_name: &str,
+    ) -> JayResult<JayError> {
+        if self.print_uncaught_exceptions {
+            let roots = [Value::Reference(exception)];
+            let text = self.stack_trace_text(&roots, exception)?;
+            let stream = self.system_stream("err")?;
+            self.write_to_print_stream(
+                stream,
+                &format!("Exception in thread \"{thread_name}\" {text}"),
+            )?;
+        }
+        let stack_trace = self.stack_trace(exception)?;
+        self.exception_error(exception, stack_trace)
+    }
+}
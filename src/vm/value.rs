@@ -13,7 +13,6 @@ pub(super) enum Value {
     Int(i32),
     Long(i64),
     Reference(ObjectRef),
-    PrintStream,
 }
 
 impl Value {
@@ -22,7 +21,7 @@ impl Value {
             Value::Int(_) => Ok(Some(ValueType::Int)),
             Value::Long(_) => Ok(Some(ValueType::Long)),
             Value::Reference(reference) => heap.value_type(*reference),
-            Value::Uninitialized | Value::Null | Value::PrintStream => Ok(None),
+            Value::Uninitialized | Value::Null => Ok(None),
         }
     }
 
@@ -33,18 +32,13 @@ impl Value {
             Value::Int(_) => Ok("int".to_string()),
             Value::Long(_) => Ok("long".to_string()),
             Value::Reference(reference) => heap.type_name(*reference),
-            Value::PrintStream => Ok("PrintStream".to_string()),
         }
     }
 
     pub(super) fn object_ref(&self) -> Option<ObjectRef> {
         match self {
             Value::Reference(reference) => Some(*reference),
-            Value::Uninitialized
-            | Value::Null
-            | Value::Int(_)
-            | Value::Long(_)
-            | Value::PrintStream => None,
+            Value::Uninitialized | Value::Null | Value::Int(_) | Value::Long(_) => None,
         }
     }
 }
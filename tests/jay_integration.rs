@@ -21,3 +21,30 @@ mod class_initialization;
 
 #[path = "jay_integration/errors.rs"]
 mod errors;
+
+#[path = "jay_integration/access_control.rs"]
+mod access_control;
+
+#[path = "jay_integration/class_builder.rs"]
+mod class_builder;
+
+#[path = "jay_integration/assembler.rs"]
+mod assembler;
+
+#[path = "jay_integration/javap.rs"]
+mod javap;
+
+#[path = "jay_integration/check.rs"]
+mod check;
+
+#[path = "jay_integration/jars.rs"]
+mod jars;
+
+#[path = "jay_integration/console.rs"]
+mod console;
+
+#[path = "jay_integration/shutdown.rs"]
+mod shutdown;
+
+#[path = "jay_integration/embedding.rs"]
+mod embedding;
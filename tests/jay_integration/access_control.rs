@@ -0,0 +1,216 @@
+use crate::support::{compile_java, compile_java_sources, jay, temp_dir};
+
+#[test]
+fn nestmates_call_each_others_private_methods() {
+    let root = temp_dir("nestmate-private-methods");
+    compile_java(
+        &root,
+        "Outer.java",
+        r#"
+public class Outer {
+    private int secret() {
+        return 42;
+    }
+
+    private static int twice(int value) {
+        return value * 2;
+    }
+
+    class Inner {
+        private Inner() {}
+
+        int reveal() {
+            return secret();
+        }
+
+        private int doubled() {
+            return twice(reveal());
+        }
+    }
+
+    public static void main(String[] args) {
+        Outer outer = new Outer();
+        Inner inner = outer.new Inner();
+        System.out.println(inner.reveal());
+        System.out.println(inner.doubled());
+    }
+}
+"#,
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "Outer"]);
+
+    assert!(
+        output.status.success(),
+        "jay failed\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "42\n84\n");
+}
+
+#[test]
+fn private_method_access_outside_the_nest_throws_illegal_access_error() {
+    let root = temp_dir("non-nestmate-private-method");
+    compile_java_sources(
+        &root,
+        &[
+            (
+                "Vault.java",
+                r#"
+class Vault {
+    int secret() {
+        return 42;
+    }
+}
+"#,
+            ),
+            (
+                "VaultMain.java",
+                r#"
+public class VaultMain {
+    public static void main(String[] args) {
+        try {
+            System.out.println(new Vault().secret());
+        } catch (IllegalAccessError error) {
+            System.out.println(error.getMessage());
+        }
+    }
+}
+"#,
+            ),
+        ],
+    );
+    compile_java(
+        &root,
+        "Vault.java",
+        r#"
+class Vault {
+    private int secret() {
+        return 42;
+    }
+}
+"#,
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "VaultMain"]);
+
+    assert!(
+        output.status.success(),
+        "jay failed\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "class VaultMain tried to access private method 'Vault.secret()I'\n"
+    );
+}
+
+#[test]
+fn permitted_subclasses_of_sealed_classes_load() {
+    let root = temp_dir("sealed-permitted-subclass");
+    compile_java_sources(
+        &root,
+        &[
+            ("Shape.java", "sealed class Shape permits Circle {}\n"),
+            ("Circle.java", "final class Circle extends Shape {}\n"),
+            (
+                "SealedMain.java",
+                r#"
+public class SealedMain {
+    public static void main(String[] args) {
+        Shape shape = new Circle();
+        System.out.println(shape != null);
+    }
+}
+"#,
+            ),
+        ],
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "SealedMain"]);
+
+    assert!(
+        output.status.success(),
+        "jay failed\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "true\n");
+}
+
+#[test]
+fn rejects_unpermitted_subclass_of_sealed_class_at_load_time() {
+    let root = temp_dir("sealed-unpermitted-subclass");
+    compile_java_sources(
+        &root,
+        &[
+            (
+                "Shape.java",
+                "sealed class Shape permits Circle, Square {}\n",
+            ),
+            ("Circle.java", "final class Circle extends Shape {}\n"),
+            ("Square.java", "final class Square extends Shape {}\n"),
+            (
+                "SealedMain.java",
+                r#"
+public class SealedMain {
+    public static void main(String[] args) {
+        Shape shape = new Square();
+        System.out.println(shape != null);
+    }
+}
+"#,
+            ),
+            (
+                "SealedCatch.java",
+                r#"
+public class SealedCatch {
+    public static void main(String[] args) {
+        try {
+            new Square();
+        } catch (IncompatibleClassChangeError e) {
+            System.out.println("caught " + e.getMessage());
+        }
+    }
+}
+"#,
+            ),
+        ],
+    );
+    // Recompiling the sealed class without Square leaves a stale subclass.
+    compile_java_sources(
+        &root,
+        &[
+            ("Shape.java", "sealed class Shape permits Circle {}\n"),
+            ("Circle.java", "final class Circle extends Shape {}\n"),
+        ],
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "SealedMain"]);
+
+    assert!(
+        !output.status.success(),
+        "jay succeeded unexpectedly\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "java.lang.IncompatibleClassChangeError: class Square cannot inherit from sealed class Shape"
+        ),
+        "stderr missing sealed class error:\n{stderr}"
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "SealedCatch"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "caught class Square cannot inherit from sealed class Shape\n"
+    );
+}
@@ -0,0 +1,170 @@
+use crate::support::{compile_java, jay, temp_dir};
+
+const COUNTER_SOURCE: &str = r#"
+; Counts down with a loop and a string concatenation call site.
+.class public super demo/Counter
+.super java/lang/Object
+
+.field private static final START I = 3
+
+.method public static main([Ljava/lang/String;)V
+    getstatic demo/Counter/START I
+    istore_1
+Loop:
+    iload_1
+    ifle Done
+    getstatic java/lang/System/out Ljava/io/PrintStream;
+    iload_1
+    invokedynamic makeConcatWithConstants(I)Ljava/lang/String; invokestatic java/lang/invoke/StringConcatFactory/makeConcatWithConstants(Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;Ljava/lang/String;[Ljava/lang/Object;)Ljava/lang/invoke/CallSite; "tick \u0001"
+    invokevirtual java/io/PrintStream/println(Ljava/lang/String;)V
+    iinc 1 -1
+    goto Loop
+Done:
+    return
+.end method
+"#;
+
+#[test]
+fn assembles_text_source_into_a_runnable_class() {
+    let root = temp_dir("asm-counter");
+    let source = root.join("Counter.j");
+    std::fs::write(&source, COUNTER_SOURCE).unwrap();
+    let classes = root.join("classes");
+
+    let output = jay(&[
+        "asm",
+        "-d",
+        classes.to_str().unwrap(),
+        source.to_str().unwrap(),
+    ]);
+    assert!(
+        output.status.success(),
+        "jay asm failed\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(classes.join("demo/Counter.class").is_file());
+
+    let output = jay(&["-cp", classes.to_str().unwrap(), "demo.Counter"]);
+
+    assert!(
+        output.status.success(),
+        "jay failed\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "tick 3\ntick 2\ntick 1\n"
+    );
+}
+
+#[test]
+fn disassembled_javac_output_reassembles_and_runs() {
+    let root = temp_dir("disasm-roundtrip");
+    compile_java(
+        &root,
+        "Roundtrip.java",
+        r#"
+public class Roundtrip {
+    static final long BIG = 1L << 40;
+    static final double HALF = 0.5;
+
+    static int zero() {
+        return 0;
+    }
+
+    static String name(int value) {
+        if (value == 1) {
+            return "one";
+        }
+        return value == 3 ? "three" : "many";
+    }
+
+    public static void main(String[] args) {
+        String out = "";
+        for (int i = 0; i < 4; i++) {
+            out = out + name(i * 2 + 1) + ' ';
+        }
+        try {
+            out = out + (1 / zero());
+        } catch (ArithmeticException e) {
+            out = out + "caught";
+        }
+        System.out.println(out + " " + (BIG + (long) (HALF * 4)) + " caf\u00e9");
+    }
+}
+"#,
+    );
+
+    let output = jay(&["disasm", "-cp", root.to_str().unwrap(), "Roundtrip"]);
+    assert!(
+        output.status.success(),
+        "jay disasm failed\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        text.contains(".catch java/lang/ArithmeticException from "),
+        "{text}"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("jay: warning: StackMapTable omitted; ")
+            && stderr.contains("jay: warning: omitted InnerClasses, LineNumberTable, SourceFile"),
+        "{stderr}"
+    );
+
+    let source = root.join("Roundtrip.j");
+    std::fs::write(&source, &text).unwrap();
+    let classes = root.join("reassembled");
+    let output = jay(&[
+        "asm",
+        "-d",
+        classes.to_str().unwrap(),
+        source.to_str().unwrap(),
+    ]);
+    assert!(
+        output.status.success(),
+        "jay asm failed\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = jay(&["-cp", classes.to_str().unwrap(), "Roundtrip"]);
+
+    assert!(
+        output.status.success(),
+        "jay failed\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "one three many many caught 1099511627778 caf\u{e9}\n"
+    );
+}
+
+#[test]
+fn reports_assembler_errors_with_file_and_line() {
+    let root = temp_dir("asm-error");
+    let source = root.join("Broken.j");
+    std::fs::write(
+        &source,
+        ".class Broken\n.method static f()V\n    frobnicate\n.end method\n",
+    )
+    .unwrap();
+
+    let output = jay(&[
+        "asm",
+        "-d",
+        root.to_str().unwrap(),
+        source.to_str().unwrap(),
+    ]);
+
+    assert!(!output.status.success(), "jay asm unexpectedly succeeded");
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("Broken.j: line 3: unknown instruction frobnicate"),
+        "stderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
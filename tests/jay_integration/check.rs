@@ -0,0 +1,95 @@
+use crate::support::{compile_java, jay, temp_dir};
+
+#[test]
+fn check_lists_what_the_interpreter_would_reject() {
+    let root = temp_dir("check-rejected");
+    compile_java(
+        &root,
+        "Main.java",
+        r#"
+public class Main {
+    public static void main(String[] args) {
+        System.out.println(half(args.length));
+        Runnable task = () -> System.out.println("never");
+        task.run();
+    }
+
+    static int half(int value) {
+        double scaled = value * 0.5;
+        return (int) scaled;
+    }
+}
+"#,
+    );
+
+    let output = jay(&["check", "-cp", root.to_str().unwrap(), "Main"]);
+
+    assert!(!output.status.success(), "jay check unexpectedly succeeded");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("Main.half(I)I pc 1: opcode: unsupported bytecode 0x87 (i2d)\n"),
+        "stdout:\n{stdout}"
+    );
+    assert!(
+        stdout.contains("Main.half(I)I pc 5: opcode: unsupported bytecode 0x6b (dmul)\n"),
+        "stdout:\n{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "Main.main([Ljava/lang/String;)V pc 11: bootstrap: unsupported invokedynamic \
+             bootstrap java/lang/invoke/LambdaMetafactory.metafactory"
+        ),
+        "stdout:\n{stdout}"
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("incompatibilities found in"),
+        "stderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn check_accepts_programs_within_the_supported_subset() {
+    let root = temp_dir("check-supported");
+    compile_java(
+        &root,
+        "Main.java",
+        r#"
+public class Main {
+    interface Shape {
+        int area();
+    }
+
+    static class Square implements Shape {
+        private final int side;
+
+        Square(int side) {
+            this.side = side;
+        }
+
+        public int area() {
+            return side * side;
+        }
+    }
+
+    public static void main(String[] args) {
+        Shape shape = new Square(3);
+        System.out.println("area " + shape.area());
+    }
+}
+"#,
+    );
+
+    let output = jay(&["check", "-cp", root.to_str().unwrap(), "Main"]);
+
+    assert!(
+        output.status.success(),
+        "jay check failed\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "no incompatibilities found in 3 reachable methods\n"
+    );
+}
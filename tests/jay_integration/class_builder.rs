@@ -0,0 +1,96 @@
+use jay::classfile::builder::{ACC_PUBLIC, ACC_STATIC, ClassBuilder, Constant, Handle};
+use jay::classfile::opcode::*;
+
+use crate::support::{jay, temp_dir};
+
+const MAIN_DESCRIPTOR: &str = "([Ljava/lang/String;)V";
+
+#[test]
+fn runs_a_class_built_without_javac() {
+    let root = temp_dir("class-builder-loop");
+    let mut class = ClassBuilder::new("Built");
+    let mut main = class.method(ACC_PUBLIC | ACC_STATIC, "main", MAIN_DESCRIPTOR);
+    let (head, done) = (main.new_label(), main.new_label());
+    main.int(0)
+        .local(ISTORE, 1)
+        .int(10)
+        .local(ISTORE, 2)
+        .bind(head);
+    main.local(ILOAD, 2).jump(IFLE, done);
+    main.local(ILOAD, 1)
+        .local(ILOAD, 2)
+        .op(IADD)
+        .local(ISTORE, 1);
+    main.iinc(2, -1).jump(GOTO, head);
+    main.bind(done)
+        .field(
+            GETSTATIC,
+            "java/lang/System",
+            "out",
+            "Ljava/io/PrintStream;",
+        )
+        .local(ILOAD, 1)
+        .invokedynamic(
+            &Handle {
+                kind: 6,
+                owner: "java/lang/invoke/StringConcatFactory".to_string(),
+                name: "makeConcatWithConstants".to_string(),
+                descriptor: "(Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;\
+                    Ljava/lang/invoke/MethodType;Ljava/lang/String;[Ljava/lang/Object;)\
+                    Ljava/lang/invoke/CallSite;"
+                    .to_string(),
+                interface: false,
+            },
+            &[Constant::String("sum=\u{1}".to_string())],
+            "makeConcatWithConstants",
+            "(I)Ljava/lang/String;",
+        )
+        .invoke(
+            INVOKEVIRTUAL,
+            "java/io/PrintStream",
+            "println",
+            "(Ljava/lang/String;)V",
+        )
+        .op(RETURN);
+    main.finish();
+    let bytes = class.build().unwrap().to_bytes().unwrap();
+    std::fs::write(root.join("Built.class"), bytes).unwrap();
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "Built"]);
+
+    assert!(
+        output.status.success(),
+        "jay failed\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "sum=55\n");
+}
+
+#[test]
+fn rejects_invokeinterface_with_nonzero_padding() {
+    let root = temp_dir("class-builder-invokeinterface-padding");
+    let mut class = ClassBuilder::new("Padded");
+    let mut main = class.method(ACC_PUBLIC | ACC_STATIC, "main", MAIN_DESCRIPTOR);
+    let [high, low] = main
+        .constant_pool()
+        .intern_interface_method_ref("java/lang/Runnable", "run", "()V")
+        .unwrap()
+        .to_be_bytes();
+    main.op(ACONST_NULL)
+        .raw(&[INVOKEINTERFACE, high, low, 1, 7])
+        .op(RETURN);
+    main.finish();
+    let bytes = class.build().unwrap().to_bytes().unwrap();
+    std::fs::write(root.join("Padded.class"), bytes).unwrap();
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "Padded"]);
+
+    assert!(!output.status.success(), "jay unexpectedly succeeded");
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("invokeinterface at pc 1 has nonzero padding"),
+        "stderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
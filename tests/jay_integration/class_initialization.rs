@@ -199,3 +199,340 @@ public class ClassLiteralMain {
     assert_eq!(String::from_utf8_lossy(&output.stdout), "true\nfalse\n");
     assert_eq!(String::from_utf8_lossy(&output.stderr), "");
 }
+
+#[test]
+fn new_and_invokestatic_trigger_class_initialization() {
+    let root = temp_dir("new-and-invokestatic-trigger-initialization");
+    compile_java(
+        &root,
+        "InitTriggerMain.java",
+        r#"
+class Counter {
+    static {
+        System.out.println("Counter initialized");
+    }
+
+    static void touch() {
+        System.out.println("touch");
+    }
+}
+
+class Widget {
+    static {
+        System.out.println("Widget initialized");
+    }
+}
+
+public class InitTriggerMain {
+    static {
+        System.out.println("main initialized");
+    }
+
+    public static void main(String[] args) {
+        System.out.println("start");
+        Counter.touch();
+        Counter.touch();
+        new Widget();
+        new Widget();
+        System.out.println("end");
+    }
+}
+"#,
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "InitTriggerMain"]);
+
+    assert!(
+        output.status.success(),
+        "jay failed
+stdout:
+{}
+stderr:
+{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "main initialized\nstart\nCounter initialized\ntouch\ntouch\nWidget initialized\nend\n"
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+}
+
+#[test]
+fn failed_initializer_throws_exception_in_initializer_error_then_no_class_def_found_error() {
+    let root = temp_dir("failed-initializer-erroneous-state");
+    compile_java(
+        &root,
+        "ErroneousInitMain.java",
+        r#"
+class Broken {
+    static int value = 1 / zero();
+
+    static int zero() {
+        return 0;
+    }
+}
+
+public class ErroneousInitMain {
+    public static void main(String[] args) {
+        try {
+            System.out.println(Broken.value);
+        } catch (ExceptionInInitializerError error) {
+            System.out.println("first access");
+            System.out.println(error.getCause().getMessage());
+        }
+
+        try {
+            Broken.zero();
+        } catch (NoClassDefFoundError error) {
+            System.out.println("second access");
+            System.out.println(error.getMessage());
+        }
+    }
+}
+"#,
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "ErroneousInitMain"]);
+
+    assert!(
+        output.status.success(),
+        "jay failed
+stdout:
+{}
+stderr:
+{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "first access\n/ by zero\nsecond access\nCould not initialize class Broken\n"
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+}
+
+#[test]
+fn failed_superclass_initializer_leaves_subclass_erroneous() {
+    let root = temp_dir("failed-superclass-initializer");
+    compile_java_sources(
+        &root,
+        &[
+            (
+                "Base.java",
+                r#"
+class Base {
+    static int value = 10 / zero();
+
+    static int zero() {
+        return 0;
+    }
+}
+"#,
+            ),
+            (
+                "Derived.java",
+                r#"
+class Derived extends Base {
+    static int other = 1;
+
+    static {
+        System.out.println("Derived initialized");
+    }
+}
+"#,
+            ),
+            (
+                "SuperFailureMain.java",
+                r#"
+public class SuperFailureMain {
+    public static void main(String[] args) {
+        try {
+            System.out.println(Derived.other);
+        } catch (ExceptionInInitializerError error) {
+            System.out.println("derived failed");
+        }
+
+        try {
+            System.out.println(Derived.other);
+        } catch (NoClassDefFoundError error) {
+            System.out.println(error.getMessage());
+        }
+    }
+}
+"#,
+            ),
+        ],
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "SuperFailureMain"]);
+
+    assert!(
+        output.status.success(),
+        "jay failed
+stdout:
+{}
+stderr:
+{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "derived failed\nCould not initialize class Derived\n"
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+}
+
+#[test]
+fn uncaught_initializer_failure_reports_exception_in_initializer_error() {
+    let root = temp_dir("uncaught-initializer-failure");
+    compile_java(
+        &root,
+        "UncaughtInitMain.java",
+        r#"
+class Exploding {
+    static int value = 1 / zero();
+
+    static int zero() {
+        return 0;
+    }
+}
+
+public class UncaughtInitMain {
+    public static void main(String[] args) {
+        System.out.println(Exploding.value);
+    }
+}
+"#,
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "UncaughtInitMain"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("java.lang.ExceptionInInitializerError"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("java.lang.ArithmeticException: / by zero"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn getstatic_reads_constant_value_attributes_without_initializer_code() {
+    let root = temp_dir("getstatic-constant-value");
+    compile_java_sources(
+        &root,
+        &[
+            (
+                "Constants.java",
+                r#"
+class Constants {
+    static int ANSWER;
+    static long BIG;
+    static boolean FLAG;
+    static String NAME;
+}
+"#,
+            ),
+            (
+                "ConstantValueMain.java",
+                r#"
+public class ConstantValueMain {
+    public static void main(String[] args) {
+        System.out.println(Constants.ANSWER);
+        System.out.println(Constants.BIG);
+        System.out.println(Constants.FLAG);
+        System.out.println(Constants.NAME);
+    }
+}
+"#,
+            ),
+        ],
+    );
+    // Recompiling only the holder keeps `getstatic` in the caller, the way
+    // class files from other compilers read `static final` constants.
+    compile_java(
+        &root,
+        "Constants.java",
+        r#"
+class Constants {
+    static final int ANSWER = 42;
+    static final long BIG = 1234567890123L;
+    static final boolean FLAG = true;
+    static final String NAME = "jay";
+}
+"#,
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "ConstantValueMain"]);
+
+    assert!(
+        output.status.success(),
+        "jay failed
+stdout:
+{}
+stderr:
+{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "42\n1234567890123\ntrue\njay\n"
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+}
+
+#[test]
+fn reading_a_double_constant_value_field_is_reported_unsupported() {
+    let root = temp_dir("double-constant-value");
+    compile_java_sources(
+        &root,
+        &[
+            (
+                "Circle.java",
+                r#"
+class Circle {
+    static int SIDES;
+    static double PI;
+}
+"#,
+            ),
+            (
+                "DoubleConstantMain.java",
+                r#"
+public class DoubleConstantMain {
+    public static void main(String[] args) {
+        System.out.println(Circle.PI);
+    }
+}
+"#,
+            ),
+        ],
+    );
+    compile_java(
+        &root,
+        "Circle.java",
+        r#"
+class Circle {
+    static int SIDES = 1;
+    static final double PI = 3.14;
+}
+"#,
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "DoubleConstantMain"]);
+
+    assert_eq!(output.status.code(), Some(6));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("unsupported field descriptor D"),
+        "{stderr}"
+    );
+}
@@ -1,3 +1,6 @@
+use jay::classfile::ClassFile;
+use jay::classfile::annotation::ElementValue;
+
 use crate::support::{compile_java, jay, temp_dir};
 
 #[test]
@@ -100,16 +103,43 @@ public class Main {
 fn rejects_missing_cp() {
     let output = jay(&["HelloWorld"]);
 
-    assert!(!output.status.success());
+    assert_eq!(output.status.code(), Some(2));
     assert!(String::from_utf8_lossy(&output.stderr).contains("usage: jay -cp"));
 }
 
 #[test]
-fn rejects_invalid_classpath() {
+fn skips_missing_classpath_entries() {
+    let root = temp_dir("missing-classpath-entry");
+    compile_java(
+        &root,
+        "HelloWorld.java",
+        r#"
+public class HelloWorld {
+    public static void main(String[] args) {
+        System.out.println("hello");
+    }
+}
+"#,
+    );
+    let classpath = format!("/definitely/not/a/jay/classpath:{}", root.display());
+
+    let output = jay(&["-cp", &classpath, "HelloWorld"]);
+
+    assert!(
+        output.status.success(),
+        "stderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "hello\n");
+
     let output = jay(&["-cp", "/definitely/not/a/jay/classpath", "HelloWorld"]);
 
     assert!(!output.status.success());
-    assert!(String::from_utf8_lossy(&output.stderr).contains("classpath is not a directory"));
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("HelloWorld"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
 }
 
 #[test]
@@ -118,7 +148,7 @@ fn reports_missing_class() {
 
     let output = jay(&["-cp", root.to_str().unwrap(), "Missing"]);
 
-    assert!(!output.status.success());
+    assert_eq!(output.status.code(), Some(3));
     assert!(String::from_utf8_lossy(&output.stderr).contains("could not read class Missing"));
 }
 
@@ -165,6 +195,307 @@ fn reports_malformed_class_file() {
 
     let output = jay(&["-cp", root.to_str().unwrap(), "Broken"]);
 
-    assert!(!output.status.success());
+    assert_eq!(output.status.code(), Some(4));
     assert!(String::from_utf8_lossy(&output.stderr).contains("invalid class file magic"));
 }
+
+#[test]
+fn runs_annotated_classes_and_exposes_their_annotations() {
+    let root = temp_dir("annotated-classes");
+    compile_java(
+        &root,
+        "Annotated.java",
+        r#"
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+
+@Retention(RetentionPolicy.RUNTIME)
+@interface Check {
+    String name() default "unnamed";
+    int[] sizes() default {};
+}
+
+@Deprecated(since = "17")
+public class Annotated {
+    @Check(name = "main", sizes = {1, 2})
+    public static void main(@Check String[] args) {
+        System.out.println("annotated");
+    }
+}
+"#,
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "Annotated"]);
+
+    assert!(
+        output.status.success(),
+        "jay failed\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "annotated\n");
+
+    let bytes = std::fs::read(root.join("Annotated.class")).unwrap();
+    let class_file = ClassFile::parse(&bytes).unwrap();
+    let deprecated = class_file
+        .annotations
+        .find("Ljava/lang/Deprecated;")
+        .unwrap();
+    assert_eq!(
+        deprecated.element("since"),
+        Some(&ElementValue::String("17".to_string()))
+    );
+    let main = class_file
+        .find_method("main", "([Ljava/lang/String;)V")
+        .unwrap();
+    let check = main.annotations.find("LCheck;").unwrap();
+    assert_eq!(
+        check.element("sizes"),
+        Some(&ElementValue::Array(vec![
+            ElementValue::Int(1),
+            ElementValue::Int(2)
+        ]))
+    );
+    assert_eq!(
+        main.parameter_annotations.visible[0][0].type_name,
+        "LCheck;"
+    );
+
+    let bytes = std::fs::read(root.join("Check.class")).unwrap();
+    let check_class = ClassFile::parse(&bytes).unwrap();
+    assert!(check_class.is_annotation());
+    let name = check_class
+        .find_method("name", "()Ljava/lang/String;")
+        .unwrap();
+    assert_eq!(
+        name.annotation_default,
+        Some(ElementValue::String("unnamed".to_string()))
+    );
+}
+
+#[test]
+fn loads_string_constants_encoded_as_modified_utf8() {
+    let root = temp_dir("modified-utf8-constants");
+    compile_java(
+        &root,
+        "Unicode.java",
+        r#"
+public class Unicode {
+    static final String NUL = "a\0b";
+
+    public static void main(String[] args) {
+        System.out.println("smile \uD83D\uDE00");
+        System.out.println(NUL);
+        System.out.println("caf\u00e9");
+    }
+}
+"#,
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "Unicode"]);
+
+    assert!(
+        output.status.success(),
+        "jay failed\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "smile \u{1F600}\na\0b\ncaf\u{E9}\n"
+    );
+}
+
+#[test]
+fn rewritten_class_files_parse_back_unchanged_and_still_run() {
+    let root = temp_dir("class-file-round-trip");
+    compile_java(
+        &root,
+        "RoundTrip.java",
+        r#"
+import java.lang.annotation.ElementType;
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+import java.lang.annotation.Target;
+import java.util.List;
+
+@Retention(RetentionPolicy.RUNTIME)
+@interface Tag {
+    String value() default "none";
+    int[] weights() default {1, 2};
+}
+
+@Target(ElementType.TYPE_USE)
+@interface Checked {
+}
+
+enum Color {
+    RED,
+    GREEN
+}
+
+record Point(int x, int y) {
+}
+
+@Tag(value = "main", weights = {3})
+public class RoundTrip<T extends Comparable<T>> {
+    static final long BIG = 1234567890123L;
+    static final String TEXT = "caf\u00e9 \uD83D\uDE00";
+
+    private List<@Checked T> items;
+
+    static class Counter {
+        private int count;
+
+        int next() {
+            return ++count;
+        }
+    }
+
+    @Tag("helper")
+    static int twice(@Tag int value) {
+        return value * 2;
+    }
+
+    public static void main(String[] args) {
+        Counter counter = new Counter();
+        counter.next();
+        int total = twice(counter.next());
+        System.out.println("total=" + total + " " + TEXT);
+        System.out.println(BIG);
+    }
+}
+"#,
+    );
+
+    let mut class_count = 0;
+    for entry in std::fs::read_dir(&root).unwrap() {
+        let path = entry.unwrap().path();
+        if path
+            .extension()
+            .is_none_or(|extension| extension != "class")
+        {
+            continue;
+        }
+        let class_file = ClassFile::parse(&std::fs::read(&path).unwrap()).unwrap();
+        let bytes = class_file.to_bytes().unwrap();
+        assert_eq!(
+            ClassFile::parse(&bytes).unwrap(),
+            class_file,
+            "{} did not round-trip",
+            path.display()
+        );
+        std::fs::write(&path, bytes).unwrap();
+        class_count += 1;
+    }
+    assert_eq!(class_count, 6);
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "RoundTrip"]);
+
+    assert!(
+        output.status.success(),
+        "jay failed\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "total=4 caf\u{E9} \u{1F600}\n1234567890123\n"
+    );
+}
+
+#[test]
+fn passes_trailing_arguments_to_main() {
+    let root = temp_dir("main-arguments");
+    compile_java(
+        &root,
+        "Echo.java",
+        r#"
+public class Echo {
+    public static void main(String[] args) {
+        System.out.println(args.length);
+        for (int i = 0; i < args.length; i++) {
+            System.out.println(i + ": " + args[i]);
+        }
+    }
+}
+"#,
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "Echo", "one", "-cp", ""]);
+
+    assert!(
+        output.status.success(),
+        "stderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "3\n0: one\n1: -cp\n2: \n"
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "Echo"]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "0\n");
+}
+
+#[test]
+fn reads_and_writes_system_properties() {
+    let root = temp_dir("system-properties");
+    compile_java(
+        &root,
+        "Props.java",
+        r#"
+public class Props {
+    public static void main(String[] args) {
+        System.out.println(System.getProperty("app.name"));
+        System.out.println(System.getProperty("missing", "fallback"));
+        System.out.println(System.getProperty("missing") == null);
+        System.out.println(System.setProperty("app.name", "changed"));
+        System.out.println(System.getProperty("app.name"));
+        System.out.println(Boolean.getBoolean("app.debug"));
+        System.out.println(Boolean.getBoolean("app.name"));
+        System.out.println(Integer.getInteger("app.port").intValue());
+        System.out.println(Integer.getInteger("missing", 7).intValue());
+        System.out.println(System.getProperty("java.version"));
+        System.out.println(System.getProperty("file.separator"));
+        try {
+            System.getProperty("");
+        } catch (IllegalArgumentException e) {
+            System.out.println(e.getMessage());
+        }
+    }
+}
+"#,
+    );
+
+    let output = jay(&[
+        "-Dapp.name=demo",
+        "-Dapp.debug=TRUE",
+        "-Dapp.port=0x1F",
+        "-cp",
+        root.to_str().unwrap(),
+        "Props",
+    ]);
+
+    assert!(
+        output.status.success(),
+        "stderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines[..9],
+        [
+            "demo", "fallback", "true", "demo", "changed", "true", "false", "31", "7"
+        ]
+    );
+    assert!(
+        lines[9].starts_with(|c: char| c.is_ascii_digit()),
+        "java.version: {}",
+        lines[9]
+    );
+    assert_eq!(lines[10..], ["/", "key can't be empty"]);
+}
@@ -0,0 +1,249 @@
+use jay::vm::Vm;
+
+use crate::support::{compile_java, jay, jay_with_input, temp_dir};
+
+#[test]
+fn prints_through_print_stream_objects() {
+    let root = temp_dir("print-streams");
+    compile_java(
+        &root,
+        "Streams.java",
+        r#"
+import java.io.PrintStream;
+import java.util.UnknownFormatConversionException;
+
+public class Streams {
+    static PrintStream saved;
+
+    private final String name;
+
+    Streams(String name) {
+        this.name = name;
+    }
+
+    public String toString() {
+        return "Streams(" + name + ")";
+    }
+
+    static void report(PrintStream out, String text) {
+        out.println(text);
+    }
+
+    public static void main(String[] args) {
+        System.out.print("a");
+        System.out.print('b');
+        System.out.print(1);
+        System.out.print(2L);
+        System.out.print(true);
+        System.out.println();
+        System.out.println('c');
+        System.out.println(new Streams("x"));
+        System.out.println((Object) null);
+        System.out.printf("%d|%5s|%-3d|%x|%c|%b|%,d%n", 42, "hi", 7, 255, 'z', true, 1234567);
+        System.out.format("%s and %s%n", new Streams("y"), null);
+        System.out.write('w');
+        System.out.write('\n');
+        System.out.flush();
+        System.err.println("to stderr");
+        saved = System.out;
+        report(saved, "via field and parameter");
+        System.setOut(System.err);
+        System.out.println("redirected");
+        System.setOut(saved);
+        System.out.println("restored");
+        try {
+            System.out.printf("%q");
+        } catch (UnknownFormatConversionException e) {
+            System.out.println("caught");
+        }
+    }
+}
+"#,
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "Streams"]);
+
+    assert!(
+        output.status.success(),
+        "stderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "ab12true\nc\nStreams(x)\nnull\n42|   hi|7  |ff|z|true|1,234,567\n\
+         Streams(y) and null\nw\nvia field and parameter\nrestored\ncaught\n"
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "to stderr\nredirected\n"
+    );
+}
+
+#[test]
+fn prints_objects_without_to_string_with_identity_hashes() {
+    let root = temp_dir("print-default-to-string");
+    compile_java(
+        &root,
+        "Plain.java",
+        r#"
+public class Plain {
+    public static void main(String[] args) {
+        System.out.println(new Plain());
+    }
+}
+"#,
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "Plain"]);
+
+    assert!(
+        output.status.success(),
+        "stderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let hash = stdout
+        .strip_prefix("Plain@")
+        .and_then(|rest| rest.strip_suffix('\n'))
+        .unwrap_or_else(|| panic!("stdout:\n{stdout}"));
+    assert!(u32::from_str_radix(hash, 16).is_ok(), "stdout:\n{stdout}");
+}
+
+#[test]
+fn reports_printing_char_arrays_as_unsupported() {
+    let root = temp_dir("print-char-array");
+    compile_java(
+        &root,
+        "Chars.java",
+        r#"
+public class Chars {
+    public static void main(String[] args) {
+        char[] chars = null;
+        System.out.print(chars);
+        System.out.println(chars);
+    }
+}
+"#,
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "Chars"]);
+
+    assert_eq!(output.status.code(), Some(6));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.starts_with("jay: unsupported array type in method descriptor ([C)V\n"),
+        "{stderr}"
+    );
+
+    let output = jay(&["check", "-cp", root.to_str().unwrap(), "Chars"]);
+
+    assert_eq!(output.status.code(), Some(6));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Chars.main([Ljava/lang/String;)V pc 6: reference: \
+         unsupported PrintStream.print descriptor ([C)V\n\
+         Chars.main([Ljava/lang/String;)V pc 13: reference: \
+         unsupported PrintStream.println descriptor ([C)V\n"
+    );
+}
+
+#[test]
+fn reads_lines_from_standard_input() {
+    let root = temp_dir("stdin-lines");
+    compile_java(
+        &root,
+        "Echo.java",
+        r#"
+import java.io.BufferedReader;
+import java.io.IOException;
+import java.io.InputStreamReader;
+
+public class Echo {
+    public static void main(String[] args) throws IOException {
+        BufferedReader reader = new BufferedReader(new InputStreamReader(System.in));
+        String line = reader.readLine();
+        while (line != null) {
+            System.out.println("> " + line);
+            line = reader.readLine();
+        }
+        System.out.println("done");
+    }
+}
+"#,
+    );
+
+    let output = jay_with_input(
+        &["-cp", root.to_str().unwrap(), "Echo"],
+        "first\r\nsecond\nlast",
+    );
+
+    assert!(
+        output.status.success(),
+        "stderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "> first\n> second\n> last\ndone\n"
+    );
+}
+
+#[test]
+fn scans_tokens_and_lines_from_supplied_input() {
+    let root = temp_dir("stdin-scanner");
+    compile_java(
+        &root,
+        "Sum.java",
+        r#"
+import java.util.InputMismatchException;
+import java.util.NoSuchElementException;
+import java.util.Scanner;
+
+public class Sum {
+    public static void main(String[] args) {
+        Scanner scanner = new Scanner(System.in);
+        int count = scanner.nextInt();
+        int total = 0;
+        for (int i = 0; i < count; i++) {
+            total += scanner.nextInt();
+        }
+        System.out.println("total " + total);
+        scanner.nextLine();
+        System.out.println("name " + scanner.nextLine());
+        if (!scanner.hasNextInt()) {
+            System.out.println("not an int");
+        }
+        try {
+            scanner.nextInt();
+        } catch (InputMismatchException e) {
+            System.out.println("mismatch " + scanner.next());
+        }
+        while (scanner.hasNext()) {
+            System.out.println("word " + scanner.next());
+        }
+        System.out.println("rest [" + scanner.nextLine() + "]");
+        try {
+            scanner.nextLine();
+        } catch (NoSuchElementException e) {
+            System.out.println(e.getMessage());
+        }
+        scanner.close();
+    }
+}
+"#,
+    );
+
+    let vm = Vm::new(vec![root]).unwrap();
+    let mut input = "3\n10 20\n  30\nAda Lovelace\nabc\nx y\n".as_bytes();
+    let mut output = Vec::new();
+    let mut error = Vec::new();
+    vm.run_main_with_io("Sum", &[], &mut input, &mut output, &mut error)
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8_lossy(&output),
+        "total 60\nname Ada Lovelace\nnot an int\nmismatch abc\n\
+         word x\nword y\nrest []\nNo line found\n"
+    );
+    assert!(error.is_empty());
+}
@@ -0,0 +1,449 @@
+use std::cell::RefCell;
+use std::io::Write;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use jay::ErrorKind;
+use jay::vm::{JValue, Vm};
+
+use crate::support::{compile_java_sources, temp_dir};
+
+/// A writer whose bytes stay readable after the `Vm` takes ownership of it.
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn take(&self) -> String {
+        String::from_utf8(self.0.take()).unwrap()
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn boot_image() -> PathBuf {
+    PathBuf::from(std::env::var_os("JAVA_HOME").unwrap()).join("lib/modules")
+}
+
+#[test]
+fn builder_configures_streams_properties_and_environment_for_several_runs() {
+    let root = temp_dir("embedding");
+    compile_java_sources(
+        &root,
+        &[
+            (
+                "Greeter.java",
+                r#"
+import java.util.Scanner;
+
+public class Greeter {
+    public static void main(String[] args) {
+        Scanner scanner = new Scanner(System.in);
+        String name = scanner.nextLine();
+        System.out.println(System.getenv("GREETING") + ", " + name + System.getProperty("suffix"));
+        if (System.getenv("HOME") == null) {
+            System.err.println("no HOME");
+        }
+    }
+}
+"#,
+            ),
+            (
+                "Counter.java",
+                r#"
+public class Counter {
+    static int runs;
+
+    public static void main(String[] args) {
+        runs = runs + 1;
+        System.out.println("run " + runs);
+    }
+}
+"#,
+            ),
+        ],
+    );
+    let (stdout, stderr) = (SharedBuffer::default(), SharedBuffer::default());
+    let vm = Vm::builder()
+        .classpath([&root])
+        .boot_image(boot_image())
+        .stdin("Ada\nGrace\nLinus\n".as_bytes())
+        .stdout(stdout.clone())
+        .stderr(stderr.clone())
+        .property("suffix", "!")
+        .env_clear()
+        .env("GREETING", "Hello")
+        .build()
+        .unwrap();
+
+    assert_eq!(vm.run_main("Greeter", &[]).unwrap(), 0);
+    assert_eq!(vm.run_main("Counter", &[]).unwrap(), 0);
+    assert_eq!(vm.run_main("Greeter", &[]).unwrap(), 0);
+    assert_eq!(vm.run_main("Counter", &[]).unwrap(), 0);
+
+    assert_eq!(stdout.take(), "Hello, Ada!\nrun 1\nHello, Grace!\nrun 1\n");
+    assert_eq!(stderr.take(), "no HOME\nno HOME\n");
+
+    let mut captured = Vec::new();
+    assert_eq!(
+        vm.run_main_to_writer("Greeter", &[], &mut captured)
+            .unwrap(),
+        0
+    );
+    assert_eq!(String::from_utf8(captured).unwrap(), "Hello, Linus!\n");
+    assert_eq!(stdout.take(), "");
+    assert_eq!(stderr.take(), "no HOME\n");
+}
+
+#[test]
+fn builder_limits_heap_stack_depth_and_instructions() {
+    let root = temp_dir("embedding-limits");
+    compile_java_sources(
+        &root,
+        &[
+            (
+                "Recurse.java",
+                r#"
+public class Recurse {
+    static int depth;
+
+    static void down() {
+        depth = depth + 1;
+        down();
+    }
+
+    public static void main(String[] args) {
+        try {
+            down();
+        } catch (StackOverflowError e) {
+            System.out.println("overflow at " + depth);
+        }
+    }
+}
+"#,
+            ),
+            (
+                "Hoard.java",
+                r#"
+public class Hoard {
+    public static void main(String[] args) {
+        Object[] head = null;
+        try {
+            while (true) {
+                Object[] next = new Object[1];
+                next[0] = head;
+                head = next;
+            }
+        } catch (OutOfMemoryError e) {
+            head = null;
+            System.out.println(e.getMessage());
+        }
+    }
+}
+"#,
+            ),
+            (
+                "Spin.java",
+                r#"
+public class Spin {
+    public static void main(String[] args) {
+        while (true) {
+        }
+    }
+}
+"#,
+            ),
+        ],
+    );
+    let stdout = SharedBuffer::default();
+    let vm = Vm::builder()
+        .classpath([&root])
+        .stdout(stdout.clone())
+        .max_stack_depth(32)
+        .max_heap_objects(1000)
+        .instruction_budget(100_000)
+        .build()
+        .unwrap();
+
+    vm.run_main("Recurse", &[]).unwrap();
+    vm.run_main("Hoard", &[]).unwrap();
+    assert_eq!(stdout.take(), "overflow at 31\nJava heap space\n");
+
+    let error = vm.run_main("Spin", &[]).unwrap_err();
+    assert_eq!(
+        error.kind(),
+        &ErrorKind::LimitExceeded {
+            limit: "instruction budget".to_string()
+        }
+    );
+    assert_eq!(error.to_string(), "instruction budget of 100000 exhausted");
+    assert_eq!(error.java_stack_trace()[0].method_name, "main");
+}
+
+#[test]
+fn builder_reports_a_missing_boot_image_as_io_error() {
+    let error = Vm::builder()
+        .boot_image("/definitely/not/a/jay/boot/image")
+        .build()
+        .unwrap_err();
+
+    assert_eq!(error.kind(), &ErrorKind::Io);
+}
+
+#[test]
+fn invoke_static_converts_arguments_results_and_exceptions() {
+    let root = temp_dir("embedding-invoke");
+    compile_java_sources(
+        &root,
+        &[(
+            "com/example/Calc.java",
+            r#"
+package com.example;
+
+public class Calc {
+    static int calls;
+
+    private final String owner;
+
+    private Calc(String owner) {
+        this.owner = owner;
+    }
+
+    public static int add(int a, int b) {
+        calls = calls + 1;
+        return a + b;
+    }
+
+    public static int calls() {
+        return calls;
+    }
+
+    public static long keepIf(long value, boolean keep) {
+        return keep ? value : 0L;
+    }
+
+    public static boolean isMissing(String text) {
+        return text == null;
+    }
+
+    public static String greet(String name) {
+        return "Hello, " + name;
+    }
+
+    public static String join(String[] parts) {
+        String joined = "";
+        for (int i = 0; i < parts.length; i++) {
+            joined = joined + parts[i];
+        }
+        return joined;
+    }
+
+    public static Object[] pair(String first, String second) {
+        return new Object[] { first, null, second };
+    }
+
+    public static Calc open(String owner) {
+        return new Calc(owner);
+    }
+
+    public static String owner(Calc calc) {
+        return calc.owner;
+    }
+
+    public static int sum(int[] values) {
+        return values.length;
+    }
+
+    public static void quit(int status) {
+        System.exit(status);
+    }
+
+    public static int divide(int a, int b) {
+        return a / b;
+    }
+}
+"#,
+        )],
+    );
+    let vm = Vm::builder().classpath([&root]).build().unwrap();
+    let calc = "com.example.Calc";
+
+    let sum = vm.invoke_static(calc, "add", "(II)I", &[JValue::Int(1), JValue::Int(2)]);
+    assert_eq!(sum.unwrap(), JValue::Int(3));
+    vm.invoke_static(calc, "add", "(II)I", &[JValue::Int(4), JValue::Int(5)])
+        .unwrap();
+    assert_eq!(
+        vm.invoke_static(calc, "calls", "()I", &[]).unwrap(),
+        JValue::Int(2)
+    );
+    assert_eq!(
+        vm.invoke_static(
+            calc,
+            "keepIf",
+            "(JZ)J",
+            &[JValue::Long(1 << 40), JValue::Boolean(true)]
+        )
+        .unwrap(),
+        JValue::Long(1 << 40)
+    );
+    assert_eq!(
+        vm.invoke_static(calc, "isMissing", "(Ljava/lang/String;)Z", &[JValue::Null])
+            .unwrap(),
+        JValue::Boolean(true)
+    );
+    assert_eq!(
+        vm.invoke_static(
+            calc,
+            "greet",
+            "(Ljava/lang/String;)Ljava/lang/String;",
+            &[JValue::String("Ada".to_string())]
+        )
+        .unwrap(),
+        JValue::String("Hello, Ada".to_string())
+    );
+    let parts = JValue::Array(vec![
+        JValue::String("a".to_string()),
+        JValue::String("b".to_string()),
+    ]);
+    assert_eq!(
+        vm.invoke_static(
+            calc,
+            "join",
+            "([Ljava/lang/String;)Ljava/lang/String;",
+            &[parts]
+        )
+        .unwrap(),
+        JValue::String("ab".to_string())
+    );
+    assert_eq!(
+        vm.invoke_static(
+            calc,
+            "pair",
+            "(Ljava/lang/String;Ljava/lang/String;)[Ljava/lang/Object;",
+            &[
+                JValue::String("x".to_string()),
+                JValue::String("y".to_string())
+            ]
+        )
+        .unwrap(),
+        JValue::Array(vec![
+            JValue::String("x".to_string()),
+            JValue::Null,
+            JValue::String("y".to_string()),
+        ])
+    );
+
+    let handle = vm
+        .invoke_static(
+            calc,
+            "open",
+            "(Ljava/lang/String;)Lcom/example/Calc;",
+            &[JValue::String("Grace".to_string())],
+        )
+        .unwrap();
+    assert!(matches!(handle, JValue::Object(_)));
+    assert_eq!(
+        vm.invoke_static(
+            calc,
+            "owner",
+            "(Lcom/example/Calc;)Ljava/lang/String;",
+            std::slice::from_ref(&handle)
+        )
+        .unwrap(),
+        JValue::String("Grace".to_string())
+    );
+
+    let error = vm
+        .invoke_static(calc, "divide", "(II)I", &[JValue::Int(1), JValue::Int(0)])
+        .unwrap_err();
+    assert_eq!(
+        error.kind(),
+        &ErrorKind::JavaException {
+            class: "java.lang.ArithmeticException".to_string(),
+            message: Some("/ by zero".to_string()),
+        }
+    );
+    let frame = &error.java_stack_trace()[0];
+    assert_eq!(
+        (frame.class_name.as_str(), frame.method_name.as_str()),
+        ("com.example.Calc", "divide")
+    );
+
+    let error = vm
+        .invoke_static(calc, "add", "(II)I", &[JValue::String("1".to_string())])
+        .unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::Usage);
+    let error = vm
+        .invoke_static(
+            calc,
+            "greet",
+            "(Ljava/lang/String;)Ljava/lang/String;",
+            &[JValue::Int(1)],
+        )
+        .unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::Usage);
+    let wrong_elements = JValue::Array(vec![JValue::String("a".to_string()), handle.clone()]);
+    let error = vm
+        .invoke_static(
+            calc,
+            "join",
+            "([Ljava/lang/String;)Ljava/lang/String;",
+            &[wrong_elements],
+        )
+        .unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::Usage);
+    assert!(
+        error
+            .to_string()
+            .contains("expected java.lang.String, found com.example.Calc"),
+        "{error}"
+    );
+    let error = vm
+        .invoke_static(calc, "sum", "([I)I", &[JValue::Array(vec![JValue::Int(1)])])
+        .unwrap_err();
+    assert!(
+        matches!(error.kind(), ErrorKind::UnsupportedFeature { .. }),
+        "{error:?}"
+    );
+
+    let error = vm
+        .invoke_static(calc, "add", "(IX)I", &[JValue::Int(1), JValue::Int(2)])
+        .unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::ClassFormat);
+    assert_eq!(
+        error.to_string(),
+        "calling com.example.Calc.add(IX)I: invalid method descriptor (IX)I"
+    );
+
+    let error = vm
+        .invoke_static(calc, "quit", "(I)V", &[JValue::Int(3)])
+        .unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::Exit { status: 3 });
+    assert_eq!(
+        vm.invoke_static(calc, "calls", "()I", &[]).unwrap(),
+        JValue::Int(2)
+    );
+
+    let JValue::Object(object) = handle else {
+        unreachable!()
+    };
+    vm.release_object(object);
+    let error = vm
+        .invoke_static(
+            calc,
+            "owner",
+            "(Lcom/example/Calc;)Ljava/lang/String;",
+            &[handle],
+        )
+        .unwrap_err();
+    assert_eq!(error.kind(), &ErrorKind::Usage);
+    assert!(error.to_string().contains("was released"), "{error}");
+}
@@ -1,3 +1,6 @@
+use jay::ErrorKind;
+use jay::vm::Vm;
+
 use crate::support::{compile_java, jay, temp_dir};
 
 #[test]
@@ -32,6 +35,7 @@ public class Main {
         String::from_utf8_lossy(&output.stdout),
         String::from_utf8_lossy(&output.stderr)
     );
+    assert_eq!(output.status.code(), Some(6));
     let stderr = String::from_utf8_lossy(&output.stderr);
     assert!(
         stderr.contains("jay: unsupported anewarray component java.util.Map$Entry"),
@@ -50,3 +54,264 @@ public class Main {
         "stderr missing main frame:\n{stderr}"
     );
 }
+
+#[test]
+fn uncaught_exceptions_print_jdk_stack_traces() {
+    let root = temp_dir("uncaught-stack-trace");
+    compile_java(
+        &root,
+        "Trace.java",
+        r#"
+public class Trace {
+    static class AppException extends RuntimeException {
+        AppException(String message, Throwable cause) {
+            super(message, cause);
+        }
+    }
+
+    static int zero;
+
+    static int divide() {
+        return 1 / zero;
+    }
+
+    static void load() {
+        try {
+            divide();
+        } catch (ArithmeticException e) {
+            throw new AppException("load failed", e);
+        }
+    }
+
+    public static void main(String[] args) {
+        try {
+            load();
+        } catch (AppException e) {
+            StackTraceElement[] trace = e.getStackTrace();
+            System.out.println(trace.length);
+            System.out.println(trace[0]);
+            System.out.println(trace[0].getMethodName() + ":" + trace[0].getLineNumber());
+            System.out.println(e);
+            System.out.println(e.getCause().getMessage());
+            e.printStackTrace();
+        }
+        Exception plain = new Exception();
+        if (plain.fillInStackTrace() == plain) {
+            System.out.println("refilled");
+        }
+        System.out.println(new IllegalStateException(new RuntimeException("inner")).getMessage());
+        load();
+    }
+}
+"#,
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "Trace"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "2\nTrace.load(Trace.java:19)\nload:19\nTrace$AppException: load failed\n/ by zero\n\
+         refilled\njava.lang.RuntimeException: inner\n"
+    );
+    let cause = "Caused by: java.lang.ArithmeticException: / by zero\n\
+                 \tat Trace.divide(Trace.java:12)\n\
+                 \tat Trace.load(Trace.java:17)\n\
+                 \t... 1 more\n";
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        format!(
+            "Trace$AppException: load failed\n\
+             \tat Trace.load(Trace.java:19)\n\
+             \tat Trace.main(Trace.java:25)\n\
+             {cause}\
+             Exception in thread \"main\" Trace$AppException: load failed\n\
+             \tat Trace.load(Trace.java:19)\n\
+             \tat Trace.main(Trace.java:40)\n\
+             {cause}"
+        )
+    );
+}
+
+#[test]
+fn caused_by_trace_shares_frames_that_differ_only_in_pc() {
+    let root = temp_dir("caused-by-shared-frames");
+    compile_java(
+        &root,
+        "Wrapped.java",
+        r#"
+public class Wrapped {
+    static void inner() { throw new IllegalStateException("inner"); }
+    static void outer() { try { inner(); } catch (IllegalStateException e) { throw new RuntimeException("wrapped", e); } }
+    public static void main(String[] args) {
+        outer();
+    }
+}
+"#,
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "Wrapped"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Exception in thread \"main\" java.lang.RuntimeException: wrapped\n\
+         \tat Wrapped.outer(Wrapped.java:4)\n\
+         \tat Wrapped.main(Wrapped.java:6)\n\
+         Caused by: java.lang.IllegalStateException: inner\n\
+         \tat Wrapped.inner(Wrapped.java:3)\n\
+         \t... 2 more\n"
+    );
+}
+
+#[test]
+fn stack_traces_name_the_module_of_jdk_frames() {
+    let root = temp_dir("jdk-frame-modules");
+    compile_java(
+        &root,
+        "Npe.java",
+        r#"
+import java.util.Objects;
+
+public class Npe {
+    public static void main(String[] args) {
+        Objects.requireNonNull(null, "value");
+    }
+}
+"#,
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "Npe"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let lines = stderr.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 3, "stderr:\n{stderr}");
+    assert_eq!(
+        lines[0],
+        "Exception in thread \"main\" java.lang.NullPointerException: value"
+    );
+    assert!(
+        lines[1].starts_with("\tat java.base/java.util.Objects.requireNonNull(Objects.java:"),
+        "stderr:\n{stderr}"
+    );
+    assert_eq!(lines[2], "\tat Npe.main(Npe.java:6)");
+}
+
+#[test]
+fn library_errors_carry_their_kind() {
+    let root = temp_dir("error-kinds");
+    compile_java(
+        &root,
+        "Kinds.java",
+        r#"
+public class Kinds {
+    public static void main(String[] args) {
+        throw new IllegalStateException("not ready");
+    }
+}
+"#,
+    );
+    let vm = Vm::new(vec![root.clone()]).unwrap();
+    let run = |main_class: &str| {
+        let mut stdin = std::io::empty();
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        vm.run_main_with_io(main_class, &[], &mut stdin, &mut stdout, &mut stderr)
+            .unwrap_err()
+    };
+
+    let error = run("Kinds");
+    assert_eq!(
+        error.kind(),
+        &ErrorKind::JavaException {
+            class: "java.lang.IllegalStateException".to_string(),
+            message: Some("not ready".to_string()),
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "java.lang.IllegalStateException: not ready"
+    );
+    assert_eq!(
+        error.java_stack_trace()[0].to_string(),
+        "Kinds.main(Kinds.java:4)"
+    );
+
+    assert_eq!(run("Missing").kind(), &ErrorKind::ClassNotFound);
+    assert_eq!(run("java.lang.Object").kind(), &ErrorKind::Usage);
+}
+
+#[test]
+fn json_error_format_reports_kind_message_and_frames() {
+    let root = temp_dir("json-error-format");
+    compile_java(
+        &root,
+        "Main.java",
+        r#"
+public class Main {
+    public static void main(String[] args) {
+        inner();
+    }
+
+    static void inner() {
+        java.util.Map.Entry[] entries = new java.util.Map.Entry[1];
+    }
+}
+"#,
+    );
+
+    let output = jay(&["--error-format=json", "-cp", root.to_str().unwrap(), "Main"]);
+
+    assert_eq!(output.status.code(), Some(6));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "{\"kind\":\"unsupported_feature\",\
+         \"feature\":\"anewarray component java.util.Map$Entry\",\
+         \"message\":\"unsupported anewarray component java.util.Map$Entry\",\
+         \"stack_trace\":[\
+         {\"class\":\"Main\",\"method\":\"inner\",\"descriptor\":\"()V\",\"pc\":1,\"line\":8},\
+         {\"class\":\"Main\",\"method\":\"main\",\"descriptor\":\"([Ljava/lang/String;)V\",\"pc\":0,\"line\":4}\
+         ]}\n"
+    );
+
+    compile_java(
+        &root,
+        "Thrower.java",
+        r#"
+public class Thrower {
+    public static void main(String[] args) {
+        throw new IllegalStateException("broken");
+    }
+}
+"#,
+    );
+
+    let output = jay(&[
+        "--error-format=json",
+        "-cp",
+        root.to_str().unwrap(),
+        "Thrower",
+    ]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "{\"kind\":\"java_exception\",\
+         \"exception_class\":\"java.lang.IllegalStateException\",\
+         \"exception_message\":\"broken\",\
+         \"message\":\"java.lang.IllegalStateException: broken\",\
+         \"stack_trace\":[\
+         {\"class\":\"Thrower\",\"method\":\"main\",\"descriptor\":\"([Ljava/lang/String;)V\",\"pc\":6,\"line\":4}\
+         ]}\n"
+    );
+
+    let output = jay(&["--error-format=json", "-cp"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .starts_with("{\"kind\":\"usage\",\"message\":\"missing classpath after -cp\""),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
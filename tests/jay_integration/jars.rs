@@ -0,0 +1,256 @@
+use crate::support::{compile_java_sources, create_jar, jay, jay_with_env, temp_dir};
+
+fn compile_greeter(root: &std::path::Path) -> std::path::PathBuf {
+    let classes = root.join("classes");
+    compile_java_sources(
+        &classes,
+        &[
+            (
+                "com/example/Main.java",
+                r#"
+package com.example;
+
+public class Main {
+    public static void main(String[] args) {
+        System.out.println(Greeting.text());
+    }
+}
+"#,
+            ),
+            (
+                "com/example/Greeting.java",
+                r#"
+package com.example;
+
+class Greeting {
+    static String text() {
+        return "hello from a jar";
+    }
+}
+"#,
+            ),
+        ],
+    );
+    for source in ["Main.java", "Greeting.java"] {
+        std::fs::remove_file(classes.join("com/example").join(source)).unwrap();
+    }
+    classes
+}
+
+#[test]
+fn runs_classes_from_deflated_and_stored_jars() {
+    let root = temp_dir("jar-classpath");
+    let classes = compile_greeter(&root);
+    let deflated = root.join("app.jar");
+    let stored = root.join("app-stored.zip");
+    create_jar(&classes, &deflated, &[]);
+    create_jar(&classes, &stored, &["--no-compress"]);
+
+    for archive in [&deflated, &stored] {
+        let output = jay(&["-cp", archive.to_str().unwrap(), "com.example.Main"]);
+
+        assert!(
+            output.status.success(),
+            "jay failed for {}\nstderr:\n{}",
+            archive.display(),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "hello from a jar\n"
+        );
+    }
+}
+
+#[test]
+fn reports_corrupted_jars() {
+    let root = temp_dir("jar-corrupted");
+    let classes = compile_greeter(&root);
+    let archive = root.join("app.jar");
+    create_jar(&classes, &archive, &[]);
+    let bytes = std::fs::read(&archive).unwrap();
+    std::fs::write(&archive, &bytes[..bytes.len() - 30]).unwrap();
+
+    let output = jay(&["-cp", archive.to_str().unwrap(), "com.example.Main"]);
+
+    assert!(!output.status.success(), "jay unexpectedly succeeded");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        format!(
+            "jay: corrupted archive {}: missing end of central directory record\n",
+            archive.display()
+        )
+    );
+}
+
+#[test]
+fn javap_reports_jar_origins() {
+    let root = temp_dir("jar-javap");
+    let classes = compile_greeter(&root);
+    let archive = root.join("app.jar");
+    create_jar(&classes, &archive, &[]);
+
+    let output = jay(&[
+        "javap",
+        "-cp",
+        archive.to_str().unwrap(),
+        "com.example.Main",
+    ]);
+
+    assert!(
+        output.status.success(),
+        "jay javap failed\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let origin = std::fs::canonicalize(&archive).unwrap();
+    assert!(
+        String::from_utf8_lossy(&output.stdout).starts_with(&format!(
+            "Classfile jar:file:{}!/com/example/Main.class\n",
+            origin.display()
+        )),
+        "stdout:\n{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
+
+#[test]
+fn searches_classpath_lists_with_wildcards_and_the_classpath_variable() {
+    let root = temp_dir("jar-classpath-list");
+    let classes = compile_greeter(&root);
+    let libs = root.join("libs");
+    let app = root.join("app");
+    std::fs::create_dir_all(&libs).unwrap();
+    std::fs::create_dir_all(app.join("com/example")).unwrap();
+    std::fs::rename(
+        classes.join("com/example/Main.class"),
+        app.join("com/example/Main.class"),
+    )
+    .unwrap();
+    create_jar(&classes, &libs.join("greeting.jar"), &[]);
+    let classpath = format!("{}:{}/*", app.display(), libs.display());
+
+    for output in [
+        jay(&["--class-path", &classpath, "com.example.Main"]),
+        jay_with_env(&["com.example.Main"], &[("CLASSPATH", &classpath)]),
+    ] {
+        assert!(
+            output.status.success(),
+            "jay failed\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "hello from a jar\n"
+        );
+    }
+
+    let output = jay(&["-classpath", &classpath, "com.example.Missing"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let jar = std::fs::canonicalize(libs.join("greeting.jar")).unwrap();
+    assert!(
+        stderr.starts_with(&format!(
+            "jay: could not read class com.example.Missing; tried {}, \
+             jar:file:{}!/com/example/Missing.class, boot image ",
+            app.join("com/example/Missing.class").display(),
+            jar.display()
+        )),
+        "stderr:\n{stderr}"
+    );
+}
+
+#[test]
+fn launches_executable_jars_with_manifest_class_paths() {
+    let root = temp_dir("jar-executable");
+    let classes = compile_greeter(&root);
+    let app = root.join("app");
+    std::fs::create_dir_all(app.join("com/example")).unwrap();
+    std::fs::create_dir_all(root.join("lib")).unwrap();
+    std::fs::rename(
+        classes.join("com/example/Main.class"),
+        app.join("com/example/Main.class"),
+    )
+    .unwrap();
+    create_jar(&classes, &root.join("lib/greeting.jar"), &[]);
+    let manifest = root.join("manifest.txt");
+    std::fs::write(
+        &manifest,
+        "Main-Class: com.exam\n ple.Main\nClass-Path: lib/greeting.jar\n",
+    )
+    .unwrap();
+    let jar = root.join("app.jar");
+    create_jar(&app, &jar, &["--manifest", manifest.to_str().unwrap()]);
+
+    let output = jay_with_env(
+        &["-jar", jar.to_str().unwrap()],
+        &[("CLASSPATH", "/nonexistent")],
+    );
+
+    assert!(
+        output.status.success(),
+        "jay failed\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "hello from a jar\n"
+    );
+
+    let plain = root.join("plain.jar");
+    create_jar(&app, &plain, &["--no-manifest"]);
+    let output = jay(&["-jar", plain.to_str().unwrap()]);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        format!("jay: no main manifest attribute, in {}\n", plain.display())
+    );
+}
+
+#[test]
+fn prefers_multi_release_class_variants() {
+    let root = temp_dir("jar-multi-release");
+    let classes = compile_greeter(&root);
+    let versioned = root.join("versioned");
+    compile_java_sources(
+        &versioned,
+        &[(
+            "com/example/Greeting.java",
+            r#"
+package com.example;
+
+class Greeting {
+    static String text() {
+        return "hello from a versioned class";
+    }
+}
+"#,
+        )],
+    );
+    std::fs::remove_file(versioned.join("com/example/Greeting.java")).unwrap();
+    let manifest = root.join("manifest.txt");
+    std::fs::write(&manifest, "Main-Class: com.example.Main\n").unwrap();
+    let jar = root.join("app.jar");
+    create_jar(
+        &versioned,
+        &jar,
+        &[
+            "--manifest",
+            manifest.to_str().unwrap(),
+            "-C",
+            classes.to_str().unwrap(),
+            ".",
+            "--release",
+            "9",
+        ],
+    );
+
+    let output = jay(&["-jar", jar.to_str().unwrap()]);
+
+    assert!(
+        output.status.success(),
+        "jay failed\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "hello from a versioned class\n"
+    );
+}
@@ -0,0 +1,76 @@
+use crate::support::{compile_java, jay, temp_dir};
+
+#[test]
+fn javap_disassembles_classpath_classes() {
+    let root = temp_dir("javap-classpath");
+    compile_java(
+        &root,
+        "demo/Greeter.java",
+        r#"
+package demo;
+
+public class Greeter {
+    private static final int TIMES = 2;
+
+    public static void main(String[] args) {
+        for (int i = 0; i < TIMES; i++) {
+            System.out.println("hi");
+        }
+    }
+}
+"#,
+    );
+
+    let output = jay(&["javap", "-c", "-cp", root.to_str().unwrap(), "demo.Greeter"]);
+
+    assert!(
+        output.status.success(),
+        "jay javap failed\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let text = String::from_utf8_lossy(&output.stdout);
+    let class_path = root.join("demo").join("Greeter.class");
+    assert!(
+        text.starts_with(&format!("Classfile {}\n", class_path.display())),
+        "{text}"
+    );
+    assert!(
+        text.contains("public class demo.Greeter extends java.lang.Object {\n"),
+        "{text}"
+    );
+    assert!(
+        text.contains("  public static void main(java.lang.String[]);\n    Code:\n"),
+        "{text}"
+    );
+    assert!(
+        text.contains("// Field java/lang/System.out:Ljava/io/PrintStream;\n"),
+        "{text}"
+    );
+    assert!(text.contains("// String hi\n"), "{text}");
+}
+
+#[test]
+fn javap_verbose_reports_boot_image_classes_and_constant_pool() {
+    let output = jay(&["javap", "-v", "java.lang.Runnable"]);
+
+    assert!(
+        output.status.success(),
+        "jay javap failed\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let text = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        text.starts_with("Classfile jrt:/java.base/java/lang/Runnable.class\n"),
+        "{text}"
+    );
+    assert!(
+        text.contains("public interface java.lang.Runnable\n"),
+        "{text}"
+    );
+    assert!(text.contains("Constant pool:\n"), "{text}");
+    assert!(text.contains("  public abstract void run();\n"), "{text}");
+    assert!(
+        text.contains("    flags: (0x0401) ACC_PUBLIC, ACC_ABSTRACT\n"),
+        "{text}"
+    );
+}
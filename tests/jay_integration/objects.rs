@@ -1075,3 +1075,96 @@ public class Main {
     assert_eq!(String::from_utf8_lossy(&output.stdout), "");
     assert_eq!(String::from_utf8_lossy(&output.stderr), "");
 }
+
+#[test]
+fn new_of_class_that_became_abstract_throws_instantiation_error() {
+    let root = temp_dir("instantiation-error");
+    compile_java_sources(
+        &root,
+        &[
+            ("Shape.java", "class Shape {}\n"),
+            (
+                "InstantiationMain.java",
+                r#"
+public class InstantiationMain {
+    public static void main(String[] args) {
+        try {
+            new Shape();
+            System.out.println("constructed");
+        } catch (InstantiationError error) {
+            System.out.println(error.getMessage());
+        }
+    }
+}
+"#,
+            ),
+        ],
+    );
+    // Recompiling only the class keeps the caller's `new` instruction.
+    compile_java(&root, "Shape.java", "abstract class Shape {}\n");
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "InstantiationMain"]);
+
+    assert!(
+        output.status.success(),
+        "jay failed\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Shape\n");
+}
+
+#[test]
+fn invoking_unimplemented_abstract_method_throws_abstract_method_error() {
+    let root = temp_dir("abstract-method-error");
+    compile_java_sources(
+        &root,
+        &[
+            (
+                "Base.java",
+                r#"
+class Base {
+    int value() {
+        return 1;
+    }
+}
+"#,
+            ),
+            ("Impl.java", "class Impl extends Base {}\n"),
+            (
+                "AbstractMethodMain.java",
+                r#"
+public class AbstractMethodMain {
+    public static void main(String[] args) {
+        Base base = new Impl();
+        try {
+            System.out.println(base.value());
+        } catch (AbstractMethodError error) {
+            System.out.println(error.getMessage());
+        }
+    }
+}
+"#,
+            ),
+        ],
+    );
+    compile_java(
+        &root,
+        "Base.java",
+        r#"
+abstract class Base {
+    abstract int value();
+}
+"#,
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "AbstractMethodMain"]);
+
+    assert!(
+        output.status.success(),
+        "jay failed\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Impl.value()I\n");
+}
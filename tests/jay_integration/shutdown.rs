@@ -0,0 +1,138 @@
+use crate::support::{compile_java, jay, temp_dir};
+
+#[test]
+fn exits_with_status_after_running_shutdown_hooks() {
+    let root = temp_dir("system-exit");
+    compile_java(
+        &root,
+        "Exit.java",
+        r#"
+public class Exit {
+    static int zero;
+
+    static class Hook extends Thread {
+        private final String label;
+
+        Hook(String label) {
+            this.label = label;
+        }
+
+        public void run() {
+            System.out.println("hook " + label + " in " + getName());
+        }
+    }
+
+    static class Task implements Runnable {
+        public void run() {
+            System.out.println("runnable hook");
+            System.out.println(1 / zero);
+        }
+    }
+
+    public static void main(String[] args) {
+        Runtime runtime = Runtime.getRuntime();
+        Thread removed = new Hook("removed");
+        runtime.addShutdownHook(new Hook("first"));
+        runtime.addShutdownHook(new Thread(new Task(), "worker"));
+        runtime.addShutdownHook(removed);
+        if (runtime.removeShutdownHook(removed)) {
+            System.out.println("removed");
+        }
+        try {
+            runtime.addShutdownHook(removed);
+            runtime.addShutdownHook(removed);
+        } catch (IllegalArgumentException e) {
+            System.out.println(e.getMessage());
+        }
+        runtime.removeShutdownHook(removed);
+        try {
+            System.exit(40 + args.length);
+        } finally {
+            System.out.println("finally must not run");
+        }
+    }
+}
+"#,
+    );
+
+    let output = jay(&["-cp", root.to_str().unwrap(), "Exit", "a", "b"]);
+
+    assert_eq!(output.status.code(), Some(42));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "removed\nHook previously registered\nhook first in Thread-1\nrunnable hook\n"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.starts_with(
+            "Exception in thread \"worker\" java.lang.ArithmeticException: / by zero\n\
+             \tat Exit$Task.run(Exit.java:20)\n\
+             \tat java.base/java.lang.Thread.run(Thread.java:"
+        ),
+        "stderr:\n{stderr}"
+    );
+}
+
+#[test]
+fn maps_returns_uncaught_exceptions_and_halts_to_exit_statuses() {
+    let root = temp_dir("exit-codes");
+    compile_java(
+        &root,
+        "Codes.java",
+        r#"
+public class Codes {
+    static class Hook extends Thread {
+        public void run() {
+            System.out.println("hook");
+            if (Codes.mode == 3) {
+                Runtime.getRuntime().exit(7);
+            }
+        }
+    }
+
+    static int mode;
+
+    public static void main(String[] args) {
+        mode = args.length;
+        Runtime.getRuntime().addShutdownHook(new Hook());
+        if (mode == 1) {
+            System.out.println(mode / (mode - 1));
+        }
+        if (mode == 2) {
+            Runtime.getRuntime().halt(-1);
+        }
+        System.out.println("main done");
+    }
+}
+"#,
+    );
+    let classpath = root.to_str().unwrap();
+
+    let returned = jay(&["-cp", classpath, "Codes"]);
+    assert_eq!(returned.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&returned.stdout),
+        "main done\nhook\n"
+    );
+
+    let uncaught = jay(&["-cp", classpath, "Codes", "a"]);
+    assert_eq!(uncaught.status.code(), Some(1));
+    assert_eq!(String::from_utf8_lossy(&uncaught.stdout), "hook\n");
+    assert!(
+        String::from_utf8_lossy(&uncaught.stderr)
+            .contains("java.lang.ArithmeticException: / by zero"),
+        "stderr:\n{}",
+        String::from_utf8_lossy(&uncaught.stderr)
+    );
+
+    let halted = jay(&["-cp", classpath, "Codes", "a", "b"]);
+    assert_eq!(halted.status.code(), Some(255));
+    assert!(halted.stdout.is_empty());
+
+    let exited_in_hook = jay(&["-cp", classpath, "Codes", "a", "b", "c"]);
+    assert_eq!(exited_in_hook.status.code(), Some(7));
+    assert_eq!(
+        String::from_utf8_lossy(&exited_in_hook.stdout),
+        "main done\nhook\n"
+    );
+}
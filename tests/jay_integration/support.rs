@@ -3,10 +3,13 @@
 //! The tests compile tiny Java programs on the fly and then execute them
 //! through the compiled `jay` binary. Keeping the helpers in one module makes
 //! each behavior-focused test module independent without duplicating process
-//! setup or class-file patching code.
+//! setup or class-file editing code.
 
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Output};
+use std::process::{Command, Output, Stdio};
+
+use jay::classfile::ClassFile;
 
 pub(crate) fn temp_dir(name: &str) -> PathBuf {
     let path = std::env::temp_dir().join(format!(
@@ -69,193 +72,109 @@ pub(crate) fn compile_java_sources(root: &Path, sources: &[(&str, &str)]) {
     );
 }
 
+/// Packs every file under `classes` into `jar` with the JDK `jar` tool.
+pub(crate) fn create_jar(classes: &Path, jar: &Path, options: &[&str]) {
+    let output = Command::new("jar")
+        .arg("--create")
+        .arg("--file")
+        .arg(jar)
+        .args(options)
+        .arg("-C")
+        .arg(classes)
+        .arg(".")
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "jar failed\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
 pub(crate) fn jay(args: &[&str]) -> Output {
+    jay_with_env(args, &[])
+}
+
+/// Runs `jay` with extra environment variables and without any inherited `CLASSPATH`.
+pub(crate) fn jay_with_env(args: &[&str], env: &[(&str, &str)]) -> Output {
     Command::new(env!("CARGO_BIN_EXE_jay"))
         .args(args)
+        .env_remove("CLASSPATH")
+        .envs(env.iter().copied())
         .output()
         .unwrap()
 }
 
-pub(crate) fn make_method_non_static(root: &Path, relative_class_path: &str, method_name: &str) {
-    let class_path = root.join(relative_class_path);
-    let mut bytes = std::fs::read(&class_path).unwrap();
-    let mut cursor = ClassEditor::new(&mut bytes);
-    cursor.clear_method_access_flag(method_name, 0x0008);
-    std::fs::write(class_path, bytes).unwrap();
-}
-
-pub(crate) fn replace_utf8_constant(root: &Path, relative_class_path: &str, from: &str, to: &str) {
-    assert_eq!(
-        from.len(),
-        to.len(),
-        "replacement must preserve UTF-8 length"
-    );
-    let class_path = root.join(relative_class_path);
-    let mut bytes = std::fs::read(&class_path).unwrap();
-    let mut cursor = ClassEditor::new(&mut bytes);
-    cursor.replace_utf8_constant(from, to);
-    std::fs::write(class_path, bytes).unwrap();
+/// Runs `jay` with `input` piped to its standard input.
+pub(crate) fn jay_with_input(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_jay"))
+        .args(args)
+        .env_remove("CLASSPATH")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
 }
 
-struct ClassEditor<'a> {
-    bytes: &'a mut [u8],
-    offset: usize,
-    utf8_entries: Vec<Option<String>>,
+pub(crate) fn make_method_non_static(root: &Path, relative_class_path: &str, method_name: &str) {
+    edit_class_file(root, relative_class_path, |class_file| {
+        let method = class_file
+            .methods
+            .iter_mut()
+            .find(|method| method.name == method_name)
+            .unwrap_or_else(|| panic!("method {method_name} not found"));
+        method.access_flags &= !0x0008;
+    });
 }
 
-impl<'a> ClassEditor<'a> {
-    fn new(bytes: &'a mut [u8]) -> Self {
-        Self {
-            bytes,
-            offset: 0,
-            utf8_entries: Vec::new(),
-        }
-    }
-
-    fn clear_method_access_flag(&mut self, method_name: &str, flag: u16) {
-        assert_eq!(self.read_u4(), 0xCAFEBABE);
-        self.skip(4);
-        self.read_constant_pool();
-        self.skip(6);
-        self.skip_table(2);
-        self.skip_members();
-
-        let methods_count = self.read_u2();
-        for _ in 0..methods_count {
-            let access_flags_offset = self.offset;
-            let access_flags = self.read_u2();
-            let name_index = self.read_u2() as usize;
-            self.skip(2);
-            let attributes_count = self.read_u2();
-
-            if self.utf8_entries[name_index].as_deref() == Some(method_name) {
-                let new_access_flags = access_flags & !flag;
-                self.bytes[access_flags_offset..access_flags_offset + 2]
-                    .copy_from_slice(&new_access_flags.to_be_bytes());
-                return;
-            }
-
-            for _ in 0..attributes_count {
-                self.skip(2);
-                let length = self.read_u4() as usize;
-                self.skip(length);
-            }
-        }
-
-        panic!("method {method_name} not found");
-    }
-
-    fn replace_utf8_constant(&mut self, from: &str, to: &str) {
-        assert_eq!(self.read_u4(), 0xCAFEBABE);
-        self.skip(4);
-
-        let count = self.read_u2() as usize;
-        let mut index = 1;
+pub(crate) fn replace_utf8_constant(root: &Path, relative_class_path: &str, from: &str, to: &str) {
+    edit_class_file(root, relative_class_path, |class_file| {
+        let constant_pool = &mut class_file.constant_pool;
         let mut replacements = 0usize;
-        while index < count {
-            let tag = self.read_u1();
-            match tag {
-                1 => {
-                    let length = self.read_u2() as usize;
-                    let value = std::str::from_utf8(&self.bytes[self.offset..self.offset + length])
-                        .unwrap();
-                    if value == from {
-                        self.bytes[self.offset..self.offset + length]
-                            .copy_from_slice(to.as_bytes());
-                        replacements += 1;
-                    }
-                    self.skip(length);
-                }
-                3 | 4 => self.skip(4),
-                5 | 6 => {
-                    self.skip(8);
-                    index += 1;
-                }
-                7 | 8 | 16 | 19 | 20 => self.skip(2),
-                9 | 10 | 11 | 12 | 17 | 18 => self.skip(4),
-                15 => self.skip(3),
-                other => panic!("unsupported test constant pool tag {other}"),
+        for index in 1..constant_pool.count() {
+            if constant_pool.utf8(index).ok() == Some(from) {
+                constant_pool.set_utf8(index, to).unwrap();
+                replacements += 1;
             }
-            index += 1;
         }
-
         assert!(
             replacements > 0,
             "UTF-8 constant {from} not found in class file"
         );
-    }
 
-    fn read_constant_pool(&mut self) {
-        let count = self.read_u2() as usize;
-        self.utf8_entries = vec![None; count];
-        let mut index = 1;
-        while index < count {
-            let tag = self.read_u1();
-            match tag {
-                1 => {
-                    let length = self.read_u2() as usize;
-                    let value =
-                        String::from_utf8(self.bytes[self.offset..self.offset + length].to_vec())
-                            .unwrap();
-                    self.utf8_entries[index] = Some(value);
-                    self.skip(length);
-                }
-                3 | 4 => self.skip(4),
-                5 | 6 => {
-                    self.skip(8);
-                    index += 1;
-                }
-                7 | 8 | 16 | 19 | 20 => self.skip(2),
-                9 | 10 | 11 | 12 | 17 | 18 => self.skip(4),
-                15 => self.skip(3),
-                other => panic!("unsupported test constant pool tag {other}"),
+        // Names and descriptors held by the model are interned again on write.
+        let rename = |value: &mut String| {
+            if value == from {
+                *value = to.to_string();
             }
-            index += 1;
+        };
+        rename(&mut class_file.this_class);
+        class_file.super_class.iter_mut().for_each(rename);
+        class_file.interfaces.iter_mut().for_each(rename);
+        for method in &mut class_file.methods {
+            rename(&mut method.name);
+            rename(&mut method.descriptor);
         }
-    }
-
-    fn skip_members(&mut self) {
-        let count = self.read_u2();
-        for _ in 0..count {
-            self.skip(6);
-            let attributes_count = self.read_u2();
-            for _ in 0..attributes_count {
-                self.skip(2);
-                let length = self.read_u4() as usize;
-                self.skip(length);
-            }
+        for field in &mut class_file.fields {
+            rename(&mut field.name);
+            rename(&mut field.descriptor);
         }
-    }
-
-    fn skip_table(&mut self, entry_size: usize) {
-        let count = self.read_u2() as usize;
-        self.skip(count * entry_size);
-    }
-
-    fn read_u1(&mut self) -> u8 {
-        let value = self.bytes[self.offset];
-        self.offset += 1;
-        value
-    }
-
-    fn read_u2(&mut self) -> u16 {
-        let value = u16::from_be_bytes([self.bytes[self.offset], self.bytes[self.offset + 1]]);
-        self.offset += 2;
-        value
-    }
-
-    fn read_u4(&mut self) -> u32 {
-        let value = u32::from_be_bytes([
-            self.bytes[self.offset],
-            self.bytes[self.offset + 1],
-            self.bytes[self.offset + 2],
-            self.bytes[self.offset + 3],
-        ]);
-        self.offset += 4;
-        value
-    }
+    });
+}
 
-    fn skip(&mut self, length: usize) {
-        self.offset += length;
-    }
+fn edit_class_file(root: &Path, relative_class_path: &str, edit: impl FnOnce(&mut ClassFile)) {
+    let class_path = root.join(relative_class_path);
+    let mut class_file = ClassFile::parse(&std::fs::read(&class_path).unwrap()).unwrap();
+    edit(&mut class_file);
+    std::fs::write(class_path, class_file.to_bytes().unwrap()).unwrap();
 }